@@ -61,8 +61,9 @@ use crate::{
     id::{
         Condition, ConditionResult, Evaluate, Match, MatchSettings, MatchStack, Pattern,
         PatternAtomTreeIterator, PatternRestriction, Relation, ReplaceIterator, ReplaceWith,
-        Replacement, WildcardRestriction,
+        Replacement, RuleSet, WildcardRestriction,
     },
+    limit::{Limit, LimitDirection, LimitPoint},
     numerical_integration::{ContinuousGrid, DiscreteGrid, Grid, MonteCarloRng, Sample},
     parser::Token,
     poly::{
@@ -290,7 +291,7 @@ fn get_license_key(email: String) -> PyResult<()> {
         .map_err(exceptions::PyConnectionError::new_err)
 }
 
-#[pyfunction(name = "S", signature = (*names,is_symmetric=None,is_antisymmetric=None,is_cyclesymmetric=None,is_linear=None,custom_normalization=None,custom_print=None))]
+#[pyfunction(name = "S", signature = (*names,is_symmetric=None,is_antisymmetric=None,is_cyclesymmetric=None,is_linear=None,is_linear_in_first_argument=None,is_noncommutative=None,is_grassmann=None,custom_normalization=None,custom_print=None))]
 /// Shorthand notation for :func:`Expression.symbol`.
 fn symbol_shorthand(
     names: &Bound<'_, PyTuple>,
@@ -298,6 +299,9 @@ fn symbol_shorthand(
     is_antisymmetric: Option<bool>,
     is_cyclesymmetric: Option<bool>,
     is_linear: Option<bool>,
+    is_linear_in_first_argument: Option<bool>,
+    is_noncommutative: Option<bool>,
+    is_grassmann: Option<bool>,
     custom_normalization: Option<PythonTransformer>,
     custom_print: Option<PyObject>,
     py: Python<'_>,
@@ -310,6 +314,9 @@ fn symbol_shorthand(
         is_antisymmetric,
         is_cyclesymmetric,
         is_linear,
+        is_linear_in_first_argument,
+        is_noncommutative,
+        is_grassmann,
         custom_normalization,
         custom_print,
     )
@@ -1864,9 +1871,9 @@ impl PythonTransformer {
         &self,
         replacements: Vec<PythonReplacement>,
     ) -> PyResult<PythonTransformer> {
-        self.append_transformer(Transformer::ReplaceAllMultiple(
+        self.append_transformer(Transformer::ReplaceAllRuleSet(RuleSet::new(
             replacements.into_iter().map(|r| r.replacement).collect(),
-        ))
+        )))
     }
 
     /// Create a transformer that prints the expression.
@@ -2704,8 +2711,10 @@ impl PythonExpression {
     /// Create a new symbol from a `name`. Symbols carry information about their attributes.
     /// The symbol can signal that it is symmetric if it is used as a function
     /// using `is_symmetric=True`, antisymmetric using `is_antisymmetric=True`,
-    /// cyclesymmetric using `is_cyclesymmetric=True` and
-    /// multilinear using `is_linear=True`. If no attributes
+    /// cyclesymmetric using `is_cyclesymmetric=True`, multilinear using `is_linear=True`,
+    /// and linear in its first argument only using `is_linear_in_first_argument=True`.
+    /// A symbol that keeps its order in products is defined using `is_noncommutative=True`
+    /// and an anticommuting symbol using `is_grassmann=True`. If no attributes
     /// are specified, the attributes are inherited from the symbol if it was already defined,
     /// otherwise all attributes are set to `false`.  A transformer that is executed
     /// after normalization can be defined with `custom_normalization`.
@@ -2732,7 +2741,7 @@ impl PythonExpression {
     /// >>> print(e)
     /// f(1,2)
     ///
-    /// Define a linear and symmetric function:
+    /// Define a multilinear and symmetric function:
     /// >>> p1, p2, p3, p4 = Expression.symbol('p1', 'p2', 'p3', 'p4')
     /// >>> dot = Expression.symbol('dot', is_symmetric=True, is_linear=True)
     /// >>> e = dot(p2+2*p3,p1+3*p2-p3)
@@ -2742,7 +2751,7 @@ impl PythonExpression {
     /// Define a custom normalization function:
     /// >>> e = S('real_log', custom_normalization=Transformer().replace(E("x_(exp(x1_))"), E("x1_")))
     /// >>> E("real_log(exp(x)) + real_log(5)")
-    #[pyo3(signature = (*names,is_symmetric=None,is_antisymmetric=None,is_cyclesymmetric=None,is_linear=None,custom_normalization=None, custom_print=None))]
+    #[pyo3(signature = (*names,is_symmetric=None,is_antisymmetric=None,is_cyclesymmetric=None,is_linear=None,is_linear_in_first_argument=None,is_noncommutative=None,is_grassmann=None,custom_normalization=None, custom_print=None))]
     #[classmethod]
     pub fn symbol(
         _cls: &Bound<'_, PyType>,
@@ -2752,6 +2761,9 @@ impl PythonExpression {
         is_antisymmetric: Option<bool>,
         is_cyclesymmetric: Option<bool>,
         is_linear: Option<bool>,
+        is_linear_in_first_argument: Option<bool>,
+        is_noncommutative: Option<bool>,
+        is_grassmann: Option<bool>,
         custom_normalization: Option<PythonTransformer>,
         custom_print: Option<PyObject>,
     ) -> PyResult<PyObject> {
@@ -2772,6 +2784,9 @@ impl PythonExpression {
             && is_antisymmetric.is_none()
             && is_cyclesymmetric.is_none()
             && is_linear.is_none()
+            && is_linear_in_first_argument.is_none()
+            && is_noncommutative.is_none()
+            && is_grassmann.is_none()
             && custom_normalization.is_none()
             && custom_print.is_none()
         {
@@ -2827,6 +2842,18 @@ impl PythonExpression {
             opts.push(FunctionAttribute::Linear);
         }
 
+        if let Some(true) = is_linear_in_first_argument {
+            opts.push(FunctionAttribute::LinearInFirstArgument);
+        }
+
+        if let Some(true) = is_noncommutative {
+            opts.push(FunctionAttribute::Noncommutative);
+        }
+
+        if let Some(true) = is_grassmann {
+            opts.push(FunctionAttribute::Grassmann);
+        }
+
         if names.len() == 1 {
             let name = names.get_item(0).unwrap().extract::<PyBackedStr>()?;
             let name = namespace.attach_namespace(&name);
@@ -4564,6 +4591,96 @@ impl PythonExpression {
         Ok(b.into())
     }
 
+    /// Integrate the expression w.r.t the variable `x`. Terms that cannot be
+    /// integrated are returned as an unevaluated `integrate(term, x)`. Rational functions
+    /// whose integral requires the roots of an irreducible polynomial of degree higher
+    /// than two, such as `1/(x^3+2)`, are returned unevaluated.
+    ///
+    /// Examples
+    /// --------
+    /// >>> from symbolica import Expression
+    /// >>> x = Expression.symbol('x')
+    /// >>> print((1/(x**2+1)).integrate(x))
+    ///
+    /// yields `atan(x)`.
+    pub fn integrate(&self, x: ConvertibleToExpression) -> PyResult<PythonExpression> {
+        let id = if let AtomView::Var(x) = x.to_expression().expr.as_view() {
+            x.get_symbol()
+        } else {
+            return Err(exceptions::PyValueError::new_err(
+                "Integration must be done wrt a variable",
+            ));
+        };
+
+        Ok(self.expr.integrate(id).into())
+    }
+
+    /// Compute the limit of the expression for `x` going to `point`, which is an expression
+    /// or `float('inf')`/`float('-inf')`. The `direction` is `both`, `+` (from above) or `-` (from below).
+    ///
+    /// The result is an expression, `float('inf')`, `float('-inf')`, or `None` if the limit
+    /// does not exist. An error is raised if the limit could not be determined.
+    ///
+    /// Examples
+    /// --------
+    /// >>> from symbolica import Expression
+    /// >>> x = Expression.symbol('x')
+    /// >>> print((x*(-x).exp()).limit(x, float('inf')))
+    ///
+    /// yields `0`.
+    #[pyo3(signature = (x, point, direction = "both"))]
+    pub fn limit(
+        &self,
+        x: ConvertibleToExpression,
+        point: &Bound<'_, PyAny>,
+        direction: &str,
+        py: Python,
+    ) -> PyResult<PyObject> {
+        let id = if let AtomView::Var(x) = x.to_expression().expr.as_view() {
+            x.get_symbol()
+        } else {
+            return Err(exceptions::PyValueError::new_err(
+                "Limit must be taken wrt a variable",
+            ));
+        };
+
+        let point = match point.extract::<f64>() {
+            Ok(f) if f == f64::INFINITY => LimitPoint::PositiveInfinity,
+            Ok(f) if f == f64::NEG_INFINITY => LimitPoint::NegativeInfinity,
+            _ => LimitPoint::Finite(
+                point
+                    .extract::<ConvertibleToExpression>()?
+                    .to_expression()
+                    .expr,
+            ),
+        };
+
+        let direction = match direction {
+            "both" => LimitDirection::Both,
+            "+" => LimitDirection::FromAbove,
+            "-" => LimitDirection::FromBelow,
+            _ => {
+                return Err(exceptions::PyValueError::new_err(
+                    "Invalid direction specified: use 'both', '+' or '-'",
+                ));
+            }
+        };
+
+        match self
+            .expr
+            .limit(id, point, direction)
+            .map_err(exceptions::PyValueError::new_err)?
+        {
+            Limit::Finite(a) => PythonExpression::from(a).into_py_any(py),
+            Limit::PositiveInfinity => f64::INFINITY.into_py_any(py),
+            Limit::NegativeInfinity => f64::NEG_INFINITY.into_py_any(py),
+            Limit::DoesNotExist => Ok(py.None()),
+            Limit::Unknown => Err(exceptions::PyValueError::new_err(
+                "The limit could not be determined",
+            )),
+        }
+    }
+
     /// Series expand in `x` around `expansion_point` to depth `depth`.
     ///
     /// Examples
@@ -4606,6 +4723,41 @@ impl PythonExpression {
         }
     }
 
+    /// Series expand in `x` around infinity to depth `depth`, i.e., in powers of `x^-1`.
+    ///
+    /// Examples
+    /// -------
+    /// >>> from symbolica import Expression
+    /// >>> x = Expression.symbol('x')
+    /// >>> e = (x**2/(x-1)).series_at_infinity(x, 2)
+    /// >>> print(e.to_expression())
+    ///
+    /// yields `x+1+x^-1+x^-2`.
+    #[pyo3(signature = (x, depth, depth_denom = 1, depth_is_absolute = true))]
+    pub fn series_at_infinity(
+        &self,
+        x: ConvertibleToExpression,
+        depth: i64,
+        depth_denom: i64,
+        depth_is_absolute: bool,
+    ) -> PyResult<PythonSeries> {
+        let id = if let AtomView::Var(x) = x.to_expression().expr.as_view() {
+            x.get_symbol()
+        } else {
+            return Err(exceptions::PyValueError::new_err(
+                "Series must be taken wrt a variable",
+            ));
+        };
+
+        match self
+            .expr
+            .series_at_infinity(id, (depth, depth_denom).into(), depth_is_absolute)
+        {
+            Ok(s) => Ok(PythonSeries { series: s }),
+            Err(e) => Err(exceptions::PyValueError::new_err(e.to_string())),
+        }
+    }
+
     /// Compute the partial fraction decomposition in `x`.
     ///
     /// If `None` is passed, the expression will be decomposed in all variables
@@ -6264,7 +6416,8 @@ impl PythonTermStreamer {
         self.stream.to_expression().into()
     }
 
-    /// Map the transformations to every term in the stream.
+    /// Map the transformations to every term in the stream. The resulting stream is normalized.
+    /// The execution is aborted on the first error, which is raised.
     #[pyo3(signature = (op, stats_to_file=None))]
     pub fn map(
         &mut self,
@@ -6291,19 +6444,14 @@ impl PythonTermStreamer {
         // within the term mapper
         py.allow_threads(move || {
             // map every term in the expression
-            let m = self.stream.map(|x| {
-                let mut out = Atom::default();
-                let _ = Workspace::get_local().with(|ws| {
-                    let _ =
-                        Transformer::execute_chain(x.as_view(), &op.chain, ws, &state, &mut out)
-                            .unwrap_or_else(|e| {
-                                // TODO: capture and abort the parallel run
-                                panic!("Transformer failed during parallel execution: {:?}", e)
-                            });
-                });
-                out
-            });
-            Ok::<_, PyErr>(m)
+            self.stream
+                .execute_chain(&op.chain, &state)
+                .map_err(|e| match e {
+                    TransformerError::Interrupt => {
+                        exceptions::PyKeyboardInterrupt::new_err("Interrupted by user")
+                    }
+                    TransformerError::ValueError(v) => exceptions::PyValueError::new_err(v),
+                })
         })
         .map(|x| PythonTermStreamer { stream: x })
     }
@@ -39,6 +39,7 @@
 //! let p = parse!("f(2,3) + 1/4");
 //! assert_eq!(expr, p);
 //! ```
+mod assumptions;
 mod coefficient;
 mod core;
 pub mod representation;
@@ -57,6 +58,7 @@ use crate::{
 
 use std::{borrow::Cow, cmp::Ordering, hash::Hash, ops::DerefMut};
 
+pub use self::assumptions::{Assumption, ValueProperties};
 pub use self::core::AtomCore;
 pub use self::representation::{
     Add, AddView, Fun, InlineNum, InlineVar, KeyLookup, ListIterator, ListSlice, Mul, MulView, Num,
@@ -270,6 +272,20 @@ macro_rules! hide_namespace {
 /// ```
 pub type NormalizationFunction = Box<dyn Fn(AtomView, &mut Atom) -> bool + Send + Sync>;
 
+/// A function that yields the `n`th Taylor coefficient `f^(n)(a)/n!` of a function `f`
+/// with a single argument around the point `a`, which is the second argument.
+///
+/// # Examples
+///
+/// ```
+/// use symbolica::atom::{Atom, TaylorCoefficientFunction};
+///
+/// // the Taylor coefficients of f(y) = 1/(1-y)
+/// let taylor_fn: TaylorCoefficientFunction =
+///     Box::new(|n, a| (Atom::num(1) - a).npow(-(n as i64) - 1));
+/// ```
+pub type TaylorCoefficientFunction = Box<dyn Fn(usize, AtomView) -> Atom + Send + Sync>;
+
 /// Attributes that can be assigned to functions.
 #[derive(Clone, Copy, PartialEq)]
 pub enum FunctionAttribute {
@@ -279,8 +295,22 @@ pub enum FunctionAttribute {
     Antisymmetric,
     /// The function is cyclesymmetric.
     Cyclesymmetric,
-    /// The function is linear.
+    /// The function is linear in every argument, so that it is multilinear
+    /// when it has more than one argument: `f(a+b,c+d)` is normalized to
+    /// `f(a,c)+f(a,d)+f(b,c)+f(b,d)`.
+    ///
+    /// A pattern with a sum containing wildcards in a linear argument,
+    /// such as `f(x_+y_)`, matches a sum of functions `f(a)+2*f(b)`.
     Linear,
+    /// The function is linear in its first argument only.
+    LinearInFirstArgument,
+    /// The symbol does not commute with other noncommutative symbols in a product,
+    /// so that their relative order is kept. It commutes with all other symbols,
+    /// including Grassmann symbols.
+    Noncommutative,
+    /// The symbol anticommutes with other Grassmann symbols in a product
+    /// and commutes with all other symbols.
+    Grassmann,
 }
 
 /// A symbol, for example the name of a variable or the name of a function,
@@ -312,6 +342,9 @@ pub struct Symbol {
     is_antisymmetric: bool,
     is_cyclesymmetric: bool,
     is_linear: bool,
+    is_linear_in_first_argument: bool,
+    is_noncommutative: bool,
+    is_grassmann: bool,
 }
 
 impl std::fmt::Debug for Symbol {
@@ -334,8 +367,11 @@ impl std::fmt::Display for Symbol {
 pub struct SymbolBuilder {
     symbol: NamespacedSymbol,
     attributes: Option<Cow<'static, [FunctionAttribute]>>,
+    assumptions: Option<Vec<Assumption>>,
     normalization_function: Option<NormalizationFunction>,
     print_function: Option<PrintFunction>,
+    taylor_function: Option<TaylorCoefficientFunction>,
+    derivatives: Option<(Vec<Symbol>, Vec<Atom>)>,
 }
 
 impl SymbolBuilder {
@@ -345,8 +381,11 @@ impl SymbolBuilder {
         SymbolBuilder {
             symbol,
             attributes: None,
+            assumptions: None,
             normalization_function: None,
             print_function: None,
+            taylor_function: None,
+            derivatives: None,
         }
     }
 
@@ -373,6 +412,29 @@ impl SymbolBuilder {
         self
     }
 
+    /// Set assumptions on the value of the symbol, such as its sign or whether it is real.
+    /// If the symbol is used as a function, the assumptions hold for the value of the function.
+    ///
+    /// The assumptions are used by [AtomCore::value_properties] to infer properties of expressions,
+    /// which can be used to restrict wildcards in patterns and to simplify expressions.
+    ///
+    /// This function will return an error when an existing symbol is redefined
+    /// with different assumptions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::{Assumption, AtomCore, Symbol}, parse, wrap_symbol};
+    ///
+    /// let x = Symbol::new(wrap_symbol!("x")).with_assumptions(&[Assumption::Positive]).build().unwrap();
+    /// assert_eq!(parse!("(x^2)^(1/2)"), parse!("x"));
+    /// assert!(parse!("x^2+1").value_properties().is_positive());
+    /// ```
+    pub fn with_assumptions(mut self, assumptions: &[Assumption]) -> Self {
+        self.assumptions = Some(assumptions.to_vec());
+        self
+    }
+
     /// ```
     /// use symbolica::{atom::{AtomView, Symbol}, wrap_symbol};
     ///
@@ -417,22 +479,103 @@ impl SymbolBuilder {
         self
     }
 
+    /// Set a function that yields the `n`th Taylor coefficient `f^(n)(a)/n!` of the function
+    /// around a point `a`. The coefficients are used to series expand and differentiate the
+    /// function when it has a single argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::{Atom, AtomCore, Symbol}, parse, symbol, wrap_symbol};
+    ///
+    /// // f(y) = 1/(1-y)
+    /// let f = Symbol::new(wrap_symbol!("f"))
+    ///     .with_taylor_coefficients(|n, a| (Atom::num(1) - a).npow(-(n as i64) - 1))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let x = symbol!("x");
+    /// assert_eq!(parse!("f(x^2)").derivative(x), parse!("2*x*(1-x^2)^-2"));
+    ///
+    /// let s = parse!("f(x)").series(x, Atom::num(0), 2.into(), true).unwrap();
+    /// assert_eq!(s.to_atom(), parse!("1+x+x^2"));
+    /// ```
+    pub fn with_taylor_coefficients(
+        mut self,
+        taylor_function: impl Fn(usize, AtomView) -> Atom + Send + Sync + 'static,
+    ) -> Self {
+        self.taylor_function = Some(Box::new(taylor_function));
+        self
+    }
+
+    /// Set the derivative of the function in each of its arguments. The derivative in the `i`th argument
+    /// is given by the template `derivatives[i]`, written in terms of the placeholder symbols `arguments`,
+    /// which are substituted by the actual arguments of the function.
+    ///
+    /// The rules are used when the function is called with as many arguments as there are placeholders.
+    /// A symbol cannot have both derivative templates and Taylor coefficients (see
+    /// [with_taylor_coefficients](SymbolBuilder::with_taylor_coefficients)), and
+    /// [build](SymbolBuilder::build) returns an error when both are set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::{AtomCore, Symbol}, parse, symbol, wrap_symbol};
+    ///
+    /// // f(a, b) = sin(a)*b
+    /// let (a, b) = symbol!("a", "b");
+    /// let _f = Symbol::new(wrap_symbol!("f"))
+    ///     .with_derivatives(&[a, b], &[parse!("cos(a)*b"), parse!("sin(a)")])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let x = symbol!("x");
+    /// assert_eq!(parse!("f(x^2, x)").derivative(x), parse!("2*x^2*cos(x^2)+sin(x^2)"));
+    /// ```
+    pub fn with_derivatives(mut self, arguments: &[Symbol], derivatives: &[Atom]) -> Self {
+        self.derivatives = Some((arguments.to_vec(), derivatives.to_vec()));
+        self
+    }
+
     /// Create a new symbol or return the existing symbol with the same name.
     ///
     /// This function will return an error when an existing symbol is redefined
     /// with different attributes.
     pub fn build(self) -> Result<Symbol, SmartString<LazyCompact>> {
+        if let Some((arguments, derivatives)) = &self.derivatives {
+            if arguments.len() != derivatives.len() {
+                return Err(format!(
+                    "The number of derivative templates ({}) does not match the number of arguments ({})",
+                    derivatives.len(),
+                    arguments.len()
+                )
+                .into());
+            }
+
+            if self.taylor_function.is_some() {
+                return Err(
+                    "A symbol cannot have both derivative templates and Taylor coefficients".into(),
+                );
+            }
+        }
+
         if self.attributes.is_none()
+            && self.assumptions.is_none()
             && self.normalization_function.is_none()
             && self.print_function.is_none()
+            && self.taylor_function.is_none()
+            && self.derivatives.is_none()
         {
             State::get_state_mut().get_symbol(self.symbol)
         } else {
             State::get_state_mut().get_symbol_with_attributes(
                 self.symbol,
                 self.attributes.as_ref().map(|x| x.as_ref()).unwrap_or(&[]),
+                self.assumptions.unwrap_or_default(),
                 self.normalization_function,
                 self.print_function,
+                self.taylor_function,
+                self.derivatives,
             )
         }
     }
@@ -475,6 +618,20 @@ impl Symbol {
         &d.name[d.namespace.len() + 2..]
     }
 
+    /// Get the assumptions on the value of the symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::{Assumption, Symbol}, wrap_symbol};
+    ///
+    /// let n = Symbol::new(wrap_symbol!("n")).with_assumptions(&[Assumption::Integer]).build().unwrap();
+    /// assert_eq!(n.get_assumptions(), &[Assumption::Integer]);
+    /// ```
+    pub fn get_assumptions(&self) -> &'static [Assumption] {
+        &State::get_symbol_data(*self).assumptions
+    }
+
     /// Get the internal id of the symbol.
     ///
     /// # Examples
@@ -565,7 +722,7 @@ impl Symbol {
         self.is_cyclesymmetric
     }
 
-    /// Check if the symbol is linear.
+    /// Check if the symbol is linear in every argument.
     ///
     /// # Examples
     ///
@@ -579,6 +736,48 @@ impl Symbol {
         self.is_linear
     }
 
+    /// Check if the symbol is linear in its first argument only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::symbol;
+    ///
+    /// let f = symbol!("f"; LinearInFirstArgument);
+    /// assert!(f.is_linear_in_first_argument() && !f.is_linear());
+    /// ```
+    pub fn is_linear_in_first_argument(&self) -> bool {
+        self.is_linear_in_first_argument
+    }
+
+    /// Check if the symbol is noncommutative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::symbol;
+    ///
+    /// let a = symbol!("a"; Noncommutative);
+    /// assert!(a.is_noncommutative());
+    /// ```
+    pub fn is_noncommutative(&self) -> bool {
+        self.is_noncommutative
+    }
+
+    /// Check if the symbol is a Grassmann (anticommuting) variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::symbol;
+    ///
+    /// let theta = symbol!("theta"; Grassmann);
+    /// assert!(theta.is_grassmann());
+    /// ```
+    pub fn is_grassmann(&self) -> bool {
+        self.is_grassmann
+    }
+
     /// Returns `true` iff this identifier is defined by Symbolica.
     pub fn is_builtin(id: Symbol) -> bool {
         State::is_builtin(id)
@@ -594,11 +793,15 @@ impl Symbol {
             is_antisymmetric: false,
             is_cyclesymmetric: false,
             is_linear: false,
+            is_linear_in_first_argument: false,
+            is_noncommutative: false,
+            is_grassmann: false,
         }
     }
 
     /// Expert use: create a new function symbol. This constructor should be used with care as there are no checks
     /// about the validity of the identifier.
+    #[allow(clippy::too_many_arguments)]
     pub const fn raw_fn(
         id: u32,
         wildcard_level: u8,
@@ -606,6 +809,9 @@ impl Symbol {
         is_antisymmetric: bool,
         is_cyclesymmetric: bool,
         is_linear: bool,
+        is_linear_in_first_argument: bool,
+        is_noncommutative: bool,
+        is_grassmann: bool,
     ) -> Self {
         Symbol {
             id,
@@ -614,6 +820,9 @@ impl Symbol {
             is_antisymmetric,
             is_cyclesymmetric,
             is_linear,
+            is_linear_in_first_argument,
+            is_noncommutative,
+            is_grassmann,
         }
     }
 
@@ -640,6 +849,16 @@ impl Symbol {
                 Atom::SIN => f.write_str("\\sin"),
                 Atom::EXP => f.write_str("\\exp"),
                 Atom::LOG => f.write_str("\\log"),
+                Atom::TAN => f.write_str("\\tan"),
+                Atom::ASIN => f.write_str("\\arcsin"),
+                Atom::ACOS => f.write_str("\\arccos"),
+                Atom::ATAN => f.write_str("\\arctan"),
+                Atom::SINH => f.write_str("\\sinh"),
+                Atom::COSH => f.write_str("\\cosh"),
+                Atom::TANH => f.write_str("\\tanh"),
+                Atom::ASINH => f.write_str("\\operatorname{arsinh}"),
+                Atom::ACOSH => f.write_str("\\operatorname{arcosh}"),
+                Atom::ATANH => f.write_str("\\operatorname{artanh}"),
                 _ => {
                     f.write_str(name)?;
                     if !opts.hide_all_namespaces {
@@ -1191,6 +1410,28 @@ impl Atom {
     pub const E: Symbol = State::E;
     /// The mathematical constant `π`.
     pub const PI: Symbol = State::PI;
+    /// The tangent function.
+    pub const TAN: Symbol = State::TAN;
+    /// The inverse sine function.
+    pub const ASIN: Symbol = State::ASIN;
+    /// The inverse cosine function.
+    pub const ACOS: Symbol = State::ACOS;
+    /// The inverse tangent function.
+    pub const ATAN: Symbol = State::ATAN;
+    /// The hyperbolic sine function.
+    pub const SINH: Symbol = State::SINH;
+    /// The hyperbolic cosine function.
+    pub const COSH: Symbol = State::COSH;
+    /// The hyperbolic tangent function.
+    pub const TANH: Symbol = State::TANH;
+    /// The inverse hyperbolic sine function.
+    pub const ASINH: Symbol = State::ASINH;
+    /// The inverse hyperbolic cosine function.
+    pub const ACOSH: Symbol = State::ACOSH;
+    /// The inverse hyperbolic tangent function.
+    pub const ATANH: Symbol = State::ATANH;
+    /// The built-in function that represents an unevaluated integral.
+    pub const INTEGRATE: Symbol = State::INTEGRATE;
 
     /// The number suffix that represents the imaginary unit.
     /// The suffix `i` can also be used for parsing (e.g. `2+3𝑖` or `2+3i`).
@@ -1227,6 +1468,56 @@ impl Atom {
     pub fn sqrt(&self) -> Atom {
         FunctionBuilder::new(Atom::SQRT).add_arg(self).finish()
     }
+
+    /// Take the tangent of the atom.
+    pub fn tan(&self) -> Atom {
+        FunctionBuilder::new(Atom::TAN).add_arg(self).finish()
+    }
+
+    /// Take the inverse sine of the atom.
+    pub fn asin(&self) -> Atom {
+        FunctionBuilder::new(Atom::ASIN).add_arg(self).finish()
+    }
+
+    /// Take the inverse cosine of the atom.
+    pub fn acos(&self) -> Atom {
+        FunctionBuilder::new(Atom::ACOS).add_arg(self).finish()
+    }
+
+    /// Take the inverse tangent of the atom.
+    pub fn atan(&self) -> Atom {
+        FunctionBuilder::new(Atom::ATAN).add_arg(self).finish()
+    }
+
+    /// Take the hyperbolic sine of the atom.
+    pub fn sinh(&self) -> Atom {
+        FunctionBuilder::new(Atom::SINH).add_arg(self).finish()
+    }
+
+    /// Take the hyperbolic cosine of the atom.
+    pub fn cosh(&self) -> Atom {
+        FunctionBuilder::new(Atom::COSH).add_arg(self).finish()
+    }
+
+    /// Take the hyperbolic tangent of the atom.
+    pub fn tanh(&self) -> Atom {
+        FunctionBuilder::new(Atom::TANH).add_arg(self).finish()
+    }
+
+    /// Take the inverse hyperbolic sine of the atom.
+    pub fn asinh(&self) -> Atom {
+        FunctionBuilder::new(Atom::ASINH).add_arg(self).finish()
+    }
+
+    /// Take the inverse hyperbolic cosine of the atom.
+    pub fn acosh(&self) -> Atom {
+        FunctionBuilder::new(Atom::ACOSH).add_arg(self).finish()
+    }
+
+    /// Take the inverse hyperbolic tangent of the atom.
+    pub fn atanh(&self) -> Atom {
+        FunctionBuilder::new(Atom::ATANH).add_arg(self).finish()
+    }
 }
 
 impl Default for Atom {
@@ -1671,7 +1962,10 @@ macro_rules! function {
 /// You can specify attributes for the symbol, using `;` as a separator
 /// between symbol names and attributes. The options
 /// are [Symmetric](FunctionAttribute::Symmetric), [Antisymmetric](FunctionAttribute::Antisymmetric),
-/// [Cyclesymmetric](FunctionAttribute::Cyclesymmetric), and [Linear](FunctionAttribute::Linear).
+/// [Cyclesymmetric](FunctionAttribute::Cyclesymmetric), [Linear](FunctionAttribute::Linear),
+/// [LinearInFirstArgument](FunctionAttribute::LinearInFirstArgument),
+/// [Noncommutative](FunctionAttribute::Noncommutative),
+/// and [Grassmann](FunctionAttribute::Grassmann).
 /// ```no_run
 /// use symbolica::symbol;
 /// let x = symbol!("x"; Symmetric, Linear);
@@ -1982,7 +2276,7 @@ impl AsRef<Atom> for Atom {
 #[cfg(test)]
 mod test {
     use crate::{
-        atom::{Atom, AtomCore},
+        atom::{Assumption, Atom, AtomCore, Symbol},
         function,
     };
 
@@ -1998,7 +2292,7 @@ mod test {
         let x = parse!("v1+f1(v2)");
         assert_eq!(
             format!("{:?}", x),
-            "AddView { data: [5, 17, 2, 13, 2, 1, 11, 3, 5, 0, 0, 0, 1, 41, 2, 1, 12] }"
+            "AddView { data: [5, 17, 2, 13, 2, 1, 22, 3, 5, 0, 0, 0, 1, 52, 2, 1, 23] }"
         );
         assert_eq!(
             x.get_all_symbols(true),
@@ -2023,6 +2317,56 @@ mod test {
         assert_eq!(res, r);
     }
 
+    #[test]
+    fn value_properties() {
+        let _x = Symbol::new(wrap_symbol!("vp_x"))
+            .with_assumptions(&[Assumption::Positive])
+            .build()
+            .unwrap();
+        let _n = Symbol::new(wrap_symbol!("vp_n"))
+            .with_assumptions(&[Assumption::Integer])
+            .build()
+            .unwrap();
+        let _t = Symbol::new(wrap_symbol!("vp_t"))
+            .with_assumptions(&[Assumption::Range((-1).into(), (1, 2).into())])
+            .build()
+            .unwrap();
+        let f = Symbol::new(wrap_symbol!("vp_f"))
+            .with_assumptions(&[Assumption::Real, Assumption::Nonzero])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            f.get_assumptions(),
+            &[Assumption::Real, Assumption::Nonzero]
+        );
+        assert!(
+            Symbol::new(wrap_symbol!("vp_x"))
+                .with_assumptions(&[Assumption::Real])
+                .build()
+                .is_err()
+        );
+
+        assert!(
+            parse!("vp_x^(1/3)*exp(vp_t)+vp_x")
+                .value_properties()
+                .is_positive()
+        );
+        assert!(parse!("vp_f(y)^2").value_properties().is_positive());
+        assert!(parse!("log(vp_x)").value_properties().is_real());
+        assert!(!parse!("y^2").value_properties().is_real());
+        assert!(!parse!("sqrt(vp_t)").value_properties().is_real());
+        assert!(!parse!("vp_n-1").value_properties().is_nonnegative());
+
+        let p = parse!("vp_n^2+2*vp_n").value_properties();
+        assert!(p.is_integer() && !p.is_nonnegative());
+
+        let p = parse!("vp_t^2+1").value_properties();
+        assert_eq!(p.lower_bound(), Some(&1.into()));
+        assert_eq!(p.upper_bound(), Some(&2.into()));
+        assert!(p.satisfies(&Assumption::Range(1.into(), 2.into())));
+    }
+
     #[test]
     fn building() {
         let _ = FunctionBuilder::new(symbol!("a"))
@@ -0,0 +1,530 @@
+//! Assumptions on the values of symbols and the inference of
+//! the sign and type of expressions from these assumptions.
+
+use crate::{
+    atom::{Atom, AtomView},
+    coefficient::CoefficientView,
+    domains::rational::Rational,
+};
+
+/// An assumption on the value of a symbol, set with
+/// [SymbolBuilder::with_assumptions](crate::atom::SymbolBuilder::with_assumptions).
+///
+/// When the symbol is used as a function, the assumption holds for the
+/// value of the function, for any argument.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Assumption {
+    /// The value is real.
+    Real,
+    /// The value is real and strictly positive.
+    Positive,
+    /// The value is an integer.
+    Integer,
+    /// The value is not zero.
+    Nonzero,
+    /// The value is real and lies in the closed interval `[min, max]`.
+    Range(Rational, Rational),
+}
+
+impl std::fmt::Display for Assumption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Assumption::Real => write!(f, "real"),
+            Assumption::Positive => write!(f, "positive"),
+            Assumption::Integer => write!(f, "integer"),
+            Assumption::Nonzero => write!(f, "nonzero"),
+            Assumption::Range(min, max) => write!(f, "in [{}, {}]", min, max),
+        }
+    }
+}
+
+const NEGATIVE: u8 = 0b001;
+const ZERO: u8 = 0b010;
+const POSITIVE: u8 = 0b100;
+const ANY_SIGN: u8 = NEGATIVE | ZERO | POSITIVE;
+
+/// Properties of the value of an expression, inferred from the
+/// assumptions on its symbols using [AtomCore::value_properties](crate::atom::AtomCore::value_properties).
+///
+/// The inference is conservative: a property that is not reported may still hold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueProperties {
+    real: bool,
+    integer: bool,
+    /// The set of possible signs. For non-real values only the zero bit is meaningful.
+    signs: u8,
+    lower: Option<Rational>,
+    upper: Option<Rational>,
+}
+
+impl ValueProperties {
+    /// Properties of a value about which nothing is known.
+    pub fn unknown() -> ValueProperties {
+        ValueProperties {
+            real: false,
+            integer: false,
+            signs: ANY_SIGN,
+            lower: None,
+            upper: None,
+        }
+    }
+
+    /// Properties of a real value about which nothing else is known.
+    fn real() -> ValueProperties {
+        ValueProperties {
+            real: true,
+            ..ValueProperties::unknown()
+        }
+    }
+
+    /// Properties of an exactly known rational number.
+    pub fn from_rational(r: &Rational) -> ValueProperties {
+        ValueProperties {
+            real: true,
+            integer: r.is_integer(),
+            signs: sign_of(r),
+            lower: Some(r.clone()),
+            upper: Some(r.clone()),
+        }
+    }
+
+    /// Properties of a value that satisfies all `assumptions`.
+    pub fn from_assumptions(assumptions: &[Assumption]) -> ValueProperties {
+        let mut p = ValueProperties::unknown();
+        for a in assumptions {
+            match a {
+                Assumption::Real => p.real = true,
+                Assumption::Positive => {
+                    p.real = true;
+                    p.signs &= POSITIVE;
+                }
+                Assumption::Integer => {
+                    p.real = true;
+                    p.integer = true;
+                }
+                Assumption::Nonzero => p.signs &= !ZERO,
+                Assumption::Range(min, max) => {
+                    p.real = true;
+                    p.lower = Some(match p.lower {
+                        Some(l) if l > *min => l,
+                        _ => min.clone(),
+                    });
+                    p.upper = Some(match p.upper {
+                        Some(u) if u < *max => u,
+                        _ => max.clone(),
+                    });
+                }
+            }
+        }
+        p.normalized()
+    }
+
+    /// Returns `true` if the value is known to be real.
+    pub fn is_real(&self) -> bool {
+        self.real
+    }
+
+    /// Returns `true` if the value is known to be an integer.
+    pub fn is_integer(&self) -> bool {
+        self.integer
+    }
+
+    /// Returns `true` if the value is known to be real and strictly positive.
+    pub fn is_positive(&self) -> bool {
+        self.real && self.signs == POSITIVE
+    }
+
+    /// Returns `true` if the value is known to be real and strictly negative.
+    pub fn is_negative(&self) -> bool {
+        self.real && self.signs == NEGATIVE
+    }
+
+    /// Returns `true` if the value is known to be real and not negative.
+    pub fn is_nonnegative(&self) -> bool {
+        self.real && self.signs & NEGATIVE == 0
+    }
+
+    /// Returns `true` if the value is known to be real and not positive.
+    pub fn is_nonpositive(&self) -> bool {
+        self.real && self.signs & POSITIVE == 0
+    }
+
+    /// Returns `true` if the value is known to be nonzero.
+    pub fn is_nonzero(&self) -> bool {
+        self.signs & ZERO == 0
+    }
+
+    /// Returns `true` if the value is known to be zero.
+    pub fn is_zero(&self) -> bool {
+        self.real && self.signs == ZERO
+    }
+
+    /// Get the inclusive lower bound of the value, if known.
+    pub fn lower_bound(&self) -> Option<&Rational> {
+        self.lower.as_ref()
+    }
+
+    /// Get the inclusive upper bound of the value, if known.
+    pub fn upper_bound(&self) -> Option<&Rational> {
+        self.upper.as_ref()
+    }
+
+    /// Returns `true` if the value is known to satisfy the `assumption`.
+    pub fn satisfies(&self, assumption: &Assumption) -> bool {
+        match assumption {
+            Assumption::Real => self.is_real(),
+            Assumption::Positive => self.is_positive(),
+            Assumption::Integer => self.is_integer(),
+            Assumption::Nonzero => self.is_nonzero(),
+            Assumption::Range(min, max) => {
+                self.real
+                    && self.lower.as_ref().map(|l| l >= min).unwrap_or(false)
+                    && self.upper.as_ref().map(|u| u <= max).unwrap_or(false)
+            }
+        }
+    }
+
+    /// Make the sign set and the bounds consistent with each other.
+    fn normalized(mut self) -> ValueProperties {
+        if !self.real {
+            self.integer = false;
+            self.signs |= NEGATIVE | POSITIVE;
+            self.lower = None;
+            self.upper = None;
+            return self;
+        }
+
+        if let Some(l) = &self.lower {
+            if l.is_zero() {
+                self.signs &= ZERO | POSITIVE;
+            } else if !l.is_negative() {
+                self.signs &= POSITIVE;
+            }
+        }
+        if let Some(u) = &self.upper {
+            if u.is_zero() {
+                self.signs &= NEGATIVE | ZERO;
+            } else if u.is_negative() {
+                self.signs &= NEGATIVE;
+            }
+        }
+
+        if self.signs & NEGATIVE == 0
+            && self.lower.as_ref().map(|l| l.is_negative()).unwrap_or(true)
+        {
+            self.lower = Some(Rational::zero());
+        }
+        if self.signs & POSITIVE == 0
+            && self
+                .upper
+                .as_ref()
+                .map(|u| !u.is_negative() && !u.is_zero())
+                .unwrap_or(true)
+        {
+            self.upper = Some(Rational::zero());
+        }
+
+        self
+    }
+
+    /// Properties of the sum of two values.
+    fn add(&self, other: &ValueProperties) -> ValueProperties {
+        if !self.real || !other.real {
+            return ValueProperties::unknown();
+        }
+
+        let mut signs = 0;
+        for a in [NEGATIVE, ZERO, POSITIVE] {
+            for b in [NEGATIVE, ZERO, POSITIVE] {
+                if self.signs & a != 0 && other.signs & b != 0 {
+                    signs |= match (a, b) {
+                        (ZERO, x) | (x, ZERO) => x,
+                        (x, y) if x == y => x,
+                        _ => ANY_SIGN,
+                    };
+                }
+            }
+        }
+
+        ValueProperties {
+            real: true,
+            integer: self.integer && other.integer,
+            signs,
+            lower: self
+                .lower
+                .as_ref()
+                .zip(other.lower.as_ref())
+                .map(|(a, b)| a + b),
+            upper: self
+                .upper
+                .as_ref()
+                .zip(other.upper.as_ref())
+                .map(|(a, b)| a + b),
+        }
+        .normalized()
+    }
+
+    /// Properties of the product of two values.
+    fn mul(&self, other: &ValueProperties) -> ValueProperties {
+        if !self.real || !other.real {
+            let mut p = ValueProperties::unknown();
+            if self.is_nonzero() && other.is_nonzero() {
+                p.signs &= !ZERO;
+            }
+            return p;
+        }
+
+        let mut signs = 0;
+        for a in [NEGATIVE, ZERO, POSITIVE] {
+            for b in [NEGATIVE, ZERO, POSITIVE] {
+                if self.signs & a != 0 && other.signs & b != 0 {
+                    signs |= match (a, b) {
+                        (ZERO, _) | (_, ZERO) => ZERO,
+                        (x, y) if x == y => POSITIVE,
+                        _ => NEGATIVE,
+                    };
+                }
+            }
+        }
+
+        let (lower, upper) = if let (Some(l1), Some(u1), Some(l2), Some(u2)) =
+            (&self.lower, &self.upper, &other.lower, &other.upper)
+        {
+            let p = [l1 * l2, l1 * u2, u1 * l2, u1 * u2];
+            (p.iter().min().cloned(), p.iter().max().cloned())
+        } else {
+            (None, None)
+        };
+
+        ValueProperties {
+            real: true,
+            integer: self.integer && other.integer,
+            signs,
+            lower,
+            upper,
+        }
+        .normalized()
+    }
+
+    /// Properties of a value raised to a rational power.
+    fn pow(&self, exp: &Rational) -> ValueProperties {
+        if exp.is_zero() {
+            return ValueProperties::from_rational(&Rational::one());
+        }
+
+        if exp.is_negative() && !self.is_nonzero() {
+            return ValueProperties::unknown();
+        }
+
+        if !exp.is_integer() {
+            if !self.is_nonnegative() {
+                return ValueProperties::unknown();
+            }
+
+            return ValueProperties {
+                signs: self.signs,
+                ..ValueProperties::real()
+            }
+            .normalized();
+        }
+
+        if !self.real {
+            let mut p = ValueProperties::unknown();
+            p.signs = self.signs;
+            return p;
+        }
+
+        let Some(n) = exp.numerator_ref().to_i64() else {
+            return ValueProperties::real();
+        };
+
+        let even = n % 2 == 0;
+        let mut signs = self.signs & ZERO;
+        if self.signs & POSITIVE != 0 {
+            signs |= POSITIVE;
+        }
+        if self.signs & NEGATIVE != 0 {
+            signs |= if even { POSITIVE } else { NEGATIVE };
+        }
+
+        let (mut lower, mut upper) = (None, None);
+        if n > 0 && n <= 64 {
+            let n = n as u64;
+            {
+                if !even || self.signs & NEGATIVE == 0 {
+                    lower = self.lower.as_ref().map(|l| l.pow(n));
+                    upper = self.upper.as_ref().map(|u| u.pow(n));
+                } else if self.signs & POSITIVE == 0 {
+                    lower = self.upper.as_ref().map(|u| u.pow(n));
+                    upper = self.lower.as_ref().map(|l| l.pow(n));
+                } else if let (Some(l), Some(u)) = (&self.lower, &self.upper) {
+                    upper = Some(l.pow(n).max(u.pow(n)));
+                }
+            }
+        }
+
+        ValueProperties {
+            real: true,
+            integer: self.integer && n > 0,
+            signs,
+            lower,
+            upper,
+        }
+        .normalized()
+    }
+
+    /// Properties of a value with the same sign as `self`.
+    fn same_sign(&self) -> ValueProperties {
+        ValueProperties {
+            signs: self.signs,
+            ..ValueProperties::real()
+        }
+        .normalized()
+    }
+
+    /// Properties of a real value in the closed interval `[min, max]`.
+    fn bounded(min: i64, max: i64) -> ValueProperties {
+        ValueProperties {
+            lower: Some(min.into()),
+            upper: Some(max.into()),
+            ..ValueProperties::real()
+        }
+        .normalized()
+    }
+
+    /// Returns `true` if the value is known to lie in the interval `[min, max]`.
+    fn in_interval(&self, min: i64, max: i64) -> bool {
+        self.satisfies(&Assumption::Range(min.into(), max.into()))
+    }
+}
+
+fn sign_of(r: &Rational) -> u8 {
+    if r.is_zero() {
+        ZERO
+    } else if r.is_negative() {
+        NEGATIVE
+    } else {
+        POSITIVE
+    }
+}
+
+impl AtomView<'_> {
+    /// Infer properties of the value of the expression, such as its sign and whether it is
+    /// real, from the assumptions on its symbols.
+    pub fn value_properties(&self) -> ValueProperties {
+        match self {
+            AtomView::Num(n) => match n.get_coeff_view() {
+                CoefficientView::Natural(_, _, 0, _) | CoefficientView::Large(_, _) => {
+                    match Rational::try_from(*self) {
+                        Ok(r) => ValueProperties::from_rational(&r),
+                        Err(_) => ValueProperties::unknown(),
+                    }
+                }
+                CoefficientView::Float(r, i) if i.is_zero() => ValueProperties {
+                    signs: if r.is_zero() {
+                        ZERO
+                    } else if r.to_float().is_negative() {
+                        NEGATIVE
+                    } else {
+                        POSITIVE
+                    },
+                    ..ValueProperties::real()
+                },
+                _ => ValueProperties::unknown(),
+            },
+            AtomView::Var(v) => match v.get_symbol() {
+                Atom::PI => ValueProperties::bounded(3, 4),
+                Atom::E => ValueProperties::bounded(2, 3),
+                s => ValueProperties::from_assumptions(s.get_assumptions()),
+            },
+            AtomView::Fun(f) => {
+                let s = f.get_symbol();
+                if !s.get_assumptions().is_empty() || f.get_nargs() != 1 {
+                    return ValueProperties::from_assumptions(s.get_assumptions());
+                }
+
+                let a = f.iter().next().unwrap().value_properties();
+                match s {
+                    Atom::EXP if a.real => ValueProperties {
+                        signs: POSITIVE,
+                        ..ValueProperties::real()
+                    }
+                    .normalized(),
+                    Atom::LOG if a.is_positive() => {
+                        let mut p = ValueProperties::real();
+                        if let Some(l) = &a.lower {
+                            if *l >= Rational::one() {
+                                p.signs &= ZERO | POSITIVE;
+                            }
+                        }
+                        if let Some(u) = &a.upper {
+                            if *u <= Rational::one() {
+                                p.signs &= NEGATIVE | ZERO;
+                            }
+                        }
+                        p.normalized()
+                    }
+                    Atom::SQRT if a.is_nonnegative() => a.same_sign(),
+                    Atom::SIN | Atom::COS if a.real => ValueProperties::bounded(-1, 1),
+                    Atom::TAN if a.real => ValueProperties::real(),
+                    Atom::SINH | Atom::ASINH | Atom::ATAN if a.real => a.same_sign(),
+                    Atom::TANH if a.real => {
+                        let mut p = ValueProperties::bounded(-1, 1);
+                        p.signs &= a.signs;
+                        p.normalized()
+                    }
+                    Atom::COSH if a.real => ValueProperties::bounded(1, 1).add(&ValueProperties {
+                        signs: ZERO | POSITIVE,
+                        ..ValueProperties::real()
+                    }),
+                    Atom::ASIN if a.in_interval(-1, 1) => a.same_sign(),
+                    Atom::ACOS if a.in_interval(-1, 1) => ValueProperties {
+                        signs: ZERO | POSITIVE,
+                        ..ValueProperties::real()
+                    }
+                    .normalized(),
+                    Atom::ACOSH
+                        if a.lower
+                            .as_ref()
+                            .map(|l| l.is_one() || *l > Rational::one())
+                            .unwrap_or(false) =>
+                    {
+                        ValueProperties {
+                            signs: ZERO | POSITIVE,
+                            ..ValueProperties::real()
+                        }
+                        .normalized()
+                    }
+                    _ => ValueProperties::unknown(),
+                }
+            }
+            AtomView::Pow(p) => {
+                let (base, exp) = p.get_base_exp();
+                let b = base.value_properties();
+                if let Ok(e) = Rational::try_from(exp) {
+                    return b.pow(&e);
+                }
+
+                if b.is_positive() && exp.value_properties().is_real() {
+                    ValueProperties {
+                        signs: POSITIVE,
+                        ..ValueProperties::real()
+                    }
+                    .normalized()
+                } else {
+                    ValueProperties::unknown()
+                }
+            }
+            AtomView::Mul(m) => {
+                let mut it = m.iter();
+                let first = it.next().unwrap().value_properties();
+                it.fold(first, |acc, x| acc.mul(&x.value_properties()))
+            }
+            AtomView::Add(a) => {
+                let mut it = a.iter();
+                let first = it.next().unwrap().value_properties();
+                it.fold(first, |acc, x| acc.add(&x.value_properties()))
+            }
+        }
+    }
+}
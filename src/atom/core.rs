@@ -23,11 +23,15 @@ use crate::{
     evaluate::{EvalTree, EvaluationFn, ExpressionEvaluator, FunctionMap, OptimizationSettings},
     id::{
         BorrowReplacement, Condition, ConditionResult, Context, MatchSettings, Pattern,
-        PatternAtomTreeIterator, PatternRestriction, ReplaceBuilder,
+        PatternAtomTreeIterator, PatternRestriction, ReplaceBuilder, RuleSet,
     },
+    limit::{Limit, LimitDirection, LimitPoint},
     poly::{
-        Exponent, PositiveExponent, Variable, factor::Factorize, gcd::PolynomialGCD,
-        polynomial::MultivariatePolynomial, series::Series,
+        Exponent, PositiveExponent, Variable,
+        factor::Factorize,
+        gcd::PolynomialGCD,
+        polynomial::MultivariatePolynomial,
+        series::{LogSeries, Series},
     },
     printer::{AtomPrinter, PrintOptions, PrintState},
     state::Workspace,
@@ -37,7 +41,7 @@ use crate::{
 use std::sync::Arc;
 
 use super::{
-    Atom, AtomOrView, AtomView, KeyLookup, ListSlice, Symbol,
+    Atom, AtomOrView, AtomView, KeyLookup, ListSlice, Symbol, ValueProperties,
     representation::{InlineNum, InlineVar},
 };
 
@@ -432,6 +436,27 @@ pub trait AtomCore {
         self.as_atom_view().derivative(x)
     }
 
+    /// Integrate the expression with respect to `x`. Rational functions are integrated
+    /// using Hermite reduction and the Rothstein-Trager method, and other expressions
+    /// using a table of elementary integrals, substitution and integration by parts.
+    /// Terms that cannot be integrated are returned as an unevaluated `integrate(term, x)`.
+    ///
+    /// Rational functions whose integral requires the roots of an irreducible polynomial
+    /// of degree higher than two, such as `1/(x^3+2)`, are not supported and are returned
+    /// unevaluated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, parse, symbol};
+    /// let expr = parse!("1/(x^2+1) + x*exp(x^2)");
+    /// let integral = expr.integrate(symbol!("x"));
+    /// assert_eq!(integral, parse!("atan(x) + exp(x^2)/2"));
+    /// ```
+    fn integrate(&self, x: Symbol) -> Atom {
+        self.as_atom_view().integrate(x)
+    }
+
     /// Take a derivative of the expression with respect to `x` and
     /// write the result in `out`.
     /// Returns `true` if the derivative is non-zero.
@@ -476,6 +501,105 @@ pub trait AtomCore {
             .series(x, expansion_point.as_atom_view(), depth, depth_is_absolute)
     }
 
+    /// Series expand in `x` around infinity to depth `depth`, i.e., in powers of `x^-1`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, parse, symbol};
+    /// let expr = parse!("x^2/(x-1)");
+    /// let series = expr
+    ///     .series_at_infinity(symbol!("x"), (2, 1).into(), true)
+    ///     .unwrap();
+    /// assert_eq!(series.to_atom(), parse!("x + 1 + 1/x + 1/x^2"));
+    /// ```
+    fn series_at_infinity(
+        &self,
+        x: Symbol,
+        depth: Rational,
+        depth_is_absolute: bool,
+    ) -> Result<Series<AtomField>, &'static str> {
+        self.as_atom_view()
+            .series_at_infinity(x, depth, depth_is_absolute)
+    }
+
+    /// Series expand in `x` around `expansion_point` to depth `depth`, where the coefficients
+    /// of the series may be polynomials in `log(x-expansion_point)`. A power of
+    /// `x-expansion_point` with a non-rational exponent, such as `x^eps`, is kept as a prefactor
+    /// of the series.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::{Atom, AtomCore}, parse, symbol};
+    /// let expr = parse!("x^x");
+    /// let series = expr
+    ///     .log_series(symbol!("x"), Atom::num(0), (2, 1).into(), true)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     series.to_atom(),
+    ///     parse!("1 + x*log(x) + 1/2*x^2*log(x)^2")
+    /// );
+    /// ```
+    fn log_series<T: AtomCore>(
+        &self,
+        x: Symbol,
+        expansion_point: T,
+        depth: Rational,
+        depth_is_absolute: bool,
+    ) -> Result<LogSeries<AtomField>, &'static str> {
+        self.as_atom_view()
+            .log_series(x, expansion_point.as_atom_view(), depth, depth_is_absolute)
+    }
+
+    /// Series expand in `x` around infinity to depth `depth`, i.e., in powers of `x^-1`,
+    /// where the coefficients of the series may be polynomials in `log(x)`. A power of `x`
+    /// with a non-rational exponent is kept as a prefactor of the series.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, parse, symbol};
+    /// let expr = parse!("log(x+1)/x");
+    /// let series = expr
+    ///     .log_series_at_infinity(symbol!("x"), (2, 1).into(), true)
+    ///     .unwrap();
+    /// assert_eq!(series.to_atom(), parse!("log(x)/x + 1/x^2"));
+    /// ```
+    fn log_series_at_infinity(
+        &self,
+        x: Symbol,
+        depth: Rational,
+        depth_is_absolute: bool,
+    ) -> Result<LogSeries<AtomField>, &'static str> {
+        self.as_atom_view()
+            .log_series_at_infinity(x, depth, depth_is_absolute)
+    }
+
+    /// Compute the limit of the expression when `x` approaches `point`. Finite points
+    /// can be approached from above, below or both sides. The result is a finite
+    /// value, positive or negative infinity, [Limit::DoesNotExist] if the limit
+    /// does not exist, for example because the expression oscillates, or [Limit::Unknown]
+    /// if the limit could not be determined, for example because the sign of a parameter
+    /// is unknown. An error is returned if the limit point depends on `x`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::{Atom, AtomCore}, limit::{Limit, LimitDirection}, parse, symbol};
+    /// let expr = parse!("sin(x)/x");
+    /// let l = expr.limit(symbol!("x"), Atom::num(0).into(), LimitDirection::Both);
+    /// assert_eq!(l, Ok(Limit::Finite(Atom::num(1))));
+    /// ```
+    fn limit(
+        &self,
+        x: Symbol,
+        point: LimitPoint,
+        direction: LimitDirection,
+    ) -> Result<Limit, String> {
+        self.as_atom_view().limit(x, &point, direction)
+    }
+
     /// Find the root of a function in `x` numerically over the reals using Newton's method.
     ///
     /// # Example
@@ -738,6 +862,23 @@ pub trait AtomCore {
         self.as_atom_view().zero_test(iterations, tolerance)
     }
 
+    /// Infer properties of the value of the expression, such as its sign and whether it is real,
+    /// from the assumptions on its symbols. The inference is conservative: a property that
+    /// is not reported may still hold.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::{Assumption, AtomCore, Symbol}, parse, wrap_symbol};
+    /// let t = Symbol::new(wrap_symbol!("t")).with_assumptions(&[Assumption::Real]).build().unwrap();
+    /// let p = parse!("exp(t)*t^2 + 1").value_properties();
+    /// assert!(p.is_positive());
+    /// assert!(!parse!("t+1").value_properties().is_positive());
+    /// ```
+    fn value_properties(&self) -> ValueProperties {
+        self.as_atom_view().value_properties()
+    }
+
     /// Set the coefficient ring to the multivariate rational polynomial with `vars` variables.
     ///
     /// # Example
@@ -1374,6 +1515,47 @@ pub trait AtomCore {
         self.as_atom_view().replace_multiple_into(replacements, out)
     }
 
+    /// Replace all occurrences of the patterns of a [RuleSet], where replacements are tested in the order that they are given.
+    /// Only the rules whose pattern could match a subexpression are tried.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, parse};
+    /// use symbolica::id::{Replacement, RuleSet};
+    /// let expr = parse!("f(x) + g(y)");
+    /// let rules = RuleSet::new(vec![
+    ///     Replacement::new(parse!("f(x_)").to_pattern(), parse!("x_").to_pattern()),
+    ///     Replacement::new(parse!("g(x_)").to_pattern(), parse!("x_^2").to_pattern()),
+    /// ]);
+    /// let result = expr.replace_rule_set(&rules);
+    /// assert_eq!(result, parse!("x + y^2"));
+    /// ```
+    fn replace_rule_set(&self, rules: &RuleSet) -> Atom {
+        self.as_atom_view().replace_rule_set(rules)
+    }
+
+    /// Replace all occurrences of the patterns of a [RuleSet], where replacements are tested in the order that they are given.
+    /// Returns `true` iff a match was found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use symbolica::{atom::{Atom, AtomCore}, parse};
+    /// use symbolica::id::{Replacement, RuleSet};
+    /// let expr = parse!("f(x) + g(y)");
+    /// let rules = RuleSet::new(vec![
+    ///     Replacement::new(parse!("f(x_)").to_pattern(), parse!("x_").to_pattern()),
+    /// ]);
+    /// let mut out = Atom::new();
+    /// let changed = expr.replace_rule_set_into(&rules, &mut out);
+    /// assert!(changed);
+    /// assert_eq!(out, parse!("x + g(y)"));
+    /// ```
+    fn replace_rule_set_into(&self, rules: &RuleSet, out: &mut Atom) -> bool {
+        self.as_atom_view().replace_rule_set_into(rules, out)
+    }
+
     /// Replace part of an expression by calling the map `m` on each subexpression.
     /// The function `m`  must return `true` if the expression was replaced and must write the new expression to `out`.
     /// A [Context] object is passed to the function, which contains information about the current position in the expression.
@@ -37,6 +37,25 @@ const FUN_LINEAR_FLAG: u8 = 0b01000000;
 const VAR_ANTISYMMETRIC_FLAG: u8 = 0b10000000;
 const VAR_CYCLESYMMETRIC_FLAG: u8 = 0b10100000; // coded as symmetric | antisymmetric
 const FUN_ANTISYMMETRIC_FLAG: u64 = 1 << 32; // stored in the function id
+const FUN_LINEAR_FIRST_ARG_FLAG: u64 = 1 << 33; // stored in the symbol id
+const NONCOMMUTATIVE_FLAG: u64 = 1 << 34; // stored in the symbol id
+const GRASSMANN_FLAG: u64 = 1 << 35; // stored in the symbol id
+
+/// Get the id of a symbol together with the flags that are stored in it.
+#[inline(always)]
+fn flagged_id(symbol: Symbol) -> u64 {
+    let mut id = symbol.id as u64;
+    if symbol.is_linear_in_first_argument {
+        id |= FUN_LINEAR_FIRST_ARG_FLAG;
+    }
+    if symbol.is_noncommutative {
+        id |= NONCOMMUTATIVE_FLAG;
+    }
+    if symbol.is_grassmann {
+        id |= GRASSMANN_FLAG;
+    }
+    id
+}
 const MUL_HAS_COEFF_FLAG: u8 = 0b01000000;
 
 const ZERO_DATA: [u8; 3] = [NUM_ID, 1, 0];
@@ -98,8 +117,10 @@ impl InlineVar {
 
         data[0] = flags;
 
-        let size = 1 + (symbol.id as u64, 1).get_packed_size() as u8;
-        (symbol.id as u64, 1).write_packed_fixed(&mut data[1..]);
+        let id = flagged_id(symbol);
+
+        let size = 1 + (id, 1).get_packed_size() as u8;
+        (id, 1).write_packed_fixed(&mut data[1..]);
         InlineVar { data, size }
     }
 
@@ -497,7 +518,9 @@ impl Var {
 
         self.data.put_u8(flags);
 
-        (symbol.id as u64, 1).write_packed(&mut self.data);
+        let id = flagged_id(symbol);
+
+        (id, 1).write_packed(&mut self.data);
     }
 
     #[inline]
@@ -579,9 +602,9 @@ impl Fun {
         let buf_pos = self.data.len();
 
         let id = if symbol.is_antisymmetric || symbol.is_cyclesymmetric {
-            symbol.id as u64 | FUN_ANTISYMMETRIC_FLAG
+            flagged_id(symbol) | FUN_ANTISYMMETRIC_FLAG
         } else {
-            symbol.id as u64
+            flagged_id(symbol)
         };
 
         (id, 0).write_packed(&mut self.data);
@@ -1087,14 +1110,18 @@ impl<'a> VarView<'a> {
     #[inline(always)]
     pub fn get_symbol(&self) -> Symbol {
         let is_cyclesymmetric = self.data[0] & VAR_CYCLESYMMETRIC_FLAG == VAR_CYCLESYMMETRIC_FLAG;
+        let id = self.data[1..].get_frac_u64().0;
 
         Symbol::raw_fn(
-            self.data[1..].get_frac_u64().0 as u32,
+            id as u32,
             self.get_wildcard_level(),
             !is_cyclesymmetric && self.data[0] & FUN_SYMMETRIC_FLAG == FUN_SYMMETRIC_FLAG,
             !is_cyclesymmetric && self.data[0] & VAR_ANTISYMMETRIC_FLAG == VAR_ANTISYMMETRIC_FLAG,
             is_cyclesymmetric,
             self.data[0] & FUN_LINEAR_FLAG == FUN_LINEAR_FLAG,
+            id & FUN_LINEAR_FIRST_ARG_FLAG == FUN_LINEAR_FIRST_ARG_FLAG,
+            id & NONCOMMUTATIVE_FLAG == NONCOMMUTATIVE_FLAG,
+            id & GRASSMANN_FLAG == GRASSMANN_FLAG,
         )
     }
 
@@ -1192,6 +1219,9 @@ impl<'a> FunView<'a> {
             !is_cyclesymmetric && id & FUN_ANTISYMMETRIC_FLAG == FUN_ANTISYMMETRIC_FLAG,
             is_cyclesymmetric,
             self.is_linear(),
+            id & FUN_LINEAR_FIRST_ARG_FLAG == FUN_LINEAR_FIRST_ARG_FLAG,
+            id & NONCOMMUTATIVE_FLAG == NONCOMMUTATIVE_FLAG,
+            id & GRASSMANN_FLAG == GRASSMANN_FLAG,
         )
     }
 
@@ -1230,6 +1260,12 @@ impl<'a> FunView<'a> {
         self.data[0] & FUN_LINEAR_FLAG == FUN_LINEAR_FLAG
     }
 
+    #[inline(always)]
+    pub fn is_linear_in_first_argument(&self) -> bool {
+        let id = self.data[1 + 4..].get_frac_u64().0;
+        id & FUN_LINEAR_FIRST_ARG_FLAG == FUN_LINEAR_FIRST_ARG_FLAG
+    }
+
     #[inline(always)]
     pub fn get_wildcard_level(&self) -> u8 {
         match self.data[0] & VAR_WILDCARD_LEVEL_MASK {
@@ -4,12 +4,16 @@ use std::{
 };
 
 use crate::{
-    atom::{Atom, AtomView, FunctionBuilder, Symbol},
+    atom::{Atom, AtomCore, AtomView, FunctionBuilder, Symbol},
     coefficient::{Coefficient, CoefficientView},
     combinatorics::CombinationWithReplacementIterator,
     domains::{atom::AtomField, integer::Integer, rational::Rational},
-    poly::{Variable, series::Series},
-    state::Workspace,
+    poly::{
+        Variable,
+        series::{LogSeries, Series},
+    },
+    state::{State, Workspace},
+    symbol,
 };
 
 impl AtomView<'_> {
@@ -85,9 +89,72 @@ impl AtomView<'_> {
                     return false;
                 }
 
+                // derive user functions with Taylor coefficients
+                if f.get_nargs() == 1 && !is_der {
+                    if let Some(t) = State::get_taylor_coefficient_function(f.get_symbol()) {
+                        let (_, arg_der) = args_der.pop().unwrap();
+                        let d = t(1, f.iter().next().unwrap()) * arg_der.as_view();
+                        out.set_from_view(&d.as_view());
+                        return !d.is_zero();
+                    }
+                }
+
+                // derive user functions with derivative templates
+                if !is_der {
+                    if let Some((arguments, templates)) =
+                        State::get_derivative_templates(f.get_symbol())
+                    {
+                        if arguments.len() == f.get_nargs() {
+                            let args: Vec<_> = f.iter().collect();
+
+                            let mut add = workspace.new_atom();
+                            let a = add.to_add();
+                            let mut mul = workspace.new_atom();
+                            for (index, arg_der) in args_der {
+                                let fn_der = templates[index].replace_map(|term, _ctx, out| {
+                                    if let AtomView::Var(v) = term {
+                                        if let Some(p) =
+                                            arguments.iter().position(|s| *s == v.get_symbol())
+                                        {
+                                            out.set_from_view(&args[p]);
+                                            return true;
+                                        }
+                                    }
+                                    false
+                                });
+
+                                let m = mul.to_mul();
+                                m.extend(fn_der.as_view());
+                                m.extend(arg_der.as_view());
+                                a.extend(mul.as_view());
+                            }
+
+                            add.as_view().normalize(workspace, out);
+                            return !out.is_zero();
+                        }
+                    }
+                }
+
                 // derive special functions
                 if f.get_nargs() == 1
-                    && [Atom::EXP, Atom::LOG, Atom::SIN, Atom::COS].contains(&f.get_symbol())
+                    && [
+                        Atom::EXP,
+                        Atom::LOG,
+                        Atom::SIN,
+                        Atom::COS,
+                        Atom::SQRT,
+                        Atom::TAN,
+                        Atom::ASIN,
+                        Atom::ACOS,
+                        Atom::ATAN,
+                        Atom::SINH,
+                        Atom::COSH,
+                        Atom::TANH,
+                        Atom::ASINH,
+                        Atom::ACOSH,
+                        Atom::ATANH,
+                    ]
+                    .contains(&f.get_symbol())
                 {
                     let mut fn_der = workspace.new_atom();
                     match f.get_symbol() {
@@ -116,7 +183,26 @@ impl AtomView<'_> {
                             m.extend(sin.as_view());
                             m.extend(n.as_view());
                         }
-                        _ => unreachable!(),
+                        s => {
+                            let arg = f.iter().next().unwrap().to_owned();
+                            let one = Atom::num(1);
+                            let half = Atom::num((1, 2));
+                            let d = match s {
+                                Atom::SQRT => &half / self.to_owned(),
+                                Atom::TAN => &one + self.to_owned().npow(2),
+                                Atom::ASIN => (&one - arg.npow(2)).pow(-&half),
+                                Atom::ACOS => -(&one - arg.npow(2)).pow(-&half),
+                                Atom::ATAN => &one / (&one + arg.npow(2)),
+                                Atom::SINH => arg.cosh(),
+                                Atom::COSH => arg.sinh(),
+                                Atom::TANH => &one - self.to_owned().npow(2),
+                                Atom::ASINH => (arg.npow(2) + &one).pow(-&half),
+                                Atom::ACOSH => (arg.npow(2) - &one).pow(-&half),
+                                Atom::ATANH => &one / (&one - arg.npow(2)),
+                                _ => unreachable!(),
+                            };
+                            fn_der.set_from_view(&d.as_view());
+                        }
                     }
 
                     let (_, mut arg_der) = args_der.pop().unwrap();
@@ -369,6 +455,225 @@ impl AtomView<'_> {
         }
     }
 
+    /// Series expand in `x` around infinity to depth `depth`, i.e., in powers of `x^-1`.
+    pub fn series_at_infinity(
+        &self,
+        x: Symbol,
+        depth: Rational,
+        depth_is_absolute: bool,
+    ) -> Result<Series<AtomField>, &'static str> {
+        let (e, t, inv) = self.invert_series_variable(x);
+        let series = e
+            .as_view()
+            .series(t, Atom::num(0).as_view(), depth, depth_is_absolute)?;
+
+        // write log(1/x) as -log(x), as is done for log series
+        let log_t = FunctionBuilder::new(Atom::LOG)
+            .add_arg(Atom::var(t))
+            .finish();
+        let log_x = FunctionBuilder::new(Atom::LOG)
+            .add_arg(Atom::var(x))
+            .finish();
+        Ok(series.map_variable(
+            Arc::new(Variable::Other(Arc::new(inv.clone()))),
+            Atom::num(0),
+            |c| {
+                c.replace(log_t.to_pattern())
+                    .with((-&log_x).to_pattern())
+                    .replace(Atom::var(t).to_pattern())
+                    .with(inv.to_pattern())
+            },
+        ))
+    }
+
+    /// Series expand in `x` around `expansion_point` to depth `depth`, where the
+    /// coefficients may be polynomials in `log(x-expansion_point)`.
+    pub fn log_series(
+        &self,
+        x: Symbol,
+        expansion_point: AtomView,
+        depth: Rational,
+        depth_is_absolute: bool,
+    ) -> Result<LogSeries<AtomField>, &'static str> {
+        if !depth_is_absolute && (depth.is_negative() || depth.is_zero()) {
+            return Err("Cannot series expand to negative or zero depth");
+        }
+
+        // heuristic current depth
+        let mut current_depth = if depth.is_negative() || depth.is_zero() {
+            Rational::one()
+        } else {
+            depth.clone()
+        };
+
+        let field = AtomField {
+            statistical_zero_test: false,
+            ..Default::default()
+        };
+
+        loop {
+            let info = Series::new(
+                &field,
+                None,
+                Arc::new(Variable::Symbol(x)),
+                expansion_point.to_owned(),
+                &current_depth + &(1.into(), current_depth.denominator()).into(),
+            );
+
+            let mut series = self.log_series_impl(x, expansion_point, &info)?;
+            if !depth_is_absolute && series.relative_order() >= depth {
+                series.truncate_relative_order(depth);
+                break Ok(series);
+            } else if depth_is_absolute && series.absolute_order() > depth {
+                series.truncate_absolute_order(&depth + &(1.into(), depth.denominator()).into());
+                break Ok(series);
+            } else {
+                current_depth = &current_depth * &2.into();
+            }
+        }
+    }
+
+    /// Series expand in `x` around infinity to depth `depth`, i.e., in powers of `x^-1`,
+    /// where the coefficients may be polynomials in `log(x)`.
+    pub fn log_series_at_infinity(
+        &self,
+        x: Symbol,
+        depth: Rational,
+        depth_is_absolute: bool,
+    ) -> Result<LogSeries<AtomField>, &'static str> {
+        let (e, t, inv) = self.invert_series_variable(x);
+        let series = e
+            .as_view()
+            .log_series(t, Atom::num(0).as_view(), depth, depth_is_absolute)?;
+
+        Ok(series.map_variable(
+            Arc::new(Variable::Other(Arc::new(inv.clone()))),
+            Atom::num(0),
+            |c| c.replace(Atom::var(t).to_pattern()).with(inv.to_pattern()),
+        ))
+    }
+
+    /// Substitute `x` by `1/t` for an expansion around infinity, returning the
+    /// new expression, the new variable `t` and `1/x`.
+    fn invert_series_variable(&self, x: Symbol) -> (Atom, Symbol, Atom) {
+        let t = symbol!("series_inf_var");
+        let e = self
+            .replace(Atom::var(x).to_pattern())
+            .with(Atom::var(t).npow(-1).to_pattern());
+        (e, t, Atom::var(x).npow(-1))
+    }
+
+    /// Series expand in `x` around `expansion_point` to depth `depth`, where the
+    /// coefficients may be polynomials in `log(x-expansion_point)`.
+    pub(crate) fn log_series_impl(
+        &self,
+        x: Symbol,
+        expansion_point: AtomView,
+        info: &Series<AtomField>,
+    ) -> Result<LogSeries<AtomField>, &'static str> {
+        if !self.contains_symbol(x) {
+            return Ok(info.constant(self.to_owned()).into());
+        }
+
+        match self {
+            AtomView::Num(_) | AtomView::Var(_) => {
+                Ok(self.series_impl(x, expansion_point, info)?.into())
+            }
+            AtomView::Fun(f) => match f.get_symbol() {
+                Atom::EXP if f.get_nargs() == 1 => f
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .log_series_impl(x, expansion_point, info)?
+                    .exp(),
+                Atom::LOG if f.get_nargs() == 1 => f
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .log_series_impl(x, expansion_point, info)?
+                    .log(),
+                Atom::SQRT if f.get_nargs() == 1 => f
+                    .iter()
+                    .next()
+                    .unwrap()
+                    .log_series_impl(x, expansion_point, info)?
+                    .rpow((1, 2).into()),
+                _ => {
+                    let series = self.series_impl(x, expansion_point, info)?;
+                    if series.terms().any(|(_, c)| c.contains_symbol(x)) {
+                        return Err(
+                            "Cannot series expand function with logarithmic terms in its arguments",
+                        );
+                    }
+
+                    Ok(series.into())
+                }
+            },
+            AtomView::Pow(p) => {
+                let (base, exp) = p.get_base_exp();
+
+                let mut base_series = base.log_series_impl(x, expansion_point, info)?;
+
+                if let Ok(r) = Rational::try_from(exp) {
+                    if r.is_negative() && base_series.is_zero() {
+                        // in case of 1/0, grow the expansion depth of the base series
+                        // it could be that the base series is exactly zero,
+                        // to prevent an infinite loop, we stop the loop at ep^-1000
+                        let mut current_depth = info.relative_order();
+                        while base_series.is_zero() && current_depth < 1000.into() {
+                            let info = Series::new(
+                                info.get_field(),
+                                None,
+                                info.get_variable().clone(),
+                                info.get_expansion_point().clone(),
+                                &current_depth + &(1.into(), current_depth.denominator()).into(),
+                            );
+
+                            base_series = base.log_series_impl(x, expansion_point, &info)?;
+                            current_depth = &current_depth * &2.into();
+                        }
+                    }
+
+                    base_series.rpow(r)
+                } else {
+                    let e = exp.log_series_impl(x, expansion_point, info)?;
+                    base_series.pow(&e)
+                }
+            }
+            AtomView::Mul(args) => {
+                let mut iter = args.iter();
+                let mut series = iter
+                    .next()
+                    .unwrap()
+                    .log_series_impl(x, expansion_point, info)?;
+                for arg in iter {
+                    series = &series * &arg.log_series_impl(x, expansion_point, info)?;
+                }
+
+                Ok(series)
+            }
+            AtomView::Add(args) => {
+                let mut iter = args.iter();
+                let mut series = iter
+                    .next()
+                    .unwrap()
+                    .log_series_impl(x, expansion_point, info)?;
+                for arg in iter {
+                    let s = arg.log_series_impl(x, expansion_point, info)?;
+                    if !series.is_zero()
+                        && !s.is_zero()
+                        && series.symbolic_exponent() != s.symbolic_exponent()
+                    {
+                        return Err("Cannot add series with different symbolic exponents");
+                    }
+                    series = &series + &s;
+                }
+
+                Ok(series)
+            }
+        }
+    }
+
     /// Series expand in `x` around `expansion_point` to depth `depth`.
     pub(crate) fn series_impl(
         &self,
@@ -400,12 +705,89 @@ impl AtomView<'_> {
                     return Ok(info.constant(f.to_owned().into()));
                 }
 
+                if args_series.len() == 1 {
+                    if let Some(t) = State::get_taylor_coefficient_function(f.get_symbol()) {
+                        return args_series[0].apply_taylor(t);
+                    }
+                }
+
                 match f.get_symbol() {
                     Atom::COS => args_series[0].cos(),
                     Atom::SIN => args_series[0].sin(),
                     Atom::EXP => args_series[0].exp(),
                     Atom::LOG => args_series[0].log(),
                     Atom::SQRT => args_series[0].rpow((1, 2).into()),
+                    Atom::TAN => {
+                        let s = args_series[0].sin()?;
+                        Ok(args_series[0].cos()?.rpow((-1, 1).into())? * &s)
+                    }
+                    Atom::SINH | Atom::COSH | Atom::TANH => {
+                        let e = args_series[0].exp()?;
+                        let e_inv = (-args_series[0].clone()).exp()?;
+                        let sinh = (&e - &e_inv).div_coeff(&Atom::num(2));
+                        let cosh = (&e + &e_inv).div_coeff(&Atom::num(2));
+
+                        match f.get_symbol() {
+                            Atom::SINH => Ok(sinh),
+                            Atom::COSH => Ok(cosh),
+                            _ => Ok(cosh.rpow((-1, 1).into())? * &sinh),
+                        }
+                    }
+                    Atom::ASIN
+                    | Atom::ACOS
+                    | Atom::ATAN
+                    | Atom::ASINH
+                    | Atom::ACOSH
+                    | Atom::ATANH => {
+                        // the derivatives of the inverse functions are algebraic,
+                        // so that the Taylor coefficients can be obtained by differentiation
+                        let y = symbol!("series_taylor_var");
+                        let mut d = FunctionBuilder::new(f.get_symbol())
+                            .add_arg(Atom::var(y))
+                            .finish();
+                        let mut n_fac = Integer::one();
+
+                        // the derivatives are singular at the branch points
+                        let branch_points = match f.get_symbol() {
+                            Atom::ATAN | Atom::ASINH => [Atom::i(), -Atom::i()],
+                            _ => [Atom::num(1), Atom::num(-1)],
+                        };
+                        let mut at_branch_point = false;
+
+                        let s = args_series[0].apply_taylor(|n, c| {
+                            if n == 1 {
+                                at_branch_point = branch_points.iter().any(|b| b.as_view() == c);
+                            }
+                            if at_branch_point {
+                                return Atom::new();
+                            }
+
+                            if n == 0 {
+                                return match f.get_symbol() {
+                                    Atom::ACOS if c.is_zero() => Atom::var(Atom::PI) / Atom::num(2),
+                                    Atom::ASIN | Atom::ATAN | Atom::ASINH | Atom::ATANH
+                                        if c.is_zero() =>
+                                    {
+                                        Atom::new()
+                                    }
+                                    _ => FunctionBuilder::new(f.get_symbol()).add_arg(c).finish(),
+                                };
+                            }
+
+                            d = d.derivative(y);
+                            n_fac *= &Integer::from(n as i64);
+
+                            d.replace(Atom::var(y).to_pattern())
+                                .with(c.to_owned().to_pattern())
+                                / Atom::num(n_fac.clone())
+                        })?;
+
+                        if at_branch_point {
+                            return Err("Cannot series expand inverse function at a branch point");
+                        }
+
+                        Ok(s)
+                    }
                     _ => {
                         // TODO: also check for log(x)?
                         if args_series
@@ -529,6 +911,57 @@ impl AtomView<'_> {
     }
 }
 
+/// Series expand `e` in the variable and around the expansion point of `series`
+/// to depth `depth`.
+fn series_in_variable_of(
+    series: &Series<AtomField>,
+    e: AtomView,
+    depth: &Rational,
+) -> Result<Series<AtomField>, &'static str> {
+    match series.get_variable().as_ref() {
+        Variable::Symbol(x) => {
+            let expansion_point = series.get_expansion_point();
+            let info = Series::new(
+                series.get_field(),
+                None,
+                series.get_variable(),
+                expansion_point.clone(),
+                depth.clone(),
+            );
+
+            e.series_impl(*x, expansion_point.as_view(), &info)
+        }
+        Variable::Other(v) => {
+            // an expansion around infinity in `x^-1`
+            let AtomView::Pow(p) = v.as_view() else {
+                panic!("Series variable is not a symbol");
+            };
+            let (AtomView::Var(x), exp) = p.get_base_exp() else {
+                panic!("Series variable is not a symbol");
+            };
+            if exp != Atom::num(-1).as_view() {
+                panic!("Series variable is not a symbol");
+            }
+
+            let (e, t, inv) = e.invert_series_variable(x.get_symbol());
+            let info = Series::new(
+                series.get_field(),
+                None,
+                Arc::new(Variable::Symbol(t)),
+                Atom::num(0),
+                depth.clone(),
+            );
+
+            Ok(e.as_view()
+                .series_impl(t, Atom::num(0).as_view(), &info)?
+                .map_variable(series.get_variable(), Atom::num(0), |c| {
+                    c.replace(Atom::var(t).to_pattern()).with(inv.to_pattern())
+                }))
+        }
+        _ => panic!("Series variable is not a symbol"),
+    }
+}
+
 impl Mul<&Atom> for Series<AtomField> {
     type Output = Result<Series<AtomField>, &'static str>;
 
@@ -557,11 +990,6 @@ impl Mul<&Atom> for &Series<AtomField> {
     type Output = Result<Series<AtomField>, &'static str>;
 
     fn mul(self, rhs: &Atom) -> Result<Series<AtomField>, &'static str> {
-        let Variable::Symbol(x) = self.get_variable().as_ref().clone() else {
-            panic!("Series variable is not a symbol");
-        };
-
-        let expansion_point = self.get_expansion_point();
         let mut current_depth = self.relative_order();
 
         if current_depth.is_zero() {
@@ -569,18 +997,7 @@ impl Mul<&Atom> for &Series<AtomField> {
         }
 
         loop {
-            let info = Series::new(
-                self.get_field(),
-                None,
-                self.get_variable().clone(),
-                expansion_point.to_owned(),
-                current_depth.clone(),
-            );
-
-            let series = rhs
-                .as_view()
-                .series_impl(x, expansion_point.as_view(), &info)?
-                * self;
+            let series = series_in_variable_of(self, rhs.as_view(), &current_depth)? * self;
             if series.relative_order() >= self.relative_order() {
                 return Ok(series);
             } else {
@@ -619,11 +1036,6 @@ impl Add<&Atom> for &Series<AtomField> {
     type Output = Result<Series<AtomField>, &'static str>;
 
     fn add(self, rhs: &Atom) -> Result<Series<AtomField>, &'static str> {
-        let Variable::Symbol(x) = self.get_variable().as_ref().clone() else {
-            panic!("Series variable is not a symbol");
-        };
-
-        let expansion_point = self.get_expansion_point();
         let mut current_depth = self.relative_order();
 
         if current_depth.is_zero() {
@@ -631,18 +1043,7 @@ impl Add<&Atom> for &Series<AtomField> {
         }
 
         loop {
-            let info = Series::new(
-                self.get_field(),
-                None,
-                self.get_variable().clone(),
-                expansion_point.to_owned(),
-                current_depth.clone(),
-            );
-
-            let series = rhs
-                .as_view()
-                .series_impl(x, expansion_point.as_view(), &info)?
-                + self.clone();
+            let series = series_in_variable_of(self, rhs.as_view(), &current_depth)? + self.clone();
             if series.absolute_order() >= self.absolute_order() {
                 return Ok(series);
             } else {
@@ -750,8 +1151,9 @@ impl Sub<&Atom> for &Series<AtomField> {
 #[cfg(test)]
 mod test {
     use crate::{
-        atom::{Atom, AtomCore},
-        parse, symbol,
+        atom::{Atom, AtomCore, FunctionBuilder, Symbol},
+        domains::integer::Integer,
+        parse, symbol, wrap_symbol,
     };
 
     #[test]
@@ -776,6 +1178,64 @@ mod test {
         assert_eq!(r, res);
     }
 
+    #[test]
+    fn derivative_builtins() {
+        let v1 = symbol!("v1");
+        let inputs = [
+            "sqrt(v1)+tan(v1)+atan(2*v1)",
+            "asin(v1)+acos(v1)",
+            "sinh(v1)+cosh(v1)+tanh(v1)",
+            "asinh(v1)+acosh(v1)+atanh(v1)",
+        ];
+        let r = inputs.map(|input| parse!(input).derivative(v1));
+
+        let res = [
+            "1/2*sqrt(v1)^-1+1+tan(v1)^2+2*(1+4*v1^2)^-1",
+            "0",
+            "cosh(v1)+sinh(v1)+1-tanh(v1)^2",
+            "(v1^2+1)^(-1/2)+(v1^2-1)^(-1/2)+(1-v1^2)^-1",
+        ];
+        let res = res.map(|input| parse!(input));
+
+        assert_eq!(r, res);
+    }
+
+    #[test]
+    fn derivative_templates() {
+        let (v1, v2, a, b) = symbol!("v1", "v2", "a", "b");
+        let f = Symbol::new(wrap_symbol!("f_der_template"))
+            .with_derivatives(&[a, b], &[parse!("b*exp(a*b)"), parse!("a*exp(a*b)")])
+            .build()
+            .unwrap();
+
+        let input = FunctionBuilder::new(f)
+            .add_arg(parse!("v1^2"))
+            .add_arg(parse!("v1*v2"))
+            .finish();
+
+        assert_eq!(input.derivative(v1), parse!("3*v1^2*v2*exp(v1^3*v2)"));
+        assert_eq!(input.derivative(v2), parse!("v1^3*exp(v1^3*v2)"));
+
+        // the templates only apply to calls with a matching number of arguments
+        let input = FunctionBuilder::new(f).add_arg(parse!("v1")).finish();
+        assert_eq!(input.derivative(v1), parse!("der(1,f_der_template(v1))"));
+
+        assert!(
+            Symbol::new(wrap_symbol!("f_der_template_invalid"))
+                .with_derivatives(&[a, b], &[Atom::num(1)])
+                .build()
+                .is_err()
+        );
+
+        assert!(
+            Symbol::new(wrap_symbol!("f_der_template_taylor"))
+                .with_derivatives(&[a], &[Atom::num(1)])
+                .with_taylor_coefficients(|n, _| Atom::num(n as i64))
+                .build()
+                .is_err()
+        );
+    }
+
     #[test]
     fn series() {
         let v1 = symbol!("v1");
@@ -900,6 +1360,94 @@ mod test {
         assert_eq!(t, res);
     }
 
+    #[test]
+    fn series_elementary() {
+        let v1 = symbol!("v1");
+
+        let t = parse!("tan(v1)")
+            .series(v1, Atom::num(0).as_view(), 5.into(), true)
+            .unwrap()
+            .to_atom();
+        assert_eq!(t, parse!("v1+1/3*v1^3+2/15*v1^5"));
+
+        let t = parse!("atan(v1)+sinh(v1)")
+            .series(v1, Atom::num(0).as_view(), 3.into(), true)
+            .unwrap()
+            .to_atom();
+        assert_eq!(t, parse!("2*v1-1/6*v1^3"));
+
+        let t = parse!("cosh(v1^(1/2))")
+            .series(v1, Atom::num(0).as_view(), 2.into(), true)
+            .unwrap()
+            .to_atom();
+        assert_eq!(t, parse!("1+1/2*v1+1/24*v1^2"));
+
+        let t = parse!("asin(v1)+acos(v1)+tanh(v1)")
+            .series(v1, Atom::num(0).as_view(), 3.into(), true)
+            .unwrap()
+            .to_atom();
+        assert_eq!(t, parse!("1/2*𝜋+v1-1/3*v1^3"));
+
+        for e in [
+            "asin(1+v1)",
+            "asin(-1+v1)",
+            "acos(1+v1)",
+            "acos(-1+v1)",
+            "atan(1𝑖+v1)",
+            "atan(-1𝑖+v1)",
+            "asinh(1𝑖+v1)",
+            "asinh(-1𝑖+v1)",
+            "acosh(1+v1)",
+            "acosh(-1+v1)",
+            "atanh(1+v1)",
+            "atanh(-1+v1)",
+        ] {
+            assert_eq!(
+                parse!(e)
+                    .series(v1, Atom::num(0).as_view(), 4.into(), true)
+                    .unwrap_err(),
+                "Cannot series expand inverse function at a branch point",
+                "{}",
+                e
+            );
+        }
+
+        let t = parse!("acosh(1)+atanh(2+v1)")
+            .series(v1, Atom::num(0).as_view(), 1.into(), true)
+            .unwrap()
+            .to_atom();
+        assert_eq!(t, parse!("acosh(1)+atanh(2)-1/3*v1"));
+    }
+
+    #[test]
+    fn series_taylor_function() {
+        let v1 = symbol!("v1");
+        let f = Symbol::new(wrap_symbol!("taylor_exp"))
+            .with_taylor_coefficients(|n, a| {
+                let mut n_fac = Integer::one();
+                for i in 2..=n {
+                    n_fac *= &Integer::from(i as i64);
+                }
+                FunctionBuilder::new(Atom::EXP).add_arg(a).finish() / Atom::num(n_fac)
+            })
+            .build()
+            .unwrap();
+
+        let t = FunctionBuilder::new(f)
+            .add_arg(parse!("v1+v1^2"))
+            .finish()
+            .series(v1, Atom::num(0).as_view(), 3.into(), true)
+            .unwrap()
+            .to_atom();
+        assert_eq!(t, parse!("1+v1+3/2*v1^2+7/6*v1^3"));
+
+        let t = FunctionBuilder::new(f)
+            .add_arg(parse!("v1^2"))
+            .finish()
+            .derivative(v1);
+        assert_eq!(t, parse!("2*v1*exp(v1^2)"));
+    }
+
     #[test]
     fn series_exp_log() {
         let v1 = symbol!("v1");
@@ -1008,4 +1556,143 @@ mod test {
         let mul = &r * &t2;
         assert_eq!(mul.absolute_order(), (11, 1).into());
     }
+
+    #[test]
+    fn series_infinity() {
+        let v1 = symbol!("v1");
+
+        let t = parse!("v1^2/(v1-1)")
+            .series_at_infinity(v1, 2.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("v1+1+v1^-1+v1^-2"));
+
+        let t = parse!("(v1^2+1)^(1/2)")
+            .series_at_infinity(v1, 3.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("v1+1/2*v1^-1-1/8*v1^-3"));
+
+        let t = parse!("(1+1/v1)^v1")
+            .series_at_infinity(v1, 1.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("exp(1)-1/2*exp(1)*v1^-1"));
+
+        let r = (&t * &parse!("v1")).unwrap();
+        assert_eq!(r.to_atom(), parse!("exp(1)*v1-1/2*exp(1)"));
+    }
+
+    #[test]
+    fn log_series() {
+        let v1 = symbol!("v1");
+
+        let t = parse!("v1^(1+v1)")
+            .log_series(v1, Atom::num(0), 3.into(), true)
+            .unwrap();
+        assert_eq!(t.log_degree(), 2);
+        assert_eq!(t.coefficient((3, 1).into(), 2), Atom::num((1, 2)));
+        assert_eq!(t.to_atom(), parse!("v1+v1^2*log(v1)+1/2*v1^3*log(v1)^2"));
+
+        let t = parse!("1/(1+v1*log(v1))")
+            .log_series(v1, Atom::num(0), 2.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("1-v1*log(v1)+v1^2*log(v1)^2"));
+
+        let t = parse!("log(v1+v1^2)*(v1^2+v1)^(1/2)")
+            .log_series(v1, Atom::num(0), 1.into(), true)
+            .unwrap();
+        assert_eq!(
+            t.to_atom().expand(),
+            parse!("v1^(1/2)*log(v1)+v1^(3/2)+1/2*v1^(3/2)*log(v1)")
+        );
+
+        let t = parse!("log(v1)^2/(v1+v1^2)")
+            .log_series(v1, Atom::num(0), 2.into(), false)
+            .unwrap();
+        assert_eq!(t.relative_order(), (2, 1).into());
+        assert_eq!(t.to_atom(), parse!("(v1^-1-1)*log(v1)^2"));
+
+        assert!(
+            parse!("log(v1*log(v1))")
+                .log_series(v1, Atom::num(0), 2.into(), true)
+                .is_err()
+        );
+        assert!(
+            parse!("exp(log(v1)^2)")
+                .log_series(v1, Atom::num(0), 2.into(), true)
+                .is_err()
+        );
+
+        let eps = parse!("eps");
+        let t = parse!("v1^eps")
+            .log_series(v1, Atom::num(0), 2.into(), true)
+            .unwrap();
+        assert_eq!(t.symbolic_exponent(), Some(&eps));
+        assert_eq!(t.to_atom(), parse!("v1^eps"));
+
+        let t = parse!("v1^(1+eps)*(1+v1)")
+            .log_series(v1, Atom::num(0), 3.into(), true)
+            .unwrap();
+        assert_eq!(t.symbolic_exponent(), Some(&eps));
+        assert_eq!(t.coefficient((2, 1).into(), 0), Atom::num(1));
+        assert_eq!(t.to_atom().expand(), parse!("v1^(1+eps)+v1^(2+eps)"));
+
+        let t = parse!("v1^a*log(v1)")
+            .log_series(v1, Atom::num(0), 2.into(), true)
+            .unwrap();
+        assert_eq!(t.log_degree(), 1);
+        assert_eq!(t.to_atom(), parse!("v1^a*log(v1)"));
+
+        let t = parse!("log(v1^eps*(1+v1))")
+            .log_series(v1, Atom::num(0), 2.into(), true)
+            .unwrap();
+        assert_eq!(t.symbolic_exponent(), None);
+        assert_eq!(t.to_atom(), parse!("eps*log(v1)+v1-1/2*v1^2"));
+
+        assert!(
+            parse!("1+v1^eps")
+                .log_series(v1, Atom::num(0), 2.into(), true)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn log_series_infinity() {
+        let v1 = symbol!("v1");
+
+        let t = parse!("log(v1+1)/v1")
+            .log_series_at_infinity(v1, 2.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("v1^-1*log(v1)+v1^-2"));
+
+        let t = parse!("v1^(1/v1)")
+            .log_series_at_infinity(v1, 2.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("1+v1^-1*log(v1)+1/2*v1^-2*log(v1)^2"));
+
+        let t = parse!("v1^eps*(1+1/v1)")
+            .log_series_at_infinity(v1, 2.into(), true)
+            .unwrap();
+        assert_eq!(t.symbolic_exponent(), Some(&parse!("-eps")));
+        assert_eq!(t.to_atom().expand(), parse!("v1^eps+v1^(eps-1)"));
+
+        assert_eq!(
+            parse!("v1^eps")
+                .series_at_infinity(v1, 2.into(), true)
+                .unwrap_err(),
+            "Power of variable must be rational"
+        );
+
+        // both expansions write the logarithm of the leading term in the same way
+        for e in ["log(v1^2+v1)", "log(3*v1^2+v1)*v1", "log(1/v1+1/v1^2)"] {
+            let s = parse!(e).series_at_infinity(v1, 2.into(), true).unwrap();
+            let l = parse!(e)
+                .log_series_at_infinity(v1, 2.into(), true)
+                .unwrap();
+            assert_eq!(s.to_atom().expand(), l.to_atom().expand(), "{}", e);
+        }
+
+        let t = parse!("log(v1^2+v1)")
+            .series_at_infinity(v1, 2.into(), true)
+            .unwrap();
+        assert_eq!(t.to_atom(), parse!("2*log(v1)+v1^-1-1/2*v1^-2"));
+    }
 }
@@ -15,6 +15,7 @@ use crate::domains::integer::Integer;
 use super::{EuclideanDomain, Field, InternalOrdering, Ring, SelfRing, rational::Rational};
 use rug::{
     Assign, Float as MultiPrecisionFloat,
+    float::Round,
     ops::{CompleteRound, Pow},
 };
 
@@ -2086,54 +2087,2078 @@ impl Rational {
     }
 }
 
-/// A float that does linear error propagation.
-#[derive(Copy, Clone)]
-pub struct ErrorPropagatingFloat<T: NumericalFloatLike> {
-    value: T,
-    abs_err: f64,
+/// Compute `a + b` and its rounding error.
+#[inline(always)]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
 }
 
-impl From<f64> for ErrorPropagatingFloat<f64> {
+/// Compute `a + b` and its rounding error, assuming that `|a| >= |b|`.
+#[inline(always)]
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// Compute `a * b` and its rounding error.
+#[inline(always)]
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, f64::mul_add(a, b, -p))
+}
+
+/// A double-double float that represents a number as the unevaluated sum `hi + lo` of two `f64`s,
+/// with `|lo| <= ulp(hi) / 2`. It has a precision of 106 bits, which is about 32 decimal digits.
+///
+/// The arithmetic is performed in software using error-free transformations of `f64` operations,
+/// which is much faster than using a [Float] of comparable precision.
+///
+/// # Examples
+///
+/// ```
+/// use symbolica::domains::float::{DoubleDouble, Real};
+///
+/// let a = DoubleDouble::from(2.).sqrt();
+/// assert!((a * a - DoubleDouble::from(2.)).hi().abs() < 1e-30);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[derive(Copy, Clone, Default, PartialEq)]
+#[repr(C)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+impl DoubleDouble {
+    pub const PI: DoubleDouble = DoubleDouble {
+        hi: std::f64::consts::PI,
+        lo: 1.2246467991473532e-16,
+    };
+    pub const FRAC_PI_2: DoubleDouble = DoubleDouble {
+        hi: std::f64::consts::FRAC_PI_2,
+        lo: 6.123233995736766e-17,
+    };
+    pub const E: DoubleDouble = DoubleDouble {
+        hi: std::f64::consts::E,
+        lo: 1.4456468917292502e-16,
+    };
+    pub const LN_2: DoubleDouble = DoubleDouble {
+        hi: std::f64::consts::LN_2,
+        lo: 2.3190468138462996e-17,
+    };
+    pub const EULER: DoubleDouble = DoubleDouble {
+        hi: 0.577_215_664_901_532_9,
+        lo: -4.942915152430645e-18,
+    };
+    pub const PHI: DoubleDouble = DoubleDouble {
+        hi: 1.618_033_988_749_895,
+        lo: -5.432115203682506e-17,
+    };
+
+    /// Create a new double-double from the sum `hi + lo`.
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = two_sum(hi, lo);
+        Self::from_parts(hi, lo)
+    }
+
+    /// Create a double-double from normalized parts, where a non-finite `hi`
+    /// discards `lo`.
+    #[inline(always)]
+    fn from_parts(hi: f64, lo: f64) -> Self {
+        if hi.is_finite() {
+            DoubleDouble { hi, lo }
+        } else {
+            DoubleDouble { hi, lo: 0. }
+        }
+    }
+
+    /// Get the leading part of the double-double.
+    #[inline(always)]
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// Get the trailing part of the double-double.
+    #[inline(always)]
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Convert to a multiprecision float without loss of precision.
+    pub fn to_float(&self) -> Float {
+        let mut f = MultiPrecisionFloat::with_val(107, self.hi);
+        if let (Some(e_hi), Some(e_lo)) = (
+            f.get_exp(),
+            MultiPrecisionFloat::with_val(53, self.lo).get_exp(),
+        ) {
+            // the gap between the parts may be larger than 53 bits
+            f.set_prec(((e_hi - e_lo) as u32 + 53).max(107));
+            f += self.lo;
+        }
+        f.into()
+    }
+
+    /// Convert to a multiprecision float with 107 bits of precision.
+    fn to_float_rounded(self) -> Float {
+        let mut f = self.to_float();
+        f.set_prec(107);
+        f
+    }
+
+    #[inline(always)]
+    fn add_f64(self, b: f64) -> Self {
+        let (s1, s2) = two_sum(self.hi, b);
+        let (hi, lo) = quick_two_sum(s1, s2 + self.lo);
+        Self::from_parts(hi, lo)
+    }
+
+    #[inline(always)]
+    fn mul_f64(self, b: f64) -> Self {
+        let (p1, p2) = two_prod(self.hi, b);
+        let (hi, lo) = quick_two_sum(p1, p2 + self.lo * b);
+        Self::from_parts(hi, lo)
+    }
+
+    #[inline(always)]
+    fn div_f64(self, b: f64) -> Self {
+        let q1 = self.hi / b;
+        if !q1.is_finite() {
+            return Self::from_parts(q1, 0.);
+        }
+
+        let (p1, p2) = two_prod(q1, b);
+        let (s, e) = two_sum(self.hi, -p1);
+        let q2 = (s + (e - p2 + self.lo)) / b;
+        let (hi, lo) = quick_two_sum(q1, q2);
+        DoubleDouble { hi, lo }
+    }
+
+    /// Multiply by a power of two, which is exact.
+    #[inline(always)]
+    fn mul_pwr2(self, b: f64) -> Self {
+        DoubleDouble {
+            hi: self.hi * b,
+            lo: self.lo * b,
+        }
+    }
+
+    #[inline(always)]
+    fn sqr(self) -> Self {
+        let (p1, p2) = two_prod(self.hi, self.hi);
+        let (hi, lo) = quick_two_sum(p1, p2 + 2. * self.hi * self.lo);
+        Self::from_parts(hi, lo)
+    }
+
+    /// Sum the Taylor series `sum_k sign^k x^(2k+1) / (2k+1)!` of the sine (`sign = -1`)
+    /// or the hyperbolic sine (`sign = 1`).
+    fn odd_series(self, sign: f64) -> Self {
+        let x2 = self.sqr();
+        let mut s = self;
+        let mut t = self;
+        for k in 1..30 {
+            let k = k as f64;
+            t = (t * x2).div_f64(sign * (2. * k) * (2. * k + 1.));
+            s += t;
+            if t.hi.abs() <= 1e-33 * self.hi.abs() {
+                break;
+            }
+        }
+        s
+    }
+
+    /// Compute the sine and cosine.
+    fn sin_cos(&self) -> (Self, Self) {
+        if !self.hi.is_finite() {
+            return (f64::NAN.into(), f64::NAN.into());
+        }
+
+        if self.hi == 0. {
+            return (*self, 1f64.into());
+        }
+
+        // reduce the argument to [-π/4, π/4], where π/2 is split
+        // into three parts whose products with k are exact
+        let k = (self.hi / Self::FRAC_PI_2.hi).round();
+        let mut r = *self;
+        for p in [
+            Self::FRAC_PI_2.hi,
+            Self::FRAC_PI_2.lo,
+            -1.4973849048591698e-33,
+        ] {
+            let (p1, p2) = two_prod(k, p);
+            r -= DoubleDouble { hi: p1, lo: p2 };
+        }
+
+        let s = r.odd_series(-1.);
+        let c = (Self::from(1.) - s.sqr()).sqrt();
+
+        match k.rem_euclid(4.) as u8 {
+            0 => (s, c),
+            1 => (c, -s),
+            2 => (-s, -c),
+            _ => (-c, s),
+        }
+    }
+
+    /// Perform a Newton step for solving `f(z) = self`, where
+    /// `df` is the derivative of `f`.
+    #[inline(always)]
+    fn newton_step(self, z: f64, f: impl Fn(Self) -> (Self, Self)) -> Self {
+        let z = DoubleDouble::from(z);
+        if !z.hi.is_finite() {
+            return z;
+        }
+
+        let (fz, df) = f(z);
+        z - (fz - self) / df
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    #[inline(always)]
     fn from(value: f64) -> Self {
-        if value == 0. {
-            ErrorPropagatingFloat {
-                value,
-                abs_err: f64::EPSILON,
+        DoubleDouble { hi: value, lo: 0. }
+    }
+}
+
+impl From<&Rational> for DoubleDouble {
+    /// Convert a rational to the nearest double-double.
+    fn from(value: &Rational) -> Self {
+        let hi = value.to_f64();
+        if !hi.is_finite() {
+            return hi.into();
+        }
+
+        let lo = (value - &Rational::from(hi)).to_f64();
+        DoubleDouble::new(hi, lo)
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match self.hi.partial_cmp(&other.hi) {
+            Some(std::cmp::Ordering::Equal) => self.lo.partial_cmp(&other.lo),
+            o => o,
+        }
+    }
+}
+
+impl Debug for DoubleDouble {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for DoubleDouble {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.hi.is_finite() {
+            return Display::fmt(&self.hi, f);
+        }
+
+        Display::fmt(&self.to_float_rounded(), f)
+    }
+}
+
+impl LowerExp for DoubleDouble {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.hi.is_finite() {
+            return LowerExp::fmt(&self.hi, f);
+        }
+
+        LowerExp::fmt(&self.to_float_rounded(), f)
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self::Output {
+        DoubleDouble {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add<&DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: &Self) -> Self::Output {
+        let (s1, s2) = two_sum(self.hi, rhs.hi);
+        let (t1, t2) = two_sum(self.lo, rhs.lo);
+        let (s1, s2) = quick_two_sum(s1, s2 + t1);
+        let (hi, lo) = quick_two_sum(s1, s2 + t2);
+        Self::from_parts(hi, lo)
+    }
+}
+
+impl Add<DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl Sub<&DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: &Self) -> Self::Output {
+        self + -*rhs
+    }
+}
+
+impl Sub<DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul<&DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: &Self) -> Self::Output {
+        let (p1, p2) = two_prod(self.hi, rhs.hi);
+        let (hi, lo) = quick_two_sum(p1, p2 + (self.hi * rhs.lo + self.lo * rhs.hi));
+        Self::from_parts(hi, lo)
+    }
+}
+
+impl Mul<DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        self * &rhs
+    }
+}
+
+impl Div<&DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: &Self) -> Self::Output {
+        let q1 = self.hi / rhs.hi;
+        if !q1.is_finite() || !rhs.hi.is_finite() {
+            return Self::from_parts(q1, 0.);
+        }
+
+        let r = self - rhs.mul_f64(q1);
+        let q2 = r.hi / rhs.hi;
+        let r = r - rhs.mul_f64(q2);
+        let q3 = r.hi / rhs.hi;
+
+        let (hi, lo) = quick_two_sum(q1, q2);
+        DoubleDouble { hi, lo }.add_f64(q3)
+    }
+}
+
+impl Div<DoubleDouble> for DoubleDouble {
+    type Output = Self;
+
+    #[inline(always)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self / &rhs
+    }
+}
+
+impl AddAssign<&DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: &DoubleDouble) {
+        *self = *self + rhs;
+    }
+}
+
+impl AddAssign<DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign<&DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: &DoubleDouble) {
+        *self = *self - rhs;
+    }
+}
+
+impl SubAssign<DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<&DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: &DoubleDouble) {
+        *self = *self * rhs;
+    }
+}
+
+impl MulAssign<DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn mul_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<&DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: &DoubleDouble) {
+        *self = *self / rhs;
+    }
+}
+
+impl DivAssign<DoubleDouble> for DoubleDouble {
+    #[inline(always)]
+    fn div_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self / rhs;
+    }
+}
+
+impl NumericalFloatLike for DoubleDouble {
+    #[inline(always)]
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        *self * a + b
+    }
+
+    #[inline(always)]
+    fn neg(&self) -> Self {
+        -*self
+    }
+
+    #[inline(always)]
+    fn zero(&self) -> Self {
+        DoubleDouble::default()
+    }
+
+    #[inline(always)]
+    fn new_zero() -> Self {
+        DoubleDouble::default()
+    }
+
+    #[inline(always)]
+    fn one(&self) -> Self {
+        1f64.into()
+    }
+
+    fn pow(&self, e: u64) -> Self {
+        let mut r = self.one();
+        let mut b = *self;
+        let mut e = e;
+        while e > 0 {
+            if e & 1 == 1 {
+                r *= b;
             }
+            e >>= 1;
+            if e > 0 {
+                b = b.sqr();
+            }
+        }
+        r
+    }
+
+    #[inline(always)]
+    fn inv(&self) -> Self {
+        self.one() / self
+    }
+
+    #[inline(always)]
+    fn from_usize(&self, a: usize) -> Self {
+        Self::new_from_usize(a)
+    }
+
+    #[inline(always)]
+    fn from_i64(&self, a: i64) -> Self {
+        Self::new_from_i64(a)
+    }
+
+    #[inline(always)]
+    fn get_precision(&self) -> u32 {
+        106
+    }
+
+    #[inline(always)]
+    fn get_epsilon(&self) -> f64 {
+        2f64.powi(-104)
+    }
+
+    #[inline(always)]
+    fn fixed_precision(&self) -> bool {
+        true
+    }
+
+    fn sample_unit<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        Self::new_sample_unit(rng)
+    }
+}
+
+impl SingleFloat for DoubleDouble {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.hi == 0.
+    }
+
+    #[inline(always)]
+    fn is_one(&self) -> bool {
+        self.hi == 1. && self.lo == 0.
+    }
+
+    #[inline(always)]
+    fn is_finite(&self) -> bool {
+        self.hi.is_finite()
+    }
+
+    #[inline(always)]
+    fn from_rational(&self, rat: &Rational) -> Self {
+        rat.into()
+    }
+}
+
+impl RealNumberLike for DoubleDouble {
+    fn to_usize_clamped(&self) -> usize {
+        if self.hi.fract() == 0. && self.lo < 0. {
+            (self.hi - 1.) as usize
+        } else {
+            self.hi as usize
+        }
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> f64 {
+        self.hi
+    }
+
+    fn round_to_nearest_integer(&self) -> Integer {
+        if self.hi.fract() != 0. {
+            // `lo` can only change the result if the fractional part is 1/2
+            let t = self.hi.trunc();
+            let frac = (*self - DoubleDouble::from(t)).hi;
+            if frac >= 0.5 {
+                Integer::from_f64(t) + Integer::one()
+            } else if frac <= -0.5 {
+                Integer::from_f64(t) - Integer::one()
+            } else {
+                Integer::from_f64(t)
+            }
+        } else {
+            Integer::from_f64(self.hi) + Integer::from_f64(self.lo.round())
+        }
+    }
+}
+
+impl ConstructibleFloat for DoubleDouble {
+    #[inline(always)]
+    fn new_one() -> Self {
+        1f64.into()
+    }
+
+    #[inline(always)]
+    fn new_from_usize(a: usize) -> Self {
+        let hi = a as f64;
+        DoubleDouble::new(hi, (a as i128 - hi as i128) as f64)
+    }
+
+    #[inline(always)]
+    fn new_from_i64(a: i64) -> Self {
+        let hi = a as f64;
+        DoubleDouble::new(hi, (a as i128 - hi as i128) as f64)
+    }
+
+    fn new_sample_unit<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let hi: f64 = rng.random();
+        let lo: f64 = rng.random();
+        DoubleDouble::new(hi, lo * f64::EPSILON / 2.)
+    }
+}
+
+impl Real for DoubleDouble {
+    #[inline(always)]
+    fn pi(&self) -> Self {
+        Self::PI
+    }
+
+    #[inline(always)]
+    fn e(&self) -> Self {
+        Self::E
+    }
+
+    #[inline(always)]
+    fn euler(&self) -> Self {
+        Self::EULER
+    }
+
+    #[inline(always)]
+    fn phi(&self) -> Self {
+        Self::PHI
+    }
+
+    #[inline(always)]
+    fn i(&self) -> Option<Self> {
+        None
+    }
+
+    #[inline(always)]
+    fn norm(&self) -> Self {
+        if self.hi < 0. { -*self } else { *self }
+    }
+
+    fn sqrt(&self) -> Self {
+        if self.hi <= 0. || !self.hi.is_finite() {
+            return self.hi.sqrt().into();
+        }
+
+        // Karp's trick: one Newton step on 1/sqrt(x)
+        let x = 1. / self.hi.sqrt();
+        let ax = self.hi * x;
+        let (p1, p2) = two_prod(ax, ax);
+        let r = (*self - DoubleDouble { hi: p1, lo: p2 }).hi;
+        DoubleDouble::from(ax).add_f64(r * (x * 0.5))
+    }
+
+    fn log(&self) -> Self {
+        if self.hi <= 0. || !self.hi.is_finite() {
+            return self.hi.ln().into();
+        }
+
+        // one Newton step on exp(x) - self
+        let x = DoubleDouble::from(self.hi.ln());
+        x + *self * (-x).exp() - DoubleDouble::from(1.)
+    }
+
+    fn exp(&self) -> Self {
+        if self.hi > 709.8 {
+            return f64::INFINITY.into();
+        }
+        if self.hi < -745.2 {
+            return self.zero();
+        }
+        if self.hi.is_nan() {
+            return *self;
+        }
+
+        // write self = m * ln(2) + 512 * r
+        let m = (self.hi / Self::LN_2.hi).round();
+        let r = (*self - Self::LN_2.mul_f64(m)).mul_pwr2(1. / 512.);
+
+        // compute exp(r) - 1 and square it 9 times
+        let mut s = r;
+        let mut t = r;
+        for k in 2..20 {
+            t = (t * r).div_f64(k as f64);
+            s += t;
+            if t.hi.abs() <= 1e-36 {
+                break;
+            }
+        }
+
+        for _ in 0..9 {
+            s = s.mul_pwr2(2.) + s.sqr();
+        }
+
+        // split the power of two to prevent overflow of 2^m
+        let s = s.add_f64(1.);
+        let m1 = (m / 2.).floor();
+        s.mul_pwr2(2f64.powi(m1 as i32))
+            .mul_pwr2(2f64.powi((m - m1) as i32))
+    }
+
+    #[inline]
+    fn sin(&self) -> Self {
+        self.sin_cos().0
+    }
+
+    #[inline]
+    fn cos(&self) -> Self {
+        self.sin_cos().1
+    }
+
+    #[inline]
+    fn tan(&self) -> Self {
+        let (s, c) = self.sin_cos();
+        s / c
+    }
+
+    fn asin(&self) -> Self {
+        // compute 1 - x^2 without cancellation
+        let c = ((self.one() - self) * (self.one() + self)).sqrt();
+        self.atan2(&c)
+    }
+
+    fn acos(&self) -> Self {
+        let s = ((self.one() - self) * (self.one() + self)).sqrt();
+        s.atan2(self)
+    }
+
+    fn atan2(&self, x: &Self) -> Self {
+        if self.hi == 0. || x.hi == 0. || !self.hi.is_finite() || !x.hi.is_finite() {
+            let z = self.hi.atan2(x.hi);
+            return if z.abs() == std::f64::consts::PI {
+                Self::PI.mul_f64(z.signum())
+            } else if z.abs() == std::f64::consts::FRAC_PI_2 {
+                Self::FRAC_PI_2.mul_f64(z.signum())
+            } else {
+                z.into()
+            };
+        }
+
+        // one Newton step on the equation (cos(z), sin(z)) = (x, y) / r
+        let r = (x.sqr() + self.sqr()).sqrt();
+        let xx = *x / r;
+        let yy = *self / r;
+
+        let z = DoubleDouble::from(self.hi.atan2(x.hi));
+        let (s, c) = z.sin_cos();
+
+        if xx.hi.abs() > yy.hi.abs() {
+            z + (yy - s) / c
+        } else {
+            z - (xx - c) / s
+        }
+    }
+
+    fn sinh(&self) -> Self {
+        if self.hi.abs() < 0.05 {
+            return self.odd_series(1.);
+        }
+
+        let e = self.exp();
+        (e - e.inv()).mul_pwr2(0.5)
+    }
+
+    fn cosh(&self) -> Self {
+        let e = self.exp();
+        (e + e.inv()).mul_pwr2(0.5)
+    }
+
+    fn tanh(&self) -> Self {
+        if self.hi.abs() > 40. {
+            return self.hi.signum().into();
+        }
+
+        if self.hi.abs() < 0.05 {
+            let s = self.sinh();
+            return s / (s.sqr() + self.one()).sqrt();
+        }
+
+        let e = self.exp();
+        let inv = e.inv();
+        (e - inv) / (e + inv)
+    }
+
+    fn asinh(&self) -> Self {
+        self.newton_step(self.hi.asinh(), |z| (z.sinh(), z.cosh()))
+    }
+
+    fn acosh(&self) -> Self {
+        if self.hi < 1. {
+            return f64::NAN.into();
+        }
+
+        // use acosh(x) = asinh(sqrt((x - 1)(x + 1))), which is accurate close to 1
+        let one = self.one();
+        ((*self - one) * (*self + one)).sqrt().asinh()
+    }
+
+    fn atanh(&self) -> Self {
+        if self.hi.abs() > 0.5 {
+            // tanh(z) - x suffers from cancellation close to 1
+            let one = self.one();
+            return ((one + self) / (one - self)).log().mul_pwr2(0.5);
+        }
+
+        self.newton_step(self.hi.atanh(), |z| {
+            let c = z.cosh();
+            (z.tanh(), c.sqr().inv())
+        })
+    }
+
+    fn powf(&self, e: &Self) -> Self {
+        if e.lo == 0. && e.hi.fract() == 0. && e.hi.abs() < 2f64.powi(53) {
+            let r = self.pow(e.hi.abs() as u64);
+            return if e.hi < 0. { r.inv() } else { r };
+        }
+
+        if self.hi == 0. {
+            return self.hi.powf(e.hi).into();
+        }
+
+        (*e * self.log()).exp()
+    }
+}
+
+/// A float that does linear error propagation.
+#[derive(Copy, Clone)]
+pub struct ErrorPropagatingFloat<T: NumericalFloatLike> {
+    value: T,
+    abs_err: f64,
+}
+
+impl From<f64> for ErrorPropagatingFloat<f64> {
+    fn from(value: f64) -> Self {
+        if value == 0. {
+            ErrorPropagatingFloat {
+                value,
+                abs_err: f64::EPSILON,
+            }
+        } else {
+            ErrorPropagatingFloat {
+                value,
+                abs_err: f64::EPSILON * value.abs(),
+            }
+        }
+    }
+}
+
+impl<T: NumericalFloatLike> Neg for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        ErrorPropagatingFloat {
+            value: -self.value,
+            abs_err: self.abs_err,
+        }
+    }
+}
+
+impl<T: RealNumberLike> Add<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: &Self) -> Self::Output {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err + rhs.abs_err,
+            value: self.value + &rhs.value,
+        }
+    }
+}
+
+impl<T: RealNumberLike> Add<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        self + &rhs
+    }
+}
+
+impl<T: RealNumberLike> Sub<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: &Self) -> Self::Output {
+        self - rhs.clone()
+    }
+}
+
+impl<T: RealNumberLike> Sub<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl<T: RealNumberLike> Mul<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: &Self) -> Self::Output {
+        let value = self.value.clone() * &rhs.value;
+        let r = rhs.value.to_f64().abs();
+        let s = self.value.to_f64().abs();
+
+        if s == 0. && r == 0. {
+            ErrorPropagatingFloat {
+                value,
+                abs_err: self.abs_err * rhs.abs_err,
+            }
+        } else {
+            ErrorPropagatingFloat {
+                value,
+                abs_err: self.abs_err * r + rhs.abs_err * s,
+            }
+        }
+    }
+}
+
+impl<T: RealNumberLike + Add<Rational, Output = T>> Add<Rational> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Rational) -> Self::Output {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err,
+            value: self.value + rhs,
+        }
+    }
+}
+
+impl<T: RealNumberLike + Add<Rational, Output = T>> Sub<Rational> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Rational) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<T: RealNumberLike + Mul<Rational, Output = T>> Mul<Rational> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Rational) -> Self::Output {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * rhs.to_f64().abs(),
+            value: self.value * rhs,
+        }
+        .truncate()
+    }
+}
+
+impl<T: RealNumberLike + Div<Rational, Output = T>> Div<Rational> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Rational) -> Self::Output {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * rhs.inv().to_f64().abs(),
+            value: self.value.clone() / rhs,
+        }
+        .truncate()
+    }
+}
+
+impl<T: RealNumberLike> Mul<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        self * &rhs
+    }
+}
+
+impl<T: RealNumberLike> Div<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: &Self) -> Self::Output {
+        self * rhs.inv()
+    }
+}
+
+impl<T: RealNumberLike> Div<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        self / &rhs
+    }
+}
+
+impl<T: RealNumberLike> AddAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
+        // TODO: optimize
+        *self = self.clone() + rhs;
+    }
+}
+
+impl<T: RealNumberLike> AddAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+        self.add_assign(&rhs)
+    }
+}
+
+impl<T: RealNumberLike> SubAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
+        // TODO: optimize
+        *self = self.clone() - rhs;
+    }
+}
+
+impl<T: RealNumberLike> SubAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+        self.sub_assign(&rhs)
+    }
+}
+
+impl<T: RealNumberLike> MulAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
+        // TODO: optimize
+        *self = self.clone() * rhs;
+    }
+}
+
+impl<T: RealNumberLike> MulAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+        self.mul_assign(&rhs)
+    }
+}
+
+impl<T: RealNumberLike> DivAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
+        // TODO: optimize
+        *self = self.clone() / rhs;
+    }
+}
+
+impl<T: RealNumberLike> DivAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+        self.div_assign(&rhs)
+    }
+}
+
+impl<T: RealNumberLike> ErrorPropagatingFloat<T> {
+    /// Create a new precision tracking float with a number of precise decimal digits `prec`.
+    /// The `prec` must be smaller than the precision of the underlying float.
+    ///
+    /// If the value provided is 0, the precision argument is interpreted as an accuracy (
+    /// the number of digits of the absolute error).
+    pub fn new(value: T, prec: f64) -> Self {
+        let r = value.to_f64().abs();
+
+        if r == 0. {
+            ErrorPropagatingFloat {
+                abs_err: 10f64.pow(-prec),
+                value,
+            }
+        } else {
+            ErrorPropagatingFloat {
+                abs_err: 10f64.pow(-prec) * r,
+                value,
+            }
+        }
+    }
+
+    pub fn get_absolute_error(&self) -> f64 {
+        self.abs_err
+    }
+
+    pub fn get_relative_error(&self) -> f64 {
+        self.abs_err / self.value.to_f64().abs()
+    }
+
+    /// Get the precision in number of decimal digits.
+    #[inline(always)]
+    pub fn get_precision(&self) -> Option<f64> {
+        let r = self.value.to_f64().abs();
+        if r == 0. {
+            None
+        } else {
+            Some(-(self.abs_err / r).log10())
+        }
+    }
+
+    /// Get the accuracy in number of decimal digits.
+    #[inline(always)]
+    pub fn get_accuracy(&self) -> f64 {
+        -self.abs_err.log10()
+    }
+
+    /// Truncate the precision to the maximal number of stable decimal digits
+    /// of the underlying float.
+    #[inline(always)]
+    pub fn truncate(mut self) -> Self {
+        if self.value.fixed_precision() {
+            self.abs_err = self
+                .abs_err
+                .max(self.value.get_epsilon() * self.value.to_f64());
+        }
+        self
+    }
+}
+
+impl<T: NumericalFloatLike> ErrorPropagatingFloat<T> {
+    pub fn new_with_accuracy(value: T, acc: f64) -> Self {
+        ErrorPropagatingFloat {
+            value,
+            abs_err: 10f64.pow(-acc),
+        }
+    }
+
+    /// Get the number.
+    #[inline(always)]
+    pub fn get_num(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: RealNumberLike> fmt::Display for ErrorPropagatingFloat<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(p) = self.get_precision() {
+            if p < 0. {
+                f.write_char('0')
+            } else {
+                f.write_fmt(format_args!("{0:.1$}", self.value, p as usize))
+            }
+        } else {
+            f.write_char('0')
+        }
+    }
+}
+
+impl<T: RealNumberLike> Debug for ErrorPropagatingFloat<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.value, f)?;
+
+        if let Some(p) = self.get_precision() {
+            f.write_fmt(format_args!("`{:.2}", p))
+        } else {
+            f.write_fmt(format_args!("``{:.2}", -self.abs_err.log10()))
+        }
+    }
+}
+
+impl<T: RealNumberLike> LowerExp for ErrorPropagatingFloat<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<T: NumericalFloatLike> PartialEq for ErrorPropagatingFloat<T> {
+    fn eq(&self, other: &Self) -> bool {
+        // TODO: ignore precision for partial equality?
+        self.value == other.value
+    }
+}
+
+impl<T: NumericalFloatLike + PartialOrd> PartialOrd for ErrorPropagatingFloat<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: RealNumberLike> NumericalFloatLike for ErrorPropagatingFloat<T> {
+    fn mul_add(&self, a: &Self, b: &Self) -> Self {
+        self.clone() * a + b
+    }
+
+    fn neg(&self) -> Self {
+        -self.clone()
+    }
+
+    fn zero(&self) -> Self {
+        ErrorPropagatingFloat {
+            value: self.value.zero(),
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
+        }
+    }
+
+    fn new_zero() -> Self {
+        ErrorPropagatingFloat {
+            value: T::new_zero(),
+            abs_err: 2f64.powi(-53),
+        }
+    }
+
+    fn one(&self) -> Self {
+        ErrorPropagatingFloat {
+            value: self.value.one(),
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
+        }
+    }
+
+    fn pow(&self, e: u64) -> Self {
+        let i = self.to_f64().abs();
+
+        if i == 0. {
+            return ErrorPropagatingFloat {
+                value: self.value.pow(e),
+                abs_err: self.abs_err.pow(e as f64),
+            };
+        }
+
+        let r = self.value.pow(e);
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * e as f64 * r.to_f64().abs() / i,
+            value: r,
+        }
+    }
+
+    fn inv(&self) -> Self {
+        let r = self.value.inv();
+        let rr = r.to_f64().abs();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * rr * rr,
+            value: r,
+        }
+    }
+
+    /// Convert from a `usize`.
+    fn from_usize(&self, a: usize) -> Self {
+        let v = self.value.from_usize(a);
+        let r = v.to_f64().abs();
+        if r == 0. {
+            ErrorPropagatingFloat {
+                value: v,
+                abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
+            }
+        } else {
+            ErrorPropagatingFloat {
+                value: v,
+                abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * r,
+            }
+        }
+    }
+
+    /// Convert from a `i64`.
+    fn from_i64(&self, a: i64) -> Self {
+        let v = self.value.from_i64(a);
+        let r = v.to_f64().abs();
+        if r == 0. {
+            ErrorPropagatingFloat {
+                value: v,
+                abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
+            }
+        } else {
+            ErrorPropagatingFloat {
+                value: v,
+                abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * r,
+            }
+        }
+    }
+
+    fn get_precision(&self) -> u32 {
+        // return the precision of the underlying float instead
+        // of the current tracked precision
+        self.value.get_precision()
+    }
+
+    fn get_epsilon(&self) -> f64 {
+        2.0f64.powi(-(self.value.get_precision() as i32))
+    }
+
+    #[inline(always)]
+    fn fixed_precision(&self) -> bool {
+        self.value.fixed_precision()
+    }
+
+    fn sample_unit<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        let v = self.value.sample_unit(rng);
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * v.to_f64().abs(),
+            value: v,
+        }
+    }
+}
+
+impl<T: RealNumberLike> SingleFloat for ErrorPropagatingFloat<T> {
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    fn is_one(&self) -> bool {
+        self.value.is_one()
+    }
+
+    fn is_finite(&self) -> bool {
+        self.value.is_finite()
+    }
+
+    fn from_rational(&self, rat: &Rational) -> Self {
+        if rat.is_zero() {
+            ErrorPropagatingFloat {
+                value: self.value.from_rational(rat),
+                abs_err: self.abs_err,
+            }
+        } else {
+            ErrorPropagatingFloat {
+                value: self.value.from_rational(rat),
+                abs_err: self.abs_err * rat.to_f64(),
+            }
+        }
+    }
+}
+
+impl<T: RealNumberLike> RealNumberLike for ErrorPropagatingFloat<T> {
+    fn to_usize_clamped(&self) -> usize {
+        self.value.to_usize_clamped()
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.value.to_f64()
+    }
+
+    fn round_to_nearest_integer(&self) -> Integer {
+        // TODO: what does this do with the error?
+        self.value.round_to_nearest_integer()
+    }
+}
+
+impl<T: Real + RealNumberLike> Real for ErrorPropagatingFloat<T> {
+    fn pi(&self) -> Self {
+        let v = self.value.pi();
+        ErrorPropagatingFloat {
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
+            value: v,
+        }
+    }
+
+    fn e(&self) -> Self {
+        let v = self.value.e();
+        ErrorPropagatingFloat {
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
+            value: v,
+        }
+    }
+
+    fn euler(&self) -> Self {
+        let v = self.value.euler();
+        ErrorPropagatingFloat {
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
+            value: v,
+        }
+    }
+
+    fn phi(&self) -> Self {
+        let v = self.value.phi();
+        ErrorPropagatingFloat {
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
+            value: v,
+        }
+    }
+
+    #[inline(always)]
+    fn i(&self) -> Option<Self> {
+        Some(ErrorPropagatingFloat {
+            value: self.value.i()?,
+            abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
+        })
+    }
+
+    fn norm(&self) -> Self {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err,
+            value: self.value.norm(),
+        }
+    }
+
+    fn sqrt(&self) -> Self {
+        let v = self.value.sqrt();
+        let r = v.to_f64().abs();
+
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / (2. * r),
+            value: v,
+        }
+        .truncate()
+    }
+
+    fn log(&self) -> Self {
+        let r = self.value.log();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / self.value.to_f64().abs(),
+            value: r,
+        }
+        .truncate()
+    }
+
+    fn exp(&self) -> Self {
+        let v = self.value.exp();
+        ErrorPropagatingFloat {
+            abs_err: v.to_f64().abs() * self.abs_err,
+            value: v,
+        }
+        .truncate()
+    }
+
+    fn sin(&self) -> Self {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * self.value.to_f64().cos().abs(),
+            value: self.value.sin(),
+        }
+        .truncate()
+    }
+
+    fn cos(&self) -> Self {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * self.value.to_f64().sin().abs(),
+            value: self.value.cos(),
+        }
+        .truncate()
+    }
+
+    fn tan(&self) -> Self {
+        let t = self.value.tan();
+        let tt = t.to_f64().abs();
+
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * (1. + tt * tt),
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn asin(&self) -> Self {
+        let v = self.value.to_f64();
+        let t = self.value.asin();
+        let tt = (1. - v * v).sqrt();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / tt,
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn acos(&self) -> Self {
+        let v = self.value.to_f64();
+        let t = self.value.acos();
+        let tt = (1. - v * v).sqrt();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / tt,
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn atan2(&self, x: &Self) -> Self {
+        let t = self.value.atan2(&x.value);
+        let r = self.clone() / x;
+        let r2 = r.value.to_f64().abs();
+
+        let tt = 1. + r2 * r2;
+        ErrorPropagatingFloat {
+            abs_err: r.abs_err / tt,
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn sinh(&self) -> Self {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * self.value.cosh().to_f64().abs(),
+            value: self.value.sinh(),
+        }
+        .truncate()
+    }
+
+    fn cosh(&self) -> Self {
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * self.value.sinh().to_f64().abs(),
+            value: self.value.cosh(),
+        }
+        .truncate()
+    }
+
+    fn tanh(&self) -> Self {
+        let t = self.value.tanh();
+        let tt = t.clone().to_f64().abs();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err * (1. - tt * tt),
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn asinh(&self) -> Self {
+        let v = self.value.to_f64();
+        let t = self.value.asinh();
+        let tt = (1. + v * v).sqrt();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / tt,
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn acosh(&self) -> Self {
+        let v = self.value.to_f64();
+        let t = self.value.acosh();
+        let tt = (v * v - 1.).sqrt();
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / tt,
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn atanh(&self) -> Self {
+        let v = self.value.to_f64();
+        let t = self.value.atanh();
+        let tt = 1. - v * v;
+        ErrorPropagatingFloat {
+            abs_err: self.abs_err / tt,
+            value: t,
+        }
+        .truncate()
+    }
+
+    fn powf(&self, e: &Self) -> Self {
+        let i = self.to_f64().abs();
+
+        if i == 0. {
+            return ErrorPropagatingFloat {
+                value: self.value.powf(&e.value),
+                abs_err: 0.,
+            };
+        }
+
+        let r = self.value.powf(&e.value);
+        ErrorPropagatingFloat {
+            abs_err: (self.abs_err * e.value.to_f64() + i * e.abs_err * i.ln().abs())
+                * r.to_f64().abs()
+                / i,
+            value: r,
+        }
+        .truncate()
+    }
+}
+
+/// A float that supports directed rounding, so that it can be used
+/// as a bound of an [Interval].
+///
+/// Every operation returns a result that is rounded in the direction `round`, so that
+/// [Round::Down] yields a lower bound and [Round::Up] an upper bound of the exact result.
+pub trait IntervalBound: Real + RealNumberLike + PartialOrd {
+    /// Positive infinity with the same precision as `self`.
+    fn infinity(&self) -> Self;
+    /// Convert a rational to a float, rounding in the direction `round`.
+    fn rational_round(&self, rat: &Rational, round: Round) -> Self;
+
+    fn add_round(&self, rhs: &Self, round: Round) -> Self;
+    fn sub_round(&self, rhs: &Self, round: Round) -> Self;
+    /// Multiply two floats, where zero times infinity is zero.
+    fn mul_round(&self, rhs: &Self, round: Round) -> Self;
+    fn div_round(&self, rhs: &Self, round: Round) -> Self;
+
+    fn pi_round(&self, round: Round) -> Self;
+    fn e_round(&self, round: Round) -> Self;
+    fn euler_round(&self, round: Round) -> Self;
+    fn phi_round(&self, round: Round) -> Self;
+
+    fn sqrt_round(&self, round: Round) -> Self;
+    fn log_round(&self, round: Round) -> Self;
+    fn exp_round(&self, round: Round) -> Self;
+    fn sin_round(&self, round: Round) -> Self;
+    fn cos_round(&self, round: Round) -> Self;
+    fn tan_round(&self, round: Round) -> Self;
+    fn asin_round(&self, round: Round) -> Self;
+    fn acos_round(&self, round: Round) -> Self;
+    fn atan_round(&self, round: Round) -> Self;
+    fn sinh_round(&self, round: Round) -> Self;
+    fn cosh_round(&self, round: Round) -> Self;
+    fn tanh_round(&self, round: Round) -> Self;
+    fn asinh_round(&self, round: Round) -> Self;
+    fn acosh_round(&self, round: Round) -> Self;
+    fn atanh_round(&self, round: Round) -> Self;
+}
+
+/// Get the smallest `f64` that is larger than `x`.
+#[inline(always)]
+fn next_up_f64(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0. {
+        f64::from_bits(1)
+    } else if x > 0. {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+/// Get the largest `f64` that is smaller than `x`.
+#[inline(always)]
+fn next_down_f64(x: f64) -> f64 {
+    -next_up_f64(-x)
+}
+
+/// Round the result `r` of a floating point operation in the direction `round`,
+/// given the sign of the rounding error `err = exact - r`.
+#[inline(always)]
+fn round_f64(r: f64, err: f64, round: Round) -> f64 {
+    match round {
+        Round::Up if err > 0. || r == f64::NEG_INFINITY => next_up_f64(r),
+        Round::Down if err < 0. || r == f64::INFINITY => next_down_f64(r),
+        _ => r,
+    }
+}
+
+/// Widen the result of an `f64` function that is accurate up to one ulp.
+#[inline(always)]
+fn widen_f64(r: f64, round: Round) -> f64 {
+    match round {
+        Round::Up => next_up_f64(r),
+        Round::Down => next_down_f64(r),
+        _ => r,
+    }
+}
+
+macro_rules! f64_widened_impl {
+    ($($name:ident => $f:ident),*) => {
+        $(
+            #[inline(always)]
+            fn $name(&self, round: Round) -> Self {
+                widen_f64(self.$f(), round)
+            }
+        )*
+    };
+}
+
+/// The rounding of the operations is exact for the arithmetic operations and
+/// the square root, which are correctly rounded by IEEE 754. All other functions
+/// are widened by one ulp, assuming that the platform's math library is accurate
+/// up to one ulp.
+impl IntervalBound for f64 {
+    #[inline(always)]
+    fn infinity(&self) -> Self {
+        f64::INFINITY
+    }
+
+    fn rational_round(&self, rat: &Rational, round: Round) -> Self {
+        MultiPrecisionFloat::with_val_round(53, rat.clone().to_multi_prec(), round)
+            .0
+            .to_f64()
+    }
+
+    #[inline]
+    fn add_round(&self, rhs: &Self, round: Round) -> Self {
+        // TwoSum error-free transformation
+        let r = self + rhs;
+        if !r.is_finite() {
+            return if self.is_finite() && rhs.is_finite() {
+                round_f64(r, 0., round)
+            } else {
+                r
+            };
+        }
+
+        let b = r - self;
+        let err = (self - (r - b)) + (rhs - b);
+        round_f64(r, err, round)
+    }
+
+    #[inline]
+    fn sub_round(&self, rhs: &Self, round: Round) -> Self {
+        self.add_round(&-rhs, round)
+    }
+
+    #[inline]
+    fn mul_round(&self, rhs: &Self, round: Round) -> Self {
+        if *self == 0. || *rhs == 0. {
+            return 0.;
+        }
+
+        let r = self * rhs;
+        if !r.is_finite() {
+            return if self.is_finite() && rhs.is_finite() {
+                round_f64(r, 0., round)
+            } else {
+                r
+            };
+        }
+
+        let err = f64::mul_add(*self, *rhs, -r);
+        if err == 0. && r.abs() < f64::MIN_POSITIVE {
+            // the error may be lost in the subnormal range
+            return widen_f64(r, round);
+        }
+
+        round_f64(r, err, round)
+    }
+
+    #[inline]
+    fn div_round(&self, rhs: &Self, round: Round) -> Self {
+        let r = self / rhs;
+        if !r.is_finite() || !rhs.is_finite() {
+            return if self.is_finite() && rhs.is_finite() && *rhs != 0. {
+                round_f64(r, 0., round)
+            } else {
+                r
+            };
+        }
+
+        let rem = f64::mul_add(-r, *rhs, *self);
+        if rem == 0. && r.abs() < f64::MIN_POSITIVE && r != 0. {
+            return widen_f64(r, round);
+        }
+
+        round_f64(r, if *rhs > 0. { rem } else { -rem }, round)
+    }
+
+    f64_widened_impl!(pi_round => pi, e_round => e, euler_round => euler, phi_round => phi);
+
+    #[inline]
+    fn sqrt_round(&self, round: Round) -> Self {
+        let r = f64::sqrt(*self);
+        if !r.is_finite() {
+            return r;
+        }
+
+        round_f64(r, f64::mul_add(-r, r, *self), round)
+    }
+
+    f64_widened_impl!(log_round => ln, exp_round => exp, sin_round => sin, cos_round => cos,
+        tan_round => tan, asin_round => asin, acos_round => acos, atan_round => atan,
+        sinh_round => sinh, cosh_round => cosh, tanh_round => tanh, asinh_round => asinh,
+        acosh_round => acosh, atanh_round => atanh);
+}
+
+macro_rules! float_rounded_impl {
+    ($($name:ident => $f:ident),*) => {
+        $(
+            #[inline]
+            fn $name(&self, round: Round) -> Self {
+                let mut r = self.0.clone();
+                r.$f(round);
+                r.into()
+            }
+        )*
+    };
+}
+
+macro_rules! float_binary_rounded_impl {
+    ($($name:ident => $op:tt),*) => {
+        $(
+            #[inline]
+            fn $name(&self, rhs: &Self, round: Round) -> Self {
+                MultiPrecisionFloat::with_val_round(self.prec().max(rhs.prec()), &self.0 $op &rhs.0, round)
+                    .0
+                    .into()
+            }
+        )*
+    };
+}
+
+/// The operations use the directed rounding of MPFR and are therefore exact.
+impl IntervalBound for Float {
+    #[inline(always)]
+    fn infinity(&self) -> Self {
+        Float::with_val(self.prec(), rug::float::Special::Infinity)
+    }
+
+    fn rational_round(&self, rat: &Rational, round: Round) -> Self {
+        MultiPrecisionFloat::with_val_round(self.prec(), rat.clone().to_multi_prec(), round)
+            .0
+            .into()
+    }
+
+    float_binary_rounded_impl!(add_round => +, sub_round => -, div_round => /);
+
+    #[inline]
+    fn mul_round(&self, rhs: &Self, round: Round) -> Self {
+        if self.0.is_zero() || rhs.0.is_zero() {
+            return Float::new(self.prec().max(rhs.prec()));
+        }
+
+        MultiPrecisionFloat::with_val_round(self.prec().max(rhs.prec()), &self.0 * &rhs.0, round)
+            .0
+            .into()
+    }
+
+    fn pi_round(&self, round: Round) -> Self {
+        MultiPrecisionFloat::with_val_round(self.prec(), rug::float::Constant::Pi, round)
+            .0
+            .into()
+    }
+
+    fn e_round(&self, round: Round) -> Self {
+        self.one().exp_round(round)
+    }
+
+    fn euler_round(&self, round: Round) -> Self {
+        MultiPrecisionFloat::with_val_round(self.prec(), rug::float::Constant::Euler, round)
+            .0
+            .into()
+    }
+
+    fn phi_round(&self, round: Round) -> Self {
+        // (1 + sqrt(5)) / 2, where the division by 2 is exact
+        let mut r = self
+            .from_i64(5)
+            .sqrt_round(round)
+            .add_round(&self.one(), round);
+        r.0 /= 2;
+        r
+    }
+
+    float_rounded_impl!(sqrt_round => sqrt_round, log_round => ln_round, exp_round => exp_round,
+        sin_round => sin_round, cos_round => cos_round, tan_round => tan_round,
+        asin_round => asin_round, acos_round => acos_round, atan_round => atan_round,
+        sinh_round => sinh_round, cosh_round => cosh_round, tanh_round => tanh_round,
+        asinh_round => asinh_round, acosh_round => acosh_round, atanh_round => atanh_round);
+}
+
+/// An interval `[lower, upper]` of floats that is guaranteed to contain the exact result
+/// of every operation, by rounding the lower bound down and the upper bound up.
+///
+/// Functions are evaluated on the part of the interval that lies in their domain,
+/// for example the square root of `[-1, 4]` is `[0, 2]`.
+///
+/// # Examples
+///
+/// ```
+/// use symbolica::domains::float::{Float, Interval, Real, RealNumberLike, SingleFloat};
+/// use symbolica::domains::rational::Rational;
+///
+/// let third = Interval::from(Float::new(100)).from_rational(&Rational::from((1, 3)));
+/// let r = (third.clone() * &third.exp()).sin();
+/// assert!(r.lower() < r.upper());
+/// assert!(r.width().to_f64() < 1e-28);
+/// assert!(r.lower().to_f64() > 0.4486 && r.upper().to_f64() < 0.4487);
+/// ```
+#[derive(Copy, Clone, PartialEq)]
+pub struct Interval<T> {
+    lower: T,
+    upper: T,
+}
+
+impl<T: IntervalBound> From<T> for Interval<T> {
+    /// Create an interval that consists of a single point.
+    fn from(value: T) -> Self {
+        Interval {
+            lower: value.clone(),
+            upper: value,
+        }
+    }
+}
+
+impl<T: IntervalBound> Interval<T> {
+    /// Create a new interval `[lower, upper]`.
+    pub fn new(lower: T, upper: T) -> Self {
+        assert!(
+            upper.partial_cmp(&lower) != Some(std::cmp::Ordering::Less),
+            "Lower bound {} is larger than the upper bound {}",
+            lower,
+            upper
+        );
+        Interval { lower, upper }
+    }
+
+    /// Get the lower bound.
+    #[inline(always)]
+    pub fn lower(&self) -> &T {
+        &self.lower
+    }
+
+    /// Get the upper bound.
+    #[inline(always)]
+    pub fn upper(&self) -> &T {
+        &self.upper
+    }
+
+    /// Get the midpoint of the interval.
+    pub fn midpoint(&self) -> T {
+        let mut r = self.lower.add_round(&self.upper, Round::Nearest);
+        r /= self.lower.from_i64(2);
+        r
+    }
+
+    /// Get the width of the interval, rounded up.
+    pub fn width(&self) -> T {
+        self.upper.sub_round(&self.lower, Round::Up)
+    }
+
+    /// Check if `x` lies in the interval.
+    pub fn contains(&self, x: &T) -> bool {
+        self.lower <= *x && *x <= self.upper
+    }
+
+    /// Check if the interval intersects with `other`.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+
+    /// The interval `[-inf, inf]`.
+    fn entire(&self) -> Self {
+        let inf = self.lower.infinity();
+        Interval {
+            lower: -inf.clone(),
+            upper: inf,
+        }
+    }
+
+    /// Create an interval from a constant that is evaluated with directed rounding.
+    fn constant(&self, f: impl Fn(&T, Round) -> T) -> Self {
+        Interval {
+            lower: f(&self.lower, Round::Down),
+            upper: f(&self.lower, Round::Up),
+        }
+    }
+
+    /// Apply a monotonically increasing function.
+    fn increasing(&self, f: impl Fn(&T, Round) -> T) -> Self {
+        Interval {
+            lower: f(&self.lower, Round::Down),
+            upper: f(&self.upper, Round::Up),
+        }
+    }
+
+    /// Apply a monotonically decreasing function.
+    fn decreasing(&self, f: impl Fn(&T, Round) -> T) -> Self {
+        Interval {
+            lower: f(&self.upper, Round::Down),
+            upper: f(&self.lower, Round::Up),
+        }
+    }
+
+    /// Restrict the interval to `[min, max]`.
+    fn clamp(&self, min: T, max: T) -> Self {
+        Interval {
+            lower: if self.lower < min {
+                min
+            } else {
+                self.lower.clone()
+            },
+            upper: if self.upper > max {
+                max
+            } else {
+                self.upper.clone()
+            },
+        }
+    }
+
+    fn min_bound(a: T, b: T) -> T {
+        if b < a { b } else { a }
+    }
+
+    fn max_bound(a: T, b: T) -> T {
+        if b > a { b } else { a }
+    }
+
+    /// Compute `x^e` for a bound `x` that is non-negative or for odd `e`.
+    fn pow_bound(x: &T, e: u64, round: Round) -> T {
+        if *x < x.zero() {
+            let opposite = if round == Round::Up {
+                Round::Down
+            } else {
+                Round::Up
+            };
+            return -Self::pow_bound(&-x.clone(), e, opposite);
+        }
+
+        // binary exponentiation, where all factors are non-negative
+        let mut r = x.one();
+        let mut b = x.clone();
+        let mut e = e;
+        while e > 0 {
+            if e & 1 == 1 {
+                r = r.mul_round(&b, round);
+            }
+            e >>= 1;
+            if e > 0 {
+                b = b.mul_round(&b, round);
+            }
+        }
+        r
+    }
+
+    /// Check if the interval may contain a point `quarter * π/2 + 2πk` for some integer `k`.
+    /// The test is conservative: it may return `true` if the point lies just outside the interval.
+    fn contains_periodic_point(&self, quarter: i64) -> bool {
+        let (l, u) = (self.lower.to_f64(), self.upper.to_f64());
+        if !l.is_finite() || !u.is_finite() || l.abs().max(u.abs()) > 2f64.powi(50) {
+            return true;
+        }
+
+        let pi = self.constant(T::pi_round);
+        let offset = pi.clone() * &self.from_i64(quarter) / &self.from_i64(2);
+        let two_pi = pi * &self.from_i64(2);
+
+        let tau = 2. * std::f64::consts::PI;
+        let k = ((l - quarter as f64 * std::f64::consts::FRAC_PI_2) / tau).floor() as i64;
+        (k - 1..=k + 2).any(|k| {
+            let p = two_pi.clone() * &self.from_i64(k) + &offset;
+            self.intersects(&p)
+        })
+    }
+}
+
+impl<T: IntervalBound> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('[')?;
+        Display::fmt(&self.lower, f)?;
+        f.write_str(", ")?;
+        Display::fmt(&self.upper, f)?;
+        f.write_char(']')
+    }
+}
+
+impl<T: IntervalBound> Debug for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('[')?;
+        Debug::fmt(&self.lower, f)?;
+        f.write_str(", ")?;
+        Debug::fmt(&self.upper, f)?;
+        f.write_char(']')
+    }
+}
+
+impl<T: IntervalBound> LowerExp for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('[')?;
+        LowerExp::fmt(&self.lower, f)?;
+        f.write_str(", ")?;
+        LowerExp::fmt(&self.upper, f)?;
+        f.write_char(']')
+    }
+}
+
+impl<T: IntervalBound> PartialOrd for Interval<T> {
+    /// Two intervals are ordered when they do not overlap, or
+    /// when they are equal points.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.upper < other.lower {
+            Some(std::cmp::Ordering::Less)
+        } else if self.lower > other.upper {
+            Some(std::cmp::Ordering::Greater)
+        } else if self.lower == self.upper && self == other {
+            Some(std::cmp::Ordering::Equal)
         } else {
-            ErrorPropagatingFloat {
-                value,
-                abs_err: f64::EPSILON * value.abs(),
-            }
+            None
         }
     }
 }
 
-impl<T: NumericalFloatLike> Neg for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Neg for Interval<T> {
     type Output = Self;
 
     #[inline]
     fn neg(self) -> Self::Output {
-        ErrorPropagatingFloat {
-            value: -self.value,
-            abs_err: self.abs_err,
+        Interval {
+            lower: -self.upper,
+            upper: -self.lower,
         }
     }
 }
 
-impl<T: RealNumberLike> Add<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Add<&Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
     fn add(self, rhs: &Self) -> Self::Output {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err + rhs.abs_err,
-            value: self.value + &rhs.value,
+        Interval {
+            lower: self.lower.add_round(&rhs.lower, Round::Down),
+            upper: self.upper.add_round(&rhs.upper, Round::Up),
         }
     }
 }
 
-impl<T: RealNumberLike> Add<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Add<Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
@@ -2142,113 +4167,95 @@ impl<T: RealNumberLike> Add<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<
     }
 }
 
-impl<T: RealNumberLike> Sub<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Sub<&Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
     fn sub(self, rhs: &Self) -> Self::Output {
-        self - rhs.clone()
+        Interval {
+            lower: self.lower.sub_round(&rhs.upper, Round::Down),
+            upper: self.upper.sub_round(&rhs.lower, Round::Up),
+        }
     }
 }
 
-impl<T: RealNumberLike> Sub<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Sub<Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
     fn sub(self, rhs: Self) -> Self::Output {
-        self + (-rhs)
+        self - &rhs
     }
 }
 
-impl<T: RealNumberLike> Mul<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Mul<&Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
     fn mul(self, rhs: &Self) -> Self::Output {
-        let value = self.value.clone() * &rhs.value;
-        let r = rhs.value.to_f64().abs();
-        let s = self.value.to_f64().abs();
-
-        if s == 0. && r == 0. {
-            ErrorPropagatingFloat {
-                value,
-                abs_err: self.abs_err * rhs.abs_err,
-            }
-        } else {
-            ErrorPropagatingFloat {
-                value,
-                abs_err: self.abs_err * r + rhs.abs_err * s,
-            }
-        }
-    }
-}
-
-impl<T: RealNumberLike + Add<Rational, Output = T>> Add<Rational> for ErrorPropagatingFloat<T> {
-    type Output = Self;
-
-    #[inline]
-    fn add(self, rhs: Rational) -> Self::Output {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err,
-            value: self.value + rhs,
-        }
-    }
-}
+        let bounds = [
+            (&self.lower, &rhs.lower),
+            (&self.lower, &rhs.upper),
+            (&self.upper, &rhs.lower),
+            (&self.upper, &rhs.upper),
+        ];
 
-impl<T: RealNumberLike + Add<Rational, Output = T>> Sub<Rational> for ErrorPropagatingFloat<T> {
-    type Output = Self;
+        let lower = bounds
+            .iter()
+            .map(|(a, b)| a.mul_round(b, Round::Down))
+            .reduce(Self::min_bound)
+            .unwrap();
+        let upper = bounds
+            .iter()
+            .map(|(a, b)| a.mul_round(b, Round::Up))
+            .reduce(Self::max_bound)
+            .unwrap();
 
-    #[inline]
-    fn sub(self, rhs: Rational) -> Self::Output {
-        self + -rhs
+        Interval { lower, upper }
     }
 }
 
-impl<T: RealNumberLike + Mul<Rational, Output = T>> Mul<Rational> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Mul<Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
-    fn mul(self, rhs: Rational) -> Self::Output {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * rhs.to_f64().abs(),
-            value: self.value * rhs,
-        }
-        .truncate()
+    fn mul(self, rhs: Self) -> Self::Output {
+        self * &rhs
     }
 }
 
-impl<T: RealNumberLike + Div<Rational, Output = T>> Div<Rational> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Div<&Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
-    fn div(self, rhs: Rational) -> Self::Output {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * rhs.inv().to_f64().abs(),
-            value: self.value.clone() / rhs,
+    fn div(self, rhs: &Self) -> Self::Output {
+        if rhs.contains(&rhs.lower.zero()) {
+            return self.entire();
         }
-        .truncate()
-    }
-}
 
-impl<T: RealNumberLike> Mul<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
-    type Output = Self;
-
-    #[inline]
-    fn mul(self, rhs: Self) -> Self::Output {
-        self * &rhs
-    }
-}
+        let bounds = [
+            (&self.lower, &rhs.lower),
+            (&self.lower, &rhs.upper),
+            (&self.upper, &rhs.lower),
+            (&self.upper, &rhs.upper),
+        ];
 
-impl<T: RealNumberLike> Div<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
-    type Output = Self;
+        let lower = bounds
+            .iter()
+            .map(|(a, b)| a.div_round(b, Round::Down))
+            .reduce(Self::min_bound)
+            .unwrap();
+        let upper = bounds
+            .iter()
+            .map(|(a, b)| a.div_round(b, Round::Up))
+            .reduce(Self::max_bound)
+            .unwrap();
 
-    #[inline]
-    fn div(self, rhs: &Self) -> Self::Output {
-        self * rhs.inv()
+        Interval { lower, upper }
     }
 }
 
-impl<T: RealNumberLike> Div<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Div<Interval<T>> for Interval<T> {
     type Output = Self;
 
     #[inline]
@@ -2257,187 +4264,63 @@ impl<T: RealNumberLike> Div<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<
     }
 }
 
-impl<T: RealNumberLike> AddAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> AddAssign<&Interval<T>> for Interval<T> {
     #[inline]
-    fn add_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
-        // TODO: optimize
+    fn add_assign(&mut self, rhs: &Interval<T>) {
         *self = self.clone() + rhs;
     }
 }
 
-impl<T: RealNumberLike> AddAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> AddAssign<Interval<T>> for Interval<T> {
     #[inline]
-    fn add_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+    fn add_assign(&mut self, rhs: Interval<T>) {
         self.add_assign(&rhs)
     }
 }
 
-impl<T: RealNumberLike> SubAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> SubAssign<&Interval<T>> for Interval<T> {
     #[inline]
-    fn sub_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
-        // TODO: optimize
+    fn sub_assign(&mut self, rhs: &Interval<T>) {
         *self = self.clone() - rhs;
     }
 }
 
-impl<T: RealNumberLike> SubAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> SubAssign<Interval<T>> for Interval<T> {
     #[inline]
-    fn sub_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+    fn sub_assign(&mut self, rhs: Interval<T>) {
         self.sub_assign(&rhs)
     }
 }
 
-impl<T: RealNumberLike> MulAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> MulAssign<&Interval<T>> for Interval<T> {
     #[inline]
-    fn mul_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
-        // TODO: optimize
+    fn mul_assign(&mut self, rhs: &Interval<T>) {
         *self = self.clone() * rhs;
     }
 }
 
-impl<T: RealNumberLike> MulAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> MulAssign<Interval<T>> for Interval<T> {
     #[inline]
-    fn mul_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+    fn mul_assign(&mut self, rhs: Interval<T>) {
         self.mul_assign(&rhs)
     }
 }
 
-impl<T: RealNumberLike> DivAssign<&ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> DivAssign<&Interval<T>> for Interval<T> {
     #[inline]
-    fn div_assign(&mut self, rhs: &ErrorPropagatingFloat<T>) {
-        // TODO: optimize
+    fn div_assign(&mut self, rhs: &Interval<T>) {
         *self = self.clone() / rhs;
     }
 }
 
-impl<T: RealNumberLike> DivAssign<ErrorPropagatingFloat<T>> for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> DivAssign<Interval<T>> for Interval<T> {
     #[inline]
-    fn div_assign(&mut self, rhs: ErrorPropagatingFloat<T>) {
+    fn div_assign(&mut self, rhs: Interval<T>) {
         self.div_assign(&rhs)
     }
 }
 
-impl<T: RealNumberLike> ErrorPropagatingFloat<T> {
-    /// Create a new precision tracking float with a number of precise decimal digits `prec`.
-    /// The `prec` must be smaller than the precision of the underlying float.
-    ///
-    /// If the value provided is 0, the precision argument is interpreted as an accuracy (
-    /// the number of digits of the absolute error).
-    pub fn new(value: T, prec: f64) -> Self {
-        let r = value.to_f64().abs();
-
-        if r == 0. {
-            ErrorPropagatingFloat {
-                abs_err: 10f64.pow(-prec),
-                value,
-            }
-        } else {
-            ErrorPropagatingFloat {
-                abs_err: 10f64.pow(-prec) * r,
-                value,
-            }
-        }
-    }
-
-    pub fn get_absolute_error(&self) -> f64 {
-        self.abs_err
-    }
-
-    pub fn get_relative_error(&self) -> f64 {
-        self.abs_err / self.value.to_f64().abs()
-    }
-
-    /// Get the precision in number of decimal digits.
-    #[inline(always)]
-    pub fn get_precision(&self) -> Option<f64> {
-        let r = self.value.to_f64().abs();
-        if r == 0. {
-            None
-        } else {
-            Some(-(self.abs_err / r).log10())
-        }
-    }
-
-    /// Get the accuracy in number of decimal digits.
-    #[inline(always)]
-    pub fn get_accuracy(&self) -> f64 {
-        -self.abs_err.log10()
-    }
-
-    /// Truncate the precision to the maximal number of stable decimal digits
-    /// of the underlying float.
-    #[inline(always)]
-    pub fn truncate(mut self) -> Self {
-        if self.value.fixed_precision() {
-            self.abs_err = self
-                .abs_err
-                .max(self.value.get_epsilon() * self.value.to_f64());
-        }
-        self
-    }
-}
-
-impl<T: NumericalFloatLike> ErrorPropagatingFloat<T> {
-    pub fn new_with_accuracy(value: T, acc: f64) -> Self {
-        ErrorPropagatingFloat {
-            value,
-            abs_err: 10f64.pow(-acc),
-        }
-    }
-
-    /// Get the number.
-    #[inline(always)]
-    pub fn get_num(&self) -> &T {
-        &self.value
-    }
-}
-
-impl<T: RealNumberLike> fmt::Display for ErrorPropagatingFloat<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        if let Some(p) = self.get_precision() {
-            if p < 0. {
-                f.write_char('0')
-            } else {
-                f.write_fmt(format_args!("{0:.1$}", self.value, p as usize))
-            }
-        } else {
-            f.write_char('0')
-        }
-    }
-}
-
-impl<T: RealNumberLike> Debug for ErrorPropagatingFloat<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Debug::fmt(&self.value, f)?;
-
-        if let Some(p) = self.get_precision() {
-            f.write_fmt(format_args!("`{:.2}", p))
-        } else {
-            f.write_fmt(format_args!("``{:.2}", -self.abs_err.log10()))
-        }
-    }
-}
-
-impl<T: RealNumberLike> LowerExp for ErrorPropagatingFloat<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt(self, f)
-    }
-}
-
-impl<T: NumericalFloatLike> PartialEq for ErrorPropagatingFloat<T> {
-    fn eq(&self, other: &Self) -> bool {
-        // TODO: ignore precision for partial equality?
-        self.value == other.value
-    }
-}
-
-impl<T: NumericalFloatLike + PartialOrd> PartialOrd for ErrorPropagatingFloat<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.value.partial_cmp(&other.value)
-    }
-}
-
-impl<T: RealNumberLike> NumericalFloatLike for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> NumericalFloatLike for Interval<T> {
     fn mul_add(&self, a: &Self, b: &Self) -> Self {
         self.clone() * a + b
     }
@@ -2447,369 +4330,296 @@ impl<T: RealNumberLike> NumericalFloatLike for ErrorPropagatingFloat<T> {
     }
 
     fn zero(&self) -> Self {
-        ErrorPropagatingFloat {
-            value: self.value.zero(),
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
-        }
+        self.lower.zero().into()
     }
 
     fn new_zero() -> Self {
-        ErrorPropagatingFloat {
-            value: T::new_zero(),
-            abs_err: 2f64.powi(-53),
-        }
-    }
-
-    fn one(&self) -> Self {
-        ErrorPropagatingFloat {
-            value: self.value.one(),
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
-        }
+        T::new_zero().into()
     }
 
-    fn pow(&self, e: u64) -> Self {
-        let i = self.to_f64().abs();
-
-        if i == 0. {
-            return ErrorPropagatingFloat {
-                value: self.value.pow(e),
-                abs_err: self.abs_err.pow(e as f64),
-            };
-        }
-
-        let r = self.value.pow(e);
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * e as f64 * r.to_f64().abs() / i,
-            value: r,
+    fn one(&self) -> Self {
+        self.lower.one().into()
+    }
+
+    fn pow(&self, e: u64) -> Self {
+        if e % 2 == 0 {
+            self.norm()
+                .increasing(|x, round| Self::pow_bound(x, e, round))
+        } else {
+            self.increasing(|x, round| Self::pow_bound(x, e, round))
         }
     }
 
     fn inv(&self) -> Self {
-        let r = self.value.inv();
-        let rr = r.to_f64().abs();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * rr * rr,
-            value: r,
-        }
+        self.one() / self
     }
 
-    /// Convert from a `usize`.
     fn from_usize(&self, a: usize) -> Self {
-        let v = self.value.from_usize(a);
-        let r = v.to_f64().abs();
-        if r == 0. {
-            ErrorPropagatingFloat {
-                value: v,
-                abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
-            }
-        } else {
-            ErrorPropagatingFloat {
-                value: v,
-                abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * r,
-            }
-        }
+        self.from_rational(&(a as u64).into())
     }
 
-    /// Convert from a `i64`.
     fn from_i64(&self, a: i64) -> Self {
-        let v = self.value.from_i64(a);
-        let r = v.to_f64().abs();
-        if r == 0. {
-            ErrorPropagatingFloat {
-                value: v,
-                abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
-            }
-        } else {
-            ErrorPropagatingFloat {
-                value: v,
-                abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * r,
-            }
-        }
+        self.from_rational(&a.into())
     }
 
     fn get_precision(&self) -> u32 {
-        // return the precision of the underlying float instead
-        // of the current tracked precision
-        self.value.get_precision()
+        self.lower.get_precision()
     }
 
     fn get_epsilon(&self) -> f64 {
-        2.0f64.powi(-(self.value.get_precision() as i32))
+        self.lower.get_epsilon()
     }
 
     #[inline(always)]
     fn fixed_precision(&self) -> bool {
-        self.value.fixed_precision()
+        self.lower.fixed_precision()
     }
 
     fn sample_unit<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
-        let v = self.value.sample_unit(rng);
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * v.to_f64().abs(),
-            value: v,
-        }
+        self.lower.sample_unit(rng).into()
     }
 }
 
-impl<T: RealNumberLike> SingleFloat for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> SingleFloat for Interval<T> {
     fn is_zero(&self) -> bool {
-        self.value.is_zero()
+        self.lower.is_zero() && self.upper.is_zero()
     }
 
     fn is_one(&self) -> bool {
-        self.value.is_one()
+        self.lower.is_one() && self.upper.is_one()
     }
 
     fn is_finite(&self) -> bool {
-        self.value.is_finite()
+        self.lower.is_finite() && self.upper.is_finite()
     }
 
     fn from_rational(&self, rat: &Rational) -> Self {
-        if rat.is_zero() {
-            ErrorPropagatingFloat {
-                value: self.value.from_rational(rat),
-                abs_err: self.abs_err,
-            }
-        } else {
-            ErrorPropagatingFloat {
-                value: self.value.from_rational(rat),
-                abs_err: self.abs_err * rat.to_f64(),
-            }
+        Interval {
+            lower: self.lower.rational_round(rat, Round::Down),
+            upper: self.lower.rational_round(rat, Round::Up),
         }
     }
 }
 
-impl<T: RealNumberLike> RealNumberLike for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> RealNumberLike for Interval<T> {
     fn to_usize_clamped(&self) -> usize {
-        self.value.to_usize_clamped()
+        self.midpoint().to_usize_clamped()
     }
 
     fn to_f64(&self) -> f64 {
-        self.value.to_f64()
+        self.midpoint().to_f64()
     }
 
     fn round_to_nearest_integer(&self) -> Integer {
-        // TODO: what does this do with the error?
-        self.value.round_to_nearest_integer()
+        self.midpoint().round_to_nearest_integer()
     }
 }
 
-impl<T: Real + RealNumberLike> Real for ErrorPropagatingFloat<T> {
+impl<T: IntervalBound> Real for Interval<T> {
     fn pi(&self) -> Self {
-        let v = self.value.pi();
-        ErrorPropagatingFloat {
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
-            value: v,
-        }
+        self.constant(T::pi_round)
     }
 
     fn e(&self) -> Self {
-        let v = self.value.e();
-        ErrorPropagatingFloat {
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
-            value: v,
-        }
+        self.constant(T::e_round)
     }
 
     fn euler(&self) -> Self {
-        let v = self.value.euler();
-        ErrorPropagatingFloat {
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
-            value: v,
-        }
+        self.constant(T::euler_round)
     }
 
     fn phi(&self) -> Self {
-        let v = self.value.phi();
-        ErrorPropagatingFloat {
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)) * v.to_f64(),
-            value: v,
-        }
+        self.constant(T::phi_round)
     }
 
     #[inline(always)]
     fn i(&self) -> Option<Self> {
-        Some(ErrorPropagatingFloat {
-            value: self.value.i()?,
-            abs_err: 2f64.pow(-(self.value.get_precision() as f64)),
-        })
+        None
     }
 
     fn norm(&self) -> Self {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err,
-            value: self.value.norm(),
+        let zero = self.lower.zero();
+        if self.lower >= zero {
+            self.clone()
+        } else if self.upper <= zero {
+            -self.clone()
+        } else {
+            Interval {
+                lower: zero,
+                upper: Self::max_bound(-self.lower.clone(), self.upper.clone()),
+            }
         }
     }
 
     fn sqrt(&self) -> Self {
-        let v = self.value.sqrt();
-        let r = v.to_f64().abs();
-
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / (2. * r),
-            value: v,
-        }
-        .truncate()
+        let zero = self.lower.zero();
+        let inf = zero.infinity();
+        self.clamp(zero, inf).increasing(T::sqrt_round)
     }
 
     fn log(&self) -> Self {
-        let r = self.value.log();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / self.value.to_f64().abs(),
-            value: r,
+        let zero = self.lower.zero();
+        let r = self.increasing(T::log_round);
+        if self.lower <= zero {
+            Interval {
+                lower: -zero.infinity(),
+                upper: r.upper,
+            }
+        } else {
+            r
         }
-        .truncate()
     }
 
     fn exp(&self) -> Self {
-        let v = self.value.exp();
-        ErrorPropagatingFloat {
-            abs_err: v.to_f64().abs() * self.abs_err,
-            value: v,
-        }
-        .truncate()
+        self.increasing(T::exp_round)
     }
 
     fn sin(&self) -> Self {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * self.value.to_f64().cos().abs(),
-            value: self.value.sin(),
+        let one = self.lower.one();
+        let two_pi = self.pi() * &self.from_i64(2);
+        if self.width() >= two_pi.lower {
+            return Interval {
+                lower: -one.clone(),
+                upper: one,
+            };
         }
-        .truncate()
+
+        let mut r = Interval {
+            lower: Self::min_bound(
+                self.lower.sin_round(Round::Down),
+                self.upper.sin_round(Round::Down),
+            ),
+            upper: Self::max_bound(
+                self.lower.sin_round(Round::Up),
+                self.upper.sin_round(Round::Up),
+            ),
+        };
+
+        if self.contains_periodic_point(1) {
+            r.upper = one.clone();
+        }
+        if self.contains_periodic_point(3) {
+            r.lower = -one.clone();
+        }
+
+        r.clamp(-one.clone(), one)
     }
 
     fn cos(&self) -> Self {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * self.value.to_f64().sin().abs(),
-            value: self.value.cos(),
+        let one = self.lower.one();
+        let two_pi = self.pi() * &self.from_i64(2);
+        if self.width() >= two_pi.lower {
+            return Interval {
+                lower: -one.clone(),
+                upper: one,
+            };
         }
-        .truncate()
+
+        let mut r = Interval {
+            lower: Self::min_bound(
+                self.lower.cos_round(Round::Down),
+                self.upper.cos_round(Round::Down),
+            ),
+            upper: Self::max_bound(
+                self.lower.cos_round(Round::Up),
+                self.upper.cos_round(Round::Up),
+            ),
+        };
+
+        if self.contains_periodic_point(0) {
+            r.upper = one.clone();
+        }
+        if self.contains_periodic_point(2) {
+            r.lower = -one.clone();
+        }
+
+        r.clamp(-one.clone(), one)
     }
 
     fn tan(&self) -> Self {
-        let t = self.value.tan();
-        let tt = t.to_f64().abs();
-
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * (1. + tt * tt),
-            value: t,
+        // tan has a pole at π/2 + kπ
+        if self.width() >= self.pi().lower
+            || self.contains_periodic_point(1)
+            || self.contains_periodic_point(3)
+        {
+            return self.entire();
         }
-        .truncate()
+
+        self.increasing(T::tan_round)
     }
 
     fn asin(&self) -> Self {
-        let v = self.value.to_f64();
-        let t = self.value.asin();
-        let tt = (1. - v * v).sqrt();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / tt,
-            value: t,
-        }
-        .truncate()
+        let one = self.lower.one();
+        self.clamp(-one.clone(), one).increasing(T::asin_round)
     }
 
     fn acos(&self) -> Self {
-        let v = self.value.to_f64();
-        let t = self.value.acos();
-        let tt = (1. - v * v).sqrt();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / tt,
-            value: t,
-        }
-        .truncate()
+        let one = self.lower.one();
+        self.clamp(-one.clone(), one).decreasing(T::acos_round)
     }
 
     fn atan2(&self, x: &Self) -> Self {
-        let t = self.value.atan2(&x.value);
-        let r = self.clone() / x;
-        let r2 = r.value.to_f64().abs();
-
-        let tt = 1. + r2 * r2;
-        ErrorPropagatingFloat {
-            abs_err: r.abs_err / tt,
-            value: t,
+        let zero = self.lower.zero();
+        let pi = self.pi();
+        let half_pi = pi.clone() / &self.from_i64(2);
+
+        if x.lower > zero {
+            (self.clone() / x).increasing(T::atan_round)
+        } else if self.lower > zero {
+            half_pi - &(x.clone() / self).increasing(T::atan_round)
+        } else if self.upper < zero {
+            -half_pi - &(x.clone() / self).increasing(T::atan_round)
+        } else if x.upper < zero && self.lower >= zero {
+            pi + &(self.clone() / x).increasing(T::atan_round)
+        } else {
+            // the interval contains the origin or crosses the branch cut
+            Interval {
+                lower: -pi.upper.clone(),
+                upper: pi.upper,
+            }
         }
-        .truncate()
     }
 
     fn sinh(&self) -> Self {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * self.value.cosh().to_f64().abs(),
-            value: self.value.sinh(),
-        }
-        .truncate()
+        self.increasing(T::sinh_round)
     }
 
     fn cosh(&self) -> Self {
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * self.value.sinh().to_f64().abs(),
-            value: self.value.cosh(),
-        }
-        .truncate()
+        let n = self.norm();
+        n.increasing(T::cosh_round)
     }
 
     fn tanh(&self) -> Self {
-        let t = self.value.tanh();
-        let tt = t.clone().to_f64().abs();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err * (1. - tt * tt),
-            value: t,
-        }
-        .truncate()
+        self.increasing(T::tanh_round)
     }
 
     fn asinh(&self) -> Self {
-        let v = self.value.to_f64();
-        let t = self.value.asinh();
-        let tt = (1. + v * v).sqrt();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / tt,
-            value: t,
-        }
-        .truncate()
+        self.increasing(T::asinh_round)
     }
 
     fn acosh(&self) -> Self {
-        let v = self.value.to_f64();
-        let t = self.value.acosh();
-        let tt = (v * v - 1.).sqrt();
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / tt,
-            value: t,
-        }
-        .truncate()
+        let one = self.lower.one();
+        let inf = one.infinity();
+        self.clamp(one, inf).increasing(T::acosh_round)
     }
 
     fn atanh(&self) -> Self {
-        let v = self.value.to_f64();
-        let t = self.value.atanh();
-        let tt = 1. - v * v;
-        ErrorPropagatingFloat {
-            abs_err: self.abs_err / tt,
-            value: t,
-        }
-        .truncate()
+        let one = self.lower.one();
+        self.clamp(-one.clone(), one).increasing(T::atanh_round)
     }
 
     fn powf(&self, e: &Self) -> Self {
-        let i = self.to_f64().abs();
-
-        if i == 0. {
-            return ErrorPropagatingFloat {
-                value: self.value.powf(&e.value),
-                abs_err: 0.,
-            };
+        if e.lower == e.upper && e.lower.is_finite() {
+            let n = e.lower.round_to_nearest_integer();
+            if e.lower.from_rational(&(&n).into()) == e.lower {
+                if let Some(n) = n.to_i64() {
+                    let r = self.pow(n.unsigned_abs());
+                    return if n < 0 { r.inv() } else { r };
+                }
+            }
         }
 
-        let r = self.value.powf(&e.value);
-        ErrorPropagatingFloat {
-            abs_err: (self.abs_err * e.value.to_f64() + i * e.abs_err * i.ln().abs())
-                * r.to_f64().abs()
-                / i,
-            value: r,
-        }
-        .truncate()
+        (e.clone() * &self.log()).exp()
     }
 }
 
@@ -2961,32 +4771,32 @@ macro_rules! simd_impl {
 
             #[inline(always)]
             fn sinh(&self) -> Self {
-                unimplemented!("Hyperbolic geometric functions are not supported with SIMD");
+                <$t>::new((*self).to_array().map(f64::sinh))
             }
 
             #[inline(always)]
             fn cosh(&self) -> Self {
-                unimplemented!("Hyperbolic geometric functions are not supported with SIMD");
+                <$t>::new((*self).to_array().map(f64::cosh))
             }
 
             #[inline(always)]
             fn tanh(&self) -> Self {
-                unimplemented!("Hyperbolic geometric functions are not supported with SIMD");
+                <$t>::new((*self).to_array().map(f64::tanh))
             }
 
             #[inline(always)]
             fn asinh(&self) -> Self {
-                unimplemented!("Hyperbolic geometric functions are not supported with SIMD");
+                <$t>::new((*self).to_array().map(f64::asinh))
             }
 
             #[inline(always)]
             fn acosh(&self) -> Self {
-                unimplemented!("Hyperbolic geometric functions are not supported with SIMD");
+                <$t>::new((*self).to_array().map(f64::acosh))
             }
 
             #[inline(always)]
             fn atanh(&self) -> Self {
-                unimplemented!("Hyperbolic geometric functions are not supported with SIMD");
+                <$t>::new((*self).to_array().map(f64::atanh))
             }
 
             #[inline(always)]
@@ -4224,6 +6034,95 @@ mod test {
         assert_eq!(c.get_precision(), 57);
     }
 
+    #[test]
+    fn interval() {
+        let a = Interval::from(0.1f64).from_rational(&(1, 10).into());
+        assert!(a.lower() < a.upper());
+
+        let b = Interval::new(-2., 3.);
+        assert_eq!(b.pow(2), Interval::new(0., 9.));
+        assert_eq!(b.pow(3), Interval::new(-8., 27.));
+        assert_eq!(b.norm(), Interval::new(0., 3.));
+        assert_eq!(b.sin(), Interval::new(-1., 1.));
+        assert!(b.inv().lower().is_infinite());
+
+        let c = Interval::new(1., 2.).cos();
+        assert!(c.upper() >= &1f64.cos() && c.lower() <= &2f64.cos());
+        assert!(c.upper() < &1.);
+
+        let c = Interval::new(2., 4.).cos();
+        assert_eq!(c.lower(), &-1.);
+
+        let x = Rational::from((3, 7));
+        let exact = x.to_multi_prec_float(300);
+        let exact = (exact.clone() * exact.sin() + exact.exp()) / exact.sqrt();
+
+        let xi = Interval::from(Float::new(100)).from_rational(&x);
+        let r = (xi.clone() * xi.sin() + xi.exp()) / xi.sqrt();
+        assert!(r.contains(&exact));
+        assert!(r.width().to_f64() < 1e-28);
+
+        let xi = Interval::from(0.).from_rational(&x);
+        let r = (xi * xi.sin() + xi.exp()) / xi.sqrt();
+        assert!(*r.lower() <= exact.to_f64() && exact.to_f64() <= *r.upper());
+    }
+
+    #[test]
+    fn double_double() {
+        let check = |a: DoubleDouble, b: Float| {
+            let err = (a.to_float() - b.clone()).norm() / b.norm();
+            assert!(err.to_f64() < 1e-30, "{} vs {}: {}", a, b, err.to_f64());
+        };
+
+        for x in [
+            Rational::from((3, 7)),
+            Rational::from((-17, 5)),
+            Rational::from((123456789, 1000)),
+            Rational::from((1, 300)),
+        ] {
+            // compare with the exact value of the double-double
+            let a = DoubleDouble::from(&x);
+            let mut b = a.to_float();
+            b.set_prec(300);
+            let (ap, bp) = (a.norm(), b.norm());
+
+            check(a, x.to_multi_prec_float(300));
+            check(
+                a * a / (a + a.one()),
+                b.clone() * &b / (b.clone() + b.one()),
+            );
+            check(ap.sqrt(), bp.sqrt());
+            check(ap.log(), bp.log());
+            check(a.sin(), b.sin());
+            check(a.cos(), b.cos());
+            check(a.tan(), b.tan());
+            check(a.pow(5), b.pow(5));
+            check(ap.powf(&a.sin()), bp.powf(&b.sin()));
+            check(a.atan2(&ap), b.atan2(&bp));
+            check(a.asinh(), b.asinh());
+            check((ap + ap.one()).acosh(), (bp.clone() + bp.one()).acosh());
+
+            let a = a / (ap + ap.one());
+            let mut b = a.to_float();
+            b.set_prec(300);
+            check(a.exp(), b.exp());
+            check(a.asin(), b.asin());
+            check(a.acos(), b.acos());
+            check(a.sinh(), b.sinh());
+            check(a.cosh(), b.cosh());
+            check(a.tanh(), b.tanh());
+            check(a.atanh(), b.atanh());
+        }
+
+        let a = DoubleDouble::from(&Rational::from((5, 2)));
+        assert_eq!(a.round_to_nearest_integer(), Integer::from(3));
+        assert_eq!((-a).round_to_nearest_integer(), Integer::from(-3));
+        assert_eq!(
+            DoubleDouble::new_from_i64(i64::MAX).to_float(),
+            Float::with_val(107, i64::MAX)
+        );
+    }
+
     #[cfg(feature = "bincode")]
     #[test]
     fn bincode_export() {
@@ -1286,9 +1286,12 @@ where
             // TODO: use resultant_prs instead?
             let r = p.resultant(&b);
 
-            // drop the denominator as it is constant in x
-            let mut sqf = r.numerator.square_free_factorization();
-            sqf.retain(|(x, _)| !x.is_constant());
+            // drop the denominator as it is constant in x, and the content that does
+            // not depend on t, such as the parameters of the leading coefficient
+            let content = r.numerator.univariate_content(new_var);
+            let r_num = r.numerator.try_div(&content).unwrap();
+            let mut sqf = r_num.square_free_factorization();
+            sqf.retain(|(x, _)| x.degree(new_var) > E::zero());
 
             let factors: Vec<(Vec<_>, _, _)> = sqf
                 .into_iter()
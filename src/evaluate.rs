@@ -2,7 +2,11 @@
 //!
 //! The main entry point is through [AtomCore::evaluator].
 
+#[cfg(all(target_arch = "x86_64", unix))]
+pub mod jit;
+
 use std::{
+    f64::consts::LOG10_2,
     hash::{Hash, Hasher},
     os::raw::c_ulong,
     sync::{
@@ -13,6 +17,7 @@ use std::{
 
 use ahash::{AHasher, HashMap};
 use rand::Rng;
+use wide::f64x4;
 
 use self_cell::self_cell;
 
@@ -24,8 +29,8 @@ use crate::{
     domains::{
         InternalOrdering,
         float::{
-            Complex, ConstructibleFloat, ErrorPropagatingFloat, F64, Float, NumericalFloatLike,
-            Real, RealNumberLike, SingleFloat,
+            Complex, ConstructibleFloat, DoubleDouble, ErrorPropagatingFloat, F64, Float,
+            NumericalFloatLike, Real, RealNumberLike, SingleFloat,
         },
         integer::Integer,
         rational::Rational,
@@ -251,7 +256,8 @@ impl serde::Serialize for BuiltinSymbol {
 impl<'de> serde::Deserialize<'de> for BuiltinSymbol {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let id: u32 = u32::deserialize(deserializer)?;
-        Ok(BuiltinSymbol(unsafe { State::symbol_from_id(id) }))
+        BuiltinSymbol::from_id(id)
+            .ok_or_else(|| serde::de::Error::custom(format!("Unknown built-in symbol id {}", id)))
     }
 }
 
@@ -273,14 +279,107 @@ impl<Context> bincode::Decode<Context> for BuiltinSymbol {
         decoder: &mut D,
     ) -> Result<Self, bincode::error::DecodeError> {
         let id: u32 = u32::decode(decoder)?;
-        Ok(BuiltinSymbol(unsafe { State::symbol_from_id(id) }))
+        BuiltinSymbol::from_id(id).ok_or_else(|| {
+            bincode::error::DecodeError::OtherString(format!("Unknown built-in symbol id {}", id))
+        })
     }
 }
 
 impl BuiltinSymbol {
+    /// The built-in functions that can be evaluated natively.
+    pub const FUNCTIONS: [Symbol; 15] = [
+        Atom::EXP,
+        Atom::LOG,
+        Atom::SIN,
+        Atom::COS,
+        Atom::SQRT,
+        Atom::TAN,
+        Atom::ASIN,
+        Atom::ACOS,
+        Atom::ATAN,
+        Atom::SINH,
+        Atom::COSH,
+        Atom::TANH,
+        Atom::ASINH,
+        Atom::ACOSH,
+        Atom::ATANH,
+    ];
+
+    /// Get the built-in function with the given symbol id.
+    #[cfg(any(feature = "serde", feature = "bincode"))]
+    fn from_id(id: u32) -> Option<BuiltinSymbol> {
+        Self::FUNCTIONS
+            .iter()
+            .find(|s| s.get_id() == id)
+            .map(|s| BuiltinSymbol(*s))
+    }
+
+    /// Create a built-in symbol from `symbol`, if it is a function
+    /// that can be evaluated natively.
+    pub fn new(symbol: Symbol) -> Option<BuiltinSymbol> {
+        if Self::FUNCTIONS.contains(&symbol) {
+            Some(BuiltinSymbol(symbol))
+        } else {
+            None
+        }
+    }
+
     pub fn get_symbol(&self) -> Symbol {
         self.0
     }
+
+    /// Evaluate the built-in function at `arg`.
+    #[inline]
+    pub fn evaluate<T: Real>(&self, arg: &T) -> T {
+        match self.0 {
+            Atom::EXP => arg.exp(),
+            Atom::LOG => arg.log(),
+            Atom::SIN => arg.sin(),
+            Atom::COS => arg.cos(),
+            Atom::SQRT => arg.sqrt(),
+            Atom::TAN => arg.tan(),
+            Atom::ASIN => arg.asin(),
+            Atom::ACOS => arg.acos(),
+            Atom::ATAN => arg.atan2(&arg.one()),
+            Atom::SINH => arg.sinh(),
+            Atom::COSH => arg.cosh(),
+            Atom::TANH => arg.tanh(),
+            Atom::ASINH => arg.asinh(),
+            Atom::ACOSH => arg.acosh(),
+            Atom::ATANH => arg.atanh(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the name of the function in the C++ standard library.
+    pub fn cpp_name(&self) -> &'static str {
+        match self.0 {
+            Atom::EXP => "exp",
+            Atom::LOG => "log",
+            Atom::SIN => "sin",
+            Atom::COS => "cos",
+            Atom::SQRT => "sqrt",
+            Atom::TAN => "tan",
+            Atom::ASIN => "asin",
+            Atom::ACOS => "acos",
+            Atom::ATAN => "atan",
+            Atom::SINH => "sinh",
+            Atom::COSH => "cosh",
+            Atom::TANH => "tanh",
+            Atom::ASINH => "asinh",
+            Atom::ACOSH => "acosh",
+            Atom::ATANH => "atanh",
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the name of the method that evaluates the function on `f64` in Rust.
+    pub fn rust_name(&self) -> &'static str {
+        match self.0 {
+            Atom::LOG => "ln",
+            _ => self.cpp_name(),
+        }
+    }
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -728,10 +827,16 @@ impl<T: std::hash::Hash + Clone> Expression<T> {
     }
 }
 
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
-#[derive(Clone, PartialEq, Debug)]
+/// The version of the format in which an [ExpressionEvaluator] is serialized.
+/// Evaluators serialized with a different version cannot be deserialized.
+pub const EVALUATOR_FORMAT_VERSION: u16 = 1;
 
+/// An optimized evaluator of expressions.
+///
+/// With the `serde` or `bincode` feature enabled, the evaluator can be serialized in a versioned
+/// format (see [EVALUATOR_FORMAT_VERSION]), so that the result of an expensive optimization
+/// can be stored on disk and loaded later.
+#[derive(Clone, PartialEq, Debug)]
 pub struct ExpressionEvaluator<T> {
     stack: Vec<T>,
     param_count: usize,
@@ -746,6 +851,149 @@ impl<T: SingleFloat> ExpressionEvaluator<Complex<T>> {
     }
 }
 
+impl<T> ExpressionEvaluator<T> {
+    /// Check that all indices of the evaluator refer to a slot in the stack,
+    /// which may not be the case for a corrupted serialized evaluator.
+    #[cfg(any(feature = "serde", feature = "bincode"))]
+    fn validate(&self) -> Result<(), String> {
+        let len = self.stack.len();
+        if self.param_count > self.reserved_indices || self.reserved_indices > len {
+            return Err(format!(
+                "Invalid evaluator: {} parameters and {} reserved indices for a stack of length {}",
+                self.param_count, self.reserved_indices, len
+            ));
+        }
+
+        for i in &self.instructions {
+            let valid = match i {
+                Instr::Add(r, a) | Instr::Mul(r, a) => {
+                    *r < len && !a.is_empty() && a.iter().all(|x| *x < len)
+                }
+                Instr::Pow(r, b, _) => *r < len && *b < len,
+                Instr::Powf(r, b, e) => *r < len && *b < len && *e < len,
+                Instr::BuiltinFun(r, s, a) => {
+                    *r < len && *a < len && BuiltinSymbol::FUNCTIONS.contains(&s.0)
+                }
+            };
+
+            if !valid {
+                return Err(format!("Invalid evaluator instruction: {:?}", i));
+            }
+        }
+
+        if let Some(r) = self.result_indices.iter().find(|r| **r >= len) {
+            return Err(format!(
+                "Invalid evaluator: result index {} out of bounds for a stack of length {}",
+                r, len
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for ExpressionEvaluator<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("ExpressionEvaluator", 6)?;
+        s.serialize_field("version", &EVALUATOR_FORMAT_VERSION)?;
+        s.serialize_field("stack", &self.stack)?;
+        s.serialize_field("param_count", &self.param_count)?;
+        s.serialize_field("reserved_indices", &self.reserved_indices)?;
+        s.serialize_field("instructions", &self.instructions)?;
+        s.serialize_field("result_indices", &self.result_indices)?;
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for ExpressionEvaluator<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "ExpressionEvaluator")]
+        struct SerializedEvaluator<T> {
+            version: u16,
+            stack: Vec<T>,
+            param_count: usize,
+            reserved_indices: usize,
+            instructions: Vec<Instr>,
+            result_indices: Vec<usize>,
+        }
+
+        let e = SerializedEvaluator::deserialize(deserializer)?;
+        if e.version != EVALUATOR_FORMAT_VERSION {
+            return Err(serde::de::Error::custom(format!(
+                "Unsupported evaluator format version {}, expected {}",
+                e.version, EVALUATOR_FORMAT_VERSION
+            )));
+        }
+
+        let eval = ExpressionEvaluator {
+            stack: e.stack,
+            param_count: e.param_count,
+            reserved_indices: e.reserved_indices,
+            instructions: e.instructions,
+            result_indices: e.result_indices,
+        };
+        eval.validate().map_err(serde::de::Error::custom)?;
+        Ok(eval)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<T: bincode::Encode> bincode::Encode for ExpressionEvaluator<T> {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        EVALUATOR_FORMAT_VERSION.encode(encoder)?;
+        self.stack.encode(encoder)?;
+        self.param_count.encode(encoder)?;
+        self.reserved_indices.encode(encoder)?;
+        self.instructions.encode(encoder)?;
+        self.result_indices.encode(encoder)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<Context, T: bincode::Decode<Context>> bincode::Decode<Context> for ExpressionEvaluator<T> {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let version = u16::decode(decoder)?;
+        if version != EVALUATOR_FORMAT_VERSION {
+            return Err(bincode::error::DecodeError::OtherString(format!(
+                "Unsupported evaluator format version {}, expected {}",
+                version, EVALUATOR_FORMAT_VERSION
+            )));
+        }
+
+        let eval = ExpressionEvaluator {
+            stack: Vec::decode(decoder)?,
+            param_count: usize::decode(decoder)?,
+            reserved_indices: usize::decode(decoder)?,
+            instructions: Vec::decode(decoder)?,
+            result_indices: Vec::decode(decoder)?,
+        };
+        eval.validate()
+            .map_err(bincode::error::DecodeError::OtherString)?;
+        Ok(eval)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<'de, Context, T: bincode::Decode<Context>> bincode::BorrowDecode<'de, Context>
+    for ExpressionEvaluator<T>
+{
+    fn borrow_decode<D: bincode::de::BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        bincode::Decode::decode(decoder)
+    }
+}
+
 impl<T: Real> ExpressionEvaluator<T> {
     pub fn evaluate_single(&mut self, params: &[T]) -> T {
         let mut res = T::new_zero();
@@ -758,48 +1006,307 @@ impl<T: Real> ExpressionEvaluator<T> {
             *t = p.clone();
         }
 
-        let mut tmp;
-        for i in &self.instructions {
-            match i {
-                Instr::Add(r, v) => {
-                    tmp = self.stack[v[0]].clone();
-                    for x in &v[1..] {
-                        let e = self.stack[*x].clone();
-                        tmp += e;
-                    }
-                    std::mem::swap(&mut self.stack[*r], &mut tmp);
-                }
-                Instr::Mul(r, v) => {
-                    tmp = self.stack[v[0]].clone();
-                    for x in &v[1..] {
-                        let e = self.stack[*x].clone();
-                        tmp *= e;
-                    }
-                    std::mem::swap(&mut self.stack[*r], &mut tmp);
+        evaluate_instructions(&self.instructions, &mut self.stack);
+
+        for (o, i) in out.iter_mut().zip(&self.result_indices) {
+            *o = self.stack[*i].clone();
+        }
+    }
+}
+
+/// The number of points that are evaluated at once by the batch
+/// functions of exported C++ code.
+const BATCH_LANES: usize = 4;
+
+/// A number type that can be evaluated on multiple points at once
+/// using SIMD lanes, with [ExpressionEvaluator::evaluate_batch].
+///
+/// `f64` and `Complex<f64>` use four lanes of `f64x4`. An eight-lane `f64x8`
+/// is not supported, since the `wide` version Symbolica depends on does not provide it.
+pub trait BatchEvaluatorFloat: Real {
+    /// The SIMD type that holds one number per lane.
+    type Lanes: Real;
+    /// The number of lanes.
+    const LANES: usize;
+
+    /// Create a SIMD number with `self` in every lane.
+    fn splat(&self) -> Self::Lanes;
+    /// Create a SIMD number from exactly [Self::LANES] numbers.
+    fn pack(values: &[Self]) -> Self::Lanes;
+    /// Write the [Self::LANES] lanes of `lanes` into `out`.
+    fn unpack(lanes: &Self::Lanes, out: &mut [Self]);
+}
+
+impl BatchEvaluatorFloat for f64 {
+    type Lanes = f64x4;
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    fn splat(&self) -> f64x4 {
+        f64x4::splat(*self)
+    }
+
+    #[inline(always)]
+    fn pack(values: &[Self]) -> f64x4 {
+        f64x4::new([values[0], values[1], values[2], values[3]])
+    }
+
+    #[inline(always)]
+    fn unpack(lanes: &f64x4, out: &mut [Self]) {
+        out[..4].copy_from_slice(&lanes.to_array());
+    }
+}
+
+impl BatchEvaluatorFloat for Complex<f64> {
+    type Lanes = Complex<f64x4>;
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    fn splat(&self) -> Complex<f64x4> {
+        Complex::new(f64x4::splat(self.re), f64x4::splat(self.im))
+    }
+
+    #[inline(always)]
+    fn pack(values: &[Self]) -> Complex<f64x4> {
+        Complex::new(
+            f64x4::new([values[0].re, values[1].re, values[2].re, values[3].re]),
+            f64x4::new([values[0].im, values[1].im, values[2].im, values[3].im]),
+        )
+    }
+
+    #[inline(always)]
+    fn unpack(lanes: &Complex<f64x4>, out: &mut [Self]) {
+        for ((o, re), im) in out
+            .iter_mut()
+            .zip(lanes.re.to_array())
+            .zip(lanes.im.to_array())
+        {
+            *o = Complex::new(re, im);
+        }
+    }
+}
+
+impl<T: BatchEvaluatorFloat> ExpressionEvaluator<T> {
+    /// Evaluate the expression at `n_points` points, processing [BatchEvaluatorFloat::LANES]
+    /// points at the same time using SIMD instructions.
+    ///
+    /// The parameters of point `i` are `params[i * param_count..(i + 1) * param_count]` and
+    /// its results are written to `out[i * output_len..(i + 1) * output_len]`.
+    ///
+    /// # Example
+    /// ```
+    /// # use symbolica::{atom::AtomCore, evaluate::{FunctionMap, OptimizationSettings}, parse};
+    /// let e = parse!("x^2 + y");
+    /// let eval = e
+    ///     .evaluator(&FunctionMap::new(), &[parse!("x"), parse!("y")], OptimizationSettings::default())
+    ///     .unwrap();
+    /// let mut eval = eval.map_coeff(&|x| x.re.to_f64());
+    ///
+    /// let params = [1., 2., 3., 4., 5., 6.];
+    /// let mut out = [0.; 3];
+    /// eval.evaluate_batch(&params, 3, &mut out);
+    /// assert_eq!(out, [3., 13., 31.]);
+    /// ```
+    pub fn evaluate_batch(&mut self, params: &[T], n_points: usize, out: &mut [T]) {
+        let out_len = self.result_indices.len();
+        assert!(
+            params.len() >= n_points * self.param_count && out.len() >= n_points * out_len,
+            "Parameter or output buffer too small for {} points",
+            n_points
+        );
+
+        let mut stack: Vec<_> = self.stack.iter().map(|x| x.splat()).collect();
+        let mut lanes = vec![T::new_zero(); T::LANES];
+
+        for start in (0..n_points).step_by(T::LANES) {
+            let n = T::LANES.min(n_points - start);
+
+            for (j, s) in stack[..self.param_count].iter_mut().enumerate() {
+                // unused lanes repeat the last point
+                for (l, x) in lanes.iter_mut().enumerate() {
+                    *x = params[(start + l.min(n - 1)) * self.param_count + j].clone();
                 }
-                Instr::Pow(r, b, e) => {
-                    if *e >= 0 {
-                        self.stack[*r] = self.stack[*b].pow(*e as u64);
-                    } else {
-                        self.stack[*r] = self.stack[*b].pow(e.unsigned_abs()).inv();
-                    }
+                *s = T::pack(&lanes);
+            }
+
+            evaluate_instructions(&self.instructions, &mut stack);
+
+            for (i, r) in self.result_indices.iter().enumerate() {
+                T::unpack(&stack[*r], &mut lanes);
+                for (l, x) in lanes[..n].iter().enumerate() {
+                    out[(start + l) * out_len + i] = x.clone();
                 }
-                Instr::Powf(r, b, e) => {
-                    self.stack[*r] = self.stack[*b].powf(&self.stack[*e]);
-                }
-                Instr::BuiltinFun(r, s, arg) => match s.0 {
-                    Atom::EXP => self.stack[*r] = self.stack[*arg].exp(),
-                    Atom::LOG => self.stack[*r] = self.stack[*arg].log(),
-                    Atom::SIN => self.stack[*r] = self.stack[*arg].sin(),
-                    Atom::COS => self.stack[*r] = self.stack[*arg].cos(),
-                    Atom::SQRT => self.stack[*r] = self.stack[*arg].sqrt(),
-                    _ => unreachable!(),
-                },
             }
         }
+    }
+}
 
-        for (o, i) in out.iter_mut().zip(&self.result_indices) {
-            *o = self.stack[*i].clone();
+/// An evaluator that evaluates in double precision and transparently re-evaluates
+/// in higher precision for the inputs where the result does not reach the requested accuracy.
+///
+/// The loss of precision is estimated by propagating the rounding error of the inputs
+/// and constants with an [ErrorPropagatingFloat]. If the estimated number of correct
+/// decimal digits of any output is below the requested accuracy, the evaluation is
+/// repeated with a multiprecision [Float] at increasing binary precisions, until the
+/// accuracy is reached.
+///
+/// # Example
+/// ```
+/// # use symbolica::{atom::AtomCore, evaluate::{FunctionMap, OptimizationSettings, StableEvaluator}, parse};
+/// let e = parse!("(x + 1)^2 - x^2 - 2*x");
+/// let eval = e
+///     .evaluator(&FunctionMap::new(), &[parse!("x")], OptimizationSettings::default())
+///     .unwrap();
+/// let mut stable = StableEvaluator::new(&eval, 10., vec![113, 256]).unwrap();
+///
+/// let mut out = [0.];
+/// assert_eq!(stable.evaluate(&[1.], &mut out), Some(53));
+/// assert_eq!(out, [1.]);
+///
+/// // catastrophic cancellation in double precision
+/// assert_eq!(stable.evaluate(&[1e10], &mut out), Some(113));
+/// assert_eq!(out, [1.]);
+/// ```
+#[derive(Clone)]
+pub struct StableEvaluator {
+    eval_f64: ExpressionEvaluator<ErrorPropagatingFloat<f64>>,
+    eval_mp: Vec<(u32, ExpressionEvaluator<ErrorPropagatingFloat<Float>>)>,
+    required_digits: f64,
+    evaluation_counts: Vec<usize>,
+}
+
+impl StableEvaluator {
+    /// Create a new stable evaluator from a real-valued evaluator, that requires
+    /// `required_digits` correct decimal digits for every output. The evaluation is
+    /// escalated through the binary `precisions` in the given order.
+    pub fn new(
+        eval: &ExpressionEvaluator<Complex<Rational>>,
+        required_digits: f64,
+        precisions: Vec<u32>,
+    ) -> Result<Self, String> {
+        if !eval.is_real() {
+            return Err("The evaluator contains complex constants".to_owned());
+        }
+
+        let eval_f64 = eval
+            .clone()
+            .map_coeff(&|x| ErrorPropagatingFloat::from(x.re.to_f64()));
+
+        let eval_mp = precisions
+            .iter()
+            .map(|&p| {
+                (
+                    p,
+                    eval.clone().map_coeff(&|x| {
+                        ErrorPropagatingFloat::new(x.re.to_multi_prec_float(p), p as f64 * LOG10_2)
+                    }),
+                )
+            })
+            .collect();
+
+        Ok(StableEvaluator {
+            eval_f64,
+            eval_mp,
+            required_digits,
+            evaluation_counts: vec![0; precisions.len() + 2],
+        })
+    }
+
+    /// Get the number of evaluations that reached the required accuracy in double
+    /// precision, followed by the count for every escalated precision and finally
+    /// the number of evaluations that did not reach the required accuracy at all.
+    pub fn evaluation_counts(&self) -> &[usize] {
+        &self.evaluation_counts
+    }
+
+    /// Check if the estimated number of correct digits of `x` meets the required accuracy.
+    fn is_accurate<T: RealNumberLike>(&self, x: &ErrorPropagatingFloat<T>) -> bool {
+        // use the number of correct digits after the decimal point for zero
+        let digits = x.get_precision().unwrap_or_else(|| x.get_accuracy());
+        x.get_num().is_finite() && digits >= self.required_digits
+    }
+
+    /// Evaluate the expressions at `params` and write the results into `out`.
+    ///
+    /// Returns the binary precision of the evaluation that reached the required accuracy,
+    /// or `None` if none did, in which case `out` contains the result of the
+    /// most precise evaluation.
+    pub fn evaluate(&mut self, params: &[f64], out: &mut [f64]) -> Option<u32> {
+        let params_f64: Vec<_> = params
+            .iter()
+            .map(|x| ErrorPropagatingFloat::from(*x))
+            .collect();
+        let mut out_f64 = vec![ErrorPropagatingFloat::new_zero(); out.len()];
+        self.eval_f64.evaluate(&params_f64, &mut out_f64);
+
+        for (o, r) in out.iter_mut().zip(&out_f64) {
+            *o = *r.get_num();
+        }
+
+        if out_f64.iter().all(|x| self.is_accurate(x)) {
+            self.evaluation_counts[0] += 1;
+            return Some(53);
+        }
+
+        for i in 0..self.eval_mp.len() {
+            let p = self.eval_mp[i].0;
+            let params_mp: Vec<_> = params
+                .iter()
+                .map(|x| ErrorPropagatingFloat::new(Float::with_val(p, *x), p as f64 * LOG10_2))
+                .collect();
+            let mut out_mp = vec![ErrorPropagatingFloat::new_zero(); out.len()];
+            self.eval_mp[i].1.evaluate(&params_mp, &mut out_mp);
+
+            for (o, r) in out.iter_mut().zip(&out_mp) {
+                *o = r.get_num().to_f64();
+            }
+
+            if out_mp.iter().all(|x| self.is_accurate(x)) {
+                self.evaluation_counts[i + 1] += 1;
+                return Some(p);
+            }
+        }
+
+        *self.evaluation_counts.last_mut().unwrap() += 1;
+        None
+    }
+}
+
+/// Evaluate the instructions on `stack`, which contains the parameters and constants
+/// at the start.
+fn evaluate_instructions<T: Real>(instructions: &[Instr], stack: &mut [T]) {
+    let mut tmp;
+    for i in instructions {
+        match i {
+            Instr::Add(r, v) => {
+                tmp = stack[v[0]].clone();
+                for x in &v[1..] {
+                    let e = stack[*x].clone();
+                    tmp += e;
+                }
+                std::mem::swap(&mut stack[*r], &mut tmp);
+            }
+            Instr::Mul(r, v) => {
+                tmp = stack[v[0]].clone();
+                for x in &v[1..] {
+                    let e = stack[*x].clone();
+                    tmp *= e;
+                }
+                std::mem::swap(&mut stack[*r], &mut tmp);
+            }
+            Instr::Pow(r, b, e) => {
+                if *e >= 0 {
+                    stack[*r] = stack[*b].pow(*e as u64);
+                } else {
+                    stack[*r] = stack[*b].pow(e.unsigned_abs()).inv();
+                }
+            }
+            Instr::Powf(r, b, e) => {
+                stack[*r] = stack[*b].powf(&stack[*e]);
+            }
+            Instr::BuiltinFun(r, s, arg) => {
+                stack[*r] = s.evaluate(&stack[*arg]);
+            }
         }
     }
 }
@@ -1219,70 +1726,488 @@ impl<T: Default + Clone + Eq + Hash> ExpressionEvaluator<T> {
     }
 }
 
-impl<T> ExpressionEvaluator<T> {
-    pub fn optimize_stack(&mut self) {
-        let mut last_use: Vec<usize> = vec![0; self.stack.len()];
+impl<T: Clone + Default + PartialEq> ExpressionEvaluator<T> {
+    /// Create a new evaluator that computes the outputs of this evaluator together with their
+    /// gradients with respect to all parameters, using reverse-mode automatic differentiation.
+    ///
+    /// For `n` parameters and `m` outputs, the new evaluator has `m * (n + 1)` outputs:
+    /// first the `m` original outputs, followed by `df_j/dp_i` for every output `j`
+    /// and parameter `i`, grouped per output.
+    ///
+    /// The constants that appear in the derivatives are created from rationals
+    /// using `coeff_map`. The adjoint code is linearized with `cpe_rounds` rounds of
+    /// common pair elimination, just like [EvalTree::linearize].
+    ///
+    /// # Example
+    /// ```
+    /// # use symbolica::{atom::AtomCore, domains::{float::Complex, rational::Rational}, evaluate::{FunctionMap, OptimizationSettings}, parse};
+    /// let e = parse!("x*sin(y)");
+    /// let eval = e
+    ///     .evaluator(&FunctionMap::new(), &[parse!("x"), parse!("y")], OptimizationSettings::default())
+    ///     .unwrap();
+    ///
+    /// let grad = eval.gradient(|r| Complex::new(r.clone(), Rational::zero()), None);
+    /// let mut grad_f64 = grad.map_coeff(&|x| x.re.to_f64());
+    ///
+    /// let mut out = [0.; 3];
+    /// grad_f64.evaluate(&[2., 0.], &mut out);
+    /// assert_eq!(out, [0., 0., 2.]);
+    /// ```
+    pub fn gradient<F: Fn(&Rational) -> T>(
+        &self,
+        coeff_map: F,
+        cpe_rounds: Option<usize>,
+    ) -> ExpressionEvaluator<T> {
+        let zero = coeff_map(&Rational::zero());
+        let one = coeff_map(&Rational::one());
+
+        // construct a product, filtering out factors of one
+        let mul = |f: Vec<Expression<T>>| -> Expression<T> {
+            let mut f: Vec<_> = f
+                .into_iter()
+                .filter(|x| !matches!(x, Expression::Const(c) if *c == one))
+                .collect();
+            match f.len() {
+                0 => Expression::Const(one.clone()),
+                1 => f.pop().unwrap(),
+                _ => Expression::Mul(f),
+            }
+        };
 
-        for (i, x) in self.instructions.iter().enumerate() {
-            match x {
-                Instr::Add(_, a) | Instr::Mul(_, a) => {
-                    for v in a {
-                        last_use[*v] = i;
-                    }
-                }
-                Instr::Pow(_, b, _) | Instr::BuiltinFun(_, _, b) => {
-                    last_use[*b] = i;
+        let inv = |e: Expression<T>| Expression::Pow(Box::new((e, -1)));
+        let fun =
+            |s: Symbol, e: Expression<T>| Expression::BuiltinFun(BuiltinSymbol(s), Box::new(e));
+
+        // perform the forward sweep, giving every instruction its own subexpression,
+        // since stack slots may be reused
+        let mut values: Vec<Expression<T>> = (0..self.stack.len())
+            .map(|i| {
+                if i < self.param_count {
+                    Expression::Parameter(i)
+                } else if i < self.reserved_indices {
+                    Expression::Const(self.stack[i].clone())
+                } else {
+                    Expression::Const(zero.clone())
                 }
-                Instr::Powf(_, a, b) => {
-                    last_use[*a] = i;
-                    last_use[*b] = i;
+            })
+            .collect();
+
+        let mut subexpressions = Vec::with_capacity(self.instructions.len());
+        for i in &self.instructions {
+            let (r, e) = match i {
+                Instr::Add(r, a) => (
+                    *r,
+                    Expression::Add(a.iter().map(|x| values[*x].clone()).collect()),
+                ),
+                Instr::Mul(r, a) => (
+                    *r,
+                    Expression::Mul(a.iter().map(|x| values[*x].clone()).collect()),
+                ),
+                Instr::Pow(r, b, e) => (*r, Expression::Pow(Box::new((values[*b].clone(), *e)))),
+                Instr::Powf(r, b, e) => (
+                    *r,
+                    Expression::Powf(Box::new((values[*b].clone(), values[*e].clone()))),
+                ),
+                Instr::BuiltinFun(r, s, a) => {
+                    (*r, Expression::BuiltinFun(*s, Box::new(values[*a].clone())))
                 }
             };
-        }
-
-        // prevent init slots from being overwritten
-        for i in 0..self.reserved_indices {
-            last_use[i] = self.instructions.len();
-        }
 
-        // prevent the output slots from being overwritten
-        for i in &self.result_indices {
-            last_use[*i] = self.instructions.len();
+            subexpressions.push(e);
+            values[r] = Expression::SubExpression(subexpressions.len() - 1);
         }
 
-        let mut rename_map: Vec<_> = (0..self.stack.len()).collect(); // identity map
+        let forward_len = subexpressions.len();
+        let mut tree: Vec<_> = self
+            .result_indices
+            .iter()
+            .map(|i| values[*i].clone())
+            .collect();
 
-        let mut max_reg = self.reserved_indices;
-        for (i, x) in self.instructions.iter_mut().enumerate() {
-            let cur_reg = match x {
-                Instr::Add(r, _)
-                | Instr::Mul(r, _)
-                | Instr::Pow(r, _, _)
-                | Instr::Powf(r, _, _)
-                | Instr::BuiltinFun(r, _, _) => *r,
-            };
+        let add_adjoint = |adj_param: &mut Vec<Vec<Expression<T>>>,
+                           adj_sub: &mut Vec<Vec<Expression<T>>>,
+                           target: &Expression<T>,
+                           contribution: Expression<T>| match target {
+            Expression::Parameter(p) => adj_param[*p].push(contribution),
+            Expression::SubExpression(s) => adj_sub[*s].push(contribution),
+            _ => {} // constants have no adjoint
+        };
 
-            let cur_last_use = last_use[cur_reg];
+        for o in &self.result_indices {
+            let mut adj_param = vec![vec![]; self.param_count];
+            let mut adj_sub = vec![vec![]; forward_len];
+            add_adjoint(
+                &mut adj_param,
+                &mut adj_sub,
+                &values[*o],
+                Expression::Const(one.clone()),
+            );
 
-            let new_reg = if let Some((new_v, lu)) = last_use[..cur_reg]
-                .iter_mut()
-                .enumerate()
-                .find(|(_, r)| **r <= i)
-            // <= is ok because we store intermediate results in temp values
-            {
-                *lu = cur_last_use; // set the last use to the current variable last use
-                last_use[cur_reg] = 0; // make the current index available
-                rename_map[cur_reg] = new_v; // set the rename map so that every occurrence on the rhs is replaced
-                new_v
-            } else {
-                cur_reg
-            };
+            // the reverse sweep
+            for k in (0..forward_len).rev() {
+                let a = match adj_sub[k].len() {
+                    0 => continue,
+                    1 => adj_sub[k].pop().unwrap(),
+                    _ => Expression::Add(std::mem::take(&mut adj_sub[k])),
+                };
 
-            max_reg = max_reg.max(new_reg);
+                // share the adjoint between all its uses
+                let a = match a {
+                    Expression::Const(_)
+                    | Expression::Parameter(_)
+                    | Expression::SubExpression(_) => a,
+                    _ => {
+                        subexpressions.push(a);
+                        Expression::SubExpression(subexpressions.len() - 1)
+                    }
+                };
 
-            match x {
-                Instr::Add(r, a) | Instr::Mul(r, a) => {
-                    *r = new_reg;
+                let r = Expression::SubExpression(k);
+                match subexpressions[k].clone() {
+                    Expression::Add(args) => {
+                        for x in &args {
+                            add_adjoint(&mut adj_param, &mut adj_sub, x, a.clone());
+                        }
+                    }
+                    Expression::Mul(args) => {
+                        for (i, x) in args.iter().enumerate() {
+                            let mut f = vec![a.clone()];
+                            f.extend(
+                                args.iter()
+                                    .enumerate()
+                                    .filter(|(j, _)| *j != i)
+                                    .map(|(_, y)| y.clone()),
+                            );
+                            add_adjoint(&mut adj_param, &mut adj_sub, x, mul(f));
+                        }
+                    }
+                    Expression::Pow(p) => {
+                        let (b, e) = *p;
+                        if e == 0 {
+                            continue;
+                        }
+
+                        let d = if e > 0 {
+                            let mut f = vec![Expression::Const(coeff_map(&e.into())), a];
+                            f.extend(std::iter::repeat_n(b.clone(), e as usize - 1));
+                            mul(f)
+                        } else {
+                            mul(vec![
+                                Expression::Const(coeff_map(&e.into())),
+                                a,
+                                r,
+                                inv(b.clone()),
+                            ])
+                        };
+                        add_adjoint(&mut adj_param, &mut adj_sub, &b, d);
+                    }
+                    Expression::Powf(p) => {
+                        let (b, e) = *p;
+                        let db = mul(vec![a.clone(), e.clone(), r.clone(), inv(b.clone())]);
+                        let de = mul(vec![a, r, fun(Atom::LOG, b.clone())]);
+                        add_adjoint(&mut adj_param, &mut adj_sub, &b, db);
+                        add_adjoint(&mut adj_param, &mut adj_sub, &e, de);
+                    }
+                    Expression::BuiltinFun(s, x) => {
+                        let x = *x;
+                        let c = |n: i64| Expression::Const(coeff_map(&n.into()));
+                        let one_minus_sq = Expression::Add(vec![
+                            c(1),
+                            Expression::Mul(vec![c(-1), x.clone(), x.clone()]),
+                        ]);
+
+                        let d = match s.0 {
+                            Atom::EXP => r,
+                            Atom::LOG => inv(x.clone()),
+                            Atom::SIN => fun(Atom::COS, x.clone()),
+                            Atom::COS => mul(vec![c(-1), fun(Atom::SIN, x.clone())]),
+                            Atom::SQRT => {
+                                mul(vec![Expression::Const(coeff_map(&(1, 2).into())), inv(r)])
+                            }
+                            Atom::TAN => {
+                                Expression::Add(vec![c(1), Expression::Mul(vec![r.clone(), r])])
+                            }
+                            Atom::ASIN => inv(fun(Atom::SQRT, one_minus_sq)),
+                            Atom::ACOS => mul(vec![c(-1), inv(fun(Atom::SQRT, one_minus_sq))]),
+                            Atom::ATAN => inv(Expression::Add(vec![
+                                c(1),
+                                Expression::Mul(vec![x.clone(), x.clone()]),
+                            ])),
+                            Atom::SINH => fun(Atom::COSH, x.clone()),
+                            Atom::COSH => fun(Atom::SINH, x.clone()),
+                            Atom::TANH => Expression::Add(vec![
+                                c(1),
+                                Expression::Mul(vec![c(-1), r.clone(), r]),
+                            ]),
+                            Atom::ASINH => inv(fun(
+                                Atom::SQRT,
+                                Expression::Add(vec![
+                                    c(1),
+                                    Expression::Mul(vec![x.clone(), x.clone()]),
+                                ]),
+                            )),
+                            Atom::ACOSH => inv(Expression::Mul(vec![
+                                fun(Atom::SQRT, Expression::Add(vec![x.clone(), c(-1)])),
+                                fun(Atom::SQRT, Expression::Add(vec![x.clone(), c(1)])),
+                            ])),
+                            Atom::ATANH => inv(one_minus_sq),
+                            _ => unreachable!(),
+                        };
+
+                        add_adjoint(&mut adj_param, &mut adj_sub, &x, mul(vec![d, a]));
+                    }
+                    _ => unreachable!(),
+                }
+            }
+
+            for a in adj_param {
+                tree.push(match a.len() {
+                    0 => Expression::Const(zero.clone()),
+                    1 => a.into_iter().next().unwrap(),
+                    _ => Expression::Add(a),
+                });
+            }
+        }
+
+        EvalTree {
+            functions: vec![],
+            expressions: SplitExpression {
+                tree,
+                subexpressions,
+            },
+            param_count: self.param_count,
+        }
+        .linearize(cpe_rounds)
+    }
+}
+
+impl<T> ExpressionEvaluator<T> {
+    /// Assign the XMM registers `0..15` to intermediate results that are only used
+    /// by additions, multiplications and inversions, so that they never have to be
+    /// written to memory. Register `15` is kept free as scratch space.
+    fn assign_registers(&self, instr: &[Instr]) -> Vec<RegInstr> {
+        let mut reg_last_use = vec![self.instructions.len(); self.instructions.len()];
+        let mut stack_to_reg = HashMap::default();
+
+        for (i, ins) in instr.iter().enumerate() {
+            match ins {
+                Instr::Add(r, a) | Instr::Mul(r, a) => {
+                    for x in a {
+                        if x >= &self.reserved_indices {
+                            reg_last_use[stack_to_reg[x]] = i;
+                        }
+                    }
+
+                    stack_to_reg.insert(r, i);
+                }
+                Instr::Pow(r, b, _) => {
+                    if b >= &self.reserved_indices {
+                        reg_last_use[stack_to_reg[b]] = i;
+                    }
+                    stack_to_reg.insert(r, i);
+                }
+                Instr::Powf(r, b, e) => {
+                    if b >= &self.reserved_indices {
+                        reg_last_use[stack_to_reg[b]] = i;
+                    }
+                    if e >= &self.reserved_indices {
+                        reg_last_use[stack_to_reg[e]] = i;
+                    }
+                    stack_to_reg.insert(r, i);
+                }
+                Instr::BuiltinFun(r, _, b) => {
+                    if b >= &self.reserved_indices {
+                        reg_last_use[stack_to_reg[b]] = i;
+                    }
+                    stack_to_reg.insert(r, i);
+                }
+            }
+        }
+
+        for x in &self.result_indices {
+            if x >= &self.reserved_indices {
+                reg_last_use[stack_to_reg[x]] = self.instructions.len();
+            }
+        }
+
+        let mut new_instr: Vec<RegInstr> = instr
+            .iter()
+            .map(|i| match i {
+                Instr::Add(r, a) => RegInstr::Add(
+                    MemOrReg::Mem(*r),
+                    u16::MAX,
+                    a.iter().map(|x| MemOrReg::Mem(*x)).collect(),
+                ),
+                Instr::Mul(r, a) => RegInstr::Mul(
+                    MemOrReg::Mem(*r),
+                    u16::MAX,
+                    a.iter().map(|x| MemOrReg::Mem(*x)).collect(),
+                ),
+                Instr::Pow(r, b, e) => {
+                    RegInstr::Pow(MemOrReg::Mem(*r), u16::MAX, MemOrReg::Mem(*b), *e)
+                }
+                Instr::Powf(r, b, e) => RegInstr::Powf(*r, *b, *e),
+                Instr::BuiltinFun(r, s, a) => RegInstr::BuiltinFun(*r, *s, *a),
+            })
+            .collect();
+
+        // sort the list of instructions based on the distance
+        let mut reg_list = reg_last_use.iter().enumerate().collect::<Vec<_>>();
+        reg_list.sort_by_key(|x| (*x.1 - x.0, x.0));
+
+        'next: for (j, last_use) in reg_list {
+            if *last_use == self.instructions.len() {
+                continue;
+            }
+
+            let old_reg = if let RegInstr::Add(r, _, _)
+            | RegInstr::Mul(r, _, _)
+            | RegInstr::Pow(r, _, _, -1) = &new_instr[j]
+            {
+                if let MemOrReg::Mem(r) = r {
+                    *r
+                } else {
+                    continue;
+                }
+            } else {
+                continue;
+            };
+
+            // find free registers in the range
+            // start at j+1 as we can recycle registers that are last used in iteration j
+            let mut free_regs = u16::MAX & !(1 << 15); // leave xmmm15 open
+
+            for k in &new_instr[j + 1..=*last_use] {
+                match k {
+                    RegInstr::Add(_, f, _)
+                    | RegInstr::Mul(_, f, _)
+                    | RegInstr::Pow(_, f, _, -1) => {
+                        free_regs &= f;
+                    }
+
+                    _ => {
+                        free_regs = 0; // the current instruction is not allowed to be used outside of ASM blocks
+                    }
+                }
+
+                if free_regs == 0 {
+                    continue 'next;
+                }
+            }
+
+            if let Some(k) = (0..16).position(|k| free_regs & (1 << k) != 0) {
+                if let RegInstr::Add(r, _, _) | RegInstr::Mul(r, _, _) | RegInstr::Pow(r, _, _, _) =
+                    &mut new_instr[j]
+                {
+                    *r = MemOrReg::Reg(k);
+                }
+
+                for l in &mut new_instr[j + 1..=*last_use] {
+                    match l {
+                        RegInstr::Add(_, f, a) | RegInstr::Mul(_, f, a) => {
+                            *f &= !(1 << k); // FIXME: do not set on last use?
+                            for x in a {
+                                if *x == MemOrReg::Mem(old_reg) {
+                                    *x = MemOrReg::Reg(k);
+                                }
+                            }
+                        }
+                        RegInstr::Pow(_, f, a, -1) => {
+                            *f &= !(1 << k); // FIXME: do not set on last use?
+                            if *a == MemOrReg::Mem(old_reg) {
+                                *a = MemOrReg::Reg(k);
+                            }
+                        }
+                        RegInstr::Pow(_, _, _, _) => {
+                            panic!("use outside of ASM block");
+                        }
+                        RegInstr::Powf(_, a, b) => {
+                            if *a == old_reg {
+                                panic!("use outside of ASM block");
+                            }
+                            if *b == old_reg {
+                                panic!("use outside of ASM block");
+                            }
+                        }
+                        RegInstr::BuiltinFun(_, _, a) => {
+                            if *a == old_reg {
+                                panic!("use outside of ASM block");
+                            }
+                        }
+                    }
+                }
+
+                // TODO: if last use is not already set to a register, we can set it to the current one
+                // this prevents a copy
+            }
+        }
+
+        new_instr
+    }
+
+    pub fn optimize_stack(&mut self) {
+        let mut last_use: Vec<usize> = vec![0; self.stack.len()];
+
+        for (i, x) in self.instructions.iter().enumerate() {
+            match x {
+                Instr::Add(_, a) | Instr::Mul(_, a) => {
+                    for v in a {
+                        last_use[*v] = i;
+                    }
+                }
+                Instr::Pow(_, b, _) | Instr::BuiltinFun(_, _, b) => {
+                    last_use[*b] = i;
+                }
+                Instr::Powf(_, a, b) => {
+                    last_use[*a] = i;
+                    last_use[*b] = i;
+                }
+            };
+        }
+
+        // prevent init slots from being overwritten
+        for i in 0..self.reserved_indices {
+            last_use[i] = self.instructions.len();
+        }
+
+        // prevent the output slots from being overwritten
+        for i in &self.result_indices {
+            last_use[*i] = self.instructions.len();
+        }
+
+        let mut rename_map: Vec<_> = (0..self.stack.len()).collect(); // identity map
+
+        let mut max_reg = self.reserved_indices;
+        for (i, x) in self.instructions.iter_mut().enumerate() {
+            let cur_reg = match x {
+                Instr::Add(r, _)
+                | Instr::Mul(r, _)
+                | Instr::Pow(r, _, _)
+                | Instr::Powf(r, _, _)
+                | Instr::BuiltinFun(r, _, _) => *r,
+            };
+
+            let cur_last_use = last_use[cur_reg];
+
+            let new_reg = if let Some((new_v, lu)) = last_use[..cur_reg]
+                .iter_mut()
+                .enumerate()
+                .find(|(_, r)| **r <= i)
+            // <= is ok because we store intermediate results in temp values
+            {
+                *lu = cur_last_use; // set the last use to the current variable last use
+                last_use[cur_reg] = 0; // make the current index available
+                rename_map[cur_reg] = new_v; // set the rename map so that every occurrence on the rhs is replaced
+                new_v
+            } else {
+                cur_reg
+            };
+
+            max_reg = max_reg.max(new_reg);
+
+            match x {
+                Instr::Add(r, a) | Instr::Mul(r, a) => {
+                    *r = new_reg;
                     for v in a {
                         *v = rename_map[*v];
                     }
@@ -1317,6 +2242,9 @@ pub trait ExportNumber {
     }
     /// Check if the number is real.
     fn is_real(&self) -> bool;
+    /// Whether the number is exported with double-double precision. Only then
+    /// does the exported C++ code contain a double-double evaluation function.
+    const IS_DOUBLE_DOUBLE: bool = false;
 }
 
 impl ExportNumber for f64 {
@@ -1349,27 +2277,680 @@ impl ExportNumber for Float {
     }
 }
 
+impl ExportNumber for DoubleDouble {
+    fn export(&self) -> String {
+        self.to_string()
+    }
+
+    /// Export the number as a sum of its two parts, which is exact
+    /// for the C++ double-double type.
+    fn export_wrapped(&self) -> String {
+        if self.lo() == 0. {
+            format!("T({:e})", self.hi())
+        } else {
+            format!("(T({:e}) + T({:e}))", self.hi(), self.lo())
+        }
+    }
+
+    fn is_real(&self) -> bool {
+        true
+    }
+
+    const IS_DOUBLE_DOUBLE: bool = true;
+}
+
 impl ExportNumber for Rational {
     fn export(&self) -> String {
         self.to_string()
     }
 
-    fn is_real(&self) -> bool {
-        true
-    }
-}
+    fn is_real(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ExportNumber + SingleFloat> ExportNumber for Complex<T> {
+    fn export(&self) -> String {
+        if self.im.is_zero() {
+            self.re.export()
+        } else {
+            format!("{}, {}", self.re.export(), self.im.export())
+        }
+    }
+
+    fn is_real(&self) -> bool {
+        self.im.is_zero()
+    }
+}
+
+/// A number that can be converted to a native double-precision number,
+/// so that it can be used as a constant in JIT-compiled or exported Rust code.
+pub trait NativeNumber {
+    /// Convert the number to a double-precision complex number.
+    fn to_complex_f64(&self) -> Complex<f64>;
+}
+
+impl NativeNumber for f64 {
+    fn to_complex_f64(&self) -> Complex<f64> {
+        Complex::new(*self, 0.)
+    }
+}
+
+impl NativeNumber for DoubleDouble {
+    fn to_complex_f64(&self) -> Complex<f64> {
+        Complex::new(self.hi(), 0.)
+    }
+}
+
+impl NativeNumber for F64 {
+    fn to_complex_f64(&self) -> Complex<f64> {
+        Complex::new(self.to_f64(), 0.)
+    }
+}
+
+impl NativeNumber for Float {
+    fn to_complex_f64(&self) -> Complex<f64> {
+        Complex::new(self.to_f64(), 0.)
+    }
+}
+
+impl NativeNumber for Rational {
+    fn to_complex_f64(&self) -> Complex<f64> {
+        Complex::new(self.to_f64(), 0.)
+    }
+}
+
+impl<T: NativeNumber> NativeNumber for Complex<T> {
+    fn to_complex_f64(&self) -> Complex<f64> {
+        let re = self.re.to_complex_f64();
+        let im = self.im.to_complex_f64();
+        Complex::new(re.re - im.im, re.im + im.re)
+    }
+}
+
+/// A double-double type for C++, used by code exported with [ExpressionEvaluator::export_cpp_str].
+/// Its layout and algorithms match [DoubleDouble]. The functions are compiled without fast-math
+/// optimizations, since those break the error-free transformations.
+const CPP_DOUBLE_DOUBLE: &str = r#"#ifndef SYMBOLICA_DOUBLE_DOUBLE
+#define SYMBOLICA_DOUBLE_DOUBLE
+#if defined(__clang__)
+#pragma float_control(precise, on, push)
+#define DD_EXACT static inline
+#elif defined(__GNUC__)
+#define DD_EXACT __attribute__((optimize("no-fast-math"))) static inline
+#else
+#define DD_EXACT static inline
+#endif
+
+struct dd_real {
+	double hi, lo;
+	dd_real() : hi(0), lo(0) {}
+	dd_real(double h) : hi(h), lo(0) {}
+	dd_real(double h, double l) : hi(h), lo(l) {}
+};
+
+DD_EXACT dd_real dd_parts(double hi, double lo) { return std::isfinite(hi) ? dd_real(hi, lo) : dd_real(hi, 0.); }
+DD_EXACT dd_real dd_two_sum(double a, double b) { double s = a + b; double bb = s - a; return dd_real(s, (a - (s - bb)) + (b - bb)); }
+DD_EXACT dd_real dd_quick_two_sum(double a, double b) { double s = a + b; return dd_real(s, b - (s - a)); }
+DD_EXACT dd_real dd_two_prod(double a, double b) {
+	double p = a * b;
+#ifdef __FMA__
+	return dd_real(p, std::fma(a, b, -p));
+#else
+	const double split = 134217729.0;
+	double t = split * a, ah = t - (t - a), al = a - ah;
+	t = split * b;
+	double bh = t - (t - b), bl = b - bh;
+	return dd_real(p, ((ah * bh - p) + ah * bl + al * bh) + al * bl);
+#endif
+}
+
+DD_EXACT dd_real operator-(const dd_real &a) { return dd_real(-a.hi, -a.lo); }
+DD_EXACT dd_real operator+(const dd_real &a, const dd_real &b) {
+	dd_real s = dd_two_sum(a.hi, b.hi), t = dd_two_sum(a.lo, b.lo);
+	s = dd_quick_two_sum(s.hi, s.lo + t.hi);
+	s = dd_quick_two_sum(s.hi, s.lo + t.lo);
+	return dd_parts(s.hi, s.lo);
+}
+DD_EXACT dd_real operator-(const dd_real &a, const dd_real &b) { return a + (-b); }
+DD_EXACT dd_real operator*(const dd_real &a, const dd_real &b) {
+	dd_real p = dd_two_prod(a.hi, b.hi);
+	p = dd_quick_two_sum(p.hi, p.lo + (a.hi * b.lo + a.lo * b.hi));
+	return dd_parts(p.hi, p.lo);
+}
+DD_EXACT dd_real dd_add_d(const dd_real &a, double b) {
+	dd_real s = dd_two_sum(a.hi, b);
+	s = dd_quick_two_sum(s.hi, s.lo + a.lo);
+	return dd_parts(s.hi, s.lo);
+}
+DD_EXACT dd_real dd_mul_d(const dd_real &a, double b) {
+	dd_real p = dd_two_prod(a.hi, b);
+	p = dd_quick_two_sum(p.hi, p.lo + a.lo * b);
+	return dd_parts(p.hi, p.lo);
+}
+DD_EXACT dd_real dd_div_d(const dd_real &a, double b) {
+	double q1 = a.hi / b;
+	if (!std::isfinite(q1)) return dd_real(q1);
+	dd_real p = dd_two_prod(q1, b);
+	dd_real s = dd_two_sum(a.hi, -p.hi);
+	double q2 = (s.hi + (s.lo - p.lo + a.lo)) / b;
+	return dd_quick_two_sum(q1, q2);
+}
+DD_EXACT dd_real dd_mul_pwr2(const dd_real &a, double b) { return dd_real(a.hi * b, a.lo * b); }
+DD_EXACT dd_real dd_sqr(const dd_real &a) {
+	dd_real p = dd_two_prod(a.hi, a.hi);
+	p = dd_quick_two_sum(p.hi, p.lo + 2. * a.hi * a.lo);
+	return dd_parts(p.hi, p.lo);
+}
+DD_EXACT dd_real operator/(const dd_real &a, const dd_real &b) {
+	double q1 = a.hi / b.hi;
+	if (!std::isfinite(q1) || !std::isfinite(b.hi)) return dd_real(q1);
+	dd_real r = a - dd_mul_d(b, q1);
+	double q2 = r.hi / b.hi;
+	r = r - dd_mul_d(b, q2);
+	double q3 = r.hi / b.hi;
+	return dd_add_d(dd_quick_two_sum(q1, q2), q3);
+}
+
+DD_EXACT dd_real sqrt(const dd_real &a) {
+	if (a.hi <= 0. || !std::isfinite(a.hi)) return dd_real(std::sqrt(a.hi));
+	double x = 1. / std::sqrt(a.hi), ax = a.hi * x;
+	return dd_add_d(dd_real(ax), (a - dd_two_prod(ax, ax)).hi * (x * 0.5));
+}
+
+DD_EXACT dd_real exp(const dd_real &a) {
+	if (a.hi > 709.8) return dd_real(INFINITY);
+	if (a.hi < -745.2) return dd_real(0.);
+	if (std::isnan(a.hi)) return a;
+	const dd_real ln2(0.6931471805599453, 2.3190468138462996e-17);
+	double m = std::round(a.hi / ln2.hi);
+	dd_real r = dd_mul_pwr2(a - dd_mul_d(ln2, m), 1. / 512.);
+	dd_real s = r, t = r;
+	for (int k = 2; k < 20; k++) {
+		t = dd_div_d(t * r, k);
+		s = s + t;
+		if (std::abs(t.hi) <= 1e-36) break;
+	}
+	for (int i = 0; i < 9; i++) s = dd_mul_pwr2(s, 2.) + dd_sqr(s);
+	s = dd_add_d(s, 1.);
+	double m1 = std::floor(m / 2.);
+	return dd_mul_pwr2(dd_mul_pwr2(s, std::ldexp(1., (int)m1)), std::ldexp(1., (int)(m - m1)));
+}
+
+DD_EXACT dd_real log(const dd_real &a) {
+	if (a.hi <= 0. || !std::isfinite(a.hi)) return dd_real(std::log(a.hi));
+	dd_real x(std::log(a.hi));
+	return dd_add_d(x + a * exp(-x), -1.);
+}
+
+DD_EXACT dd_real dd_odd_series(const dd_real &x, double sign) {
+	dd_real x2 = dd_sqr(x), s = x, t = x;
+	for (int i = 1; i < 30; i++) {
+		double k = i;
+		t = dd_div_d(t * x2, sign * (2. * k) * (2. * k + 1.));
+		s = s + t;
+		if (std::abs(t.hi) <= 1e-33 * std::abs(x.hi)) break;
+	}
+	return s;
+}
+
+DD_EXACT void dd_sin_cos(const dd_real &a, dd_real &s, dd_real &c) {
+	if (!std::isfinite(a.hi)) { s = dd_real(NAN); c = dd_real(NAN); return; }
+	if (a.hi == 0.) { s = a; c = dd_real(1.); return; }
+	const double pi2[3] = {1.5707963267948966, 6.123233995736766e-17, -1.4973849048591698e-33};
+	double k = std::round(a.hi / pi2[0]);
+	dd_real r = a;
+	for (int i = 0; i < 3; i++) r = r - dd_two_prod(k, pi2[i]);
+	dd_real sr = dd_odd_series(r, -1.);
+	dd_real cr = sqrt(dd_real(1.) - dd_sqr(sr));
+	double q = k - 4. * std::floor(k / 4.);
+	if (q == 0.) { s = sr; c = cr; }
+	else if (q == 1.) { s = cr; c = -sr; }
+	else if (q == 2.) { s = -sr; c = -cr; }
+	else { s = -cr; c = sr; }
+}
+
+DD_EXACT dd_real sin(const dd_real &a) { dd_real s, c; dd_sin_cos(a, s, c); return s; }
+DD_EXACT dd_real cos(const dd_real &a) { dd_real s, c; dd_sin_cos(a, s, c); return c; }
+DD_EXACT dd_real tan(const dd_real &a) { dd_real s, c; dd_sin_cos(a, s, c); return s / c; }
+
+DD_EXACT dd_real atan2(const dd_real &y, const dd_real &x) {
+	const dd_real pi(3.141592653589793, 1.2246467991473532e-16), pi2(1.5707963267948966, 6.123233995736766e-17);
+	if (y.hi == 0. || x.hi == 0. || !std::isfinite(y.hi) || !std::isfinite(x.hi)) {
+		double z = std::atan2(y.hi, x.hi);
+		if (std::abs(z) == pi.hi) return z > 0 ? pi : -pi;
+		if (std::abs(z) == pi2.hi) return z > 0 ? pi2 : -pi2;
+		return dd_real(z);
+	}
+	dd_real r = sqrt(dd_sqr(x) + dd_sqr(y)), xx = x / r, yy = y / r;
+	dd_real z(std::atan2(y.hi, x.hi)), s, c;
+	dd_sin_cos(z, s, c);
+	if (std::abs(xx.hi) > std::abs(yy.hi)) return z + (yy - s) / c;
+	return z - (xx - c) / s;
+}
+
+DD_EXACT dd_real atan(const dd_real &a) { return atan2(a, dd_real(1.)); }
+DD_EXACT dd_real asin(const dd_real &a) { return atan2(a, sqrt((dd_real(1.) - a) * (dd_real(1.) + a))); }
+DD_EXACT dd_real acos(const dd_real &a) { return atan2(sqrt((dd_real(1.) - a) * (dd_real(1.) + a)), a); }
+
+DD_EXACT dd_real sinh(const dd_real &a) {
+	if (std::abs(a.hi) < 0.05) return dd_odd_series(a, 1.);
+	dd_real e = exp(a);
+	return dd_mul_pwr2(e - dd_real(1.) / e, 0.5);
+}
+DD_EXACT dd_real cosh(const dd_real &a) {
+	dd_real e = exp(a);
+	return dd_mul_pwr2(e + dd_real(1.) / e, 0.5);
+}
+DD_EXACT dd_real tanh(const dd_real &a) {
+	if (std::abs(a.hi) > 40.) return dd_real(a.hi > 0. ? 1. : -1.);
+	if (std::abs(a.hi) < 0.05) { dd_real s = sinh(a); return s / sqrt(dd_add_d(dd_sqr(s), 1.)); }
+	dd_real e = exp(a), inv = dd_real(1.) / e;
+	return (e - inv) / (e + inv);
+}
+DD_EXACT dd_real asinh(const dd_real &a) {
+	dd_real z(std::asinh(a.hi));
+	if (!std::isfinite(z.hi)) return z;
+	return z - (sinh(z) - a) / cosh(z);
+}
+DD_EXACT dd_real acosh(const dd_real &a) {
+	if (a.hi < 1.) return dd_real(NAN);
+	return asinh(sqrt((a - dd_real(1.)) * (a + dd_real(1.))));
+}
+DD_EXACT dd_real atanh(const dd_real &a) {
+	if (std::abs(a.hi) > 0.5) return dd_mul_pwr2(log((dd_real(1.) + a) / (dd_real(1.) - a)), 0.5);
+	dd_real z(std::atanh(a.hi));
+	if (!std::isfinite(z.hi)) return z;
+	dd_real c = cosh(z);
+	return z - (tanh(z) - a) * dd_sqr(c);
+}
+
+DD_EXACT dd_real pow(const dd_real &a, long e) {
+	dd_real r(1.), b = a;
+	unsigned long n = e < 0 ? -(unsigned long)e : e;
+	while (n > 0) {
+		if (n & 1) r = r * b;
+		n >>= 1;
+		if (n > 0) b = dd_sqr(b);
+	}
+	return e < 0 ? dd_real(1.) / r : r;
+}
+DD_EXACT dd_real pow(const dd_real &a, const dd_real &e) {
+	if (e.lo == 0. && std::trunc(e.hi) == e.hi && std::abs(e.hi) < 9007199254740992.) return pow(a, (long)e.hi);
+	if (a.hi == 0.) return dd_real(std::pow(a.hi, e.hi));
+	return exp(e * log(a));
+}
+
+#if defined(__clang__)
+#pragma float_control(pop)
+#endif
+#undef DD_EXACT
+#endif
+"#;
+
+/// Helper functions for complex numbers represented as `[re, im]`,
+/// used by code exported with [ExpressionEvaluator::export_rust_str].
+const RUST_COMPLEX_FUNCTIONS: &str = "    type C = [f64; 2];
+    const ONE: C = [1.0, 0.0];
+    const I: C = [0.0, 1.0];
+    fn add(a: C, b: C) -> C { [a[0] + b[0], a[1] + b[1]] }
+    fn sub(a: C, b: C) -> C { [a[0] - b[0], a[1] - b[1]] }
+    fn mul(a: C, b: C) -> C { [a[0] * b[0] - a[1] * b[1], a[0] * b[1] + a[1] * b[0]] }
+    fn scale(a: C, s: f64) -> C { [a[0] * s, a[1] * s] }
+    fn inv(a: C) -> C { let n = a[0] * a[0] + a[1] * a[1]; [a[0] / n, -a[1] / n] }
+    fn powi(a: C, e: i64) -> C {
+        let (mut r, mut b, mut n) = (ONE, a, e.unsigned_abs());
+        while n > 0 {
+            if n & 1 == 1 { r = mul(r, b); }
+            b = mul(b, b);
+            n >>= 1;
+        }
+        if e < 0 { inv(r) } else { r }
+    }
+    fn polar(r: f64, phi: f64) -> C { [r * phi.cos(), r * phi.sin()] }
+    fn exp(a: C) -> C { polar(a[0].exp(), a[1]) }
+    fn log(a: C) -> C { [a[0].hypot(a[1]).ln(), a[1].atan2(a[0])] }
+    fn sqrt(a: C) -> C { polar(a[0].hypot(a[1]).sqrt(), a[1].atan2(a[0]) / 2.0) }
+    fn powf(a: C, e: C) -> C {
+        if e == [0.0, 0.0] { ONE }
+        else if e[1] == 0.0 { polar(a[0].hypot(a[1]).powf(e[0]), a[1].atan2(a[0]) * e[0]) }
+        else { exp(mul(e, log(a))) }
+    }
+    fn sin(a: C) -> C { [a[0].sin() * a[1].cosh(), a[0].cos() * a[1].sinh()] }
+    fn cos(a: C) -> C { [a[0].cos() * a[1].cosh(), -a[0].sin() * a[1].sinh()] }
+    fn tan(a: C) -> C {
+        let (r, i) = (2.0 * a[0], 2.0 * a[1]);
+        let m = r.cos() + i.cosh();
+        [r.sin() / m, i.sinh() / m]
+    }
+    fn sinh(a: C) -> C { [a[0].sinh() * a[1].cos(), a[0].cosh() * a[1].sin()] }
+    fn cosh(a: C) -> C { [a[0].cosh() * a[1].cos(), a[0].sinh() * a[1].sin()] }
+    fn tanh(a: C) -> C {
+        let (r, i) = (2.0 * a[0], 2.0 * a[1]);
+        let m = r.cosh() + i.cos();
+        [r.sinh() / m, i.sin() / m]
+    }
+    fn asin(a: C) -> C { mul([0.0, -1.0], log(add(sqrt(sub(ONE, mul(a, a))), mul(I, a)))) }
+    fn acos(a: C) -> C { mul([0.0, -1.0], log(add(mul(I, sqrt(sub(ONE, mul(a, a)))), a))) }
+    fn atan(a: C) -> C { mul(sub(log(add(ONE, mul(I, a))), log(sub(ONE, mul(I, a)))), [0.0, -0.5]) }
+    fn asinh(a: C) -> C { log(add(a, sqrt(add(ONE, mul(a, a))))) }
+    fn acosh(a: C) -> C { scale(log(add(sqrt(scale(add(a, ONE), 0.5)), sqrt(scale(sub(a, ONE), 0.5)))), 2.0) }
+    fn atanh(a: C) -> C { scale(sub(log(add(ONE, a)), log(sub(ONE, a))), 0.5) }
+";
+
+/// Format a double as a Rust literal.
+fn rust_f64_literal(x: f64) -> String {
+    if x.is_nan() {
+        "f64::NAN".to_owned()
+    } else if x.is_infinite() {
+        if x > 0. {
+            "f64::INFINITY"
+        } else {
+            "f64::NEG_INFINITY"
+        }
+        .to_owned()
+    } else {
+        format!("{:?}", x)
+    }
+}
+
+impl<T: NativeNumber> ExpressionEvaluator<T> {
+    /// Write self-contained Rust code that evaluates the expressions to `filename`.
+    /// See [ExpressionEvaluator::export_rust_str].
+    pub fn export_rust(&self, filename: &str, function_name: &str) -> Result<(), std::io::Error> {
+        let mut filename = filename.to_string();
+        if !filename.ends_with(".rs") {
+            filename += ".rs";
+        }
+
+        std::fs::write(&filename, self.export_rust_str(function_name))
+    }
+
+    /// Create dependency-free Rust code that evaluates the expressions, which can be
+    /// embedded in another crate with `include!`. The code contains the function
+    /// `{function_name}_double` for real numbers and `{function_name}_complex` for complex
+    /// numbers represented as `[re, im]`. Both take a work buffer `z` of length
+    /// `{FUNCTION_NAME}_BUFFER_LEN`.
+    ///
+    /// # Example
+    /// ```
+    /// # use symbolica::{atom::AtomCore, evaluate::{FunctionMap, OptimizationSettings}, parse};
+    /// let eval = parse!("x^2 + sin(x)")
+    ///     .evaluator(&FunctionMap::new(), &[parse!("x")], OptimizationSettings::default())
+    ///     .unwrap();
+    ///
+    /// let code = eval.export_rust_str("f");
+    /// assert!(code.contains("pub fn f_double(params: &[f64], z: &mut [f64], out: &mut [f64])"));
+    /// ```
+    pub fn export_rust_str(&self, function_name: &str) -> String {
+        let constants: Vec<_> = self.stack[self.param_count..self.reserved_indices]
+            .iter()
+            .map(|x| x.to_complex_f64())
+            .collect();
+
+        let mut res = format!(
+            "/// The length of the work buffer of `{0}_double` and `{0}_complex`.\npub const {1}_BUFFER_LEN: usize = {2};\n\n",
+            function_name,
+            function_name.to_uppercase(),
+            self.stack.len()
+        );
+
+        res += &format!(
+            "#[allow(unused_mut, clippy::all)]\n#[inline]\npub fn {}_double(params: &[f64], z: &mut [f64], out: &mut [f64]) {{\n",
+            function_name
+        );
+
+        if constants.iter().all(|x| x.im == 0.) {
+            res += &format!("    let z = &mut z[..{}];\n", self.stack.len());
+            res += &format!(
+                "    z[..{0}].copy_from_slice(&params[..{0}]);\n",
+                self.param_count
+            );
+
+            for (i, c) in constants.iter().enumerate() {
+                res += &format!(
+                    "    z[{}] = {};\n",
+                    self.param_count + i,
+                    rust_f64_literal(c.re)
+                );
+            }
+
+            self.export_rust_double_impl(&mut res);
+
+            for (i, r) in self.result_indices.iter().enumerate() {
+                res += &format!("    out[{}] = z[{}];\n", i, r);
+            }
+        } else {
+            res += "    panic!(\"Cannot evaluate complex function with doubles\");\n";
+        }
+
+        res += "}\n\n";
+
+        res += &format!(
+            "#[allow(unused_mut, dead_code, clippy::all)]\n#[inline]\npub fn {}_complex(params: &[[f64; 2]], z: &mut [[f64; 2]], out: &mut [[f64; 2]]) {{\n",
+            function_name
+        );
+        res += RUST_COMPLEX_FUNCTIONS;
+        res += "\n";
+        res += &format!("    let z = &mut z[..{}];\n", self.stack.len());
+        res += &format!(
+            "    z[..{0}].copy_from_slice(&params[..{0}]);\n",
+            self.param_count
+        );
+
+        for (i, c) in constants.iter().enumerate() {
+            res += &format!(
+                "    z[{}] = [{}, {}];\n",
+                self.param_count + i,
+                rust_f64_literal(c.re),
+                rust_f64_literal(c.im)
+            );
+        }
+
+        self.export_rust_complex_impl(&mut res);
+
+        for (i, r) in self.result_indices.iter().enumerate() {
+            res += &format!("    out[{}] = z[{}];\n", i, r);
+        }
+
+        res += "}\n";
+        res
+    }
+
+    fn export_rust_double_impl(&self, out: &mut String) {
+        for ins in &self.instructions {
+            match ins {
+                Instr::Add(o, a) | Instr::Mul(o, a) => {
+                    let op = if matches!(ins, Instr::Add(_, _)) {
+                        " + "
+                    } else {
+                        " * "
+                    };
+
+                    let args = a
+                        .iter()
+                        .map(|x| format!("z[{}]", x))
+                        .collect::<Vec<_>>()
+                        .join(op);
+
+                    *out += &format!("    z[{}] = {};\n", o, args);
+                }
+                Instr::Pow(o, b, e) => {
+                    if *e == -1 {
+                        *out += &format!("    z[{}] = 1.0 / z[{}];\n", o, b);
+                    } else if let Ok(e) = i32::try_from(*e) {
+                        *out += &format!("    z[{}] = z[{}].powi({});\n", o, b, e);
+                    } else {
+                        *out += &format!("    z[{}] = z[{}].powf({}.0);\n", o, b, e);
+                    }
+                }
+                Instr::Powf(o, b, e) => {
+                    *out += &format!("    z[{}] = z[{}].powf(z[{}]);\n", o, b, e);
+                }
+                Instr::BuiltinFun(o, s, a) => {
+                    *out += &format!("    z[{}] = z[{}].{}();\n", o, a, s.rust_name());
+                }
+            }
+        }
+    }
+
+    fn export_rust_complex_impl(&self, out: &mut String) {
+        for ins in &self.instructions {
+            match ins {
+                Instr::Add(o, a) | Instr::Mul(o, a) => {
+                    let op = if matches!(ins, Instr::Add(_, _)) {
+                        "add"
+                    } else {
+                        "mul"
+                    };
+
+                    let mut r = format!("z[{}]", a[0]);
+                    for x in &a[1..] {
+                        r = format!("{}({}, z[{}])", op, r, x);
+                    }
+
+                    *out += &format!("    z[{}] = {};\n", o, r);
+                }
+                Instr::Pow(o, b, e) => {
+                    if *e == -1 {
+                        *out += &format!("    z[{}] = inv(z[{}]);\n", o, b);
+                    } else {
+                        *out += &format!("    z[{}] = powi(z[{}], {});\n", o, b, e);
+                    }
+                }
+                Instr::Powf(o, b, e) => {
+                    *out += &format!("    z[{}] = powf(z[{}], z[{}]);\n", o, b, e);
+                }
+                Instr::BuiltinFun(o, s, a) => {
+                    *out += &format!("    z[{}] = {}(z[{}]);\n", o, s.cpp_name(), a);
+                }
+            }
+        }
+    }
+}
+
+/// The dialect of the accelerator kernel source that is generated by
+/// [ExpressionEvaluator::export_kernel_source].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KernelDialect {
+    /// OpenCL C, with a `__kernel` function.
+    OpenCL,
+    /// CUDA C, with an `extern "C" __global__` function.
+    Cuda,
+}
+
+/// Format a double as a C literal.
+fn c_f64_literal(x: f64) -> String {
+    if x.is_nan() {
+        "NAN".to_owned()
+    } else if x.is_infinite() {
+        if x > 0. { "INFINITY" } else { "-INFINITY" }.to_owned()
+    } else {
+        format!("{:?}", x)
+    }
+}
+
+impl<T: NativeNumber> ExpressionEvaluator<T> {
+    /// Create OpenCL C or CUDA C source code of a kernel that evaluates the expressions
+    /// in double precision, using one thread per evaluation point.
+    ///
+    /// The kernel `{function_name}` has the arguments `params`, `out` and `n_points`.
+    /// The inputs and outputs use a structure-of-arrays layout: parameter `j` of point `i` is
+    /// `params[j * n_points + i]` and output `k` of point `i` is `out[k * n_points + i]`.
+    ///
+    /// The body of the kernel only uses C syntax and the functions of `math.h`, so that it can be
+    /// validated on the CPU by defining the dialect-specific keywords as macros. The OpenCL kernel
+    /// then compiles as C. The CUDA kernel is declared `extern "C"` and compiles as C++.
+    /// An error is returned if the evaluator contains complex constants.
+    ///
+    /// # Example
+    /// ```
+    /// # use symbolica::{atom::AtomCore, evaluate::{FunctionMap, KernelDialect, OptimizationSettings}, parse};
+    /// let eval = parse!("x^2 + sin(y)")
+    ///     .evaluator(&FunctionMap::new(), &[parse!("x"), parse!("y")], OptimizationSettings::default())
+    ///     .unwrap();
+    ///
+    /// let code = eval.export_kernel_source("f", KernelDialect::OpenCL).unwrap();
+    /// assert!(code.contains("__kernel void f(__global const double *params"));
+    /// ```
+    pub fn export_kernel_source(
+        &self,
+        function_name: &str,
+        dialect: KernelDialect,
+    ) -> Result<String, String> {
+        let mut constants = vec![];
+        for c in &self.stack[self.param_count..self.reserved_indices] {
+            let c = c.to_complex_f64();
+            if c.im != 0. {
+                return Err(
+                    "Cannot export a kernel for an evaluator with complex constants".into(),
+                );
+            }
+            constants.push(c.re);
+        }
+
+        let mut res = match dialect {
+            KernelDialect::OpenCL => format!(
+                "#pragma OPENCL EXTENSION cl_khr_fp64 : enable\n\n__kernel void {}(__global const double *params, __global double *out, const unsigned long n_points) {{\n\tconst unsigned long i = get_global_id(0);\n",
+                function_name
+            ),
+            KernelDialect::Cuda => format!(
+                "extern \"C\" __global__ void {}(const double *params, double *out, const unsigned long n_points) {{\n\tconst unsigned long i = (unsigned long)blockIdx.x * blockDim.x + threadIdx.x;\n",
+                function_name
+            ),
+        };
+
+        res += "\tif (i >= n_points) return;\n\n";
+        res += &format!("\tdouble Z[{}];\n", self.stack.len());
+
+        for j in 0..self.param_count {
+            res += &format!("\tZ[{0}] = params[{0} * n_points + i];\n", j);
+        }
+
+        for (j, c) in constants.iter().enumerate() {
+            res += &format!("\tZ[{}] = {};\n", self.param_count + j, c_f64_literal(*c));
+        }
+
+        for ins in &self.instructions {
+            match ins {
+                Instr::Add(o, a) | Instr::Mul(o, a) => {
+                    let op = if matches!(ins, Instr::Add(_, _)) {
+                        " + "
+                    } else {
+                        " * "
+                    };
+
+                    let args = a
+                        .iter()
+                        .map(|x| format!("Z[{}]", x))
+                        .collect::<Vec<_>>()
+                        .join(op);
+
+                    res += &format!("\tZ[{}] = {};\n", o, args);
+                }
+                Instr::Pow(o, b, e) => {
+                    if *e == -1 {
+                        res += &format!("\tZ[{}] = 1.0 / Z[{}];\n", o, b);
+                    } else {
+                        res += &format!("\tZ[{}] = pow(Z[{}], {}.0);\n", o, b, e);
+                    }
+                }
+                Instr::Powf(o, b, e) => {
+                    res += &format!("\tZ[{}] = pow(Z[{}], Z[{}]);\n", o, b, e);
+                }
+                Instr::BuiltinFun(o, s, a) => {
+                    res += &format!("\tZ[{}] = {}(Z[{}]);\n", o, s.cpp_name(), a);
+                }
+            }
+        }
 
-impl<T: ExportNumber + SingleFloat> ExportNumber for Complex<T> {
-    fn export(&self) -> String {
-        if self.im.is_zero() {
-            self.re.export()
-        } else {
-            format!("{}, {}", self.re.export(), self.im.export())
+        res += "\n";
+        for (k, r) in self.result_indices.iter().enumerate() {
+            res += &format!("\tout[{} * n_points + i] = Z[{}];\n", k, r);
         }
-    }
 
-    fn is_real(&self) -> bool {
-        self.im.is_zero()
+        res += "}\n";
+        Ok(res)
     }
 }
 
@@ -1404,14 +2985,23 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
         })
     }
 
+    /// Create C++ code that evaluates the expressions. The code contains the functions
+    /// `{function_name}_double` and `{function_name}_complex` and their batched versions.
+    /// If the coefficients of the evaluator are [DoubleDouble], for example after converting
+    /// them with [ExpressionEvaluator::map_coeff], the code also contains a double-double type
+    /// and the function `{function_name}_double_double`.
     pub fn export_cpp_str(&self, function_name: &str, include_header: bool) -> String {
         let mut res = String::new();
         if include_header {
             res += "#include <iostream>\n#include <complex>\n#include <cmath>\n\n";
         };
 
+        if T::IS_DOUBLE_DOUBLE {
+            res += CPP_DOUBLE_DOUBLE;
+        }
+
         res += &format!(
-            "extern \"C\" unsigned long {}_get_buffer_len()\n{{\n\treturn {};\n}}\n\n",
+            "\nextern \"C\" unsigned long {}_get_buffer_len()\n{{\n\treturn {};\n}}\n\n",
             function_name,
             self.stack.len()
         );
@@ -1429,7 +3019,7 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
             res += &format!("\tZ[{}] = {};\n", i, self.stack[i].export_wrapped());
         }
 
-        Self::export_cpp_impl(&self.instructions, &mut res);
+        Self::export_cpp_impl(&self.instructions, 1, &mut res);
 
         for (i, r) in &mut self.result_indices.iter().enumerate() {
             res += &format!("\tout[{}] = Z[{}];\n", i, r);
@@ -1437,6 +3027,50 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
 
         res += "\treturn;\n}\n";
 
+        // evaluate multiple points in a loop that the compiler can vectorize,
+        // the buffer `Z` stores `BATCH_LANES` points per entry
+        res += &format!(
+            "\nextern \"C\" unsigned long {}_get_batch_lanes()\n{{\n\treturn {};\n}}\n",
+            function_name, BATCH_LANES
+        );
+
+        self.export_io_len(function_name, &mut res);
+
+        res += &format!(
+            "\ntemplate<typename T>\nvoid {0}_batch(T* params, T* Z, T* out, unsigned long n_points) {{\n\tfor (unsigned long b = 0; b < n_points; b += {1}) {{\n\t\tunsigned long n = n_points - b < {1} ? n_points - b : {1};\n\t\t#pragma omp simd\n\t\tfor (unsigned long l = 0; l < n; l++) {{\n",
+            function_name, BATCH_LANES
+        );
+
+        for i in 0..self.param_count {
+            res += &format!(
+                "\t\t\tZ[{}+l] = params[(b+l)*{}+{}];\n",
+                i * BATCH_LANES,
+                self.param_count,
+                i
+            );
+        }
+
+        for i in self.param_count..self.reserved_indices {
+            res += &format!(
+                "\t\t\tZ[{}+l] = {};\n",
+                i * BATCH_LANES,
+                self.stack[i].export_wrapped()
+            );
+        }
+
+        Self::export_cpp_impl(&self.instructions, BATCH_LANES, &mut res);
+
+        for (i, r) in &mut self.result_indices.iter().enumerate() {
+            res += &format!(
+                "\t\t\tout[(b+l)*{}+{}] = Z[{}+l];\n",
+                self.result_indices.len(),
+                i,
+                r * BATCH_LANES
+            );
+        }
+
+        res += "\t\t}\n\t}\n\treturn;\n}\n";
+
         if self.stack.iter().all(|x| x.is_real()) {
             res += &format!(
                 "\nextern \"C\" {{\n\tvoid {0}_double(double *params, double *buffer, double *out) {{\n\t\t{0}(params, buffer, out);\n\t\treturn;\n\t}}\n}}\n",
@@ -1454,66 +3088,84 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
             function_name
         );
 
+        if T::IS_DOUBLE_DOUBLE {
+            res += &format!(
+                "\nextern \"C\" {{\n\tvoid {0}_double_double(dd_real *params, dd_real *buffer, dd_real *out) {{\n\t\t{0}(params, buffer, out);\n\t\treturn;\n\t}}\n}}\n",
+                function_name
+            );
+        }
+
+        if self.stack.iter().all(|x| x.is_real()) {
+            res += &format!(
+                "\nextern \"C\" {{\n\tvoid {0}_double_batch(double *params, double *buffer, double *out, unsigned long n_points) {{\n\t\t{0}_batch(params, buffer, out, n_points);\n\t\treturn;\n\t}}\n}}\n",
+                function_name
+            );
+        }
+
+        res += &format!(
+            "\nextern \"C\" {{\n\tvoid {0}_complex_batch(std::complex<double> *params, std::complex<double> *buffer, std::complex<double> *out, unsigned long n_points) {{\n\t\t{0}_batch(params, buffer, out, n_points);\n\t\treturn;\n\t}}\n}}\n",
+            function_name
+        );
+
         res
     }
 
-    fn export_cpp_impl(instr: &[Instr], out: &mut String) {
+    /// Export the number of parameters and outputs, which are used to check the
+    /// buffer lengths of the batch functions of [CompiledEvaluator].
+    fn export_io_len(&self, function_name: &str, out: &mut String) {
+        *out += &format!(
+            "\nextern \"C\" unsigned long {}_get_param_count()\n{{\n\treturn {};\n}}\n",
+            function_name, self.param_count
+        );
+
+        *out += &format!(
+            "\nextern \"C\" unsigned long {}_get_output_len()\n{{\n\treturn {};\n}}\n\n",
+            function_name,
+            self.result_indices.len()
+        );
+    }
+
+    /// Export the instructions to C++. If `lanes` is larger than 1, the
+    /// buffer `Z` holds `lanes` points per entry and the current point is `l`.
+    fn export_cpp_impl(instr: &[Instr], lanes: usize, out: &mut String) {
+        let indent = if lanes == 1 { "\t" } else { "\t\t\t" };
+        let z = |i: usize| {
+            if lanes == 1 {
+                format!("Z[{}]", i)
+            } else {
+                format!("Z[{}+l]", i * lanes)
+            }
+        };
+
         for ins in instr {
             match ins {
                 Instr::Add(o, a) => {
-                    let args = a
-                        .iter()
-                        .map(|x| format!("Z[{}]", x))
-                        .collect::<Vec<_>>()
-                        .join("+");
+                    let args = a.iter().map(|x| z(*x)).collect::<Vec<_>>().join("+");
 
-                    *out += format!("\tZ[{}] = {};\n", o, args).as_str();
+                    *out += format!("{}{} = {};\n", indent, z(*o), args).as_str();
                 }
                 Instr::Mul(o, a) => {
-                    let args = a
-                        .iter()
-                        .map(|x| format!("Z[{}]", x))
-                        .collect::<Vec<_>>()
-                        .join("*");
+                    let args = a.iter().map(|x| z(*x)).collect::<Vec<_>>().join("*");
 
-                    *out += format!("\tZ[{}] = {};\n", o, args).as_str();
+                    *out += format!("{}{} = {};\n", indent, z(*o), args).as_str();
                 }
                 Instr::Pow(o, b, e) => {
-                    let base = format!("Z[{}]", b);
+                    let base = z(*b);
                     if *e == -1 {
-                        *out += format!("\tZ[{}] = T(1) / {};\n", o, base).as_str();
+                        *out += format!("{}{} = T(1) / {};\n", indent, z(*o), base).as_str();
                     } else {
-                        *out += format!("\tZ[{}] = pow({}, {});\n", o, base, e).as_str();
+                        *out += format!("{}{} = pow({}, {});\n", indent, z(*o), base, e).as_str();
                     }
                 }
                 Instr::Powf(o, b, e) => {
-                    let base = format!("Z[{}]", b);
-                    let exp = format!("Z[{}]", e);
-                    *out += format!("\tZ[{}] = pow({}, {});\n", o, base, exp).as_str();
+                    let base = z(*b);
+                    let exp = z(*e);
+                    *out += format!("{}{} = pow({}, {});\n", indent, z(*o), base, exp).as_str();
+                }
+                Instr::BuiltinFun(o, s, a) => {
+                    let arg = z(*a);
+                    *out += format!("{}{} = {}({});\n", indent, z(*o), s.cpp_name(), arg).as_str();
                 }
-                Instr::BuiltinFun(o, s, a) => match s.0 {
-                    Atom::EXP => {
-                        let arg = format!("Z[{}]", a);
-                        *out += format!("\tZ[{}] = exp({});\n", o, arg).as_str();
-                    }
-                    Atom::LOG => {
-                        let arg = format!("Z[{}]", a);
-                        *out += format!("\tZ[{}] = log({});\n", o, arg).as_str();
-                    }
-                    Atom::SIN => {
-                        let arg = format!("Z[{}]", a);
-                        *out += format!("\tZ[{}] = sin({});\n", o, arg).as_str();
-                    }
-                    Atom::COS => {
-                        let arg = format!("Z[{}]", a);
-                        *out += format!("\tZ[{}] = cos({});\n", o, arg).as_str();
-                    }
-                    Atom::SQRT => {
-                        let arg = format!("Z[{}]", a);
-                        *out += format!("\tZ[{}] = sqrt({});\n", o, arg).as_str();
-                    }
-                    _ => unreachable!(),
-                },
             }
         }
     }
@@ -1535,6 +3187,8 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
             self.stack.len()
         );
 
+        self.export_io_len(function_name, &mut res);
+
         res += &format!(
             "static const std::complex<double> {}_CONSTANTS_complex[{}] = {{{}}};\n\n",
             function_name,
@@ -1707,177 +3361,8 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
             };
         }
 
-        let mut reg_last_use = vec![self.instructions.len(); self.instructions.len()];
-        let mut stack_to_reg = HashMap::default();
-
-        for (i, ins) in instr.iter().enumerate() {
-            match ins {
-                Instr::Add(r, a) | Instr::Mul(r, a) => {
-                    for x in a {
-                        if x >= &self.reserved_indices {
-                            reg_last_use[stack_to_reg[x]] = i;
-                        }
-                    }
-
-                    stack_to_reg.insert(r, i);
-                }
-                Instr::Pow(r, b, _) => {
-                    if b >= &self.reserved_indices {
-                        reg_last_use[stack_to_reg[b]] = i;
-                    }
-                    stack_to_reg.insert(r, i);
-                }
-                Instr::Powf(r, b, e) => {
-                    if b >= &self.reserved_indices {
-                        reg_last_use[stack_to_reg[b]] = i;
-                    }
-                    if e >= &self.reserved_indices {
-                        reg_last_use[stack_to_reg[e]] = i;
-                    }
-                    stack_to_reg.insert(r, i);
-                }
-                Instr::BuiltinFun(r, _, b) => {
-                    if b >= &self.reserved_indices {
-                        reg_last_use[stack_to_reg[b]] = i;
-                    }
-                    stack_to_reg.insert(r, i);
-                }
-            }
-        }
-
-        for x in &self.result_indices {
-            if x >= &self.reserved_indices {
-                reg_last_use[stack_to_reg[x]] = self.instructions.len();
-            }
-        }
-
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-        enum MemOrReg {
-            Mem(usize),
-            Reg(usize),
-        }
-
-        #[derive(Debug, Clone)]
-        enum RegInstr {
-            Add(MemOrReg, u16, Vec<MemOrReg>),
-            Mul(MemOrReg, u16, Vec<MemOrReg>),
-            Pow(MemOrReg, u16, MemOrReg, i64),
-            Powf(usize, usize, usize),
-            BuiltinFun(usize, BuiltinSymbol, usize),
-        }
-
-        let mut new_instr: Vec<RegInstr> = instr
-            .iter()
-            .map(|i| match i {
-                Instr::Add(r, a) => RegInstr::Add(
-                    MemOrReg::Mem(*r),
-                    u16::MAX,
-                    a.iter().map(|x| MemOrReg::Mem(*x)).collect(),
-                ),
-                Instr::Mul(r, a) => RegInstr::Mul(
-                    MemOrReg::Mem(*r),
-                    u16::MAX,
-                    a.iter().map(|x| MemOrReg::Mem(*x)).collect(),
-                ),
-                Instr::Pow(r, b, e) => {
-                    RegInstr::Pow(MemOrReg::Mem(*r), u16::MAX, MemOrReg::Mem(*b), *e)
-                }
-                Instr::Powf(r, b, e) => RegInstr::Powf(*r, *b, *e),
-                Instr::BuiltinFun(r, s, a) => RegInstr::BuiltinFun(*r, *s, *a),
-            })
-            .collect();
-
-        // sort the list of instructions based on the distance
-        let mut reg_list = reg_last_use.iter().enumerate().collect::<Vec<_>>();
-        reg_list.sort_by_key(|x| (*x.1 - x.0, x.0));
-
-        'next: for (j, last_use) in reg_list {
-            if *last_use == self.instructions.len() {
-                continue;
-            }
-
-            let old_reg = if let RegInstr::Add(r, _, _)
-            | RegInstr::Mul(r, _, _)
-            | RegInstr::Pow(r, _, _, -1) = &new_instr[j]
-            {
-                if let MemOrReg::Mem(r) = r {
-                    *r
-                } else {
-                    continue;
-                }
-            } else {
-                continue;
-            };
-
-            // find free registers in the range
-            // start at j+1 as we can recycle registers that are last used in iteration j
-            let mut free_regs = u16::MAX & !(1 << 15); // leave xmmm15 open
-
-            for k in &new_instr[j + 1..=*last_use] {
-                match k {
-                    RegInstr::Add(_, f, _)
-                    | RegInstr::Mul(_, f, _)
-                    | RegInstr::Pow(_, f, _, -1) => {
-                        free_regs &= f;
-                    }
-
-                    _ => {
-                        free_regs = 0; // the current instruction is not allowed to be used outside of ASM blocks
-                    }
-                }
-
-                if free_regs == 0 {
-                    continue 'next;
-                }
-            }
-
-            if let Some(k) = (0..16).position(|k| free_regs & (1 << k) != 0) {
-                if let RegInstr::Add(r, _, _) | RegInstr::Mul(r, _, _) | RegInstr::Pow(r, _, _, _) =
-                    &mut new_instr[j]
-                {
-                    *r = MemOrReg::Reg(k);
-                }
-
-                for l in &mut new_instr[j + 1..=*last_use] {
-                    match l {
-                        RegInstr::Add(_, f, a) | RegInstr::Mul(_, f, a) => {
-                            *f &= !(1 << k); // FIXME: do not set on last use?
-                            for x in a {
-                                if *x == MemOrReg::Mem(old_reg) {
-                                    *x = MemOrReg::Reg(k);
-                                }
-                            }
-                        }
-                        RegInstr::Pow(_, f, a, -1) => {
-                            *f &= !(1 << k); // FIXME: do not set on last use?
-                            if *a == MemOrReg::Mem(old_reg) {
-                                *a = MemOrReg::Reg(k);
-                            }
-                        }
-                        RegInstr::Pow(_, _, _, _) => {
-                            panic!("use outside of ASM block");
-                        }
-                        RegInstr::Powf(_, a, b) => {
-                            if *a == old_reg {
-                                panic!("use outside of ASM block");
-                            }
-                            if *b == old_reg {
-                                panic!("use outside of ASM block");
-                            }
-                        }
-                        RegInstr::BuiltinFun(_, _, a) => {
-                            if *a == old_reg {
-                                panic!("use outside of ASM block");
-                            }
-                        }
-                    }
-                }
-
-                // TODO: if last use is not already set to a register, we can set it to the current one
-                // this prevents a copy
-            }
-        }
-
+        let new_instr = self.assign_registers(instr);
+
         let mut in_asm_block = false;
         for ins in &new_instr {
             match ins {
@@ -2396,24 +3881,7 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
 
                     let arg = get_input!(*a);
 
-                    match s.0 {
-                        Atom::EXP => {
-                            *out += format!("\tZ[{}] = exp({});\n", o, arg).as_str();
-                        }
-                        Atom::LOG => {
-                            *out += format!("\tZ[{}] = log({});\n", o, arg).as_str();
-                        }
-                        Atom::SIN => {
-                            *out += format!("\tZ[{}] = sin({});\n", o, arg).as_str();
-                        }
-                        Atom::COS => {
-                            *out += format!("\tZ[{}] = cos({});\n", o, arg).as_str();
-                        }
-                        Atom::SQRT => {
-                            *out += format!("\tZ[{}] = sqrt({});\n", o, arg).as_str();
-                        }
-                        _ => unreachable!(),
-                    }
+                    *out += format!("\tZ[{}] = {}({});\n", o, s.cpp_name(), arg).as_str();
                 }
             }
         }
@@ -2820,24 +4288,7 @@ impl<T: ExportNumber + SingleFloat> ExpressionEvaluator<T> {
 
                     let arg = get_input!(*a);
 
-                    match s.0 {
-                        Atom::EXP => {
-                            *out += format!("\tZ[{}] = exp({});\n", o, arg).as_str();
-                        }
-                        Atom::LOG => {
-                            *out += format!("\tZ[{}] = log({});\n", o, arg).as_str();
-                        }
-                        Atom::SIN => {
-                            *out += format!("\tZ[{}] = sin({});\n", o, arg).as_str();
-                        }
-                        Atom::COS => {
-                            *out += format!("\tZ[{}] = cos({});\n", o, arg).as_str();
-                        }
-                        Atom::SQRT => {
-                            *out += format!("\tZ[{}] = sqrt({});\n", o, arg).as_str();
-                        }
-                        _ => unreachable!(),
-                    }
+                    *out += format!("\tZ[{}] = {}({});\n", o, s.cpp_name(), arg).as_str();
                 }
             }
         }
@@ -3055,6 +4506,21 @@ impl<T: Clone> ExpressionEvaluator<T> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemOrReg {
+    Mem(usize),
+    Reg(usize),
+}
+
+#[derive(Debug, Clone)]
+enum RegInstr {
+    Add(MemOrReg, u16, Vec<MemOrReg>),
+    Mul(MemOrReg, u16, Vec<MemOrReg>),
+    Pow(MemOrReg, u16, MemOrReg, i64),
+    Powf(usize, usize, usize),
+    BuiltinFun(usize, BuiltinSymbol, usize),
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 #[derive(Debug, Clone, PartialEq)]
@@ -4311,14 +5777,7 @@ impl<T: Real> EvalTree<T> {
             Expression::ReadArg(i) => args[*i].clone(),
             Expression::BuiltinFun(s, a) => {
                 let arg = self.evaluate_impl(a, subexpressions, params, args);
-                match s.0 {
-                    Atom::EXP => arg.exp(),
-                    Atom::LOG => arg.log(),
-                    Atom::SIN => arg.sin(),
-                    Atom::COS => arg.cos(),
-                    Atom::SQRT => arg.sqrt(),
-                    _ => unreachable!(),
-                }
+                s.evaluate(&arg)
             }
             Expression::SubExpression(s) => {
                 // TODO: cache
@@ -4346,6 +5805,9 @@ impl CompiledCode {
 
 type L = std::sync::Arc<libloading::Library>;
 
+type BatchFunction<T> =
+    unsafe extern "C" fn(params: *const T, buffer: *mut T, out: *mut T, n_points: c_ulong);
+
 struct EvaluatorFunctions<'a> {
     eval_double: libloading::Symbol<
         'a,
@@ -4360,6 +5822,21 @@ struct EvaluatorFunctions<'a> {
         ),
     >,
     get_buffer_len: libloading::Symbol<'a, unsafe extern "C" fn() -> c_ulong>,
+    eval_double_double: Option<
+        libloading::Symbol<
+            'a,
+            unsafe extern "C" fn(
+                params: *const DoubleDouble,
+                buffer: *mut DoubleDouble,
+                out: *mut DoubleDouble,
+            ),
+        >,
+    >,
+    eval_double_batch: Option<libloading::Symbol<'a, BatchFunction<f64>>>,
+    eval_complex_batch: Option<libloading::Symbol<'a, BatchFunction<Complex<f64>>>>,
+    get_batch_lanes: Option<libloading::Symbol<'a, unsafe extern "C" fn() -> c_ulong>>,
+    get_param_count: Option<libloading::Symbol<'a, unsafe extern "C" fn() -> c_ulong>>,
+    get_output_len: Option<libloading::Symbol<'a, unsafe extern "C" fn() -> c_ulong>>,
 }
 
 pub struct CompiledEvaluator {
@@ -4367,6 +5844,7 @@ pub struct CompiledEvaluator {
     library: Library,
     buffer_double: Vec<f64>,
     buffer_complex: Vec<Complex<f64>>,
+    buffer_double_double: Vec<DoubleDouble>,
 }
 
 self_cell!(
@@ -4404,6 +5882,13 @@ impl CompiledEvaluatorFloat for f64 {
     }
 }
 
+impl CompiledEvaluatorFloat for DoubleDouble {
+    #[inline(always)]
+    fn evaluate(eval: &mut CompiledEvaluator, args: &[Self], out: &mut [Self]) {
+        eval.evaluate_double_double(args, out);
+    }
+}
+
 impl CompiledEvaluatorFloat for Complex<f64> {
     #[inline(always)]
     fn evaluate(eval: &mut CompiledEvaluator, args: &[Self], out: &mut [Self]) {
@@ -4426,6 +5911,24 @@ impl CompiledEvaluator {
                     get_buffer_len: lib
                         .get(format!("{}_get_buffer_len", function_name).as_bytes())
                         .map_err(|e| e.to_string())?,
+                    eval_double_double: lib
+                        .get(format!("{}_double_double", function_name).as_bytes())
+                        .ok(),
+                    eval_double_batch: lib
+                        .get(format!("{}_double_batch", function_name).as_bytes())
+                        .ok(),
+                    eval_complex_batch: lib
+                        .get(format!("{}_complex_batch", function_name).as_bytes())
+                        .ok(),
+                    get_batch_lanes: lib
+                        .get(format!("{}_get_batch_lanes", function_name).as_bytes())
+                        .ok(),
+                    get_param_count: lib
+                        .get(format!("{}_get_param_count", function_name).as_bytes())
+                        .ok(),
+                    get_output_len: lib
+                        .get(format!("{}_get_output_len", function_name).as_bytes())
+                        .ok(),
                 })
             })
         }?;
@@ -4436,6 +5939,7 @@ impl CompiledEvaluator {
             fn_name: function_name.to_string(),
             buffer_double: vec![0.; len],
             buffer_complex: vec![Complex::new(0., 0.); len],
+            buffer_double_double: vec![DoubleDouble::default(); len],
             library,
         })
     }
@@ -4461,6 +5965,24 @@ impl CompiledEvaluator {
                     get_buffer_len: lib
                         .get(format!("{}_get_buffer_len", function_name).as_bytes())
                         .map_err(|e| e.to_string())?,
+                    eval_double_double: lib
+                        .get(format!("{}_double_double", function_name).as_bytes())
+                        .ok(),
+                    eval_double_batch: lib
+                        .get(format!("{}_double_batch", function_name).as_bytes())
+                        .ok(),
+                    eval_complex_batch: lib
+                        .get(format!("{}_complex_batch", function_name).as_bytes())
+                        .ok(),
+                    get_batch_lanes: lib
+                        .get(format!("{}_get_batch_lanes", function_name).as_bytes())
+                        .ok(),
+                    get_param_count: lib
+                        .get(format!("{}_get_param_count", function_name).as_bytes())
+                        .ok(),
+                    get_output_len: lib
+                        .get(format!("{}_get_output_len", function_name).as_bytes())
+                        .ok(),
                 })
             })?;
 
@@ -4470,6 +5992,7 @@ impl CompiledEvaluator {
                 fn_name: function_name.to_string(),
                 buffer_double: vec![0.; len],
                 buffer_complex: vec![Complex::new(0., 0.); len],
+                buffer_double_double: vec![DoubleDouble::default(); len],
                 library,
             })
         }
@@ -4504,6 +6027,140 @@ impl CompiledEvaluator {
             )
         }
     }
+
+    /// Evaluate the compiled code with double-double numbers.
+    ///
+    /// Panics if the library does not contain a double-double function, for example because
+    /// the coefficients of the exported evaluator were not [DoubleDouble] or the code was
+    /// exported with inline ASM.
+    #[inline(always)]
+    pub fn evaluate_double_double(&mut self, args: &[DoubleDouble], out: &mut [DoubleDouble]) {
+        let f = self
+            .library
+            .borrow_dependent()
+            .eval_double_double
+            .as_ref()
+            .expect("The library does not contain a double-double evaluation function");
+
+        unsafe {
+            f(
+                args.as_ptr(),
+                self.buffer_double_double.as_mut_ptr(),
+                out.as_mut_ptr(),
+            )
+        }
+    }
+
+    /// Get the buffer length required by the batch functions, if they are present.
+    fn batch_buffer_len(&self) -> Option<usize> {
+        let lanes = self.library.borrow_dependent().get_batch_lanes.as_ref()?;
+        let len = unsafe { (self.library.borrow_dependent().get_buffer_len)() } as usize;
+        Some(len * unsafe { lanes() } as usize)
+    }
+
+    /// Check that `args` and `out` hold `n_points` points, if the library
+    /// exports its number of parameters and outputs.
+    fn check_batch_len(&self, args: usize, n_points: usize, out: usize) {
+        let f = self.library.borrow_dependent();
+        if let (Some(param_count), Some(output_len)) = (&f.get_param_count, &f.get_output_len) {
+            let (param_count, output_len) =
+                unsafe { (param_count() as usize, output_len() as usize) };
+            assert!(
+                args >= n_points * param_count && out >= n_points * output_len,
+                "Parameter or output buffer too small for {} points",
+                n_points
+            );
+        }
+    }
+
+    /// Evaluate the compiled code with double-precision floating point numbers at `n_points` points,
+    /// using the same layout as [ExpressionEvaluator::evaluate_batch]. The lengths
+    /// of `args` and `out` must be multiples of `n_points`.
+    ///
+    /// Code exported without a batch function, such as code with inline ASM,
+    /// is evaluated one point at a time.
+    ///
+    /// Panics if `args` or `out` is too small for `n_points` points.
+    pub fn evaluate_double_batch(&mut self, args: &[f64], n_points: usize, out: &mut [f64]) {
+        if n_points == 0 {
+            return;
+        }
+
+        self.check_batch_len(args.len(), n_points, out.len());
+
+        if let Some(len) = self.batch_buffer_len() {
+            if self.buffer_double.len() < len {
+                self.buffer_double.resize(len, 0.);
+            }
+
+            if let Some(f) = &self.library.borrow_dependent().eval_double_batch {
+                unsafe {
+                    f(
+                        args.as_ptr(),
+                        self.buffer_double.as_mut_ptr(),
+                        out.as_mut_ptr(),
+                        n_points as c_ulong,
+                    )
+                };
+                return;
+            }
+        }
+
+        let (n_args, n_out) = (args.len() / n_points, out.len() / n_points);
+        for i in 0..n_points {
+            self.evaluate_double(
+                &args[i * n_args..(i + 1) * n_args],
+                &mut out[i * n_out..(i + 1) * n_out],
+            );
+        }
+    }
+
+    /// Evaluate the compiled code with complex numbers at `n_points` points,
+    /// using the same layout as [ExpressionEvaluator::evaluate_batch]. The lengths
+    /// of `args` and `out` must be multiples of `n_points`.
+    ///
+    /// Code exported without a batch function, such as code with inline ASM,
+    /// is evaluated one point at a time.
+    ///
+    /// Panics if `args` or `out` is too small for `n_points` points.
+    pub fn evaluate_complex_batch(
+        &mut self,
+        args: &[Complex<f64>],
+        n_points: usize,
+        out: &mut [Complex<f64>],
+    ) {
+        if n_points == 0 {
+            return;
+        }
+
+        self.check_batch_len(args.len(), n_points, out.len());
+
+        if let Some(len) = self.batch_buffer_len() {
+            if self.buffer_complex.len() < len {
+                self.buffer_complex.resize(len, Complex::new(0., 0.));
+            }
+
+            if let Some(f) = &self.library.borrow_dependent().eval_complex_batch {
+                unsafe {
+                    f(
+                        args.as_ptr(),
+                        self.buffer_complex.as_mut_ptr(),
+                        out.as_mut_ptr(),
+                        n_points as c_ulong,
+                    )
+                };
+                return;
+            }
+        }
+
+        let (n_args, n_out) = (args.len() / n_points, out.len() / n_points);
+        for i in 0..n_points {
+            self.evaluate_complex(
+                &args[i * n_args..(i + 1) * n_args],
+                &mut out[i * n_out..(i + 1) * n_out],
+            );
+        }
+    }
 }
 
 /// Options for compiling exported code.
@@ -4727,39 +6384,12 @@ impl<T: NumericalFloatLike> EvalTree<T> {
                 r
             }
             Expression::ReadArg(s) => args[*s].to_string(),
-            Expression::BuiltinFun(s, a) => match s.0 {
-                Atom::EXP => {
-                    let mut r = "exp(".to_string();
-                    r += &self.export_cpp_impl(a, args);
-                    r.push(')');
-                    r
-                }
-                Atom::LOG => {
-                    let mut r = "log(".to_string();
-                    r += &self.export_cpp_impl(a, args);
-                    r.push(')');
-                    r
-                }
-                Atom::SIN => {
-                    let mut r = "sin(".to_string();
-                    r += &self.export_cpp_impl(a, args);
-                    r.push(')');
-                    r
-                }
-                Atom::COS => {
-                    let mut r = "cos(".to_string();
-                    r += &self.export_cpp_impl(a, args);
-                    r.push(')');
-                    r
-                }
-                Atom::SQRT => {
-                    let mut r = "sqrt(".to_string();
-                    r += &self.export_cpp_impl(a, args);
-                    r.push(')');
-                    r
-                }
-                _ => unreachable!(),
-            },
+            Expression::BuiltinFun(s, a) => {
+                let mut r = format!("{}(", s.cpp_name());
+                r += &self.export_cpp_impl(a, args);
+                r.push(')');
+                r
+            }
             Expression::SubExpression(id) => {
                 format!("Z{}_", id)
             }
@@ -4851,15 +6481,18 @@ impl<'a> AtomView<'a> {
             }
             AtomView::Fun(f) => {
                 let name = f.get_symbol();
-                if [Atom::EXP, Atom::LOG, Atom::SIN, Atom::COS, Atom::SQRT].contains(&name) {
-                    assert!(f.get_nargs() == 1);
+                if let Some(s) = BuiltinSymbol::new(name) {
+                    if f.get_nargs() != 1 {
+                        return Err(format!(
+                            "Built-in function {} called with {} arguments instead of 1",
+                            name,
+                            f.get_nargs()
+                        ));
+                    }
                     let arg = f.iter().next().unwrap();
                     let arg_eval = arg.to_eval_tree_impl(fn_map, params, args, funcs)?;
 
-                    return Ok(Expression::BuiltinFun(
-                        BuiltinSymbol(f.get_symbol()),
-                        Box::new(arg_eval),
-                    ));
+                    return Ok(Expression::BuiltinFun(s, Box::new(arg_eval)));
                 }
 
                 let Some(fun) = fn_map.get(*self) else {
@@ -5040,19 +6673,18 @@ impl<'a> AtomView<'a> {
             },
             AtomView::Fun(f) => {
                 let name = f.get_symbol();
-                if [Atom::EXP, Atom::LOG, Atom::SIN, Atom::COS, Atom::SQRT].contains(&name) {
-                    assert!(f.get_nargs() == 1);
+                if let Some(s) = BuiltinSymbol::new(name) {
+                    if f.get_nargs() != 1 {
+                        return Err(format!(
+                            "Built-in function {} called with {} arguments instead of 1",
+                            name,
+                            f.get_nargs()
+                        ));
+                    }
                     let arg = f.iter().next().unwrap();
                     let arg_eval = arg.evaluate_impl(coeff_map, const_map, function_map, cache)?;
 
-                    return Ok(match f.get_symbol() {
-                        Atom::EXP => arg_eval.exp(),
-                        Atom::LOG => arg_eval.log(),
-                        Atom::SIN => arg_eval.sin(),
-                        Atom::COS => arg_eval.cos(),
-                        Atom::SQRT => arg_eval.sqrt(),
-                        _ => unreachable!(),
-                    });
+                    return Ok(s.evaluate(&arg_eval));
                 }
 
                 if let Some(eval) = cache.get(self) {
@@ -5269,14 +6901,56 @@ mod test {
     use crate::{
         atom::{Atom, AtomCore},
         domains::{
-            float::{Complex, Float},
+            float::{Complex, DoubleDouble, Float, Interval, RealNumberLike, SingleFloat},
             rational::Rational,
         },
-        evaluate::{EvaluationFn, FunctionMap, OptimizationSettings},
+        evaluate::{
+            CompileOptions, EvaluationFn, FunctionMap, InlineASM, KernelDialect,
+            OptimizationSettings, StableEvaluator,
+        },
         id::ConditionResult,
         parse, symbol,
     };
 
+    /// Compile `code` with `compiler` into an executable, run it and parse its output
+    /// as whitespace-separated doubles. Returns `None` if the compiler is not available.
+    fn compile_and_run(compiler: &str, args: &[&str], name: &str, code: &str) -> Option<Vec<f64>> {
+        let dir = std::env::temp_dir().join(format!("symbolica_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let src = dir.join(name);
+        let exe = dir.join("main");
+        std::fs::write(&src, code).unwrap();
+
+        let r = match std::process::Command::new(compiler)
+            .arg("-o")
+            .arg(&exe)
+            .arg(&src)
+            .args(args)
+            .output()
+        {
+            Ok(r) => r,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => panic!("Could not run {}: {}", compiler, e),
+        };
+        assert!(
+            r.status.success(),
+            "Could not compile code: {}",
+            String::from_utf8_lossy(&r.stderr)
+        );
+
+        let r = std::process::Command::new(&exe).output().unwrap();
+        assert!(r.status.success());
+        let _ = std::fs::remove_dir_all(&dir);
+
+        Some(
+            String::from_utf8(r.stdout)
+                .unwrap()
+                .split_whitespace()
+                .map(|x| x.parse().unwrap())
+                .collect(),
+        )
+    }
+
     #[test]
     fn evaluate() {
         let x = symbol!("v1");
@@ -5339,6 +7013,209 @@ mod test {
         );
     }
 
+    #[test]
+    fn interval() {
+        let e = parse!("x*cos(x) + exp(x)/3 + sqrt(x+1)^3 + atan(x) + x^-3 + log(x)^2");
+        let x = parse!("x");
+        let x_val = Rational::from((7, 10));
+
+        let mut const_map = HashMap::default();
+        const_map.insert(x.as_view(), x_val.to_multi_prec_float(300));
+        let exact = e
+            .evaluate(
+                |r| r.to_multi_prec_float(300),
+                &const_map,
+                &HashMap::default(),
+            )
+            .unwrap();
+
+        let x_int = Interval::from(Float::new(100)).from_rational(&x_val);
+
+        let mut const_map = HashMap::default();
+        const_map.insert(x.as_view(), x_int.clone());
+        let r = e
+            .evaluate(|r| x_int.from_rational(r), &const_map, &HashMap::default())
+            .unwrap();
+        assert!(r.contains(&exact));
+        assert!(r.width().to_f64() < 1e-25);
+
+        let evaluator = e
+            .evaluator(&FunctionMap::new(), &[x], OptimizationSettings::default())
+            .unwrap();
+        let mut e_int = evaluator.map_coeff(&|c| x_int.from_rational(&c.re));
+        let r = e_int.evaluate_single(&[x_int]);
+        assert!(r.contains(&exact));
+        assert!(r.width().to_f64() < 1e-25);
+    }
+
+    #[test]
+    fn stable_evaluator() {
+        let e = parse!("(x + 1)^2 - x^2 - 2*x + sin(y)/3");
+        let evaluator = e
+            .evaluator(
+                &FunctionMap::new(),
+                &[parse!("x"), parse!("y")],
+                OptimizationSettings::default(),
+            )
+            .unwrap();
+
+        let mut stable = StableEvaluator::new(&evaluator, 12., vec![113, 256]).unwrap();
+        let expected = 1. + 0.5f64.sin() / 3.;
+
+        let mut out = [0.];
+        for (x, prec) in [(2., Some(53)), (1e10, Some(113)), (1e30, Some(256))] {
+            assert_eq!(stable.evaluate(&[x, 0.5], &mut out), prec);
+            assert!((out[0] - expected).abs() < 1e-12);
+        }
+
+        assert_eq!(stable.evaluate(&[1e80, 0.5], &mut out), None);
+        assert_eq!(stable.evaluation_counts(), &[1, 1, 1, 1]);
+
+        let e = parse!("x + 𝑖");
+        let mut fn_map = FunctionMap::new();
+        fn_map.add_constant(parse!("𝑖"), Complex::new(Rational::zero(), Rational::one()));
+        let evaluator = e
+            .evaluator(&fn_map, &[parse!("x")], OptimizationSettings::default())
+            .unwrap();
+        assert!(StableEvaluator::new(&evaluator, 12., vec![113]).is_err());
+    }
+
+    #[test]
+    fn double_double() {
+        let e = parse!("x^2*sin(y)/(1+x) + cos(x*y)^3 - exp(x/3) + log(y+2) + sqrt(x+y) + x^(1/3)");
+        let params = [parse!("x"), parse!("y")];
+        let evaluator = e
+            .evaluator(
+                &FunctionMap::new(),
+                &params,
+                OptimizationSettings::default(),
+            )
+            .unwrap();
+
+        let mut e_mp = evaluator
+            .clone()
+            .map_coeff(&|c| Float::new(300).from_rational(&c.re));
+        let exact = e_mp.evaluate_single(&[Float::with_val(300, 1.25), Float::with_val(300, 0.7)]);
+
+        let e_dd = evaluator.map_coeff(&|c| DoubleDouble::from(&c.re));
+        let r = e_dd
+            .clone()
+            .evaluate_single(&[DoubleDouble::from(1.25), DoubleDouble::from(0.7)]);
+        assert!(((r.to_float() - &exact) / &exact).to_f64().abs() < 1e-30);
+
+        // only evaluators with double-double coefficients export double-double code
+        let code = e_dd
+            .clone()
+            .map_coeff(&|c| c.hi())
+            .export_cpp_str("f", true);
+        assert!(!code.contains("dd_real"));
+
+        let code = format!(
+            "{}\n#include <cstdio>\nint main() {{
+                dd_real params[2] = {{dd_real(1.25), dd_real(0.7)}};
+                dd_real *buffer = new dd_real[dd_get_buffer_len()];
+                dd_real out[1];
+                dd_double_double(params, buffer, out);
+                printf(\"%.17g %.17g\\n\", out[0].hi, out[0].lo);
+                delete[] buffer;
+                return 0;
+            }}\n",
+            e_dd.export_cpp_str("dd", true)
+        );
+
+        let Some(r) = compile_and_run("g++", &["-O2"], "double_double.cpp", &code) else {
+            return;
+        };
+        let r = DoubleDouble::new(r[0], r[1]);
+        assert!(((r.to_float() - &exact) / &exact).to_f64().abs() < 1e-30);
+    }
+
+    #[test]
+    fn kernel_source() {
+        let e = parse!("x^2*y + sin(x)/3");
+        let evaluator = e
+            .evaluator(
+                &FunctionMap::new(),
+                &[parse!("x"), parse!("y")],
+                OptimizationSettings::default(),
+            )
+            .unwrap();
+
+        let code = evaluator
+            .export_kernel_source("f", KernelDialect::Cuda)
+            .unwrap();
+        assert!(code.starts_with("extern \"C\" __global__ void f(const double *params"));
+        assert!(code.contains("Z[1] = params[1 * n_points + i];"));
+        assert!(code.contains("Z[2] = 0.3333333333333333;"));
+        assert!(code.contains("sin("));
+        assert!(code.contains("out[0 * n_points + i] = "));
+
+        // validate the kernels on the CPU, with one thread per point
+        let params = [1.5, -0.5, 2., 3.];
+        let main = "int main() {
+                double params[4] = {1.5, -0.5, 2., 3.}, out[2];
+                for (unsigned long j = 0; j < 2; j++) {
+                    SET_THREAD(j);
+                    f(params, out, 2);
+                }
+                printf(\"%.17g %.17g\\n\", out[0], out[1]);
+                return 0;
+            }\n";
+
+        let cuda = format!(
+            "#include <cmath>\n#include <cstdio>\nusing namespace std;
+            #define __global__
+            struct dim {{ unsigned long x; }};
+            static dim blockIdx = {{0}}, blockDim = {{1}}, threadIdx = {{0}};
+            #define SET_THREAD(j) blockIdx.x = j
+            {}\n{}",
+            code, main
+        );
+
+        let opencl = format!(
+            "#include <math.h>\n#include <stdio.h>
+            #define __kernel
+            #define __global
+            static unsigned long thread_id;
+            #define get_global_id(d) thread_id
+            #define SET_THREAD(j) thread_id = j
+            {}\n{}",
+            evaluator
+                .export_kernel_source("f", KernelDialect::OpenCL)
+                .unwrap(),
+            main
+        );
+
+        let mut e_f64 = evaluator.clone().map_coeff(&|x| x.re.to_f64());
+        let expected = [
+            e_f64.evaluate_single(&[params[0], params[2]]),
+            e_f64.evaluate_single(&[params[1], params[3]]),
+        ];
+
+        for (compiler, args, name, code) in [
+            ("g++", &[][..], "kernel.cpp", cuda),
+            ("cc", &["-lm"][..], "kernel.c", opencl),
+        ] {
+            if let Some(r) = compile_and_run(compiler, args, name, &code) {
+                for (r, e) in r.iter().zip(&expected) {
+                    assert!((r - e).abs() < 1e-14 * e.abs());
+                }
+            }
+        }
+
+        let e = parse!("x + 𝑖");
+        let mut fn_map = FunctionMap::new();
+        fn_map.add_constant(parse!("𝑖"), Complex::new(Rational::zero(), Rational::one()));
+        let evaluator = e
+            .evaluator(&fn_map, &[parse!("x")], OptimizationSettings::default())
+            .unwrap();
+        assert!(
+            evaluator
+                .export_kernel_source("f", KernelDialect::OpenCL)
+                .is_err()
+        );
+    }
+
     #[test]
     fn nested() {
         let e1 = parse!("x + pi + cos(x) + f(g(x+1),h(x*2)) + p(1,x)");
@@ -5390,6 +7267,391 @@ mod test {
         assert!((r - 1622709.2254269677).abs() / 1622709.2254269677 < 1e-10);
     }
 
+    #[test]
+    fn elementary_functions() {
+        let e = parse!(
+            "tan(x) + asin(x/2) + acos(x/3) + atan(x) + sinh(x) + cosh(x) + tanh(x) + asinh(x) + acosh(x + 1) + atanh(x/2)"
+        );
+
+        let x = 0.7f64;
+        let expected = x.tan()
+            + (x / 2.).asin()
+            + (x / 3.).acos()
+            + x.atan()
+            + x.sinh()
+            + x.cosh()
+            + x.tanh()
+            + x.asinh()
+            + (x + 1.).acosh()
+            + (x / 2.).atanh();
+
+        let mut const_map = HashMap::default();
+        let v = parse!("x");
+        const_map.insert(v.as_view(), x);
+        let r = e
+            .evaluate(|x| x.into(), &const_map, &HashMap::default())
+            .unwrap();
+        assert!((r - expected).abs() < 1e-12);
+
+        let evaluator = e
+            .evaluator(&FunctionMap::new(), &[v], OptimizationSettings::default())
+            .unwrap();
+
+        let mut e_f64 = evaluator.map_coeff(&|x| x.clone().to_real().unwrap().into());
+        let r = e_f64.evaluate_single(&[x]);
+        assert!((r - expected).abs() < 1e-12);
+
+        let r = e_f64
+            .clone()
+            .map_coeff(&|x| Complex::new(*x, 0.))
+            .evaluate_single(&[Complex::new(x, 0.)]);
+        assert!((r.re - expected).abs() < 1e-12 && r.im.abs() < 1e-12);
+
+        let cpp = e_f64.export_cpp_str("f", false);
+        for f in [
+            "tan(", "asin(", "acos(", "atan(", "sinh(", "cosh(", "tanh(", "asinh(", "acosh(",
+            "atanh(",
+        ] {
+            assert!(cpp.contains(f));
+        }
+    }
+
+    #[test]
+    fn gradient() {
+        let e1 = parse!("x*sin(y) + exp(x*y)/y + atan(x^2*y)");
+        let e2 = parse!("sqrt(x+y)^3 + log(x)*cosh(y) + x^y");
+        let params = vec![parse!("x"), parse!("y")];
+
+        let evaluator = Atom::evaluator_multiple(
+            &[e1, e2],
+            &FunctionMap::new(),
+            &params,
+            OptimizationSettings::default(),
+        )
+        .unwrap();
+
+        let grad = evaluator.gradient(|r| Complex::new(r.clone(), Rational::zero()), None);
+        let mut grad_f64 = grad.map_coeff(&|x| x.re.to_f64());
+
+        let (x, y) = (1.3f64, 0.7f64);
+        let mut out = [0.; 6];
+        grad_f64.evaluate(&[x, y], &mut out);
+
+        let expected = [
+            x * y.sin() + (x * y).exp() / y + (x * x * y).atan(),
+            (x + y).sqrt().powi(3) + x.ln() * y.cosh() + x.powf(y),
+            y.sin() + (x * y).exp() + 2. * x * y / (1. + x.powi(4) * y * y),
+            x * y.cos() + (x * y).exp() * (x * y - 1.) / (y * y) + x * x / (1. + x.powi(4) * y * y),
+            1.5 * (x + y).sqrt() + y.cosh() / x + y * x.powf(y - 1.),
+            1.5 * (x + y).sqrt() + x.ln() * y.sinh() + x.powf(y) * x.ln(),
+        ];
+
+        for (r, e) in out.iter().zip(&expected) {
+            assert!((r - e).abs() < 1e-12 * e.abs().max(1.));
+        }
+    }
+
+    #[test]
+    fn evaluate_batch() {
+        let e1 = parse!("x^3*cos(y) + tanh(x+y)/(1+x^2) + sqrt(y)^x");
+        let e2 = parse!("x*y + 5");
+        let params = vec![parse!("x"), parse!("y")];
+
+        let evaluator = Atom::evaluator_multiple(
+            &[e1, e2],
+            &FunctionMap::new(),
+            &params,
+            OptimizationSettings::default(),
+        )
+        .unwrap();
+
+        let mut e_f64 = evaluator.map_coeff(&|x| x.re.to_f64());
+        let args: Vec<f64> = (0..14).map(|i| 0.1 + i as f64 / 7.).collect();
+        let mut out = vec![0.; 14];
+        e_f64.evaluate_batch(&args, 7, &mut out);
+
+        for (a, o) in args.chunks(2).zip(out.chunks(2)) {
+            let mut expected = [0.; 2];
+            e_f64.evaluate(a, &mut expected);
+            for (r, e) in o.iter().zip(&expected) {
+                assert!((r - e).abs() < 1e-12 * e.abs().max(1.));
+            }
+        }
+
+        let mut e_complex = e_f64.clone().map_coeff(&|x| Complex::new(*x, 0.));
+        let args: Vec<_> = args.iter().map(|x| Complex::new(*x, 1. - x)).collect();
+        let mut out = vec![Complex::new(0., 0.); 14];
+        e_complex.evaluate_batch(&args, 7, &mut out);
+
+        for (a, o) in args.chunks(2).zip(out.chunks(2)) {
+            let mut expected = [Complex::new(0., 0.); 2];
+            e_complex.evaluate(a, &mut expected);
+            for (r, e) in o.iter().zip(&expected) {
+                assert!((*r - *e).norm_squared() < 1e-24 * e.norm_squared().max(1.));
+            }
+        }
+
+        // compare the batch functions of the compiled C++ code with the scalar path
+        let dir = std::env::temp_dir().join(format!("symbolica_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let code = e_f64
+            .export_cpp(
+                dir.join("batch.cpp").to_str().unwrap(),
+                "f",
+                true,
+                InlineASM::None,
+            )
+            .unwrap();
+        let lib = match code.compile(
+            dir.join("batch.so").to_str().unwrap(),
+            CompileOptions::default(),
+        ) {
+            Ok(lib) => lib,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => panic!("{}", e),
+        };
+        let mut compiled = lib.load().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let args: Vec<f64> = args.iter().map(|x| x.re).collect();
+        let mut out = vec![0.; 14];
+        compiled.evaluate_double_batch(&args, 7, &mut out);
+        for (a, o) in args.chunks(2).zip(out.chunks(2)) {
+            let mut expected = [0.; 2];
+            compiled.evaluate_double(a, &mut expected);
+            for (r, e) in o.iter().zip(&expected) {
+                assert!((r - e).abs() < 1e-12 * e.abs().max(1.));
+            }
+        }
+
+        let args: Vec<_> = args.iter().map(|x| Complex::new(*x, 1. - x)).collect();
+        let mut out = vec![Complex::new(0., 0.); 14];
+        compiled.evaluate_complex_batch(&args, 7, &mut out);
+        for (a, o) in args.chunks(2).zip(out.chunks(2)) {
+            let mut expected = [Complex::new(0., 0.); 2];
+            compiled.evaluate_complex(a, &mut expected);
+            for (r, e) in o.iter().zip(&expected) {
+                assert!((*r - *e).norm_squared() < 1e-24 * e.norm_squared().max(1.));
+            }
+        }
+
+        // buffers that are too small are rejected
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            compiled.evaluate_double_batch(&[0.; 13], 7, &mut [0.; 14])
+        }));
+        assert!(r.is_err());
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            compiled.evaluate_complex_batch(&args, 7, &mut [Complex::new(0., 0.); 13])
+        }));
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn export_rust() {
+        let e = parse!("x^3*log(y) + 1/(x+y) + y^x");
+        let params = vec![parse!("x"), parse!("y")];
+        let eval = e
+            .evaluator(
+                &FunctionMap::new(),
+                &params,
+                OptimizationSettings::default(),
+            )
+            .unwrap();
+
+        let mut fn_map = FunctionMap::new();
+        fn_map.add_constant(parse!("i"), Complex::new(Rational::zero(), Rational::one()));
+        let g = parse!("i*x")
+            .evaluator(&fn_map, &params[..1], OptimizationSettings::default())
+            .unwrap();
+
+        let code = format!(
+            "{}\n{}\nfn main() {{
+                let mut z = vec![0.; F_BUFFER_LEN];
+                let mut out = [0.];
+                f_double(&[1.5, 2.5], &mut z, &mut out);
+                println!(\"{{:?}}\", out[0]);
+
+                let mut z = vec![[0., 0.]; F_BUFFER_LEN];
+                let mut out = [[0., 0.]];
+                f_complex(&[[1.5, 0.5], [2.5, -1.]], &mut z, &mut out);
+                println!(\"{{:?}} {{:?}}\", out[0][0], out[0][1]);
+
+                let mut z = vec![[0., 0.]; G_BUFFER_LEN];
+                g_complex(&[[2., 1.]], &mut z, &mut out);
+                println!(\"{{:?}} {{:?}}\", out[0][0], out[0][1]);
+            }}\n",
+            eval.export_rust_str("f"),
+            g.export_rust_str("g")
+        );
+
+        let Some(r) = compile_and_run(
+            &std::env::var("RUSTC").unwrap_or("rustc".to_owned()),
+            &["--edition", "2021"],
+            "export_rust.rs",
+            &code,
+        ) else {
+            return;
+        };
+
+        let mut out = [0.];
+        eval.clone()
+            .map_coeff(&|x| x.re.to_f64())
+            .evaluate(&[1.5, 2.5], &mut out);
+        assert!((r[0] - out[0]).abs() < 1e-12 * out[0].abs());
+
+        let mut out = [Complex::new(0., 0.)];
+        eval.map_coeff(&|x| Complex::new(x.re.to_f64(), x.im.to_f64()))
+            .evaluate(&[Complex::new(1.5, 0.5), Complex::new(2.5, -1.)], &mut out);
+        assert!((Complex::new(r[1], r[2]) - out[0]).norm_squared() < 1e-24 * out[0].norm_squared());
+
+        assert_eq!((r[3], r[4]), (-1., 2.));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_export() {
+        use crate::evaluate::ExpressionEvaluator;
+
+        let params = vec![parse!("x"), parse!("y")];
+        let mut eval = parse!("x^3*cos(y) + 1/(x+y)")
+            .evaluator(
+                &FunctionMap::new(),
+                &params,
+                OptimizationSettings::default(),
+            )
+            .unwrap()
+            .map_coeff(&|x| x.re.to_f64());
+
+        let json = serde_json::to_string(&eval).unwrap();
+        let mut decoded: ExpressionEvaluator<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            decoded.evaluate_single(&[0.5, 1.5]),
+            eval.evaluate_single(&[0.5, 1.5])
+        );
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["version"] = (super::EVALUATOR_FORMAT_VERSION + 1).into();
+        let err = serde_json::from_value::<ExpressionEvaluator<f64>>(value)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("format version"));
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["result_indices"] = vec![usize::MAX].into();
+        assert!(serde_json::from_value::<ExpressionEvaluator<f64>>(value).is_err());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode_export() {
+        use crate::evaluate::{BuiltinSymbol, ExpressionEvaluator, Instr};
+
+        let config = bincode::config::standard();
+        let params = vec![parse!("x"), parse!("y")];
+        let mut eval = parse!("x^3*cos(y) + 1/(x+y)")
+            .evaluator(
+                &FunctionMap::new(),
+                &params,
+                OptimizationSettings::default(),
+            )
+            .unwrap()
+            .map_coeff(&|x| x.re.to_f64());
+
+        let encoded = bincode::encode_to_vec(&eval, config).unwrap();
+        let mut decoded: ExpressionEvaluator<f64> =
+            bincode::decode_from_slice(&encoded, config).unwrap().0;
+        assert_eq!(decoded, eval);
+        assert_eq!(
+            decoded.evaluate_single(&[2., 3.]),
+            eval.evaluate_single(&[2., 3.])
+        );
+
+        // a different format version is rejected
+        let mut other_version = encoded.clone();
+        other_version[0] += 1;
+        let r = bincode::decode_from_slice::<ExpressionEvaluator<f64>, _>(&other_version, config);
+        assert!(r.unwrap_err().to_string().contains("format version"));
+
+        // truncated input
+        let r = bincode::decode_from_slice::<ExpressionEvaluator<f64>, _>(
+            &encoded[..encoded.len() / 2],
+            config,
+        );
+        assert!(r.is_err());
+
+        // corrupt instructions are rejected before evaluation
+        let corrupt = |instr: Instr| {
+            let mut e = eval.clone();
+            e.instructions.push(instr);
+            let encoded = bincode::encode_to_vec(&e, config).unwrap();
+            bincode::decode_from_slice::<ExpressionEvaluator<f64>, _>(&encoded, config).is_err()
+        };
+        let len = eval.stack.len();
+        assert!(corrupt(Instr::Add(0, vec![])));
+        assert!(corrupt(Instr::Mul(0, vec![len])));
+        assert!(corrupt(Instr::BuiltinFun(
+            0,
+            BuiltinSymbol(symbol!("v1")),
+            0
+        )));
+    }
+
+    #[cfg(all(target_arch = "x86_64", unix))]
+    #[test]
+    fn jit() {
+        let e1 = parse!("x^3*cos(y) + (x+y)^-2 + 1/(x*y+z) + sqrt(z)*exp(x*z)/y + x^y");
+        let e2 = parse!("(x+2*y+3*z)^5*(x*y*z+x+y+z)^-3 - 5/7*tanh(x+y^2)*asinh(z)");
+        let e3 = parse!("x");
+        let params = vec![parse!("x"), parse!("y"), parse!("z")];
+
+        let evaluator = Atom::evaluator_multiple(
+            &[e1, e2, e3],
+            &FunctionMap::new(),
+            &params,
+            OptimizationSettings::default(),
+        )
+        .unwrap();
+
+        let mut jit = evaluator.jit_compile().unwrap();
+        let mut e_f64 = evaluator.map_coeff(&|x| x.re.to_f64());
+        let mut e_complex = e_f64.clone().map_coeff(&|x| Complex::new(*x, 0.));
+
+        let args = [1.3, 0.7, 2.1];
+        let mut out = [0.; 3];
+        let mut expected = [0.; 3];
+        jit.evaluate_double(&args, &mut out);
+        e_f64.evaluate(&args, &mut expected);
+        for (r, e) in out.iter().zip(&expected) {
+            assert!((r - e).abs() < 1e-12 * e.abs().max(1.));
+        }
+
+        let args = [
+            Complex::new(1.3, 0.2),
+            Complex::new(0.7, -0.4),
+            Complex::new(2.1, 0.9),
+        ];
+        let mut out = [Complex::new(0., 0.); 3];
+        let mut expected = [Complex::new(0., 0.); 3];
+        jit.evaluate_complex(&args, &mut out);
+        e_complex.evaluate(&args, &mut expected);
+        for (r, e) in out.iter().zip(&expected) {
+            assert!((*r - *e).norm_squared() < 1e-24 * e.norm_squared().max(1.));
+        }
+
+        let mut fn_map = FunctionMap::new();
+        fn_map.add_constant(parse!("i"), Complex::new(Rational::zero(), Rational::one()));
+
+        let e = parse!("i*x + 2");
+        let mut jit = e
+            .evaluator(&fn_map, &[parse!("x")], OptimizationSettings::default())
+            .unwrap()
+            .jit_compile()
+            .unwrap();
+        let mut out = [Complex::new(0., 0.)];
+        jit.evaluate_complex(&[Complex::new(3., 1.)], &mut out);
+        assert_eq!(out[0], Complex::new(1., 3.));
+    }
+
     #[test]
     fn zero_test() {
         let e = parse!(
@@ -0,0 +1,624 @@
+//! A just-in-time compiler that turns an [ExpressionEvaluator] into native x86_64
+//! machine code, without requiring an external C++ compiler.
+//!
+//! The generated code mirrors the inline assembly produced by [ExpressionEvaluator::export_asm_str]:
+//! the same register allocation is used for real evaluation and the same SSE3 sequences are
+//! used for complex evaluation. Built-in functions and non-trivial powers call back into Rust.
+//!
+//! # Examples
+//!
+//! ```
+//! use symbolica::{atom::AtomCore, parse, evaluate::{FunctionMap, OptimizationSettings}};
+//! use symbolica::domains::float::Complex;
+//!
+//! let e = parse!("x^2*cos(x) + 1/(1+x)");
+//! let fn_map = FunctionMap::new();
+//! let params = vec![parse!("x")];
+//!
+//! let evaluator = e
+//!     .evaluator(&fn_map, &params, OptimizationSettings::default())
+//!     .unwrap();
+//! let mut jit = evaluator.jit_compile().unwrap();
+//!
+//! let mut out = [0.];
+//! jit.evaluate_double(&[2.], &mut out);
+//! assert!((out[0] - (4. * 2f64.cos() + 1. / 3.)).abs() < 1e-12);
+//!
+//! let mut out = [Complex::new(0., 0.)];
+//! jit.evaluate_complex(&[Complex::new(2., 0.)], &mut out);
+//! assert!((out[0].re - (4. * 2f64.cos() + 1. / 3.)).abs() < 1e-12);
+//! ```
+
+use crate::domains::float::{Complex, Real};
+
+use super::{BuiltinSymbol, ExpressionEvaluator, Instr, MemOrReg, NativeNumber, RegInstr};
+
+type JITFunction<T> = unsafe extern "C" fn(*const T, *mut T, *mut T, *const T);
+
+/// A page of executable memory that holds generated machine code.
+struct ExecutableMemory {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl ExecutableMemory {
+    fn new(code: &[u8]) -> Result<ExecutableMemory, String> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let len = code.len().div_ceil(page_size).max(1) * page_size;
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(format!(
+                "Could not allocate memory for JIT: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        let mem = ExecutableMemory { ptr, len };
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(code.as_ptr(), ptr as *mut u8, code.len());
+            if libc::mprotect(ptr, len, libc::PROT_READ | libc::PROT_EXEC) != 0 {
+                return Err(format!(
+                    "Could not make JIT memory executable: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+        }
+
+        Ok(mem)
+    }
+
+    /// Get the entry point of the generated code.
+    ///
+    /// # Safety
+    /// The memory must contain a function with the signature `F`.
+    unsafe fn function<F: Copy>(&self) -> F {
+        unsafe { std::mem::transmute_copy(&self.ptr) }
+    }
+}
+
+impl Drop for ExecutableMemory {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+unsafe extern "C" fn jit_builtin<T: Real>(index: usize, arg: *const T, out: *mut T) {
+    unsafe {
+        *out = BuiltinSymbol(BuiltinSymbol::FUNCTIONS[index]).evaluate(&*arg);
+    }
+}
+
+unsafe extern "C" fn jit_powf<T: Real>(base: *const T, exp: *const T, out: *mut T) {
+    unsafe {
+        *out = (*base).powf(&*exp);
+    }
+}
+
+unsafe extern "C" fn jit_pow<T: Real>(base: *const T, exp: i64, out: *mut T) {
+    unsafe {
+        *out = if exp >= 0 {
+            (*base).pow(exp as u64)
+        } else {
+            (*base).pow(exp.unsigned_abs()).inv()
+        };
+    }
+}
+
+// general purpose registers
+const RAX: u8 = 0;
+const RCX: u8 = 1;
+const RDX: u8 = 2;
+const RBX: u8 = 3;
+const RSI: u8 = 6;
+const RDI: u8 = 7;
+const R12: u8 = 12;
+const R13: u8 = 13;
+const R14: u8 = 14;
+const R15: u8 = 15;
+
+/// The registers that hold the pointers to the parameters, the work buffer `Z`,
+/// the output and the constants. They are callee-saved, so that they survive
+/// calls to built-in functions.
+const PARAMS: u8 = RBX;
+const WORK: u8 = R12;
+const OUT: u8 = R13;
+const CONSTANTS: u8 = R14;
+
+// SSE opcodes that follow the `0F` escape byte
+const MOV_LOAD: u8 = 0x10;
+const MOV_STORE: u8 = 0x11;
+const UNPCKL: u8 = 0x14;
+const UNPCKH: u8 = 0x15;
+const MOVA: u8 = 0x28;
+const XOR: u8 = 0x57;
+const ADD: u8 = 0x58;
+const MUL: u8 = 0x59;
+const DIV: u8 = 0x5E;
+const HADD: u8 = 0x7C;
+const SHUF: u8 = 0xC6;
+const ADDSUB: u8 = 0xD0;
+
+/// Scalar double instructions.
+const SD: u8 = 0xF2;
+/// Packed double instructions.
+const PD: u8 = 0x66;
+
+/// A memory operand `[base + disp]`.
+#[derive(Clone, Copy)]
+struct Address {
+    base: u8,
+    disp: i32,
+}
+
+/// A minimal x86_64 assembler that supports the instructions used by the JIT.
+#[derive(Default)]
+struct Assembler {
+    code: Vec<u8>,
+}
+
+impl Assembler {
+    fn rex(&mut self, w: bool, reg: u8, base: u8) {
+        let rex = 0x40 | (w as u8) << 3 | (reg >> 3) << 2 | (base >> 3);
+        if rex != 0x40 || w {
+            self.code.push(rex);
+        }
+    }
+
+    fn modrm_mem(&mut self, reg: u8, addr: Address) {
+        self.code.push(0x80 | (reg & 7) << 3 | (addr.base & 7));
+        if addr.base & 7 == 4 {
+            self.code.push(0x24); // SIB byte for rsp/r12 as base
+        }
+        self.code.extend(addr.disp.to_le_bytes());
+    }
+
+    fn modrm_reg(&mut self, reg: u8, rm: u8) {
+        self.code.push(0xC0 | (reg & 7) << 3 | (rm & 7));
+    }
+
+    /// Emit `op xmm, [addr]` (or `op [addr], xmm` for stores).
+    fn sse_mem(&mut self, prefix: u8, op: u8, xmm: u8, addr: Address) {
+        self.code.push(prefix);
+        self.rex(false, xmm, addr.base);
+        self.code.extend([0x0F, op]);
+        self.modrm_mem(xmm, addr);
+    }
+
+    /// Emit `op dst, src` for two XMM registers.
+    fn sse_reg(&mut self, prefix: u8, op: u8, dst: u8, src: u8) {
+        self.code.push(prefix);
+        self.rex(false, dst, src);
+        self.code.extend([0x0F, op]);
+        self.modrm_reg(dst, src);
+    }
+
+    fn push(&mut self, reg: u8) {
+        self.rex(false, 0, reg);
+        self.code.push(0x50 | (reg & 7));
+    }
+
+    fn pop(&mut self, reg: u8) {
+        self.rex(false, 0, reg);
+        self.code.push(0x58 | (reg & 7));
+    }
+
+    fn mov(&mut self, dst: u8, src: u8) {
+        self.rex(true, src, dst);
+        self.code.push(0x89);
+        self.modrm_reg(src, dst);
+    }
+
+    fn mov_imm(&mut self, dst: u8, imm: u64) {
+        self.rex(true, 0, dst);
+        self.code.push(0xB8 | (dst & 7));
+        self.code.extend(imm.to_le_bytes());
+    }
+
+    fn lea(&mut self, dst: u8, addr: Address) {
+        self.rex(true, dst, addr.base);
+        self.code.push(0x8D);
+        self.modrm_mem(dst, addr);
+    }
+
+    /// Call the function at address `f`, clobbering `rax`.
+    fn call(&mut self, f: usize) {
+        self.mov_imm(RAX, f as u64);
+        self.code.extend([0xFF, 0xD0]);
+    }
+
+    fn prologue(&mut self) {
+        // five pushes keep the stack 16-byte aligned for calls
+        for r in [RBX, R12, R13, R14, R15] {
+            self.push(r);
+        }
+        self.mov(PARAMS, RDI);
+        self.mov(WORK, RSI);
+        self.mov(OUT, RDX);
+        self.mov(CONSTANTS, RCX);
+    }
+
+    fn epilogue(&mut self) {
+        for r in [R15, R14, R13, R12, RBX] {
+            self.pop(r);
+        }
+        self.code.push(0xC3);
+    }
+}
+
+/// An evaluator that has been compiled to native machine code in-process,
+/// without the need for an external compiler.
+pub struct JITCompiledEvaluator {
+    code_double: Option<ExecutableMemory>,
+    code_complex: ExecutableMemory,
+    constants_double: Vec<f64>,
+    constants_complex: Vec<Complex<f64>>,
+    buffer_double: Vec<f64>,
+    buffer_complex: Vec<Complex<f64>>,
+    param_count: usize,
+    output_len: usize,
+}
+
+unsafe impl Send for JITCompiledEvaluator {}
+
+impl std::fmt::Debug for JITCompiledEvaluator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "JITCompiledEvaluator({} bytes)",
+            self.code_complex.len + self.code_double.as_ref().map(|x| x.len).unwrap_or(0)
+        )
+    }
+}
+
+impl JITCompiledEvaluator {
+    /// Evaluate the compiled code with double-precision floating point numbers.
+    ///
+    /// Panics if the evaluator has complex constants.
+    #[inline(always)]
+    pub fn evaluate_double(&mut self, args: &[f64], out: &mut [f64]) {
+        assert!(
+            args.len() >= self.param_count && out.len() >= self.output_len,
+            "Argument or output buffer too small"
+        );
+
+        let Some(code) = &self.code_double else {
+            panic!("Cannot evaluate complex function with doubles");
+        };
+
+        unsafe {
+            (code.function::<JITFunction<f64>>())(
+                args.as_ptr(),
+                self.buffer_double.as_mut_ptr(),
+                out.as_mut_ptr(),
+                self.constants_double.as_ptr(),
+            )
+        }
+    }
+
+    /// Evaluate the compiled code with complex numbers.
+    #[inline(always)]
+    pub fn evaluate_complex(&mut self, args: &[Complex<f64>], out: &mut [Complex<f64>]) {
+        assert!(
+            args.len() >= self.param_count && out.len() >= self.output_len,
+            "Argument or output buffer too small"
+        );
+
+        unsafe {
+            (self.code_complex.function::<JITFunction<Complex<f64>>>())(
+                args.as_ptr(),
+                self.buffer_complex.as_mut_ptr(),
+                out.as_mut_ptr(),
+                self.constants_complex.as_ptr(),
+            )
+        }
+    }
+}
+
+impl<T: NativeNumber> ExpressionEvaluator<T> {
+    /// Compile the evaluator to native x86_64 machine code in an executable memory page.
+    /// This does not require an external compiler, in contrast to [ExpressionEvaluator::export_cpp].
+    pub fn jit_compile(&self) -> Result<JITCompiledEvaluator, String> {
+        if !std::arch::is_x86_feature_detected!("sse3") {
+            return Err("JIT compilation requires SSE3 support".to_owned());
+        }
+
+        if self.stack.len() + 1 >= i32::MAX as usize / 16 {
+            return Err("Evaluator is too large for JIT compilation".to_owned());
+        }
+
+        let mut constants_complex: Vec<_> = self.stack[self.param_count..self.reserved_indices]
+            .iter()
+            .map(|x| x.to_complex_f64())
+            .collect();
+        let is_real = constants_complex.iter().all(|x| x.im == 0.);
+        let mut constants_double: Vec<_> = constants_complex.iter().map(|x| x.re).collect();
+        constants_double.push(1.); // used for inversion
+        constants_complex.push(Complex::new(0., -0.)); // used for inversion
+
+        let code_double = if is_real {
+            let mut asm = Assembler::default();
+            self.jit_double(&self.assign_registers(&self.instructions), &mut asm);
+            Some(ExecutableMemory::new(&asm.code)?)
+        } else {
+            None
+        };
+
+        let mut asm = Assembler::default();
+        self.jit_complex(&self.instructions, &mut asm);
+        let code_complex = ExecutableMemory::new(&asm.code)?;
+
+        Ok(JITCompiledEvaluator {
+            code_double,
+            code_complex,
+            constants_double,
+            constants_complex,
+            buffer_double: vec![0.; self.stack.len()],
+            buffer_complex: vec![Complex::new(0., 0.); self.stack.len()],
+            param_count: self.param_count,
+            output_len: self.result_indices.len(),
+        })
+    }
+}
+
+impl<T> ExpressionEvaluator<T> {
+    /// Get the memory location of stack entry `i`, for elements of `size` bytes.
+    fn jit_address(&self, i: usize, size: usize) -> Address {
+        if i < self.param_count {
+            Address {
+                base: PARAMS,
+                disp: (i * size) as i32,
+            }
+        } else if i < self.reserved_indices {
+            Address {
+                base: CONSTANTS,
+                disp: ((i - self.param_count) * size) as i32,
+            }
+        } else {
+            Address {
+                base: WORK,
+                disp: (i * size) as i32,
+            }
+        }
+    }
+
+    /// Emit a call to a built-in function, a real power or an integer power that
+    /// reads from and writes to memory.
+    fn jit_call<N: Real>(&self, ins: &Instr, size: usize, asm: &mut Assembler) {
+        match ins {
+            Instr::Pow(o, b, e) => {
+                asm.lea(RDI, self.jit_address(*b, size));
+                asm.mov_imm(RSI, *e as u64);
+                asm.lea(RDX, self.jit_address(*o, size));
+                asm.call(jit_pow::<N> as *const () as usize);
+            }
+            Instr::Powf(o, b, e) => {
+                asm.lea(RDI, self.jit_address(*b, size));
+                asm.lea(RSI, self.jit_address(*e, size));
+                asm.lea(RDX, self.jit_address(*o, size));
+                asm.call(jit_powf::<N> as *const () as usize);
+            }
+            Instr::BuiltinFun(o, s, a) => {
+                let index = BuiltinSymbol::FUNCTIONS
+                    .iter()
+                    .position(|f| *f == s.0)
+                    .unwrap();
+                asm.mov_imm(RDI, index as u64);
+                asm.lea(RSI, self.jit_address(*a, size));
+                asm.lea(RDX, self.jit_address(*o, size));
+                asm.call(jit_builtin::<N> as *const () as usize);
+            }
+            Instr::Add(_, _) | Instr::Mul(_, _) => unreachable!(),
+        }
+    }
+
+    fn jit_double(&self, instr: &[RegInstr], asm: &mut Assembler) {
+        let one = Address {
+            base: CONSTANTS,
+            disp: ((self.reserved_indices - self.param_count) * 8) as i32,
+        };
+
+        asm.prologue();
+
+        for ins in instr {
+            match ins {
+                RegInstr::Add(o, free, a) | RegInstr::Mul(o, free, a) => {
+                    let oper = if matches!(ins, RegInstr::Add(_, _, _)) {
+                        ADD
+                    } else {
+                        MUL
+                    };
+
+                    let out_reg = match o {
+                        MemOrReg::Reg(r) => *r,
+                        MemOrReg::Mem(_) => (0..16)
+                            .position(|k| free & (1 << k) != 0)
+                            .expect("No free registers"),
+                    };
+
+                    // select the first argument that is accumulated into the output register
+                    let first = a
+                        .iter()
+                        .position(|x| *x == MemOrReg::Reg(out_reg))
+                        .or_else(|| a.iter().position(|x| matches!(x, MemOrReg::Reg(_))))
+                        .unwrap_or(0);
+
+                    // if the recycled output register is used more than once, such as in
+                    // `x*x*x`, its original value is kept in a scratch register
+                    let mut original = out_reg;
+                    match a[first] {
+                        MemOrReg::Reg(j) if j == out_reg => {
+                            if a.iter().filter(|x| **x == MemOrReg::Reg(out_reg)).count() > 1 {
+                                original = (0..16)
+                                    .position(|k| k != out_reg && free & (1 << k) != 0)
+                                    .expect("No free registers");
+                                asm.sse_reg(PD, MOVA, original as u8, out_reg as u8);
+                            }
+                        }
+                        MemOrReg::Reg(j) => asm.sse_reg(PD, MOVA, out_reg as u8, j as u8),
+                        MemOrReg::Mem(k) => {
+                            asm.sse_mem(SD, MOV_LOAD, out_reg as u8, self.jit_address(k, 8))
+                        }
+                    }
+
+                    for (i, x) in a.iter().enumerate() {
+                        if i != first {
+                            match x {
+                                MemOrReg::Reg(k) if *k == out_reg => {
+                                    asm.sse_reg(SD, oper, out_reg as u8, original as u8)
+                                }
+                                MemOrReg::Reg(k) => asm.sse_reg(SD, oper, out_reg as u8, *k as u8),
+                                MemOrReg::Mem(k) => {
+                                    asm.sse_mem(SD, oper, out_reg as u8, self.jit_address(*k, 8))
+                                }
+                            }
+                        }
+                    }
+
+                    if let MemOrReg::Mem(k) = o {
+                        asm.sse_mem(SD, MOV_STORE, out_reg as u8, self.jit_address(*k, 8));
+                    }
+                }
+                RegInstr::Pow(o, free, b, -1) => {
+                    let mut free_regs = (0..16).filter(|k| free & (1 << k) != 0);
+
+                    let out_reg = match o {
+                        MemOrReg::Reg(r) => *r,
+                        MemOrReg::Mem(_) => free_regs.next().expect("No free registers"),
+                    };
+
+                    match b {
+                        MemOrReg::Reg(j) if *j == out_reg => {
+                            let tmp_reg = free_regs
+                                .find(|k| *k != out_reg)
+                                .expect("No free registers for division");
+                            asm.sse_reg(PD, MOVA, tmp_reg as u8, out_reg as u8);
+                            asm.sse_mem(SD, MOV_LOAD, out_reg as u8, one);
+                            asm.sse_reg(SD, DIV, out_reg as u8, tmp_reg as u8);
+                        }
+                        MemOrReg::Reg(j) => {
+                            asm.sse_mem(SD, MOV_LOAD, out_reg as u8, one);
+                            asm.sse_reg(SD, DIV, out_reg as u8, *j as u8);
+                        }
+                        MemOrReg::Mem(k) => {
+                            asm.sse_mem(SD, MOV_LOAD, out_reg as u8, one);
+                            asm.sse_mem(SD, DIV, out_reg as u8, self.jit_address(*k, 8));
+                        }
+                    }
+
+                    if let MemOrReg::Mem(k) = o {
+                        asm.sse_mem(SD, MOV_STORE, out_reg as u8, self.jit_address(*k, 8));
+                    }
+                }
+                RegInstr::Pow(o, _, b, e) => {
+                    let (MemOrReg::Mem(o), MemOrReg::Mem(b)) = (o, b) else {
+                        unreachable!("Register use outside of native block");
+                    };
+                    self.jit_call::<f64>(&Instr::Pow(*o, *b, *e), 8, asm);
+                }
+                RegInstr::Powf(o, b, e) => {
+                    self.jit_call::<f64>(&Instr::Powf(*o, *b, *e), 8, asm);
+                }
+                RegInstr::BuiltinFun(o, s, a) => {
+                    self.jit_call::<f64>(&Instr::BuiltinFun(*o, *s, *a), 8, asm);
+                }
+            }
+        }
+
+        for (i, r) in self.result_indices.iter().enumerate() {
+            asm.sse_mem(SD, MOV_LOAD, 0, self.jit_address(*r, 8));
+            asm.sse_mem(
+                SD,
+                MOV_STORE,
+                0,
+                Address {
+                    base: OUT,
+                    disp: (i * 8) as i32,
+                },
+            );
+        }
+
+        asm.epilogue();
+    }
+
+    fn jit_complex(&self, instr: &[Instr], asm: &mut Assembler) {
+        asm.prologue();
+
+        for ins in instr {
+            match ins {
+                Instr::Add(o, a) => {
+                    asm.sse_mem(PD, MOV_LOAD, 0, self.jit_address(a[0], 16));
+                    for x in &a[1..] {
+                        asm.sse_mem(PD, MOV_LOAD, 1, self.jit_address(*x, 16));
+                        asm.sse_reg(PD, ADD, 0, 1);
+                    }
+                    asm.sse_mem(PD, MOV_STORE, 0, self.jit_address(*o, 16));
+                }
+                Instr::Mul(o, a) => {
+                    asm.sse_mem(PD, MOV_LOAD, 1, self.jit_address(a[0], 16));
+                    for x in &a[1..] {
+                        asm.sse_mem(PD, MOV_LOAD, 2, self.jit_address(*x, 16));
+                        asm.sse_reg(PD, MOVA, 0, 1);
+                        asm.sse_reg(PD, UNPCKH, 0, 0);
+                        asm.sse_reg(PD, UNPCKL, 1, 1);
+                        asm.sse_reg(PD, MUL, 0, 2);
+                        asm.sse_reg(PD, MUL, 1, 2);
+                        asm.sse_reg(PD, SHUF, 0, 0);
+                        asm.code.push(1);
+                        asm.sse_reg(PD, ADDSUB, 1, 0);
+                    }
+                    asm.sse_mem(PD, MOV_STORE, 1, self.jit_address(*o, 16));
+                }
+                Instr::Pow(o, b, -1) => {
+                    asm.sse_mem(PD, MOV_LOAD, 0, self.jit_address(*b, 16));
+                    asm.sse_mem(
+                        PD,
+                        MOV_LOAD,
+                        1,
+                        Address {
+                            base: CONSTANTS,
+                            disp: ((self.reserved_indices - self.param_count) * 16) as i32,
+                        },
+                    );
+                    asm.sse_reg(PD, MOVA, 2, 0);
+                    asm.sse_reg(PD, XOR, 0, 1);
+                    asm.sse_reg(PD, MUL, 2, 2);
+                    asm.sse_reg(PD, HADD, 2, 2);
+                    asm.sse_reg(PD, DIV, 0, 2);
+                    asm.sse_mem(PD, MOV_STORE, 0, self.jit_address(*o, 16));
+                }
+                Instr::Pow(_, _, _) | Instr::Powf(_, _, _) | Instr::BuiltinFun(_, _, _) => {
+                    self.jit_call::<Complex<f64>>(ins, 16, asm);
+                }
+            }
+        }
+
+        for (i, r) in self.result_indices.iter().enumerate() {
+            asm.sse_mem(PD, MOV_LOAD, 0, self.jit_address(*r, 16));
+            asm.sse_mem(
+                PD,
+                MOV_STORE,
+                0,
+                Address {
+                    base: OUT,
+                    disp: (i * 16) as i32,
+                },
+            );
+        }
+
+        asm.epilogue();
+    }
+}
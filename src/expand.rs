@@ -200,7 +200,42 @@ impl AtomView<'_> {
                     return changed;
                 };
 
-                if let AtomView::Add(a) = new_base.as_view() {
+                let keep_order = match new_base.as_view() {
+                    AtomView::Add(_) => new_base.as_view().is_noncommutative_factor(),
+                    AtomView::Mul(m) => {
+                        m.iter().filter(|a| a.is_noncommutative_factor()).count() > 1
+                    }
+                    _ => false,
+                };
+
+                if keep_order {
+                    // expand (a+b)^n and (a*b)^n as (a+b)*(a+b)*... and a*b*a*b*... to keep
+                    // the order of noncommuting factors
+                    let mut mul_h = workspace.new_atom();
+                    let mul = mul_h.to_mul();
+                    for _ in 0..num {
+                        mul.extend(new_base.as_view());
+                    }
+
+                    let mut expanded = workspace.new_atom();
+                    mul_h
+                        .as_view()
+                        .expand_no_norm(workspace, var, &mut expanded);
+
+                    if negative {
+                        let mut num_h = workspace.new_atom();
+                        num_h.to_num((-1i64).into());
+
+                        let mut pow_h = workspace.new_atom();
+                        pow_h.to_pow(expanded.as_view(), num_h.as_view());
+
+                        pow_h.as_view().normalize(workspace, out);
+                    } else {
+                        expanded.as_view().normalize(workspace, out);
+                    }
+
+                    true
+                } else if let AtomView::Add(a) = new_base.as_view() {
                     // expand (a+b+c+..)^n
                     let mut args: SmallVec<[AtomView; 10]> = SmallVec::with_capacity(a.get_nargs());
                     for arg in a {
@@ -512,7 +547,7 @@ impl AtomView<'_> {
 
 #[cfg(test)]
 mod test {
-    use crate::atom::AtomCore;
+    use crate::atom::{Atom, AtomCore};
     use crate::{parse, symbol};
 
     #[test]
@@ -552,18 +587,46 @@ mod test {
         assert_eq!(exp, res);
     }
 
+    #[test]
+    fn noncommutative() {
+        let _ = symbol!("nc_a", "nc_b"; Noncommutative);
+
+        let exp = parse!("(nc_a+nc_b)^2").expand();
+        let res = parse!("nc_a^2+nc_a*nc_b+nc_b*nc_a+nc_b^2");
+        assert_eq!(exp, res);
+
+        let exp = parse!("(nc_a+v1)*(nc_b+v2)*nc_a").expand();
+        let res = parse!("nc_a*nc_b*nc_a+v2*nc_a^2+v1*nc_b*nc_a+v1*v2*nc_a");
+        assert_eq!(exp, res);
+
+        let exp = parse!("(2*nc_a*nc_b)^2 - 4*nc_a*nc_b*nc_a*nc_b").expand();
+        assert_eq!(exp, Atom::num(0));
+
+        let exp = parse!("(nc_a*nc_b)^-2").expand();
+        assert_eq!(exp, parse!("(nc_a*nc_b*nc_a*nc_b)^-1"));
+    }
+
+    #[test]
+    fn grassmann() {
+        let _ = symbol!("gr_1", "gr_2"; Grassmann);
+
+        let exp = parse!("(gr_1*gr_2)^2").expand();
+        assert_eq!(exp, Atom::num(0));
+
+        let exp = parse!("(gr_1*gr_2 + v1)^2").expand();
+        assert_eq!(exp, parse!("v1^2 + 2*v1*gr_1*gr_2"));
+    }
+
     #[test]
     fn expand_in_var() {
-        let exp = parse!("(1+v1)^2+(1+v2)^100")
-            .expand_in_symbol(symbol!("v1"));
+        let exp = parse!("(1+v1)^2+(1+v2)^100").expand_in_symbol(symbol!("v1"));
         let res = parse!("1+2*v1+v1^2+(v2+1)^100");
         assert_eq!(exp, res);
     }
 
     #[test]
     fn expand_with_poly() {
-        let exp = parse!("(1+v1)^2+(1+v2)^100")
-            .expand_in_symbol(symbol!("v1"));
+        let exp = parse!("(1+v1)^2+(1+v2)^100").expand_in_symbol(symbol!("v1"));
         let res = parse!("1+2*v1+v1^2+(v2+1)^100");
         assert_eq!(exp, res);
     }
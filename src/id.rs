@@ -16,14 +16,22 @@
 //! assert_eq!(out, parse!("f(1,2,x+1)+f(1,2,4)"));
 //! ```
 
-use std::ops::DerefMut;
+mod completion;
+mod unify;
+
+pub use self::completion::{CompletionError, CompletionSettings, ConfluenceReport, CriticalPair};
+
+use std::{
+    ops::DerefMut,
+    sync::{Arc, Mutex},
+};
 
 use ahash::{HashMap, HashSet};
 use dyn_clone::DynClone;
 
 use crate::{
     atom::{
-        Atom, AtomCore, AtomType, AtomView, Num, SliceType, Symbol,
+        Assumption, Atom, AtomCore, AtomType, AtomView, MulView, Num, SliceType, Symbol,
         representation::{InlineVar, ListSlice},
     },
     coefficient::CoefficientView,
@@ -46,6 +54,9 @@ use crate::{
 pub enum Pattern {
     Literal(Atom),
     Wildcard(Symbol),
+    /// A wildcard that may be absent from an argument list, together with
+    /// the value it is bound to when absent. See [Pattern::with_optional_wildcard].
+    Optional(Symbol, Atom),
     Fn(Symbol, Vec<Pattern>),
     Pow(Box<[Pattern; 2]>),
     Mul(Vec<Pattern>),
@@ -175,6 +186,26 @@ impl Replacement {
         self.settings = Some(settings);
         self
     }
+
+    /// Get the pattern of the replacement.
+    pub fn pattern(&self) -> &Pattern {
+        &self.pat
+    }
+
+    /// Get the right-hand side of the replacement.
+    pub fn rhs(&self) -> &ReplaceWith<'static> {
+        &self.rhs
+    }
+
+    /// Get the conditions of the replacement, if any.
+    pub fn conditions(&self) -> Option<&Condition<PatternRestriction>> {
+        self.conditions.as_ref()
+    }
+
+    /// Get the match settings of the replacement, if any.
+    pub fn settings(&self) -> Option<&MatchSettings> {
+        self.settings.as_ref()
+    }
 }
 
 /// A borrowed version of a [Replacement].
@@ -218,6 +249,367 @@ impl BorrowReplacement for BorrowedReplacement<'_> {
     }
 }
 
+/// The head of an expression, used to index the patterns of a [RuleSet].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum HeadKey {
+    Num,
+    Var(Symbol),
+    Fn(Symbol),
+    Pow,
+    Mul,
+    Add,
+}
+
+impl HeadKey {
+    fn new(atom: AtomView) -> HeadKey {
+        match atom {
+            AtomView::Num(_) => HeadKey::Num,
+            AtomView::Var(v) => HeadKey::Var(v.get_symbol()),
+            AtomView::Fun(f) => HeadKey::Fn(f.get_symbol()),
+            AtomView::Pow(_) => HeadKey::Pow,
+            AtomView::Mul(_) => HeadKey::Mul,
+            AtomView::Add(_) => HeadKey::Add,
+        }
+    }
+
+    /// Get the head of all expressions the pattern can match,
+    /// or `None` if it can match expressions with different heads.
+    fn from_pattern(pattern: &Pattern) -> Option<HeadKey> {
+        match pattern {
+            Pattern::Literal(a) => Some(HeadKey::new(a.as_view())),
+            Pattern::Fn(s, _) if s.get_wildcard_level() == 0 => {
+                if pattern.linear_sums().next().is_some() {
+                    None // may also match a sum of functions
+                } else {
+                    Some(HeadKey::Fn(*s))
+                }
+            }
+            Pattern::Pow(_) => Some(HeadKey::Pow),
+            Pattern::Mul(_) => Some(HeadKey::Mul),
+            Pattern::Add(_) => Some(HeadKey::Add),
+            Pattern::Fn(..)
+            | Pattern::Wildcard(_)
+            | Pattern::Optional(..)
+            | Pattern::Transformer(_) => None,
+        }
+    }
+}
+
+/// An index of the replacements of a [RuleSet] by the head of their pattern.
+#[derive(Debug, Clone)]
+struct RuleIndex {
+    by_head: HashMap<HeadKey, Vec<usize>>,
+    /// Rules whose pattern may match any head.
+    generic: Vec<usize>,
+    /// The byte size of the smallest literal pattern, if all patterns are literals.
+    min_literal_size: Option<usize>,
+    /// The maximal tree level of rules that count levels by tree depth,
+    /// or `usize::MAX` if one of them is unbounded.
+    max_tree_level: Option<usize>,
+    /// The maximal function level of rules that count levels by function depth,
+    /// or `usize::MAX` if one of them is unbounded.
+    max_fn_level: Option<usize>,
+}
+
+impl RuleIndex {
+    fn new(replacements: &[Replacement]) -> RuleIndex {
+        let mut index = RuleIndex {
+            by_head: HashMap::default(),
+            generic: vec![],
+            min_literal_size: Some(usize::MAX),
+            max_tree_level: None,
+            max_fn_level: None,
+        };
+
+        for (i, r) in replacements.iter().enumerate() {
+            if let Some(h) = HeadKey::from_pattern(&r.pat) {
+                index.by_head.entry(h).or_default().push(i);
+            } else {
+                index.generic.push(i);
+            }
+
+            if let Pattern::Literal(l) = &r.pat {
+                index.min_literal_size = index
+                    .min_literal_size
+                    .map(|s| s.min(l.as_view().get_byte_size()));
+            } else {
+                index.min_literal_size = None;
+            }
+
+            let (level_is_tree_depth, max_level) = r
+                .settings
+                .as_ref()
+                .map(|s| (s.level_is_tree_depth, s.level_range.1))
+                .unwrap_or((false, None));
+            let max_level = max_level.unwrap_or(usize::MAX);
+            let bound = if level_is_tree_depth {
+                &mut index.max_tree_level
+            } else {
+                &mut index.max_fn_level
+            };
+            *bound = Some(bound.map_or(max_level, |b| b.max(max_level)));
+        }
+
+        index
+    }
+
+    /// Iterate over the indices of the rules that could match `atom`, in order.
+    fn candidates(&self, atom: AtomView) -> impl Iterator<Item = usize> + '_ {
+        let mut by_head = self
+            .by_head
+            .get(&HeadKey::new(atom))
+            .map(|x| x.as_slice())
+            .unwrap_or(&[])
+            .iter()
+            .peekable();
+        let mut generic = self.generic.iter().peekable();
+
+        std::iter::from_fn(move || match (by_head.peek(), generic.peek()) {
+            (Some(a), Some(b)) if a < b => by_head.next().copied(),
+            (Some(_), None) => by_head.next().copied(),
+            _ => generic.next().copied(),
+        })
+    }
+
+    /// Check if a rule could match an atom of the given byte size or any of its subexpressions.
+    fn fits(&self, byte_size: usize) -> bool {
+        self.min_literal_size.is_none_or(|s| s <= byte_size)
+    }
+
+    /// Check if all rules are restricted to lower levels.
+    fn beyond_max_level(&self, tree_level: usize, fn_level: usize) -> bool {
+        self.max_tree_level.is_none_or(|m| tree_level > m)
+            && self.max_fn_level.is_none_or(|m| fn_level > m)
+    }
+}
+
+/// A list of replacements that is indexed by the head of the patterns, so that
+/// only the replacements that could match are tried for every subexpression.
+/// The replacements are tested in the order that they are given.
+///
+/// # Examples
+///
+/// ```
+/// use symbolica::{atom::AtomCore, id::{Replacement, RuleSet}, parse};
+///
+/// let rules = RuleSet::new(vec![
+///     Replacement::new(parse!("f(x_)").to_pattern(), parse!("x_^2").to_pattern()),
+///     Replacement::new(parse!("g(x_)").to_pattern(), parse!("x_+1").to_pattern()),
+/// ]);
+///
+/// let r = parse!("f(3) + g(x)").replace_rule_set(&rules);
+/// assert_eq!(r, parse!("x + 10"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RuleSet {
+    replacements: Vec<Replacement>,
+    index: RuleIndex,
+}
+
+impl RuleSet {
+    /// Create a new rule set from a list of replacements.
+    pub fn new(replacements: Vec<Replacement>) -> RuleSet {
+        RuleSet {
+            index: RuleIndex::new(&replacements),
+            replacements,
+        }
+    }
+
+    /// Get the replacements of the rule set.
+    pub fn replacements(&self) -> &[Replacement] {
+        &self.replacements
+    }
+}
+
+impl From<Vec<Replacement>> for RuleSet {
+    fn from(replacements: Vec<Replacement>) -> Self {
+        RuleSet::new(replacements)
+    }
+}
+
+impl std::fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, r) in self.replacements.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", r)?;
+        }
+        Ok(())
+    }
+}
+
+/// A record of a single replacement, logged in a [ReplaceTrace].
+#[derive(Clone)]
+pub struct TraceEntry {
+    /// The index of the applied rule in the list of replacements.
+    pub rule: usize,
+    /// The pattern of the applied rule.
+    pub pattern: Pattern,
+    /// The subexpression that was matched.
+    pub matched: Atom,
+    /// The wildcards and the subexpressions they matched.
+    pub bindings: Vec<(Symbol, Atom)>,
+    /// The expression that replaces the matched subexpression.
+    pub result: Atom,
+}
+
+impl std::fmt::Debug for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceEntry")
+            .field("rule", &self.rule)
+            .field("pattern", &self.pattern.to_string())
+            .field("matched", &self.matched)
+            .field("bindings", &self.bindings)
+            .field("result", &self.result)
+            .finish()
+    }
+}
+
+impl std::fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rule {} ({}): {} -> {}",
+            self.rule, self.pattern, self.matched, self.result
+        )?;
+
+        for (i, (s, a)) in self.bindings.iter().enumerate() {
+            write!(f, "{}{} = {}", if i == 0 { "; " } else { ", " }, s, a)?;
+        }
+
+        Ok(())
+    }
+}
+
+thread_local!(
+    /// The trace that records the replacements on the current thread.
+    static ACTIVE_TRACE: std::cell::RefCell<Option<ReplaceTrace>> = const { std::cell::RefCell::new(None) }
+);
+
+/// A log of applied replacements, which can be used to debug rules that fire unexpectedly.
+/// The trace is a shared handle: clones record into the same log.
+///
+/// Replacements are recorded while they are executed by a [ReplaceBuilder] on which
+/// [ReplaceBuilder::trace] is called, or within [ReplaceTrace::record], which
+/// also captures the replacements performed by transformer chains.
+///
+/// # Examples
+///
+/// ```
+/// use symbolica::{atom::AtomCore, id::ReplaceTrace, parse, symbol};
+///
+/// let trace = ReplaceTrace::new();
+/// let r = parse!("f(3)*f(x)")
+///     .replace(parse!("f(x_)"))
+///     .trace(&trace)
+///     .with(parse!("x_+1"));
+/// assert_eq!(r, parse!("4*(x+1)"));
+///
+/// let entries = trace.entries();
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[0].matched, parse!("f(3)"));
+/// assert_eq!(entries[0].bindings, vec![(symbol!("x_"), parse!("3"))]);
+/// assert_eq!(entries[0].result, parse!("4"));
+/// assert!(trace.to_string().starts_with("0: rule 0 (f(x_)): f(3) -> 4; x_ = 3"));
+/// ```
+#[derive(Clone, Default)]
+pub struct ReplaceTrace {
+    entries: Arc<Mutex<Vec<TraceEntry>>>,
+}
+
+impl std::fmt::Debug for ReplaceTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.entries.lock().unwrap().iter())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for ReplaceTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, e) in self.entries.lock().unwrap().iter().enumerate() {
+            writeln!(f, "{}: {}", i, e)?;
+        }
+        Ok(())
+    }
+}
+
+impl ReplaceTrace {
+    /// Create a new empty trace.
+    pub fn new() -> ReplaceTrace {
+        ReplaceTrace::default()
+    }
+
+    /// Get a copy of the recorded entries, in the order in which the replacements were applied.
+    pub fn entries(&self) -> Vec<TraceEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Get the number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Check if no replacements have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+
+    /// Remove all recorded entries.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Record all replacements that are performed on the current thread while executing `f`,
+    /// including those of transformers that map terms in parallel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, id::ReplaceTrace, parse, transformer::Transformer};
+    ///
+    /// let t = Transformer::Repeat(vec![Transformer::ReplaceAll(
+    ///     parse!("f(x_)*f(y_)").to_pattern(),
+    ///     parse!("f(x_+y_)").to_pattern().into(),
+    ///     Default::default(),
+    ///     Default::default(),
+    /// )]);
+    ///
+    /// let trace = ReplaceTrace::new();
+    /// let r = trace.record(|| t.execute(parse!("f(1)*f(10)*f(100)").as_view()).unwrap());
+    /// assert_eq!(r, parse!("f(111)"));
+    /// assert_eq!(trace.len(), 2);
+    /// ```
+    pub fn record<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        struct Restore(Option<ReplaceTrace>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                ACTIVE_TRACE.with(|t| *t.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(ACTIVE_TRACE.with(|t| t.borrow_mut().replace(self.clone())));
+        f()
+    }
+
+    /// Get the trace that is recording on the current thread.
+    pub(crate) fn active() -> Option<ReplaceTrace> {
+        ACTIVE_TRACE.with(|t| t.borrow().clone())
+    }
+
+    /// Log an entry in the active trace, if there is one.
+    fn log<F: FnOnce() -> TraceEntry>(entry: F) {
+        ACTIVE_TRACE.with(|t| {
+            if let Some(t) = &*t.borrow() {
+                let entry = entry();
+                t.entries.lock().unwrap().push(entry);
+            }
+        });
+    }
+}
+
 /// Construct a replacement by specifying the pattern and finishing it with the right-hand side
 /// using [ReplaceBuilder::with], [ReplaceBuilder::with_into], or [ReplaceBuilder::iter].
 #[derive(Debug, Clone)]
@@ -227,6 +619,7 @@ pub struct ReplaceBuilder<'a, 'b> {
     conditions: Option<BorrowedOrOwned<'b, Condition<PatternRestriction>>>,
     settings: MatchSettings,
     repeat: bool,
+    trace: Option<ReplaceTrace>,
 }
 
 impl<'a, 'b> ReplaceBuilder<'a, 'b> {
@@ -240,6 +633,7 @@ impl<'a, 'b> ReplaceBuilder<'a, 'b> {
             conditions: None,
             settings: MatchSettings::default(),
             repeat: false,
+            trace: None,
         }
     }
 
@@ -287,30 +681,46 @@ impl<'a, 'b> ReplaceBuilder<'a, 'b> {
         self
     }
 
+    /// Record every applied replacement in `trace`.
+    pub fn trace(mut self, trace: &ReplaceTrace) -> Self {
+        self.trace = Some(trace.clone());
+        self
+    }
+
+    /// Execute `f` while recording the replacements in the trace, if one is set.
+    fn traced<R, F: FnOnce() -> R>(&self, f: F) -> R {
+        match &self.trace {
+            Some(t) => t.record(f),
+            None => f(),
+        }
+    }
+
     /// Execute the replacement by specifying the right-hand side.
     ///
     /// To use a map as a right-hand side, use [ReplaceBuilder::with_map].
     pub fn with<'c, R: Into<BorrowedOrOwned<'c, Pattern>>>(&self, rhs: R) -> Atom {
         let rhs = ReplaceWith::Pattern(rhs.into());
-        let mut expr_ref = self.target;
-        let mut out = RecycledAtom::new();
-        let mut out2 = RecycledAtom::new();
-        while expr_ref.replace_into(
-            self.pattern.borrow(),
-            &rhs,
-            self.conditions.as_ref().map(|x| x.borrow()),
-            Some(&self.settings),
-            &mut out,
-        ) {
-            if !self.repeat || expr_ref == out.as_view() {
-                break;
-            }
+        self.traced(|| {
+            let mut expr_ref = self.target;
+            let mut out = RecycledAtom::new();
+            let mut out2 = RecycledAtom::new();
+            while expr_ref.replace_into(
+                self.pattern.borrow(),
+                &rhs,
+                self.conditions.as_ref().map(|x| x.borrow()),
+                Some(&self.settings),
+                &mut out,
+            ) {
+                if !self.repeat || expr_ref == out.as_view() {
+                    break;
+                }
 
-            std::mem::swap(&mut out, &mut out2);
-            expr_ref = out2.as_view();
-        }
+                std::mem::swap(&mut out, &mut out2);
+                expr_ref = out2.as_view();
+            }
 
-        out.into_inner()
+            out.into_inner()
+        })
     }
 
     /// Execute the replacement by specifying the right-hand side and writing the result in `out`.
@@ -320,30 +730,32 @@ impl<'a, 'b> ReplaceBuilder<'a, 'b> {
         out: &mut Atom,
     ) -> bool {
         let rhs = ReplaceWith::Pattern(rhs.into());
-        let mut expr_ref = self.target;
-        let mut out2 = RecycledAtom::new();
+        self.traced(|| {
+            let mut expr_ref = self.target;
+            let mut out2 = RecycledAtom::new();
+
+            let mut replaced = false;
+            while expr_ref.replace_into(
+                self.pattern.borrow(),
+                &rhs,
+                self.conditions.as_ref().map(|x| x.borrow()),
+                Some(&self.settings),
+                out,
+            ) {
+                replaced = true;
+                if !self.repeat || expr_ref == out.as_view() {
+                    break;
+                }
 
-        let mut replaced = false;
-        while expr_ref.replace_into(
-            self.pattern.borrow(),
-            &rhs,
-            self.conditions.as_ref().map(|x| x.borrow()),
-            Some(&self.settings),
-            out,
-        ) {
-            replaced = true;
-            if !self.repeat || expr_ref == out.as_view() {
-                break;
+                std::mem::swap(out, &mut out2);
+                expr_ref = out2.as_view();
             }
 
-            std::mem::swap(out, &mut out2);
-            expr_ref = out2.as_view();
-        }
-
-        if !replaced {
-            out.set_from_view(&self.target);
-        }
-        replaced
+            if !replaced {
+                out.set_from_view(&self.target);
+            }
+            replaced
+        })
     }
 
     /// Execute the replacement by specifying the right-hand side as a map on the matched wildcards.
@@ -374,25 +786,27 @@ impl<'a, 'b> ReplaceBuilder<'a, 'b> {
     /// ```
     pub fn with_map<'c, R: MatchMap + 'static>(&self, rhs: R) -> Atom {
         let rhs = ReplaceWith::Map(Box::new(rhs));
-        let mut expr_ref = self.target;
-        let mut out = RecycledAtom::new();
-        let mut out2 = RecycledAtom::new();
-        while expr_ref.replace_into(
-            self.pattern.borrow(),
-            &rhs,
-            self.conditions.as_ref().map(|x| x.borrow()),
-            Some(&self.settings),
-            &mut out,
-        ) {
-            if !self.repeat {
-                break;
-            }
+        self.traced(|| {
+            let mut expr_ref = self.target;
+            let mut out = RecycledAtom::new();
+            let mut out2 = RecycledAtom::new();
+            while expr_ref.replace_into(
+                self.pattern.borrow(),
+                &rhs,
+                self.conditions.as_ref().map(|x| x.borrow()),
+                Some(&self.settings),
+                &mut out,
+            ) {
+                if !self.repeat {
+                    break;
+                }
 
-            std::mem::swap(&mut out, &mut out2);
-            expr_ref = out2.as_view();
-        }
+                std::mem::swap(&mut out, &mut out2);
+                expr_ref = out2.as_view();
+            }
 
-        out.into_inner()
+            out.into_inner()
+        })
     }
 
     /// Return an iterator that replaces the pattern in the target once.
@@ -458,6 +872,32 @@ pub struct Context {
     pub index: usize,
 }
 
+/// Write the factors of `m` that are not used in a match, together with the
+/// replacement `rhs`, to `out`. The replacement takes the place of the first matched
+/// noncommuting factor, so that the order of noncommuting factors is kept.
+fn replace_factors(m: MulView, used_flags: &[bool], rhs: AtomView, out: &mut Atom) {
+    let out = out.to_mul();
+
+    let pos = m
+        .iter()
+        .zip(used_flags)
+        .position(|(c, u)| *u && c.is_noncommutative_factor());
+
+    for (i, (child, used)) in m.iter().zip(used_flags).enumerate() {
+        if pos == Some(i) {
+            out.extend(rhs);
+        }
+
+        if !used {
+            out.extend(child);
+        }
+    }
+
+    if pos.is_none() {
+        out.extend(rhs);
+    }
+}
+
 impl<'a> AtomView<'a> {
     pub(crate) fn to_pattern(self) -> Pattern {
         Pattern::from_view(self, true)
@@ -1023,10 +1463,32 @@ impl<'a> AtomView<'a> {
         &self,
         replacements: &[T],
         out: &mut Atom,
+    ) -> bool {
+        self.replace_indexed_into(replacements, None, out)
+    }
+
+    /// Replace all occurrences of the patterns of the rule set, where replacements are tested in the order that they are given.
+    pub(crate) fn replace_rule_set(&self, rules: &RuleSet) -> Atom {
+        let mut out = Atom::new();
+        self.replace_rule_set_into(rules, &mut out);
+        out
+    }
+
+    /// Replace all occurrences of the patterns of the rule set, where replacements are tested in the order that they are given.
+    /// Returns `true` iff a match was found.
+    pub(crate) fn replace_rule_set_into(&self, rules: &RuleSet, out: &mut Atom) -> bool {
+        self.replace_indexed_into(&rules.replacements, Some(&rules.index), out)
+    }
+
+    fn replace_indexed_into<T: BorrowReplacement>(
+        &self,
+        replacements: &[T],
+        index: Option<&RuleIndex>,
+        out: &mut Atom,
     ) -> bool {
         Workspace::get_local().with(|ws| {
             let mut rhs_cache = HashMap::default();
-            let matched = self.replace_no_norm(replacements, ws, 0, 0, &mut rhs_cache, out);
+            let matched = self.replace_no_norm(replacements, index, ws, 0, 0, &mut rhs_cache, out);
 
             if matched {
                 let mut norm = ws.new_atom();
@@ -1039,9 +1501,12 @@ impl<'a> AtomView<'a> {
     }
 
     /// Replace all occurrences of the patterns in the target, without normalizing the output.
+    /// If an `index` of the replacements is provided, only the replacements that could match are tried.
+    #[allow(clippy::too_many_arguments)]
     fn replace_no_norm<T: BorrowReplacement>(
         &self,
         replacements: &[T],
+        index: Option<&RuleIndex>,
         workspace: &Workspace,
         tree_level: usize,
         fn_level: usize,
@@ -1050,8 +1515,16 @@ impl<'a> AtomView<'a> {
     ) -> bool {
         let mut beyond_max_level = true;
         let mut fits = false;
-        for (rep_id, r) in replacements.iter().enumerate() {
-            let r = r.borrow();
+
+        let mut all = 0..replacements.len();
+        let mut candidates = index.map(|i| i.candidates(*self));
+        let rep_ids = std::iter::from_fn(|| match &mut candidates {
+            Some(c) => c.next(),
+            None => all.next(),
+        });
+
+        for rep_id in rep_ids {
+            let r = replacements[rep_id].borrow();
 
             if let Pattern::Literal(l) = &r.pattern {
                 if l.as_view().get_byte_size() <= self.get_byte_size() {
@@ -1083,7 +1556,8 @@ impl<'a> AtomView<'a> {
             }
 
             if r.pattern.could_match(*self) {
-                let mut match_stack = WrappedMatchStack::new(conditions, settings);
+                let mut match_stack =
+                    WrappedMatchStack::with_pattern(r.pattern, conditions, settings);
 
                 let mut it = AtomMatchIterator::new(r.pattern, *self);
                 if let Some((_, used_flags)) = it.next(&mut match_stack) {
@@ -1096,20 +1570,21 @@ impl<'a> AtomView<'a> {
                         rhs_subs.set_from_view(&rhs.as_view());
                     } else {
                         match_stack.stack.stack = key.1;
+                        let stack = match_stack.stack.with_defaults(r.pattern);
 
                         match r.rhs {
                             ReplaceWith::Pattern(rhs) => {
                                 rhs.replace_wildcards_with_matches_impl(
                                     workspace,
                                     &mut rhs_subs,
-                                    &match_stack.stack,
+                                    &stack,
                                     settings.allow_new_wildcards_on_rhs,
                                     None,
                                 )
                                 .unwrap(); // TODO: escalate?
                             }
                             ReplaceWith::Map(f) => {
-                                let mut rhs = f(&match_stack.stack);
+                                let mut rhs = f(&stack);
                                 std::mem::swap(rhs_subs.deref_mut(), &mut rhs);
                             }
                         }
@@ -1133,6 +1608,32 @@ impl<'a> AtomView<'a> {
                         }
                     }
 
+                    ReplaceTrace::log(|| TraceEntry {
+                        rule: rep_id,
+                        pattern: r.pattern.clone(),
+                        matched: match self {
+                            AtomView::Mul(m) => m
+                                .iter()
+                                .zip(used_flags.iter())
+                                .filter(|(_, u)| **u)
+                                .fold(Atom::num(1), |acc, (c, _)| acc * c),
+                            AtomView::Add(a) => a
+                                .iter()
+                                .zip(used_flags.iter())
+                                .filter(|(_, u)| **u)
+                                .fold(Atom::num(0), |acc, (c, _)| acc + c),
+                            _ => self.to_owned(),
+                        },
+                        bindings: match_stack
+                            .stack
+                            .with_defaults(r.pattern)
+                            .stack
+                            .iter()
+                            .map(|(s, m)| (*s, m.to_atom()))
+                            .collect(),
+                        result: rhs_subs.deref_mut().clone(),
+                    });
+
                     if used_flags.iter().all(|x| *x) {
                         // all used, return rhs
                         out.set_from_view(&rhs_subs.as_view());
@@ -1141,15 +1642,7 @@ impl<'a> AtomView<'a> {
 
                     match self {
                         AtomView::Mul(m) => {
-                            let out = out.to_mul();
-
-                            for (child, used) in m.iter().zip(used_flags) {
-                                if !used {
-                                    out.extend(child);
-                                }
-                            }
-
-                            out.extend(rhs_subs.as_view());
+                            replace_factors(*m, used_flags, rhs_subs.as_view(), out);
                         }
                         AtomView::Add(a) => {
                             let out = out.to_add();
@@ -1169,9 +1662,17 @@ impl<'a> AtomView<'a> {
 
                     return true;
                 }
+            } else if self.replace_linear(rep_id, r, conditions, settings, workspace, out) {
+                return true;
             }
         }
 
+        if let Some(index) = index {
+            // the candidates are not representative for the children
+            beyond_max_level = index.beyond_max_level(tree_level, fn_level);
+            fits = index.fits(self.get_byte_size());
+        }
+
         if beyond_max_level || !fits {
             out.set_from_view(self);
             return false;
@@ -1188,6 +1689,7 @@ impl<'a> AtomView<'a> {
                 for child in f {
                     submatch |= child.replace_no_norm(
                         replacements,
+                        index,
                         workspace,
                         tree_level + 1,
                         fn_level + 1,
@@ -1207,6 +1709,7 @@ impl<'a> AtomView<'a> {
                 let mut base_out = workspace.new_atom();
                 let mut submatch = base.replace_no_norm(
                     replacements,
+                    index,
                     workspace,
                     tree_level + 1,
                     fn_level,
@@ -1217,6 +1720,7 @@ impl<'a> AtomView<'a> {
                 let mut exp_out = workspace.new_atom();
                 submatch |= exp.replace_no_norm(
                     replacements,
+                    index,
                     workspace,
                     tree_level + 1,
                     fn_level,
@@ -1236,6 +1740,7 @@ impl<'a> AtomView<'a> {
                 for child in m {
                     submatch |= child.replace_no_norm(
                         replacements,
+                        index,
                         workspace,
                         tree_level + 1,
                         fn_level,
@@ -1257,6 +1762,7 @@ impl<'a> AtomView<'a> {
                 for child in a {
                     submatch |= child.replace_no_norm(
                         replacements,
+                        index,
                         workspace,
                         tree_level + 1,
                         fn_level,
@@ -1278,6 +1784,154 @@ impl<'a> AtomView<'a> {
         submatch
     }
 
+    /// Match a pattern of a linear or multilinear function that has a sum in one of its
+    /// linear arguments, such as `f(x_+y_)`, against a sum of functions `f(a)+f(b)`.
+    /// The terms that differ only in the linear argument are collected into a single
+    /// function, whose argument is the sum of the arguments of the terms.
+    fn replace_linear(
+        &self,
+        rep_id: usize,
+        r: BorrowedReplacement,
+        conditions: &Condition<PatternRestriction>,
+        settings: &MatchSettings,
+        workspace: &Workspace,
+        out: &mut Atom,
+    ) -> bool {
+        let AtomView::Add(a) = self else {
+            return false;
+        };
+
+        let Pattern::Fn(symbol, _) = r.pattern else {
+            return false;
+        };
+
+        // split every term into a function and its numerical coefficient
+        let terms: Vec<_> = a
+            .iter()
+            .map(|t| match t {
+                AtomView::Fun(f) if f.get_symbol() == *symbol => Some((f, None)),
+                AtomView::Mul(m) if m.has_coefficient() && m.get_nargs() == 2 => {
+                    match (m.iter().next().unwrap(), m.iter().last().unwrap()) {
+                        (AtomView::Fun(f), AtomView::Num(n)) if f.get_symbol() == *symbol => {
+                            Some((f, Some(n)))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+
+        for pos in r.pattern.linear_sums() {
+            let mut grouped = vec![false; terms.len()];
+            for (i, t) in terms.iter().enumerate() {
+                let Some((f, _)) = t else {
+                    continue;
+                };
+
+                if grouped[i] || f.get_nargs() <= pos {
+                    continue;
+                }
+
+                // collect all terms that only differ in the linear argument
+                let used_flags: Vec<bool> = terms
+                    .iter()
+                    .enumerate()
+                    .map(|(j, t2)| {
+                        t2.is_some_and(|(f2, _)| {
+                            !grouped[j]
+                                && f2.get_nargs() == f.get_nargs()
+                                && f2
+                                    .iter()
+                                    .zip(f.iter())
+                                    .enumerate()
+                                    .all(|(k, (a1, a2))| k == pos || a1 == a2)
+                        })
+                    })
+                    .collect();
+
+                for (g, u) in grouped.iter_mut().zip(&used_flags) {
+                    *g |= *u;
+                }
+
+                if used_flags.iter().filter(|u| **u).count() < 2 {
+                    continue;
+                }
+
+                let mut sum = Atom::num(0);
+                for (t, _) in terms.iter().zip(&used_flags).filter(|(_, u)| **u) {
+                    let (f, c) = t.unwrap();
+                    let arg = f.iter().nth(pos).unwrap();
+                    if let Some(c) = c {
+                        sum += arg * c.as_view();
+                    } else {
+                        sum += arg;
+                    }
+                }
+
+                let mut collected = workspace.new_atom();
+                let cf = collected.to_fun(*symbol);
+                for (k, arg) in f.iter().enumerate() {
+                    if k == pos {
+                        cf.add_arg(sum.as_view());
+                    } else {
+                        cf.add_arg(arg);
+                    }
+                }
+
+                let mut match_stack =
+                    WrappedMatchStack::with_pattern(r.pattern, conditions, settings);
+                let mut it = AtomMatchIterator::new(r.pattern, collected.as_view());
+                if it.next(&mut match_stack).is_none() {
+                    continue;
+                }
+
+                let stack = match_stack.stack.with_defaults(r.pattern);
+                let mut rhs_subs = workspace.new_atom();
+                match r.rhs {
+                    ReplaceWith::Pattern(rhs) => {
+                        rhs.replace_wildcards_with_matches_impl(
+                            workspace,
+                            &mut rhs_subs,
+                            &stack,
+                            settings.allow_new_wildcards_on_rhs,
+                            None,
+                        )
+                        .unwrap();
+                    }
+                    ReplaceWith::Map(f) => {
+                        let mut rhs = f(&stack);
+                        std::mem::swap(rhs_subs.deref_mut(), &mut rhs);
+                    }
+                }
+
+                ReplaceTrace::log(|| TraceEntry {
+                    rule: rep_id,
+                    pattern: r.pattern.clone(),
+                    matched: a
+                        .iter()
+                        .zip(&used_flags)
+                        .filter(|(_, u)| **u)
+                        .fold(Atom::num(0), |acc, (c, _)| acc + c),
+                    bindings: stack.stack.iter().map(|(s, m)| (*s, m.to_atom())).collect(),
+                    result: rhs_subs.deref_mut().clone(),
+                });
+
+                let out = out.to_add();
+                for (child, used) in a.iter().zip(&used_flags) {
+                    if !used {
+                        out.extend(child);
+                    }
+                }
+                out.extend(rhs_subs.as_view());
+
+                return true;
+            }
+        }
+
+        false
+    }
+
     /// Replace all occurrences of the pattern in the target, returning `true` iff a match was found.
     /// For every matched atom, the first canonical match is used and then the atom is skipped.
     pub(crate) fn replace_with_ws_into(
@@ -1299,6 +1953,7 @@ impl<'a> AtomView<'a> {
         let mut rhs_cache = HashMap::default();
         let matched = self.replace_no_norm(
             std::slice::from_ref(&rep),
+            None,
             workspace,
             0,
             0,
@@ -1336,7 +1991,7 @@ impl Pattern {
             Pattern::Literal(a) => {
                 out.set_from_view(&a.as_view());
             }
-            Pattern::Wildcard(s) => {
+            Pattern::Wildcard(s) | Pattern::Optional(s, _) => {
                 out.to_var(*s);
             }
             Pattern::Fn(s, a) => {
@@ -1556,7 +2211,7 @@ impl Pattern {
             }
             (Pattern::Mul(_), AtomView::Mul(_)) => true,
             (Pattern::Add(_), AtomView::Add(_)) => true,
-            (Pattern::Wildcard(_), _) => true,
+            (Pattern::Wildcard(_) | Pattern::Optional(..), _) => true,
             (Pattern::Pow(_), AtomView::Pow(_)) => true,
             (Pattern::Literal(p), _) => p.as_view() == target,
             (Pattern::Transformer(_), _) => panic!("Pattern is a transformer"),
@@ -1564,8 +2219,102 @@ impl Pattern {
         }
     }
 
+    /// Get the positions of the sums in the linear arguments of a pattern
+    /// of a linear function.
+    fn linear_sums(&self) -> impl Iterator<Item = usize> + '_ {
+        let (n_linear, args) = match self {
+            Pattern::Fn(s, args) if s.is_linear() => (args.len(), args.as_slice()),
+            Pattern::Fn(s, args) if s.is_linear_in_first_argument() => (1, args.as_slice()),
+            _ => (0, [].as_slice()),
+        };
+
+        args.iter()
+            .take(n_linear)
+            .enumerate()
+            .filter(|(_, a)| matches!(a, Pattern::Add(_)))
+            .map(|(i, _)| i)
+    }
+
+    /// Make the wildcard `wildcard` optional in this pattern: in an argument list of a
+    /// function, product or sum it may be absent, in which case it is bound to `default`.
+    /// If fewer arguments are present, the optional wildcards are assigned from left to right.
+    /// The default value is also used to test the conditions on the wildcard.
+    ///
+    /// Matching arguments by keyword is not supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::{Atom, AtomCore}, parse, symbol};
+    ///
+    /// let expr = parse!("f(a) + f(b,2)");
+    /// let pat = parse!("f(x_,n_)")
+    ///     .to_pattern()
+    ///     .with_optional_wildcard(symbol!("n_"), Atom::num(1));
+    /// let r = expr.replace(pat).with(parse!("g(x_)^n_"));
+    /// assert_eq!(r, parse!("g(a) + g(b)^2"));
+    /// ```
+    pub fn with_optional_wildcard(self, wildcard: Symbol, default: Atom) -> Pattern {
+        match self {
+            Pattern::Wildcard(s) | Pattern::Optional(s, _) if s == wildcard => {
+                Pattern::Optional(s, default)
+            }
+            Pattern::Fn(s, args) => Pattern::Fn(
+                s,
+                args.into_iter()
+                    .map(|a| a.with_optional_wildcard(wildcard, default.clone()))
+                    .collect(),
+            ),
+            Pattern::Pow(p) => {
+                let [b, e] = *p;
+                Pattern::Pow(Box::new([
+                    b.with_optional_wildcard(wildcard, default.clone()),
+                    e.with_optional_wildcard(wildcard, default),
+                ]))
+            }
+            Pattern::Mul(args) => Pattern::Mul(
+                args.into_iter()
+                    .map(|a| a.with_optional_wildcard(wildcard, default.clone()))
+                    .collect(),
+            ),
+            Pattern::Add(args) => Pattern::Add(
+                args.into_iter()
+                    .map(|a| a.with_optional_wildcard(wildcard, default.clone()))
+                    .collect(),
+            ),
+            p => p,
+        }
+    }
+
+    /// Get the optional wildcards of the pattern and their default values.
+    pub fn optional_wildcards(&self) -> Vec<(Symbol, &Atom)> {
+        fn collect<'a>(p: &'a Pattern, out: &mut Vec<(Symbol, &'a Atom)>) {
+            match p {
+                Pattern::Optional(s, d) => {
+                    if !out.iter().any(|(o, _)| o == s) {
+                        out.push((*s, d));
+                    }
+                }
+                Pattern::Fn(_, args) | Pattern::Mul(args) | Pattern::Add(args) => {
+                    for a in args {
+                        collect(a, out);
+                    }
+                }
+                Pattern::Pow(p) => {
+                    collect(&p[0], out);
+                    collect(&p[1], out);
+                }
+                Pattern::Literal(_) | Pattern::Wildcard(_) | Pattern::Transformer(_) => {}
+            }
+        }
+
+        let mut out = vec![];
+        collect(self, &mut out);
+        out
+    }
+
     /// Check if the expression `atom` contains a wildcard.
-    fn has_wildcard(atom: AtomView<'_>) -> bool {
+    pub(crate) fn has_wildcard(atom: AtomView<'_>) -> bool {
         match atom {
             AtomView::Num(_) => false,
             AtomView::Var(v) => v.get_wildcard_level() > 0,
@@ -1701,7 +2450,7 @@ impl Pattern {
     ) {
         match self {
             Pattern::Literal(atom) => out.set_from_view(&atom.as_view()),
-            Pattern::Wildcard(symbol) => {
+            Pattern::Wildcard(symbol) | Pattern::Optional(symbol, _) => {
                 if let Some(a) = matches.get(symbol) {
                     out.set_from_view(&a.as_view());
                 } else {
@@ -1787,7 +2536,7 @@ impl Pattern {
         transformer_input: Option<&Pattern>,
     ) -> Result<(), TransformerError> {
         match self {
-            Pattern::Wildcard(name) => {
+            Pattern::Wildcard(name) | Pattern::Optional(name, _) => {
                 if let Some(w) = match_stack.get(*name) {
                     w.to_atom_into(out);
                 } else if allow_new_wildcards_on_rhs {
@@ -1831,7 +2580,7 @@ impl Pattern {
                 let func = func_h.to_fun(name);
 
                 for arg in args {
-                    if let Pattern::Wildcard(w) = arg {
+                    if let Pattern::Wildcard(w) | Pattern::Optional(w, _) = arg {
                         if let Some(w) = match_stack.get(*w) {
                             match w {
                                 Match::Single(s) => func.add_arg(*s),
@@ -1882,7 +2631,7 @@ impl Pattern {
                 let mut oas = [&mut base, &mut exp];
 
                 for (out, arg) in oas.iter_mut().zip(base_and_exp.iter()) {
-                    if let Pattern::Wildcard(w) = arg {
+                    if let Pattern::Wildcard(w) | Pattern::Optional(w, _) = arg {
                         if let Some(w) = match_stack.get(*w) {
                             match w {
                                 Match::Single(s) => out.set_from_view(s),
@@ -1927,7 +2676,7 @@ impl Pattern {
                 let mul = mul_h.to_mul();
 
                 for arg in args {
-                    if let Pattern::Wildcard(w) = arg {
+                    if let Pattern::Wildcard(w) | Pattern::Optional(w, _) = arg {
                         if let Some(w) = match_stack.get(*w) {
                             match w {
                                 Match::Single(s) => mul.extend(*s),
@@ -1974,7 +2723,7 @@ impl Pattern {
                 let add = add_h.to_add();
 
                 for arg in args {
-                    if let Pattern::Wildcard(w) = arg {
+                    if let Pattern::Wildcard(w) | Pattern::Optional(w, _) = arg {
                         if let Some(w) = match_stack.get(*w) {
                             match w {
                                 Match::Single(s) => add.extend(*s),
@@ -2059,6 +2808,9 @@ impl std::fmt::Debug for Pattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Wildcard(arg0) => f.debug_tuple("Wildcard").field(arg0).finish(),
+            Self::Optional(arg0, arg1) => {
+                f.debug_tuple("Optional").field(arg0).field(arg1).finish()
+            }
             Self::Fn(arg0, arg1) => f.debug_tuple("Fn").field(arg0).field(arg1).finish(),
             Self::Pow(arg0) => f.debug_tuple("Pow").field(arg0).finish(),
             Self::Mul(arg0) => f.debug_tuple("Mul").field(arg0).finish(),
@@ -2093,6 +2845,8 @@ pub enum WildcardRestriction {
     Filter(Box<dyn FilterFn>),
     Cmp(Symbol, Box<dyn CmpFn>),
     NotGreedy,
+    /// The value is inferred to satisfy the assumption, see [AtomCore::value_properties].
+    Satisfies(Box<Assumption>),
 }
 
 impl WildcardRestriction {
@@ -2112,6 +2866,19 @@ impl WildcardRestriction {
     pub fn cmp(s: Symbol, f: impl CmpFn + 'static) -> Self {
         WildcardRestriction::Cmp(s, Box::new(f))
     }
+
+    /// Require that the wildcard value is inferred to satisfy the assumption,
+    /// based on the assumptions on its symbols.
+    ///
+    /// # Examples
+    /// Check if `x` is known to be positive:
+    /// ```
+    /// # use symbolica::{atom::Assumption, id::WildcardRestriction};
+    /// WildcardRestriction::satisfies(Assumption::Positive);
+    /// ```
+    pub fn satisfies(a: Assumption) -> Self {
+        WildcardRestriction::Satisfies(Box::new(a))
+    }
 }
 
 impl std::fmt::Display for WildcardRestriction {
@@ -2124,6 +2891,7 @@ impl std::fmt::Display for WildcardRestriction {
             WildcardRestriction::Filter(_) => write!(f, "filter"),
             WildcardRestriction::Cmp(s, _) => write!(f, "cmp with {}", s),
             WildcardRestriction::NotGreedy => write!(f, "not greedy"),
+            WildcardRestriction::Satisfies(a) => write!(f, "{}", a),
         }
     }
 }
@@ -2556,6 +3324,14 @@ impl Evaluate for Condition<PatternRestriction> {
                                 }
                             }
                             WildcardRestriction::NotGreedy => true,
+                            WildcardRestriction::Satisfies(a) => match value {
+                                Match::Single(v) => v.value_properties().satisfies(a),
+                                Match::Multiple(
+                                    SliceType::Add | SliceType::Mul | SliceType::Pow,
+                                    _,
+                                ) => value.to_atom().value_properties().satisfies(a),
+                                _ => false,
+                            },
                         }
                         .into()
                     } else {
@@ -2656,6 +3432,13 @@ impl Condition<PatternRestriction> {
                         }
                     }
                     WildcardRestriction::NotGreedy => true.into(),
+                    WildcardRestriction::Satisfies(a) => match value {
+                        Match::Single(v) => v.value_properties().satisfies(a).into(),
+                        Match::Multiple(SliceType::Add | SliceType::Mul | SliceType::Pow, _) => {
+                            value.to_atom().value_properties().satisfies(a).into()
+                        }
+                        _ => false.into(),
+                    },
                 }
             }
         }
@@ -2740,6 +3523,7 @@ impl Clone for WildcardRestriction {
             Self::Filter(f) => Self::Filter(dyn_clone::clone_box(f)),
             Self::Cmp(i, f) => Self::Cmp(*i, dyn_clone::clone_box(f)),
             Self::NotGreedy => Self::NotGreedy,
+            Self::Satisfies(a) => Self::Satisfies(a.clone()),
         }
     }
 }
@@ -2755,6 +3539,7 @@ impl std::fmt::Debug for WildcardRestriction {
             Self::Filter(_) => f.debug_tuple("Filter").finish(),
             Self::Cmp(arg0, _) => f.debug_tuple("Cmp").field(arg0).finish(),
             Self::NotGreedy => write!(f, "NotGreedy"),
+            Self::Satisfies(a) => f.debug_tuple("Satisfies").field(a).finish(),
         }
     }
 }
@@ -2979,6 +3764,60 @@ impl<'a> MatchStack<'a> {
     pub fn into_matches(self) -> Vec<(Symbol, Match<'a>)> {
         self.stack
     }
+
+    /// Bind the optional wildcards of `pattern` that are absent to their default value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::{Atom, AtomCore}, id::Match, parse, symbol};
+    ///
+    /// let (x_, y_) = symbol!("x_", "y_");
+    /// let expr = parse!("f(a)");
+    /// let pat = parse!("f(x_,y_)")
+    ///     .to_pattern()
+    ///     .with_optional_wildcard(y_, Atom::num(1));
+    /// let mut it = expr.pattern_match(&pat, None, None);
+    /// let m = it.next_detailed().unwrap();
+    /// let m = m.match_stack.with_defaults(&pat);
+    /// assert_eq!(m.get(x_), Some(&Match::Single(parse!("a").as_view())));
+    /// assert_eq!(m.get(y_), Some(&Match::Single(Atom::num(1).as_view())));
+    /// ```
+    pub fn with_defaults<'c>(
+        &'c self,
+        pattern: &'c Pattern,
+    ) -> std::borrow::Cow<'c, MatchStack<'c>> {
+        if !self
+            .stack
+            .iter()
+            .any(|(_, m)| matches!(m, Match::Multiple(SliceType::Empty, _)))
+        {
+            return std::borrow::Cow::Borrowed(self);
+        }
+
+        let defaults = pattern.optional_wildcards();
+        let get_default = |(s, m): &(Symbol, Match)| match m {
+            Match::Multiple(SliceType::Empty, _) => {
+                defaults.iter().find(|(d, _)| d == s).map(|(_, d)| *d)
+            }
+            _ => None,
+        };
+
+        if !self.stack.iter().any(|x| get_default(x).is_some()) {
+            return std::borrow::Cow::Borrowed(self);
+        }
+
+        std::borrow::Cow::Owned(MatchStack {
+            stack: self
+                .stack
+                .iter()
+                .map(|x| match get_default(x) {
+                    Some(d) => (x.0, Match::Single(d.as_view())),
+                    None => x.clone(),
+                })
+                .collect(),
+        })
+    }
 }
 
 /// An insertion-ordered map of wildcard identifiers to subexpressions.
@@ -2988,6 +3827,7 @@ pub struct WrappedMatchStack<'a, 'b> {
     stack: MatchStack<'a>,
     conditions: &'b Condition<PatternRestriction>,
     settings: &'b MatchSettings,
+    defaults: Vec<(Symbol, &'b Atom)>,
 }
 
 impl std::fmt::Display for MatchStack<'_> {
@@ -3037,9 +3877,31 @@ impl<'a, 'b> WrappedMatchStack<'a, 'b> {
             stack: MatchStack::new(),
             conditions,
             settings,
+            defaults: vec![],
+        }
+    }
+
+    /// Create a new match stack for matching `pattern`, wrapped with the conditions and settings.
+    /// The optional wildcards of the pattern are tested with their default value when they are absent.
+    pub fn with_pattern(
+        pattern: &'b Pattern,
+        conditions: &'b Condition<PatternRestriction>,
+        settings: &'b MatchSettings,
+    ) -> WrappedMatchStack<'a, 'b> {
+        WrappedMatchStack {
+            defaults: pattern.optional_wildcards(),
+            ..WrappedMatchStack::new(conditions, settings)
         }
     }
 
+    /// Get the default value of an optional wildcard.
+    fn get_default(&self, wildcard: Symbol) -> Option<&'b Atom> {
+        self.defaults
+            .iter()
+            .find(|(s, _)| *s == wildcard)
+            .map(|(_, d)| *d)
+    }
+
     /// Add a new map of identifier `key` to value `value` to the stack and return the size the stack had before inserting this new entry.
     /// If the entry `(key, value)` already exists, it is not inserted again and therefore the returned size is the actual size.
     /// If the `key` exists in the map, but the `value` is different, the insertion is ignored and `None` is returned.
@@ -3054,14 +3916,27 @@ impl<'a, 'b> WrappedMatchStack<'a, 'b> {
             }
         }
 
+        // an absent optional wildcard is tested with its default value
+        let default = match &value {
+            Match::Multiple(SliceType::Empty, _) => self.get_default(key),
+            _ => None,
+        };
+
         // test whether the current value passes all conditions
         // or returns an inconclusive result
         self.stack.stack.push((key, value));
-        if self
-            .conditions
-            .check_possible(key, &self.stack.stack.last().unwrap().1, &self.stack)
-            == ConditionResult::False
-        {
+        let result = match default {
+            Some(d) => {
+                self.conditions
+                    .check_possible(key, &Match::Single(d.as_view()), &self.stack)
+            }
+            None => self.conditions.check_possible(
+                key,
+                &self.stack.stack.last().unwrap().1,
+                &self.stack,
+            ),
+        };
+        if result == ConditionResult::False {
             self.stack.stack.pop();
             None
         } else {
@@ -3122,10 +3997,16 @@ impl<'a, 'b> WrappedMatchStack<'a, 'b> {
 
         let (minimal, maximal) = self.conditions.get_range_hint(identifier);
 
-        match identifier.get_wildcard_level() {
+        let range = match identifier.get_wildcard_level() {
             1 => (minimal.unwrap_or(1), Some(maximal.unwrap_or(1))), // x_
             2 => (minimal.unwrap_or(1), maximal),                    // x__
             _ => (minimal.unwrap_or(0), maximal),                    // x___
+        };
+
+        if self.get_default(identifier).is_some() {
+            (0, range.1)
+        } else {
+            range
         }
     }
 }
@@ -3169,7 +4050,10 @@ pub struct AtomMatchIterator<'a, 'b> {
 
 impl<'a, 'b> AtomMatchIterator<'a, 'b> {
     pub fn new(pattern: &'b Pattern, target: AtomView<'a>) -> AtomMatchIterator<'a, 'b> {
-        let try_match_atom = matches!(pattern, Pattern::Wildcard(_) | Pattern::Literal(_));
+        let try_match_atom = matches!(
+            pattern,
+            Pattern::Wildcard(_) | Pattern::Optional(..) | Pattern::Literal(_)
+        );
 
         AtomMatchIterator {
             try_match_atom,
@@ -3187,7 +4071,7 @@ impl<'a, 'b> AtomMatchIterator<'a, 'b> {
         if self.try_match_atom {
             self.try_match_atom = false;
 
-            if let Pattern::Wildcard(w) = self.pattern {
+            if let Pattern::Wildcard(w) | Pattern::Optional(w, _) = self.pattern {
                 let range = match_stack.get_range(*w);
                 if range.0 <= 1 && range.1.map(|w| w >= 1).unwrap_or(true) {
                     // TODO: any problems with matching Single vs a list?
@@ -3221,7 +4105,10 @@ impl<'a, 'b> AtomMatchIterator<'a, 'b> {
                 self.target,
                 match_stack,
                 true,
-                matches!(self.pattern, Pattern::Wildcard(_) | Pattern::Literal(_)),
+                matches!(
+                    self.pattern,
+                    Pattern::Wildcard(_) | Pattern::Optional(..) | Pattern::Literal(_)
+                ),
             ));
         }
 
@@ -3248,6 +4135,7 @@ pub struct SubSliceIterator<'a, 'b> {
     cyclic: bool,          // pattern is cyclic
     do_not_match_to_single_atom_in_list: bool,
     do_not_match_entire_slice: bool,
+    noncommuting: Option<Vec<usize>>, // positions of the noncommuting factors in the target
 }
 
 impl<'a, 'b> SubSliceIterator<'a, 'b> {
@@ -3272,10 +4160,10 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
                 shortcut_done = true; // cannot match
                 (arg.as_slice(), ListSlice::from_one(target))
             }
-            (Pattern::Wildcard(_), AtomView::Mul(m2)) => {
+            (Pattern::Wildcard(_) | Pattern::Optional(..), AtomView::Mul(m2)) => {
                 (std::slice::from_ref(pattern), m2.to_slice())
             }
-            (Pattern::Wildcard(_), AtomView::Add(a2)) => {
+            (Pattern::Wildcard(_) | Pattern::Optional(..), AtomView::Add(a2)) => {
                 (std::slice::from_ref(pattern), a2.to_slice())
             }
             (_, AtomView::Mul(m2)) => {
@@ -3297,7 +4185,7 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
         let min_length: usize = pat_list
             .iter()
             .map(|x| match x {
-                Pattern::Wildcard(id) => match_stack.get_range(*id).0,
+                Pattern::Wildcard(id) | Pattern::Optional(id, _) => match_stack.get_range(*id).0,
                 _ => 1,
             })
             .sum();
@@ -3336,6 +4224,7 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
             cyclic: false,
             do_not_match_to_single_atom_in_list,
             do_not_match_entire_slice,
+            noncommuting: None,
         }
     }
 
@@ -3354,7 +4243,7 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
         let min_length: usize = pattern
             .iter()
             .map(|x| match x {
-                Pattern::Wildcard(id) => match_stack.get_range(*id).0,
+                Pattern::Wildcard(id) | Pattern::Optional(id, _) => match_stack.get_range(*id).0,
                 _ => 1,
             })
             .sum();
@@ -3366,7 +4255,9 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
         let max_length: usize = pattern
             .iter()
             .map(|x| match x {
-                Pattern::Wildcard(id) => match_stack.get_range(*id).1.unwrap_or(target.len()),
+                Pattern::Wildcard(id) | Pattern::Optional(id, _) => {
+                    match_stack.get_range(*id).1.unwrap_or(target.len())
+                }
                 _ => 1,
             })
             .sum();
@@ -3387,9 +4278,52 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
             cyclic,
             do_not_match_to_single_atom_in_list: false,
             do_not_match_entire_slice: false,
+            noncommuting: None,
         }
     }
 
+    /// Check if the noncommuting factors of a product target are matched in the order
+    /// of the pattern and without gaps, so that `a*b` matches neither `b*a` nor `a*c*b`
+    /// when `a`, `b` and `c` are noncommutative.
+    fn keeps_noncommuting_order(&mut self) -> bool {
+        if self.target.get_type() != SliceType::Mul {
+            return true;
+        }
+
+        let target = &self.target;
+        let noncommuting = self.noncommuting.get_or_insert_with(|| {
+            (0..target.len())
+                .filter(|i| target.get(*i).is_noncommutative_factor())
+                .collect()
+        });
+
+        if noncommuting.len() < 2 {
+            return true;
+        }
+
+        let mut last = None;
+        for it in &self.iterators {
+            let indices = match it {
+                PatternIter::Wildcard(w) => w.indices.as_slice(),
+                PatternIter::Literal(Some(i), _)
+                | PatternIter::Fn(Some(i), ..)
+                | PatternIter::Sequence(Some(i), ..) => &[*i as u32],
+                _ => &[],
+            };
+
+            for i in indices {
+                if let Some(p) = noncommuting.iter().position(|x| *x == *i as usize) {
+                    if last.is_some_and(|l| p != l + 1) {
+                        return false;
+                    }
+                    last = Some(p);
+                }
+            }
+        }
+
+        true
+    }
+
     /// Get the next matches, where the map of matches is written into `match_stack`.
     /// The function returns the length of the match stack before the last subiterator
     /// matched. This value can be ignored by the end-user. If `None` is returned,
@@ -3416,6 +4350,8 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
                 {
                     // not done as the entire target is not used
                     forward_pass = false;
+                } else if !self.keeps_noncommuting_order() {
+                    forward_pass = false;
                 } else {
                     // yield the current match
                     return Some((*self.matches.last().unwrap(), &self.used_flag));
@@ -3425,7 +4361,7 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
             if forward_pass {
                 // add new iterator
                 let it = match &self.pattern[self.iterators.len()] {
-                    Pattern::Wildcard(name) => {
+                    Pattern::Wildcard(name) | Pattern::Optional(name, _) => {
                         let mut size_left = self.used_flag.iter().filter(|x| !*x).count();
                         let range = match_stack.get_range(*name);
 
@@ -3448,7 +4384,9 @@ impl<'a, 'b> SubSliceIterator<'a, 'b> {
                             let mut new_min = size_left;
                             let mut new_max = size_left;
                             for p in &self.pattern[self.iterators.len() + 1..] {
-                                let p_range = if let Pattern::Wildcard(name) = p {
+                                let p_range = if let Pattern::Wildcard(name)
+                                | Pattern::Optional(name, _) = p
+                                {
                                     match_stack.get_range(*name)
                                 } else {
                                     (1, Some(1))
@@ -4029,7 +4967,8 @@ impl<'a: 'b, 'b> PatternAtomTreeIterator<'a, 'b> {
             ),
             current_target: None,
             pattern_iter: None,
-            match_stack: WrappedMatchStack::new(
+            match_stack: WrappedMatchStack::with_pattern(
+                pattern,
                 conditions.unwrap_or(&DEFAULT_PATTERN_CONDITION),
                 settings.unwrap_or(&DEFAULT_MATCH_SETTINGS),
             ),
@@ -4213,15 +5152,7 @@ impl<'a: 'b, 'b> ReplaceIterator<'a, 'b> {
         } else {
             match target {
                 AtomView::Mul(m) => {
-                    let out = out.to_mul();
-
-                    for (child, used) in m.iter().zip(used_flags) {
-                        if !used {
-                            out.extend(child);
-                        }
-                    }
-
-                    out.extend(rhs);
+                    replace_factors(m, used_flags, rhs, out);
                 }
                 AtomView::Add(a) => {
                     let out = out.to_add();
@@ -4243,28 +5174,26 @@ impl<'a: 'b, 'b> ReplaceIterator<'a, 'b> {
 
     /// Return the next replacement.
     pub fn next_into(&mut self, out: &mut Atom) -> Option<()> {
-        let allow = self
-            .pattern_tree_iterator
-            .atom_tree_iterator
-            .settings
-            .allow_new_wildcards_on_rhs;
+        let settings = self.pattern_tree_iterator.match_stack.settings;
+        let pattern = self.pattern_tree_iterator.pattern;
         if let Some(pattern_match) = self.pattern_tree_iterator.next_detailed() {
             Workspace::get_local().with(|ws| {
                 let mut new_rhs = ws.new_atom();
+                let stack = pattern_match.match_stack.with_defaults(pattern);
 
                 match &self.rhs {
                     ReplaceWith::Pattern(p) => {
                         p.replace_wildcards_with_matches_impl(
                             ws,
                             &mut new_rhs,
-                            pattern_match.match_stack,
-                            allow,
+                            &stack,
+                            settings.allow_new_wildcards_on_rhs,
                             None,
                         )
                         .unwrap(); // TODO: escalate?
                     }
                     ReplaceWith::Map(f) => {
-                        let mut new_atom = f(pattern_match.match_stack);
+                        let mut new_atom = f(&stack);
                         std::mem::swap(&mut new_atom, &mut new_rhs);
                     }
                 }
@@ -4300,13 +5229,73 @@ impl<'a: 'b, 'b> Iterator for ReplaceIterator<'a, 'b> {
 #[cfg(test)]
 mod test {
     use crate::{
-        atom::{Atom, AtomCore},
-        id::{Condition, ConditionResult, Match, Replacement},
+        atom::{Assumption, Atom, AtomCore, AtomView, Symbol},
+        id::{
+            Condition, ConditionResult, Match, MatchSettings, ReplaceTrace, Replacement, RuleSet,
+            WildcardRestriction,
+        },
         parse,
         printer::PrintOptions,
-        symbol,
+        symbol, wrap_symbol,
     };
 
+    #[test]
+    fn unify() {
+        let check = |a: &str, b: &str, n: usize| {
+            let (a, b) = (parse!(a).to_pattern(), parse!(b).to_pattern());
+            let unifiers = a.unify(&b).unwrap();
+            assert_eq!(unifiers.len(), n);
+            for u in &unifiers {
+                assert_eq!(a.replace_wildcards(u), b.replace_wildcards(u));
+            }
+            unifiers
+        };
+
+        let u = check("f(x_, g(x_))", "f(a, y_)", 1);
+        assert_eq!(u[0][&symbol!("y_")], parse!("g(a)"));
+
+        check("f(x_, x_)", "f(a, b)", 0);
+        check("x_ + y_", "z_ + w_", 2);
+        // fresh wildcards are renamed after the wildcards of the patterns
+        for u in check("x__*a", "y__*b", 2)
+            .iter()
+            .chain(&check("f(x__, a)", "f(b, y__)", 2))
+        {
+            for v in u.values() {
+                for x in v.get_all_symbols(true) {
+                    assert!(!x.get_name().contains("unify_z"), "{} leaked in {}", x, v);
+                }
+            }
+        }
+        let u = check("f(x__, a)", "f(b, y__)", 2);
+        assert!(
+            u.iter()
+                .any(|u| u.get(&symbol!("x__")) == Some(&parse!("arg(b, x1__)")))
+        );
+        check("x_", "f(x_)", 0);
+
+        let u = check("f(x___, a, y___)", "f(b, a, c)", 1);
+        assert_eq!(u[0][&symbol!("x___")], parse!("b"));
+
+        let u = check("x___*f(y_)", "f(a)", 1);
+        assert_eq!(u[0][&symbol!("x___")], parse!("1"));
+
+        let u = check("f_(x_)", "g(a)", 1);
+        assert_eq!(u[0][&symbol!("f_")], parse!("g"));
+
+        // the search is truncated for deeply nested expressions
+        let f = symbol!("f");
+        let nested = |mut a: Atom| {
+            for _ in 0..70 {
+                a = crate::function!(f, a);
+            }
+            a.to_pattern()
+        };
+        let a = nested(parse!("x_"));
+        let b = nested(parse!("a"));
+        assert!(a.unify(&b).is_err());
+    }
+
     #[test]
     fn replace_wildcards_with_map() {
         let a = parse!("f1(v1__, 5) + v1*v2_ + v3^v3_").to_pattern();
@@ -4390,6 +5379,22 @@ mod test {
         assert_eq!(r, res);
     }
 
+    #[test]
+    fn assumption_restriction() {
+        let _x = Symbol::new(wrap_symbol!("ar_x"))
+            .with_assumptions(&[Assumption::Positive])
+            .build()
+            .unwrap();
+
+        let a = parse!("f1(ar_x^2) + f1(y^2) + f1((ar_x+1)^2)");
+        let r = a
+            .replace(parse!("f1(x_^2)"))
+            .when(symbol!("x_").restrict(WildcardRestriction::satisfies(Assumption::Positive)))
+            .with(parse!("x_"));
+
+        assert_eq!(r, parse!("2*ar_x+1+f1(y^2)"));
+    }
+
     #[test]
     fn multiple() {
         let a = parse!("f(v1,v2)");
@@ -4403,6 +5408,153 @@ mod test {
         assert_eq!(r, res);
     }
 
+    #[test]
+    fn rule_set() {
+        let a = parse!("f(v1,g(v2,3))*v1^2 + g(f(v1),v3) + h(v2) + 3");
+
+        let replacements = vec![
+            Replacement::new(
+                parse!("g(x_,y_)").to_pattern(),
+                parse!("x_+y_").to_pattern(),
+            ),
+            Replacement::new(parse!("v1").to_pattern(), parse!("v4").to_pattern()),
+            Replacement::new(parse!("x_(v2)").to_pattern(), parse!("x_(1)").to_pattern()),
+            Replacement::new(parse!("x_^2").to_pattern(), parse!("x_").to_pattern()),
+            Replacement::new(parse!("f(x_)").to_pattern(), parse!("x_").to_pattern()),
+            Replacement::new(parse!("v2").to_pattern(), parse!("v5").to_pattern()).with_settings(
+                MatchSettings {
+                    level_range: (0, Some(0)),
+                    ..Default::default()
+                },
+            ),
+            Replacement::new(parse!("3").to_pattern(), parse!("4").to_pattern()),
+        ];
+
+        let rules = RuleSet::new(replacements.clone());
+        let r = a.replace_rule_set(&rules);
+        assert_eq!(r, a.replace_multiple(&replacements));
+        assert_eq!(r, parse!("f(v4,v2+3)*v1 + f(v1) + v3 + h(1) + 4"));
+    }
+
+    #[test]
+    fn optional_wildcards() {
+        let (c, n) = symbol!("c_", "n_");
+        let a = parse!("f(v1) + f(v2,3) + f(v3,v4,5) + f(v5,v6)");
+
+        let pat = parse!("f(x_,c_,n_)")
+            .to_pattern()
+            .with_optional_wildcard(c, parse!("v7"))
+            .with_optional_wildcard(n, Atom::num(1));
+        let r = a
+            .replace(pat)
+            .when(n.filter(|m| matches!(m, Match::Single(AtomView::Num(_)))))
+            .with(parse!("g(x_,c_)^n_"));
+        assert_eq!(r, parse!("g(v1,v7) + g(v2,3) + g(v3,v4)^5 + g(v5,v6)"));
+
+        // the condition is tested on the default value
+        let pat = parse!("f(x_,n_)")
+            .to_pattern()
+            .with_optional_wildcard(n, Atom::num(1));
+        let r = a
+            .replace(pat)
+            .when(n.filter(|m| m.to_atom() > 1))
+            .with(parse!("g(x_,n_)"));
+        assert_eq!(r, parse!("f(v1) + g(v2,3) + f(v3,v4,5) + f(v5,v6)"));
+
+        // the optional wildcards belong to the pattern
+        let rules = vec![
+            Replacement::new(
+                parse!("h(x_,n_)")
+                    .to_pattern()
+                    .with_optional_wildcard(n, Atom::num(2)),
+                parse!("x_^n_").to_pattern(),
+            ),
+            Replacement::new(
+                parse!("g(x_,n_)").to_pattern(),
+                parse!("x_*n_").to_pattern(),
+            ),
+        ];
+        let r = parse!("h(v1) + g(v2) + g(v3,v4)").replace_multiple(&rules);
+        assert_eq!(r, parse!("v1^2 + g(v2) + v3*v4"));
+    }
+
+    #[test]
+    fn noncommutative() {
+        let _ = symbol!("nc_a", "nc_b", "nc_c"; Noncommutative);
+
+        let a = parse!("nc_b*nc_a*nc_b + nc_a*nc_b*nc_a + v1*nc_a*nc_c*nc_b + nc_b*nc_a");
+        let r = a.replace(parse!("nc_a*nc_b")).with(parse!("nc_c"));
+        assert_eq!(
+            r,
+            parse!("nc_b*nc_c + nc_c*nc_a + v1*nc_a*nc_c*nc_b + nc_b*nc_a")
+        );
+    }
+
+    #[test]
+    fn linear_matching() {
+        let a = parse!("fl1(v1) + 2*fl1(v2) + fl1(v3,v4)");
+        let r = a.replace(parse!("fl1(x_+y_)")).with(parse!("f1(x_+y_)"));
+        assert_eq!(r, parse!("f1(v1+2*v2) + fl1(v3,v4)"));
+
+        let a = parse!("flf1(v1,v3) + flf1(v2,v3) + flf1(v1,v4)");
+        let r = a
+            .replace(parse!("flf1(x_+y_,z_)"))
+            .with(parse!("f1(x_+y_,z_)"));
+        assert_eq!(r, parse!("f1(v1+v2,v3) + flf1(v1,v4)"));
+
+        // only the first argument is linear
+        let a = parse!("flf1(v1,v2) + flf1(v1,v3)");
+        let r = a
+            .replace(parse!("flf1(x_,y_+z_)"))
+            .with(parse!("f1(x_,y_+z_)"));
+        assert_eq!(r, a);
+
+        let a = parse!("fl1(v1,v2) + fl1(v1,v3) + v4");
+        let r = a
+            .replace(parse!("fl1(x_,y_+z_)"))
+            .with(parse!("f1(x_,y_+z_)"));
+        assert_eq!(r, parse!("f1(v1,v2+v3) + v4"));
+
+        let r = a
+            .replace(parse!("fl1(x_+y_,z_)"))
+            .with(parse!("f1(x_+y_,z_)"));
+        assert_eq!(r, a);
+    }
+
+    #[test]
+    fn trace() {
+        let a = parse!("f(1,v1)*g(2)*v1 + h(3)");
+        let rules = [
+            Replacement::new(parse!("g(x_)*v1").to_pattern(), parse!("x_").to_pattern()),
+            Replacement::new(parse!("f(x__)").to_pattern(), parse!("x__").to_pattern()),
+        ];
+
+        let trace = ReplaceTrace::new();
+        let r = trace.record(|| a.replace_multiple(&rules));
+        assert_eq!(r, parse!("f(1,v1)*2 + h(3)"));
+
+        let entries = trace.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].rule, 0);
+        assert_eq!(entries[0].matched, parse!("g(2)*v1"));
+        assert_eq!(entries[0].bindings, vec![(symbol!("x_"), parse!("2"))]);
+        assert_eq!(entries[0].result, parse!("2"));
+
+        // replacements outside of the recording are not logged
+        let _ = a.replace_multiple(&rules);
+        assert_eq!(trace.len(), 1);
+
+        trace.clear();
+        let r = a
+            .replace(parse!("v1"))
+            .trace(&trace)
+            .level_range((1, None))
+            .with(parse!("1"));
+        assert_eq!(r, parse!("f(1,1)*g(2)*v1 + h(3)"));
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace.entries()[0].bindings, vec![]);
+    }
+
     #[test]
     fn map_rhs() {
         let (v1, v2, v4, v5) = symbol!("v1_", "v2_", "v4_", "v5_");
@@ -0,0 +1,997 @@
+//! Confluence checking and Knuth–Bendix completion of rule sets.
+//!
+//! The critical pairs of a rule set are obtained by unifying the left-hand side of a rule
+//! with a non-wildcard subexpression of the left-hand side of another rule, modulo the
+//! associativity and commutativity of products and sums. Rules whose left-hand side is a
+//! product or a sum are extended with a ranged wildcard, so that they may overlap with a part
+//! of a larger product or sum.
+//!
+//! Terms are compared using a Knuth–Bendix ordering, where the weight of an expression is its
+//! number of nodes and ties are broken by comparing the heads and then the arguments of
+//! both expressions. Rules that merely permute their wildcards are never decreasing.
+
+use std::collections::VecDeque;
+
+use ahash::HashMap;
+
+use crate::{
+    atom::{Atom, AtomCore, AtomView, FunctionBuilder, Symbol},
+    id::{ReplaceWith, Replacement, RuleSet},
+    symbol,
+};
+
+/// The substitution of wildcards in a critical pair.
+type Substitution = HashMap<Symbol, Atom>;
+
+/// Settings for checking the confluence of a [RuleSet] and for its completion.
+#[derive(Clone, Debug)]
+pub struct CompletionSettings {
+    /// The maximal number of times the rule set is applied to compute a normal form.
+    pub max_rewrite_steps: usize,
+    /// The maximal number of rules of the completed rule set.
+    pub max_rules: usize,
+}
+
+impl Default for CompletionSettings {
+    fn default() -> Self {
+        CompletionSettings {
+            max_rewrite_steps: 100,
+            max_rules: 100,
+        }
+    }
+}
+
+/// An error that occurs during the analysis or completion of a [RuleSet].
+#[derive(Clone, Debug)]
+pub enum CompletionError {
+    /// A rule cannot be analyzed, for example because its right-hand side is a map.
+    InvalidRule(usize, &'static str),
+    /// An equation whose sides cannot be ordered, so that it cannot be turned into a rule.
+    Unorientable(Atom, Atom),
+    /// The maximal number of rules is reached. The rule set that has been constructed so far is returned.
+    RuleLimit(Box<RuleSet>),
+    /// The overlaps of two rules cannot be computed completely.
+    Unification(&'static str),
+}
+
+impl std::fmt::Display for CompletionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompletionError::InvalidRule(i, e) => write!(f, "Invalid rule {}: {}", i, e),
+            CompletionError::Unorientable(l, r) => {
+                write!(f, "Cannot orient the equation {} = {}", l, r)
+            }
+            CompletionError::RuleLimit(r) => write!(
+                f,
+                "The rule limit is reached with {} rules",
+                r.replacements().len()
+            ),
+            CompletionError::Unification(e) => write!(f, "Cannot compute overlaps: {}", e),
+        }
+    }
+}
+
+/// A critical pair of two rules: an expression to which both rules apply, together with
+/// the normal forms of both results.
+#[derive(Clone, Debug)]
+pub struct CriticalPair {
+    /// The indices of the rules that overlap.
+    pub rules: (usize, usize),
+    /// The expression to which both rules apply.
+    pub overlap: Atom,
+    /// The normal form after applying the first rule.
+    pub left: Atom,
+    /// The normal form after applying the second rule.
+    pub right: Atom,
+    /// Set to `true` if the maximal number of rewrite steps was reached while computing
+    /// one of the normal forms, which indicates that the rules may not terminate.
+    pub exhausted: bool,
+}
+
+impl CriticalPair {
+    /// Check if both normal forms are the same.
+    pub fn is_joinable(&self) -> bool {
+        self.left == self.right
+    }
+}
+
+/// The result of a confluence and termination analysis of a [RuleSet].
+#[derive(Clone, Debug, Default)]
+pub struct ConfluenceReport {
+    /// The critical pairs whose normal forms differ.
+    pub non_joinable: Vec<CriticalPair>,
+    /// The indices of the rules whose left-hand side is not greater than its right-hand side
+    /// in the term ordering, for which termination cannot be established.
+    pub non_decreasing: Vec<usize>,
+}
+
+impl ConfluenceReport {
+    /// Check if all critical pairs are joinable, which means that the rule set is
+    /// locally confluent.
+    pub fn is_locally_confluent(&self) -> bool {
+        self.non_joinable.is_empty()
+    }
+
+    /// Check if all rules are decreasing in the term ordering, which means that the
+    /// rule set terminates.
+    pub fn is_terminating(&self) -> bool {
+        self.non_decreasing.is_empty()
+    }
+}
+
+/// Get the symbol if the atom is a wildcard.
+fn wildcard(a: AtomView) -> Option<Symbol> {
+    if let AtomView::Var(v) = a {
+        let s = v.get_symbol();
+        if s.get_wildcard_level() > 0 {
+            return Some(s);
+        }
+    }
+    None
+}
+
+/// Create a wildcard with the given name and wildcard level.
+fn new_wildcard(name: &str, level: usize) -> Symbol {
+    symbol!(format!("{}{}", name, "_".repeat(level)))
+}
+
+/// Apply the substitution to an atom.
+fn apply(a: &Atom, subst: &Substitution) -> Atom {
+    a.to_pattern().replace_wildcards(subst)
+}
+
+/// Collect all wildcards that appear in `a`, in order of appearance.
+fn collect_wildcards(a: AtomView, out: &mut Vec<Symbol>) {
+    let mut add = |s: Symbol| {
+        if s.get_wildcard_level() > 0 && !out.contains(&s) {
+            out.push(s);
+        }
+    };
+
+    match a {
+        AtomView::Num(_) => {}
+        AtomView::Var(v) => add(v.get_symbol()),
+        AtomView::Fun(f) => {
+            add(f.get_symbol());
+            for arg in f {
+                collect_wildcards(arg, out);
+            }
+        }
+        AtomView::Pow(p) => {
+            let (b, e) = p.get_base_exp();
+            collect_wildcards(b, out);
+            collect_wildcards(e, out);
+        }
+        AtomView::Mul(m) => {
+            for arg in m {
+                collect_wildcards(arg, out);
+            }
+        }
+        AtomView::Add(a) => {
+            for arg in a {
+                collect_wildcards(arg, out);
+            }
+        }
+    }
+}
+
+/// Rename all wildcards in the rule to `{prefix}{n}` with the same wildcard level.
+/// The renamed wildcards are recorded in `origin`, together with the original wildcard.
+fn rename_wildcards(
+    lhs: &Atom,
+    rhs: &Atom,
+    prefix: &str,
+    origin: &mut Vec<(Symbol, Symbol)>,
+) -> (Atom, Atom) {
+    let mut wildcards = vec![];
+    collect_wildcards(lhs.as_view(), &mut wildcards);
+    collect_wildcards(rhs.as_view(), &mut wildcards);
+
+    let subst: Substitution = wildcards
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let new = new_wildcard(&format!("{}{}", prefix, i), s.get_wildcard_level() as usize);
+            origin.push((new, *s));
+            (*s, Atom::var(new))
+        })
+        .collect();
+
+    (apply(lhs, &subst), apply(rhs, &subst))
+}
+
+/// Remove the wildcards that were created during unification and that appear as a factor or term
+/// of all expressions, so that a critical pair of rules that are extended to a larger product or
+/// sum is reduced to the minimal critical pair.
+fn strip_context(exprs: &mut [&mut Atom], origin: &[(Symbol, Symbol)]) {
+    let is_context = |e: &Atom, w: Symbol| match e.as_view() {
+        AtomView::Mul(m) => m.iter().any(|x| wildcard(x) == Some(w)),
+        AtomView::Add(a) => a.iter().any(|x| wildcard(x) == Some(w)),
+        e => wildcard(e) == Some(w),
+    };
+
+    let mut wildcards = vec![];
+    collect_wildcards(exprs[0].as_view(), &mut wildcards);
+
+    let mut subst = Substitution::default();
+    for w in wildcards {
+        if origin.iter().all(|(r, _)| *r != w) && exprs.iter().all(|e| is_context(e, w)) {
+            let empty = match exprs[0].as_view() {
+                AtomView::Add(_) => Atom::num(0),
+                _ => Atom::num(1),
+            };
+            subst.insert(w, empty);
+        }
+    }
+
+    if !subst.is_empty() {
+        for e in exprs {
+            **e = apply(e, &subst);
+        }
+    }
+}
+
+/// Rename the wildcards of the expressions back to the wildcards in `origin` they were renamed
+/// from. Wildcards that were created during unification are named after the wildcard they
+/// were derived from, and the ranged wildcards that extend a product or sum are named `rest`.
+/// A numeric suffix is added to avoid clashes.
+fn restore_wildcards(exprs: &mut [&mut Atom], origin: &[(Symbol, Symbol)]) {
+    let mut wildcards = vec![];
+    for e in exprs.iter() {
+        collect_wildcards(e.as_view(), &mut wildcards);
+    }
+
+    // the wildcards of the rules claim their original name first
+    wildcards.sort_by_key(|w| !origin.iter().any(|(r, _)| r == w));
+
+    let base_name = |s: Symbol| s.get_name().trim_end_matches('_').to_owned();
+    let mut used = vec![];
+    let mut subst = Substitution::default();
+    for w in wildcards {
+        let base = if let Some((_, o)) = origin.iter().find(|(r, _)| *r == w) {
+            base_name(*o)
+        } else {
+            let name = base_name(w);
+            origin
+                .iter()
+                .filter(|(r, _)| name.starts_with(&base_name(*r)))
+                .max_by_key(|(r, _)| r.get_name().len())
+                .map(|(_, o)| base_name(*o))
+                .unwrap_or_else(|| "symbolica::rest".to_owned())
+        };
+
+        let level = w.get_wildcard_level() as usize;
+        let new = std::iter::once(String::new())
+            .chain((1..).map(|k| k.to_string()))
+            .map(|k| new_wildcard(&format!("{}{}", base, k), level))
+            .find(|n| !used.contains(n))
+            .unwrap();
+        used.push(new);
+        subst.insert(w, Atom::var(new));
+    }
+
+    for e in exprs {
+        **e = apply(e, &subst);
+    }
+}
+
+/// Count the number of nodes of an expression and the number of occurrences of every wildcard.
+fn weight(a: AtomView, wildcards: &mut HashMap<Symbol, usize>) -> usize {
+    let mut add = |s: Symbol| {
+        if s.get_wildcard_level() > 0 {
+            *wildcards.entry(s).or_default() += 1;
+        }
+    };
+
+    match a {
+        AtomView::Num(_) => 1,
+        AtomView::Var(v) => {
+            add(v.get_symbol());
+            1
+        }
+        AtomView::Fun(f) => {
+            add(f.get_symbol());
+            1 + f.iter().map(|x| weight(x, wildcards)).sum::<usize>()
+        }
+        AtomView::Pow(p) => {
+            let (b, e) = p.get_base_exp();
+            1 + weight(b, wildcards) + weight(e, wildcards)
+        }
+        AtomView::Mul(m) => 1 + m.iter().map(|x| weight(x, wildcards)).sum::<usize>(),
+        AtomView::Add(a) => 1 + a.iter().map(|x| weight(x, wildcards)).sum::<usize>(),
+    }
+}
+
+/// Check if `a` is greater than `b` in the term ordering, a Knuth–Bendix ordering in which
+/// every node has weight 1. The term `a` must have at least as many occurrences of every
+/// wildcard as `b` and it must have more nodes. Terms with the same number of nodes are
+/// compared by their head, using the canonical ordering of symbols, and then by their arguments:
+/// lexicographically for functions and powers, and as multisets for products, sums and
+/// symmetric functions. A wildcard is never greater than another term of the same weight,
+/// so that permutative rules such as `f(x_, y_) -> f(y_, x_)` are not decreasing.
+fn greater(a: &Atom, b: &Atom) -> bool {
+    kbo_greater(a.as_view(), b.as_view())
+}
+
+fn kbo_greater(a: AtomView, b: AtomView) -> bool {
+    let mut wa = HashMap::default();
+    let mut wb = HashMap::default();
+    let sa = weight(a, &mut wa);
+    let sb = weight(b, &mut wb);
+
+    if wb.iter().any(|(s, n)| wa.get(s).copied().unwrap_or(0) < *n) {
+        return false;
+    }
+
+    if sa != sb {
+        return sa > sb;
+    }
+
+    if wildcard(a).is_some() || wildcard(b).is_some() {
+        return false;
+    }
+
+    match (a, b) {
+        (AtomView::Num(_), AtomView::Num(_)) => a > b,
+        (AtomView::Var(v1), AtomView::Var(v2)) => v1.get_symbol() > v2.get_symbol(),
+        (AtomView::Fun(f1), AtomView::Fun(f2)) => {
+            let (s1, s2) = (f1.get_symbol(), f2.get_symbol());
+            if s1.get_wildcard_level() > 0 || s2.get_wildcard_level() > 0 {
+                s1 == s2 && lex_greater(f1.iter(), f2.iter())
+            } else if s1 != s2 {
+                s1 > s2
+            } else if s1.is_symmetric() {
+                multiset_greater(f1.iter().collect(), f2.iter().collect())
+            } else {
+                lex_greater(f1.iter(), f2.iter())
+            }
+        }
+        (AtomView::Pow(p1), AtomView::Pow(p2)) => {
+            let (b1, e1) = p1.get_base_exp();
+            let (b2, e2) = p2.get_base_exp();
+            lex_greater([b1, e1].into_iter(), [b2, e2].into_iter())
+        }
+        (AtomView::Mul(m1), AtomView::Mul(m2)) => {
+            multiset_greater(m1.iter().collect(), m2.iter().collect())
+        }
+        (AtomView::Add(a1), AtomView::Add(a2)) => {
+            multiset_greater(a1.iter().collect(), a2.iter().collect())
+        }
+        _ => head_rank(a) > head_rank(b),
+    }
+}
+
+/// The rank of the type of the head of an expression, used to compare
+/// terms of the same weight with different types of heads.
+fn head_rank(a: AtomView) -> u8 {
+    match a {
+        AtomView::Num(_) => 0,
+        AtomView::Var(_) => 1,
+        AtomView::Fun(_) => 2,
+        AtomView::Pow(_) => 3,
+        AtomView::Mul(_) => 4,
+        AtomView::Add(_) => 5,
+    }
+}
+
+/// The lexicographic extension of the term ordering.
+fn lex_greater<'a>(
+    mut a: impl Iterator<Item = AtomView<'a>>,
+    mut b: impl Iterator<Item = AtomView<'a>>,
+) -> bool {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return kbo_greater(x, y);
+                }
+            }
+            (Some(_), None) => return true,
+            (None, _) => return false,
+        }
+    }
+}
+
+/// The multiset extension of the term ordering: after removing the common elements,
+/// every remaining element of `b` must be smaller than a remaining element of `a`.
+fn multiset_greater(mut a: Vec<AtomView>, mut b: Vec<AtomView>) -> bool {
+    a.retain(|x| match b.iter().position(|y| y == x) {
+        Some(p) => {
+            b.swap_remove(p);
+            false
+        }
+        None => true,
+    });
+
+    !a.is_empty() && b.iter().all(|y| a.iter().any(|x| kbo_greater(*x, *y)))
+}
+
+/// Replace the `i`th argument of a function, product, sum or power by `child`.
+fn replace_child(a: AtomView, i: usize, child: &Atom) -> Atom {
+    let pick = |j: usize, arg: AtomView| {
+        if i == j {
+            child.clone()
+        } else {
+            arg.to_owned()
+        }
+    };
+
+    match a {
+        AtomView::Fun(f) => f
+            .iter()
+            .enumerate()
+            .fold(FunctionBuilder::new(f.get_symbol()), |fb, (j, arg)| {
+                fb.add_arg(pick(j, arg))
+            })
+            .finish(),
+        AtomView::Pow(p) => {
+            let (b, e) = p.get_base_exp();
+            pick(0, b).pow(pick(1, e))
+        }
+        AtomView::Mul(m) => m
+            .iter()
+            .enumerate()
+            .fold(Atom::num(1), |acc, (j, arg)| acc * pick(j, arg)),
+        AtomView::Add(s) => s
+            .iter()
+            .enumerate()
+            .fold(Atom::num(0), |acc, (j, arg)| acc + pick(j, arg)),
+        AtomView::Num(_) | AtomView::Var(_) => unreachable!("Atom has no children"),
+    }
+}
+
+/// Collect all non-wildcard subexpressions of `a`, together with their context: the
+/// expression `a` in which the subexpression is replaced by `hole`.
+fn positions(a: &Atom, hole: &Atom, out: &mut Vec<(Atom, Atom)>) {
+    if wildcard(a.as_view()).is_some() {
+        return;
+    }
+
+    out.push((a.clone(), hole.clone()));
+
+    let children: Vec<Atom> = match a.as_view() {
+        AtomView::Num(_) | AtomView::Var(_) => return,
+        AtomView::Fun(f) => f.iter().map(|x| x.to_owned()).collect(),
+        AtomView::Pow(p) => {
+            let (b, e) = p.get_base_exp();
+            vec![b.to_owned(), e.to_owned()]
+        }
+        AtomView::Mul(m) => m.iter().map(|x| x.to_owned()).collect(),
+        AtomView::Add(s) => s.iter().map(|x| x.to_owned()).collect(),
+    };
+
+    for (i, c) in children.iter().enumerate() {
+        let mut sub = vec![];
+        positions(c, hole, &mut sub);
+        for (s, ctx) in sub {
+            out.push((s, replace_child(a.as_view(), i, &ctx)));
+        }
+    }
+}
+
+/// Extend a rule whose left-hand side is a product or sum with a ranged wildcard that
+/// absorbs the remaining factors or terms.
+fn extend(lhs: &Atom, rhs: &Atom, rest: Symbol) -> Option<(Atom, Atom)> {
+    match lhs.as_view() {
+        AtomView::Mul(_) => Some((lhs * Atom::var(rest), rhs * Atom::var(rest))),
+        AtomView::Add(_) => Some((lhs + Atom::var(rest), rhs + Atom::var(rest))),
+        _ => None,
+    }
+}
+
+impl RuleSet {
+    /// Get the left-hand and right-hand side of every rule.
+    fn rules(&self) -> Result<Vec<(Atom, Atom)>, CompletionError> {
+        self.replacements
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let lhs = r
+                    .pat
+                    .to_atom()
+                    .map_err(|e| CompletionError::InvalidRule(i, e))?;
+                let rhs = match &r.rhs {
+                    ReplaceWith::Pattern(p) => p
+                        .borrow()
+                        .to_atom()
+                        .map_err(|e| CompletionError::InvalidRule(i, e))?,
+                    ReplaceWith::Map(_) => Err(CompletionError::InvalidRule(
+                        i,
+                        "The right-hand side is a map",
+                    ))?,
+                };
+                Ok((lhs, rhs))
+            })
+            .collect()
+    }
+
+    /// Repeatedly apply the rule set to `expr`, at most `max_steps` times.
+    /// Returns the result and whether a normal form was reached.
+    pub fn normal_form(&self, expr: AtomView, max_steps: usize) -> (Atom, bool) {
+        let mut cur = expr.to_owned();
+        let mut out = Atom::new();
+        for _ in 0..max_steps {
+            if !cur.replace_rule_set_into(self, &mut out) || out == cur {
+                return (cur, true);
+            }
+            std::mem::swap(&mut cur, &mut out);
+        }
+
+        (cur, false)
+    }
+
+    /// Compute the unnormalized critical pairs of the rules `(l1, r1)` and `(l2, r2)`,
+    /// where the second rule is applied to a subexpression of the left-hand side of the first.
+    /// The wildcards of the critical pairs are named after the wildcards of the rules.
+    fn overlaps(
+        (l1, r1): &(Atom, Atom),
+        (l2, r2): &(Atom, Atom),
+        same_rule: bool,
+    ) -> Result<Vec<(Atom, Atom, Atom)>, CompletionError> {
+        let mut origin = vec![];
+        let (l1, r1) = rename_wildcards(l1, r1, "cp_a", &mut origin);
+        let (l2, r2) = rename_wildcards(l2, r2, "cp_b", &mut origin);
+        let hole_symbol = new_wildcard("cp_hole", 1);
+        let hole = Atom::var(hole_symbol);
+
+        let mut pos = vec![];
+        positions(&l1, &hole, &mut pos);
+
+        let mut pairs = vec![];
+        for (i, (sub, ctx)) in pos.into_iter().enumerate() {
+            let mut candidates = vec![];
+
+            if i == 0 {
+                // at the top level, both rules may be applied to part of a larger product or sum
+                if matches!(
+                    (l1.as_view(), l2.as_view()),
+                    (AtomView::Mul(_), AtomView::Mul(_)) | (AtomView::Add(_), AtomView::Add(_))
+                ) {
+                    candidates.push((
+                        extend(&l1, &r1, new_wildcard("cp_rest_a", 3)).unwrap(),
+                        extend(&l2, &r2, new_wildcard("cp_rest_b", 3)).unwrap(),
+                    ));
+                }
+
+                if !same_rule {
+                    candidates.push(((l1.clone(), r1.clone()), (l2.clone(), r2.clone())));
+                }
+            } else {
+                let e2 = match (sub.as_view(), l2.as_view()) {
+                    (AtomView::Mul(_), AtomView::Mul(_)) | (AtomView::Add(_), AtomView::Add(_)) => {
+                        extend(&l2, &r2, new_wildcard("cp_rest_b", 3)).unwrap()
+                    }
+                    _ => (l2.clone(), r2.clone()),
+                };
+                candidates.push(((sub, r1.clone()), e2));
+            }
+
+            for ((sub, r1), (l2, r2)) in candidates {
+                let unifiers = sub
+                    .to_pattern()
+                    .unify(&l2.to_pattern())
+                    .map_err(CompletionError::Unification)?;
+
+                for subst in unifiers {
+                    let mut overlap = apply(if i == 0 { &sub } else { &l1 }, &subst);
+                    let mut left = apply(&r1, &subst);
+                    let mut ctx_subst = subst.clone();
+                    ctx_subst.insert(hole_symbol, apply(&r2, &subst));
+                    let mut right = if i == 0 {
+                        ctx_subst[&hole_symbol].clone()
+                    } else {
+                        ctx.to_pattern().replace_wildcards(&ctx_subst)
+                    };
+
+                    if i == 0 {
+                        strip_context(&mut [&mut overlap, &mut left, &mut right], &origin);
+                    }
+
+                    if left != right {
+                        restore_wildcards(&mut [&mut overlap, &mut left, &mut right], &origin);
+                        pairs.push((overlap, left, right));
+                    }
+                }
+            }
+        }
+
+        Ok(pairs)
+    }
+
+    /// Compute the critical pairs of all rules. The normal forms of both sides are computed
+    /// by applying the rule set at most `max_steps` times. The wildcards in the critical pairs
+    /// are named after the wildcards of the rules, and duplicate pairs are removed.
+    ///
+    /// The conditions and settings of the replacements are ignored when computing overlaps,
+    /// so that critical pairs may be reported for expressions to which the rules do not apply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, id::{Replacement, RuleSet}, parse};
+    ///
+    /// let rules = RuleSet::new(vec![
+    ///     Replacement::new(parse!("f(g(x_))").to_pattern(), parse!("x_").to_pattern()),
+    ///     Replacement::new(parse!("g(a)").to_pattern(), parse!("b").to_pattern()),
+    /// ]);
+    ///
+    /// let pairs = rules.critical_pairs(100).unwrap();
+    /// assert_eq!(pairs.len(), 1);
+    /// assert_eq!(pairs[0].overlap, parse!("f(g(a))"));
+    /// assert_eq!(pairs[0].left, parse!("a"));
+    /// assert_eq!(pairs[0].right, parse!("f(b)"));
+    /// ```
+    pub fn critical_pairs(&self, max_steps: usize) -> Result<Vec<CriticalPair>, CompletionError> {
+        let rules = self.rules()?;
+
+        let mut pairs = vec![];
+        for (i, rule1) in rules.iter().enumerate() {
+            for (j, rule2) in rules.iter().enumerate() {
+                for (overlap, left, right) in RuleSet::overlaps(rule1, rule2, i == j)? {
+                    let (left, left_done) = self.normal_form(left.as_view(), max_steps);
+                    let (right, right_done) = self.normal_form(right.as_view(), max_steps);
+
+                    // different unifiers or the rules in reverse order may yield the same pair
+                    if pairs.iter().any(|p: &CriticalPair| {
+                        p.overlap == overlap
+                            && (p.rules == (i, j) && p.left == left && p.right == right
+                                || p.rules == (j, i) && p.left == right && p.right == left)
+                    }) {
+                        continue;
+                    }
+
+                    pairs.push(CriticalPair {
+                        rules: (i, j),
+                        overlap,
+                        left,
+                        right,
+                        exhausted: !left_done || !right_done,
+                    });
+                }
+            }
+        }
+
+        Ok(pairs)
+    }
+
+    /// Check if the rule set is locally confluent, by trying to join all critical pairs,
+    /// and if every rule is decreasing in the term ordering, which guarantees termination
+    /// up to the normalization of the expressions that are produced by the rules.
+    /// A rule set that is locally confluent and terminating is confluent: the result of
+    /// repeatedly applying it does not depend on the order in which the rules are applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{
+    ///     atom::AtomCore,
+    ///     id::{CompletionSettings, Replacement, RuleSet},
+    ///     parse,
+    /// };
+    ///
+    /// let rules = RuleSet::new(vec![
+    ///     Replacement::new(parse!("f(x_)*f(y_)").to_pattern(), parse!("f(x_+y_)").to_pattern()),
+    ///     Replacement::new(parse!("f(0)").to_pattern(), parse!("1").to_pattern()),
+    /// ]);
+    ///
+    /// let report = rules.check_confluence(&CompletionSettings::default()).unwrap();
+    /// assert!(report.is_locally_confluent());
+    /// assert!(report.is_terminating());
+    /// ```
+    pub fn check_confluence(
+        &self,
+        settings: &CompletionSettings,
+    ) -> Result<ConfluenceReport, CompletionError> {
+        let non_decreasing = self
+            .rules()?
+            .iter()
+            .enumerate()
+            .filter(|(_, (l, r))| !greater(l, r))
+            .map(|(i, _)| i)
+            .collect();
+
+        let non_joinable = self
+            .critical_pairs(settings.max_rewrite_steps)?
+            .into_iter()
+            .filter(|p| !p.is_joinable())
+            .collect();
+
+        Ok(ConfluenceReport {
+            non_joinable,
+            non_decreasing,
+        })
+    }
+
+    /// Complete the rule set using the Knuth–Bendix procedure: critical pairs that cannot
+    /// be joined are oriented using the term ordering and added as new rules, until all critical
+    /// pairs are joinable. Existing rules that are not decreasing in the term ordering or
+    /// whose left-hand side can be rewritten by a new rule are removed and their sides are
+    /// processed as an equation instead, unless they have conditions or settings.
+    ///
+    /// Returns an error if an equation cannot be oriented or if the maximal number of rules
+    /// is exceeded. The completion may not terminate if the rules do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{
+    ///     atom::AtomCore,
+    ///     id::{CompletionSettings, Replacement, RuleSet},
+    ///     parse,
+    /// };
+    ///
+    /// let rules = RuleSet::new(vec![
+    ///     Replacement::new(parse!("f(g(x_))").to_pattern(), parse!("x_").to_pattern()),
+    ///     Replacement::new(parse!("g(a)").to_pattern(), parse!("b").to_pattern()),
+    /// ]);
+    ///
+    /// let settings = CompletionSettings::default();
+    /// let completed = rules.complete(&settings).unwrap();
+    /// assert!(completed.check_confluence(&settings).unwrap().is_locally_confluent());
+    /// assert_eq!(completed.normal_form(parse!("f(b)").as_view(), 100).0, parse!("a"));
+    /// ```
+    pub fn complete(&self, settings: &CompletionSettings) -> Result<RuleSet, CompletionError> {
+        let mut rules: Vec<Option<(Atom, Atom)>> = self.rules()?.into_iter().map(Some).collect();
+        let mut replacements: Vec<Option<Replacement>> =
+            self.replacements.iter().cloned().map(Some).collect();
+
+        // rules that are not decreasing are oriented again
+        let mut equations: VecDeque<(Atom, Atom)> = VecDeque::new();
+        for (rule, replacement) in rules.iter_mut().zip(&mut replacements) {
+            let r = replacement.as_ref().unwrap();
+            let (l, rhs) = rule.as_ref().unwrap();
+            if r.conditions.is_none() && r.settings.is_none() && !greater(l, rhs) {
+                equations.push_back(rule.take().unwrap());
+                *replacement = None;
+            }
+        }
+
+        let mut rule_set = RuleSet::new(replacements.iter().flatten().cloned().collect());
+        let mut processed = 0;
+
+        loop {
+            while let Some((s, t)) = equations.pop_front() {
+                let (s, _) = rule_set.normal_form(s.as_view(), settings.max_rewrite_steps);
+                let (t, _) = rule_set.normal_form(t.as_view(), settings.max_rewrite_steps);
+                if s == t {
+                    continue;
+                }
+
+                let (lhs, rhs) = if greater(&s, &t) {
+                    (s, t)
+                } else if greater(&t, &s) {
+                    (t, s)
+                } else {
+                    return Err(CompletionError::Unorientable(s, t));
+                };
+
+                if rule_set.replacements.len() >= settings.max_rules {
+                    return Err(CompletionError::RuleLimit(Box::new(rule_set)));
+                }
+
+                let new_rule = Replacement::new(lhs.to_pattern(), rhs.to_pattern());
+
+                // move rules whose left-hand side can be rewritten by the new rule to the equations
+                let single = RuleSet::new(vec![new_rule.clone()]);
+                for (rule, replacement) in rules.iter_mut().zip(&mut replacements) {
+                    let reducible = replacement.as_ref().is_some_and(|r| {
+                        r.conditions.is_none() && r.settings.is_none() && {
+                            let (l, _) = rule.as_ref().unwrap();
+                            let mut out = Atom::new();
+                            l.replace_rule_set_into(&single, &mut out) && out != *l
+                        }
+                    });
+
+                    if reducible {
+                        equations.push_back(rule.take().unwrap());
+                        *replacement = None;
+                    }
+                }
+
+                rules.push(Some((lhs, rhs)));
+                replacements.push(Some(new_rule));
+                rule_set = RuleSet::new(replacements.iter().flatten().cloned().collect());
+            }
+
+            if processed == rules.len() {
+                return Ok(rule_set);
+            }
+
+            // compute the overlaps of the next rule with all previous rules
+            if let Some(rule) = &rules[processed] {
+                for (k, other) in rules[..=processed].iter().enumerate() {
+                    let Some(other) = other else {
+                        continue;
+                    };
+
+                    let mut overlaps = RuleSet::overlaps(rule, other, k == processed)?;
+                    if k != processed {
+                        overlaps.extend(RuleSet::overlaps(other, rule, false)?);
+                    }
+
+                    for (_, l, r) in overlaps {
+                        if !equations.contains(&(l.clone(), r.clone())) {
+                            equations.push_back((l, r));
+                        }
+                    }
+                }
+            }
+            processed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::{Atom, AtomCore},
+        function,
+        id::{CompletionError, CompletionSettings, Replacement, RuleSet},
+        parse, symbol,
+    };
+
+    fn rules(r: &[(&str, &str)]) -> RuleSet {
+        RuleSet::new(
+            r.iter()
+                .map(|(l, r)| Replacement::new(parse!(l).to_pattern(), parse!(r).to_pattern()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn confluence() {
+        let settings = CompletionSettings::default();
+
+        let r = rules(&[("f(f(x_))", "g(x_)")]);
+        let report = r.check_confluence(&settings).unwrap();
+        assert!(report.is_terminating());
+        assert_eq!(report.non_joinable.len(), 1);
+        assert_eq!(report.non_joinable[0].rules, (0, 0));
+
+        let completed = r.complete(&settings).unwrap();
+        assert_eq!(completed.replacements().len(), 2);
+        let report = completed.check_confluence(&settings).unwrap();
+        assert!(report.is_locally_confluent() && report.is_terminating());
+        assert_eq!(
+            completed.normal_form(parse!("g(f(v1))").as_view(), 10).0,
+            completed.normal_form(parse!("f(g(v1))").as_view(), 10).0
+        );
+
+        let r = rules(&[("v1", "v2"), ("v2", "v1")]);
+        let report = r.check_confluence(&settings).unwrap();
+        assert_eq!(report.non_decreasing, vec![0]);
+        assert_eq!(r.complete(&settings).unwrap().replacements().len(), 1);
+
+        let r = rules(&[("x_*inv(x_)", "1"), ("inv(inv(x_))", "x_")]);
+        let report = r.check_confluence(&settings).unwrap();
+        assert!(report.is_locally_confluent() && report.is_terminating());
+
+        // permutative rules cannot be oriented
+        let r = rules(&[("f(y_, x_)", "f(x_, y_)")]);
+        let report = r.check_confluence(&settings).unwrap();
+        assert_eq!(report.non_decreasing, vec![0]);
+        assert!(!r.normal_form(parse!("f(v1, v2)").as_view(), 10).1);
+        assert!(matches!(
+            r.complete(&settings),
+            Err(CompletionError::Unorientable(_, _))
+        ));
+    }
+
+    #[test]
+    fn critical_pairs() {
+        let pairs = rules(&[("f(x_, g(y_))", "h(y_)"), ("g(f(x_, y_))", "y_")])
+            .critical_pairs(100)
+            .unwrap();
+
+        let pairs: Vec<_> = pairs
+            .iter()
+            .map(|p| (p.rules, p.overlap.clone(), p.left.clone(), p.right.clone()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (
+                    (0, 1),
+                    parse!("f(x_, g(f(x1_, y_)))"),
+                    parse!("h(f(x1_, y_))"),
+                    parse!("f(x_, y_)")
+                ),
+                (
+                    (1, 0),
+                    parse!("g(f(x_, g(y_)))"),
+                    parse!("g(y_)"),
+                    parse!("g(h(y_))")
+                ),
+            ]
+        );
+
+        // the overlap of products is reported once
+        let pairs = rules(&[("a*b", "c"), ("b*d", "e")])
+            .critical_pairs(100)
+            .unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].overlap, parse!("a*b*d"));
+        assert_eq!(pairs[0].left, parse!("c*d"));
+        assert_eq!(pairs[0].right, parse!("a*e"));
+    }
+
+    #[test]
+    fn exhausted() {
+        let r = rules(&[("f(x_)", "f(g(x_))"), ("f(a)", "b")]);
+        let pairs = r.critical_pairs(10).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert!(pairs[0].exhausted);
+        assert_eq!(pairs[0].right, parse!("b"));
+
+        let report = r.check_confluence(&CompletionSettings::default()).unwrap();
+        assert!(!report.is_locally_confluent());
+        assert_eq!(report.non_decreasing, vec![0]);
+    }
+
+    #[test]
+    fn complete_symmetric_group() {
+        // the presentation a^2 = b^2 = 1, aba = bab of the symmetric group S3
+        let r = rules(&[
+            ("a(a(x_))", "x_"),
+            ("b(b(x_))", "x_"),
+            ("a(b(a(x_)))", "b(a(b(x_)))"),
+        ]);
+
+        let settings = CompletionSettings::default();
+        let completed = r.complete(&settings).unwrap();
+        assert!(
+            completed
+                .check_confluence(&settings)
+                .unwrap()
+                .is_locally_confluent()
+        );
+
+        let completed_rules: Vec<_> = completed
+            .replacements()
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(
+            completed_rules,
+            vec![
+                "a(a(x_)) -> x_",
+                "b(b(x_)) -> x_",
+                "b(a(b(x_))) -> a(b(a(x_)))"
+            ]
+        );
+
+        // all words of length up to 6 reduce to the 6 elements of the group
+        let (a, b) = symbol!("a", "b");
+        let mut words = vec![parse!("x")];
+        for k in 0..6 {
+            let new: Vec<_> = words[words.len() - (1 << k)..]
+                .iter()
+                .flat_map(|w| [function!(a, w), function!(b, w)])
+                .collect();
+            words.extend(new);
+        }
+
+        let mut elements: Vec<Atom> = words
+            .iter()
+            .map(|w| completed.normal_form(w.as_view(), 100).0)
+            .collect();
+        elements.sort();
+        elements.dedup();
+        assert_eq!(elements.len(), 6);
+    }
+
+    #[test]
+    fn complete_rule_limit() {
+        // the completion generates f(g^n(f(x_))) -> g^n(f(x_)) for every n
+        let r = rules(&[("f(g(f(x_)))", "g(f(x_))")]);
+        let settings = CompletionSettings {
+            max_rules: 10,
+            ..Default::default()
+        };
+
+        let Err(CompletionError::RuleLimit(r)) = r.complete(&settings) else {
+            panic!("Expected the rule limit to be reached");
+        };
+        assert_eq!(r.replacements().len(), 10);
+    }
+}
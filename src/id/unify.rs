@@ -0,0 +1,609 @@
+//! Unification of two patterns modulo associativity and commutativity.
+//!
+//! Both patterns may contain wildcards. The products, sums and arguments of symmetric
+//! functions are treated as multisets, and the arguments of other functions as sequences.
+//! Ranged wildcards such as `x__` and `x___` may absorb several elements, which may require
+//! the introduction of fresh wildcards when two ranged wildcards overlap. The fresh wildcards
+//! are internal to the search and are renamed after the wildcards of the patterns before
+//! the unifiers are returned.
+
+use ahash::HashMap;
+
+use crate::{
+    atom::{Atom, AtomCore, AtomView, FunctionBuilder, Symbol},
+    id::Pattern,
+    symbol,
+};
+
+/// The maximal number of nested decompositions before a branch of the search is abandoned.
+const MAX_DEPTH: usize = 64;
+
+/// A substitution of wildcards.
+type Substitution = HashMap<Symbol, Atom>;
+
+/// The context in which a list of elements appears.
+#[derive(Clone, Copy, PartialEq)]
+enum ListType {
+    Mul,
+    Add,
+    Arg,
+}
+
+impl ListType {
+    /// Combine the elements into a single atom that can be substituted
+    /// for a ranged wildcard in this context.
+    fn combine(&self, parts: &[Atom]) -> Atom {
+        if parts.len() == 1 {
+            return parts[0].clone();
+        }
+
+        match self {
+            ListType::Mul => parts.iter().fold(Atom::num(1), |acc, p| acc * p),
+            ListType::Add => parts.iter().fold(Atom::num(0), |acc, p| acc + p),
+            ListType::Arg => parts
+                .iter()
+                .fold(FunctionBuilder::new(Atom::ARG), |f, p| f.add_arg(p))
+                .finish(),
+        }
+    }
+}
+
+/// The kind of an element of a list.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    /// A non-wildcard atom, which must be matched by exactly one element.
+    Rigid,
+    /// A wildcard that represents exactly one element.
+    Single,
+    /// A ranged wildcard that represents one or more elements, or zero or more if `allow_empty`.
+    Ranged { allow_empty: bool },
+}
+
+impl Kind {
+    fn new(a: &Atom) -> Kind {
+        match wildcard(a) {
+            Some(s) if s.get_wildcard_level() == 1 => Kind::Single,
+            Some(s) => Kind::Ranged {
+                allow_empty: s.get_wildcard_level() > 2,
+            },
+            None => Kind::Rigid,
+        }
+    }
+
+    fn min_degree(&self) -> usize {
+        match self {
+            Kind::Ranged { allow_empty: true } => 0,
+            _ => 1,
+        }
+    }
+
+    fn max_degree(&self) -> usize {
+        match self {
+            Kind::Ranged { .. } => usize::MAX,
+            _ => 1,
+        }
+    }
+}
+
+/// Get the symbol if the atom is a wildcard.
+fn wildcard(a: &Atom) -> Option<Symbol> {
+    if let AtomView::Var(v) = a.as_view() {
+        let s = v.get_symbol();
+        if s.get_wildcard_level() > 0 {
+            return Some(s);
+        }
+    }
+    None
+}
+
+/// Apply the substitution to an atom.
+fn apply(a: &Atom, subst: &Substitution) -> Atom {
+    if subst.is_empty() {
+        a.clone()
+    } else {
+        a.to_pattern().replace_wildcards(subst)
+    }
+}
+
+/// A quick check whether two non-wildcard atoms may unify.
+fn could_unify(a: &Atom, b: &Atom) -> bool {
+    match (a.as_view(), b.as_view()) {
+        (AtomView::Num(_), AtomView::Num(_)) | (AtomView::Var(_), AtomView::Var(_)) => a == b,
+        (AtomView::Fun(f1), AtomView::Fun(f2)) => {
+            let (s1, s2) = (f1.get_symbol(), f2.get_symbol());
+            s1 == s2 || s1.get_wildcard_level() > 0 || s2.get_wildcard_level() > 0
+        }
+        (AtomView::Pow(_), AtomView::Pow(_)) => true,
+        (AtomView::Mul(_) | AtomView::Add(_), _) | (_, AtomView::Mul(_) | AtomView::Add(_)) => true,
+        _ => false,
+    }
+}
+
+/// A unification problem of two lists, whose solutions are given as alternative
+/// lists of equations.
+struct ListProblem {
+    lhs: Vec<Atom>,
+    rhs: Vec<Atom>,
+    lhs_kind: Vec<Kind>,
+    rhs_kind: Vec<Kind>,
+    list_type: ListType,
+}
+
+impl ListProblem {
+    fn new(mut lhs: Vec<Atom>, mut rhs: Vec<Atom>, list_type: ListType, ordered: bool) -> Self {
+        if !ordered {
+            // cancel identical elements
+            let mut cancelled = vec![false; rhs.len()];
+            lhs.retain(|l| {
+                if let Some(j) = (0..rhs.len()).find(|j| !cancelled[*j] && rhs[*j] == *l) {
+                    cancelled[j] = true;
+                    false
+                } else {
+                    true
+                }
+            });
+
+            let mut cancelled = cancelled.into_iter();
+            rhs.retain(|_| !cancelled.next().unwrap());
+        }
+
+        ListProblem {
+            lhs_kind: lhs.iter().map(Kind::new).collect(),
+            rhs_kind: rhs.iter().map(Kind::new).collect(),
+            lhs,
+            rhs,
+            list_type,
+        }
+    }
+
+    /// Check if the edge between element `i` of the left-hand side and element `j`
+    /// of the right-hand side may be present.
+    fn edge_allowed(&self, i: usize, j: usize) -> bool {
+        self.lhs_kind[i] != Kind::Rigid
+            || self.rhs_kind[j] != Kind::Rigid
+            || could_unify(&self.lhs[i], &self.rhs[j])
+    }
+
+    /// Enumerate all bipartite graphs between the elements of both sides, for lists
+    /// whose order does not matter.
+    fn unordered_graphs(&self) -> Vec<Vec<(usize, usize)>> {
+        let (n, m) = (self.lhs.len(), self.rhs.len());
+        let mut graphs = vec![];
+        let mut lhs_deg = vec![0; n];
+        let mut rhs_deg = vec![0; m];
+        let mut edges = vec![];
+        self.unordered_graphs_impl(0, &mut lhs_deg, &mut rhs_deg, &mut edges, &mut graphs);
+        graphs
+    }
+
+    fn unordered_graphs_impl(
+        &self,
+        pos: usize,
+        lhs_deg: &mut [usize],
+        rhs_deg: &mut [usize],
+        edges: &mut Vec<(usize, usize)>,
+        graphs: &mut Vec<Vec<(usize, usize)>>,
+    ) {
+        let m = self.rhs.len();
+        if pos == self.lhs.len() * m {
+            if rhs_deg
+                .iter()
+                .zip(&self.rhs_kind)
+                .all(|(d, k)| *d >= k.min_degree())
+            {
+                graphs.push(edges.clone());
+            }
+            return;
+        }
+
+        let (i, j) = (pos / m, pos % m);
+
+        // all edges of the left element i have been decided
+        let row_complete =
+            |lhs_deg: &[usize]| j + 1 < m || lhs_deg[i] >= self.lhs_kind[i].min_degree();
+
+        if lhs_deg[i] < self.lhs_kind[i].max_degree()
+            && rhs_deg[j] < self.rhs_kind[j].max_degree()
+            && self.edge_allowed(i, j)
+        {
+            lhs_deg[i] += 1;
+            rhs_deg[j] += 1;
+            if row_complete(lhs_deg) {
+                edges.push((i, j));
+                self.unordered_graphs_impl(pos + 1, lhs_deg, rhs_deg, edges, graphs);
+                edges.pop();
+            }
+            lhs_deg[i] -= 1;
+            rhs_deg[j] -= 1;
+        }
+
+        if row_complete(lhs_deg) {
+            self.unordered_graphs_impl(pos + 1, lhs_deg, rhs_deg, edges, graphs);
+        }
+    }
+
+    /// Enumerate all alignments of both sides for lists whose order matters.
+    /// Elements that may be empty can be skipped; the other elements are connected by
+    /// a monotone staircase path.
+    fn ordered_graphs(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut graphs = vec![];
+        let lhs_skip: Vec<_> = (0..self.lhs.len())
+            .filter(|i| self.lhs_kind[*i].min_degree() == 0)
+            .collect();
+        let rhs_skip: Vec<_> = (0..self.rhs.len())
+            .filter(|i| self.rhs_kind[*i].min_degree() == 0)
+            .collect();
+
+        for lhs_mask in 0..1usize << lhs_skip.len() {
+            let lhs: Vec<_> = (0..self.lhs.len())
+                .filter(|i| {
+                    lhs_skip
+                        .iter()
+                        .position(|s| s == i)
+                        .is_none_or(|p| lhs_mask & (1 << p) == 0)
+                })
+                .collect();
+
+            for rhs_mask in 0..1usize << rhs_skip.len() {
+                let rhs: Vec<_> = (0..self.rhs.len())
+                    .filter(|i| {
+                        rhs_skip
+                            .iter()
+                            .position(|s| s == i)
+                            .is_none_or(|p| rhs_mask & (1 << p) == 0)
+                    })
+                    .collect();
+
+                if lhs.is_empty() || rhs.is_empty() {
+                    if lhs.is_empty() && rhs.is_empty() {
+                        graphs.push(vec![]);
+                    }
+                    continue;
+                }
+
+                let mut path = vec![(lhs[0], rhs[0])];
+                self.staircase(&lhs, &rhs, (0, 0), &mut path, &mut graphs);
+            }
+        }
+
+        graphs
+    }
+
+    fn staircase(
+        &self,
+        lhs: &[usize],
+        rhs: &[usize],
+        (i, j): (usize, usize),
+        path: &mut Vec<(usize, usize)>,
+        graphs: &mut Vec<Vec<(usize, usize)>>,
+    ) {
+        if !self.edge_allowed(lhs[i], rhs[j]) {
+            return;
+        }
+
+        if i + 1 == lhs.len() && j + 1 == rhs.len() {
+            let degree_ok = |side: &[usize], kinds: &[Kind], lhs_side: bool| {
+                side.iter().all(|e| {
+                    let d = path
+                        .iter()
+                        .filter(|(a, b)| if lhs_side { a == e } else { b == e })
+                        .count();
+                    d <= kinds[*e].max_degree()
+                })
+            };
+
+            if degree_ok(lhs, &self.lhs_kind, true) && degree_ok(rhs, &self.rhs_kind, false) {
+                graphs.push(path.clone());
+            }
+            return;
+        }
+
+        for (di, dj) in [(1, 0), (0, 1), (1, 1)] {
+            if i + di < lhs.len() && j + dj < rhs.len() {
+                path.push((lhs[i + di], rhs[j + dj]));
+                self.staircase(lhs, rhs, (i + di, j + dj), path, graphs);
+                path.pop();
+            }
+        }
+    }
+
+    /// Convert a graph into a list of equations, introducing fresh wildcards
+    /// for overlapping ranged wildcards.
+    fn equations(
+        &self,
+        graph: &[(usize, usize)],
+        fresh: &mut impl FnMut() -> Atom,
+    ) -> Vec<(Atom, Atom)> {
+        let mut lhs_parts = vec![vec![]; self.lhs.len()];
+        let mut rhs_parts = vec![vec![]; self.rhs.len()];
+        for (i, j) in graph {
+            lhs_parts[*i].push(*j);
+            rhs_parts[*j].push(*i);
+        }
+
+        let mut eqs = vec![];
+        let mut lhs_comp = vec![vec![]; self.lhs.len()];
+        let mut rhs_comp = vec![vec![]; self.rhs.len()];
+        for (i, j) in graph {
+            let (dl, dr) = (lhs_parts[*i].len(), rhs_parts[*j].len());
+            if dl == 1 && dr == 1 {
+                eqs.push((self.lhs[*i].clone(), self.rhs[*j].clone()));
+            } else if dl == 1 {
+                rhs_comp[*j].push(self.lhs[*i].clone());
+            } else if dr == 1 {
+                lhs_comp[*i].push(self.rhs[*j].clone());
+            } else {
+                let z = fresh();
+                lhs_comp[*i].push(z.clone());
+                rhs_comp[*j].push(z);
+            }
+        }
+
+        let empty = match self.list_type {
+            ListType::Mul => Atom::num(1),
+            ListType::Add => Atom::num(0),
+            ListType::Arg => FunctionBuilder::new(Atom::ARG).finish(),
+        };
+
+        for (side, comp) in [(&self.lhs, lhs_comp), (&self.rhs, rhs_comp)] {
+            for (a, c) in side.iter().zip(comp) {
+                if !c.is_empty() {
+                    eqs.push((a.clone(), self.list_type.combine(&c)));
+                }
+            }
+        }
+
+        for (side, parts) in [(&self.lhs, &lhs_parts), (&self.rhs, &rhs_parts)] {
+            for (a, p) in side.iter().zip(parts) {
+                if p.is_empty() {
+                    eqs.push((a.clone(), empty.clone()));
+                }
+            }
+        }
+
+        eqs
+    }
+}
+
+/// Enumerates unifiers of two atoms with wildcards.
+struct Unifier {
+    fresh: Vec<Symbol>,
+    solutions: Vec<Substitution>,
+    truncated: bool,
+}
+
+impl Unifier {
+    /// Create a fresh ranged wildcard in the reserved `symbolica` namespace, so that it cannot
+    /// clash with a wildcard of the patterns. The names are reused in every unification.
+    fn fresh(&mut self) -> Atom {
+        let s = symbol!(format!("symbolica::unify_z{}__", self.fresh.len() + 1));
+        self.fresh.push(s);
+        Atom::var(s)
+    }
+
+    fn bind(&self, var: Symbol, value: Atom, subst: &mut Substitution) {
+        let single: Substitution = [(var, value.clone())].into_iter().collect();
+        for v in subst.values_mut() {
+            *v = apply(v, &single);
+        }
+        subst.insert(var, value);
+    }
+
+    fn solve(&mut self, mut eqs: Vec<(Atom, Atom)>, mut subst: Substitution, depth: usize) {
+        if depth > MAX_DEPTH {
+            self.truncated = true;
+            return;
+        }
+
+        while let Some((l, r)) = eqs.pop() {
+            let l = apply(&l, &subst);
+            let r = apply(&r, &subst);
+
+            if l == r {
+                continue;
+            }
+
+            match (wildcard(&l), wildcard(&r)) {
+                (Some(x), Some(y)) => {
+                    // bind the more general wildcard
+                    if x.get_wildcard_level() >= y.get_wildcard_level() {
+                        self.bind(x, r, &mut subst);
+                    } else {
+                        self.bind(y, l, &mut subst);
+                    }
+                    continue;
+                }
+                (Some(x), None) | (None, Some(x)) => {
+                    let value = if wildcard(&l).is_some() { r } else { l };
+                    if value.contains_symbol(x) {
+                        return;
+                    }
+                    self.bind(x, value, &mut subst);
+                    continue;
+                }
+                (None, None) => {}
+            }
+
+            let (list_type, lhs, rhs, ordered) = match (l.as_view(), r.as_view()) {
+                (AtomView::Fun(f1), AtomView::Fun(f2)) => {
+                    let (s1, s2) = (f1.get_symbol(), f2.get_symbol());
+                    if s1 != s2 {
+                        if s1.get_wildcard_level() > 0 {
+                            self.bind(s1, Atom::var(s2), &mut subst);
+                        } else if s2.get_wildcard_level() > 0 {
+                            self.bind(s2, Atom::var(s1), &mut subst);
+                        } else {
+                            return;
+                        }
+
+                        eqs.push((l, r));
+                        continue;
+                    }
+
+                    (
+                        ListType::Arg,
+                        f1.iter().map(|a| a.to_owned()).collect(),
+                        f2.iter().map(|a| a.to_owned()).collect(),
+                        !s1.is_symmetric(),
+                    )
+                }
+                (AtomView::Pow(p1), AtomView::Pow(p2)) => {
+                    let (b1, e1) = p1.get_base_exp();
+                    let (b2, e2) = p2.get_base_exp();
+                    eqs.push((b1.to_owned(), b2.to_owned()));
+                    eqs.push((e1.to_owned(), e2.to_owned()));
+                    continue;
+                }
+                (AtomView::Mul(_), _) | (_, AtomView::Mul(_)) => {
+                    let list = |a: AtomView| match a {
+                        AtomView::Mul(m) => m.iter().map(|x| x.to_owned()).collect(),
+                        _ => vec![a.to_owned()],
+                    };
+                    (ListType::Mul, list(l.as_view()), list(r.as_view()), false)
+                }
+                (AtomView::Add(_), _) | (_, AtomView::Add(_)) => {
+                    let list = |a: AtomView| match a {
+                        AtomView::Add(m) => m.iter().map(|x| x.to_owned()).collect(),
+                        _ => vec![a.to_owned()],
+                    };
+                    (ListType::Add, list(l.as_view()), list(r.as_view()), false)
+                }
+                _ => return,
+            };
+
+            let problem = ListProblem::new(lhs, rhs, list_type, ordered);
+            let graphs = if ordered {
+                problem.ordered_graphs()
+            } else {
+                problem.unordered_graphs()
+            };
+
+            for g in graphs {
+                let mut new_eqs = eqs.clone();
+                new_eqs.extend(problem.equations(&g, &mut || self.fresh()));
+                self.solve(new_eqs, subst.clone(), depth + 1);
+            }
+            return;
+        }
+
+        self.solutions.push(subst);
+    }
+}
+
+impl Pattern {
+    /// Enumerate a complete set of unifiers of `self` and `other` modulo the associativity and
+    /// commutativity of products, sums and symmetric functions. A unifier is a substitution
+    /// of the wildcards of both patterns that makes them equal.
+    ///
+    /// Wildcards with the same name in both patterns are considered to be the same variable.
+    /// Ranged wildcards such as `x__` may be mapped to a product, a sum or an argument list
+    /// wrapped in `arg`, depending on where they appear. The overlap of two ranged wildcards
+    /// is expressed in terms of a new wildcard that is named after a wildcard of the patterns,
+    /// such as `x1__` for `x__`.
+    ///
+    /// The search abandons a branch after 64 nested decompositions. If this limit is reached,
+    /// the set of unifiers may be incomplete and an error is returned instead.
+    ///
+    /// Returns an error if a pattern contains a transformer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use symbolica::{atom::AtomCore, parse, symbol};
+    ///
+    /// let a = parse!("f(x_, y_ + z_)").to_pattern();
+    /// let b = parse!("f(1, 2 + w_)").to_pattern();
+    /// let unifiers = a.unify(&b).unwrap();
+    ///
+    /// assert_eq!(unifiers.len(), 2);
+    /// for u in &unifiers {
+    ///     assert_eq!(u[&symbol!("x_")], parse!("1"));
+    /// }
+    /// ```
+    pub fn unify(&self, other: &Pattern) -> Result<Vec<HashMap<Symbol, Atom>>, &'static str> {
+        let lhs = self.to_atom()?;
+        let rhs = other.to_atom()?;
+
+        let mut unifier = Unifier {
+            fresh: vec![],
+            solutions: vec![],
+            truncated: false,
+        };
+        unifier.solve(vec![(lhs.clone(), rhs.clone())], HashMap::default(), 0);
+
+        if unifier.truncated {
+            return Err(
+                "The unification depth limit was reached: the set of unifiers may be incomplete",
+            );
+        }
+
+        let mut used = vec![];
+        for a in [&lhs, &rhs] {
+            for x in a.as_view().get_all_symbols(true) {
+                if x.get_wildcard_level() > 0 {
+                    used.push(x);
+                }
+            }
+        }
+
+        let mut unifiers: Vec<Substitution> = vec![];
+        for mut s in unifier.solutions {
+            s.retain(|k, _| !unifier.fresh.contains(k));
+
+            // the decomposition may produce non-normalized substitutions, such as
+            // powers of the same base that merge after substitution, so verify the result
+            if apply(&lhs, &s) != apply(&rhs, &s) {
+                continue;
+            }
+
+            let s = rename_fresh(s, &unifier.fresh, &used);
+            if !unifiers.contains(&s) {
+                unifiers.push(s);
+            }
+        }
+
+        Ok(unifiers)
+    }
+}
+
+/// Rename the fresh wildcards that remain in the substitution `s`. If a wildcard of the patterns
+/// is mapped to exactly a fresh wildcard, the fresh wildcard is renamed back to it and the mapping
+/// is removed. Otherwise, the fresh wildcard is named after a wildcard whose value contains it,
+/// with a numeric suffix that does not clash with the wildcards in `used`.
+fn rename_fresh(mut s: Substitution, fresh: &[Symbol], used: &[Symbol]) -> Substitution {
+    let mut used = used.to_vec();
+    for z in fresh {
+        let mut keys: Vec<_> = s
+            .iter()
+            .filter(|(_, v)| v.contains_symbol(*z))
+            .map(|(k, _)| *k)
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+        keys.sort();
+
+        let name = if let Some(w) = keys
+            .iter()
+            .find(|w| s[w] == Atom::var(*z) && w.get_wildcard_level() == z.get_wildcard_level())
+        {
+            let w = *w;
+            s.remove(&w);
+            w
+        } else {
+            let level = z.get_wildcard_level() as usize;
+            let base = keys[0].get_name().trim_end_matches('_');
+            (1..)
+                .map(|k| symbol!(format!("{}{}{}", base, k, "_".repeat(level))))
+                .find(|n| !used.contains(n))
+                .unwrap()
+        };
+        used.push(name);
+
+        let single: Substitution = [(*z, Atom::var(name))].into_iter().collect();
+        for v in s.values_mut() {
+            *v = apply(v, &single);
+        }
+    }
+    s
+}
@@ -0,0 +1,730 @@
+use ahash::HashMap;
+
+use crate::{
+    atom::{Atom, AtomCore, AtomView, FunctionBuilder, Symbol},
+    coefficient::CoefficientView,
+    domains::{
+        integer::{Integer, IntegerRing, Z},
+        rational::{Q, Rational},
+        rational_polynomial::RationalPolynomial,
+    },
+    poly::{Variable, factor::Factorize, polynomial::MultivariatePolynomial},
+    state::Workspace,
+    symbol,
+};
+
+/// The maximal recursion depth of the heuristic integration strategies.
+const MAX_DEPTH: usize = 12;
+
+impl AtomView<'_> {
+    /// Integrate the expression in `x`. Terms that cannot be integrated are
+    /// kept as an unevaluated `integrate(term, x)`.
+    pub(crate) fn integrate(&self, x: Symbol) -> Atom {
+        if let AtomView::Add(a) = self {
+            if let Some(r) = self.integrate_rational(x) {
+                return r;
+            }
+
+            let mut res = Atom::new();
+            for t in a {
+                res += t.integrate_impl(x, 0).unwrap_or_else(|| unevaluated(t, x));
+            }
+            res
+        } else {
+            self.integrate_impl(x, 0)
+                .unwrap_or_else(|| unevaluated(*self, x))
+        }
+    }
+
+    fn integrate_impl(&self, x: Symbol, depth: usize) -> Option<Atom> {
+        if !self.contains_symbol(x) {
+            return Some(self.to_owned() * Atom::var(x));
+        }
+
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        if let Some(r) = self.integrate_rational(x) {
+            return Some(r);
+        }
+
+        match self {
+            AtomView::Add(a) => {
+                let mut res = Atom::new();
+                for t in a {
+                    res += t.integrate_impl(x, depth)?;
+                }
+                return Some(res);
+            }
+            AtomView::Mul(m) => {
+                // move the factors that are independent of x out of the integral
+                let mut constant = Atom::num(1);
+                let mut rest = Atom::num(1);
+                for f in m {
+                    if f.contains_symbol(x) {
+                        rest *= f;
+                    } else {
+                        constant *= f;
+                    }
+                }
+
+                if !constant.is_one() {
+                    return Some(constant * rest.as_view().integrate_impl(x, depth)?);
+                }
+            }
+            _ => {}
+        }
+
+        self.integrate_elementary(x)
+            .or_else(|| self.integrate_substitution(x, depth))
+            .or_else(|| self.integrate_by_parts(x, depth))
+            .or_else(|| {
+                let e = self.expand();
+                if e.as_view() != *self {
+                    e.as_view().integrate_impl(x, depth + 1)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Check if the expression is a rational function in `x` with rational coefficients.
+    fn is_rational_in(&self, x: Symbol) -> bool {
+        if !self.contains_symbol(x) {
+            return match self {
+                AtomView::Num(_) => Rational::try_from(*self).is_ok(),
+                _ => !self.has_complex_coefficients(),
+            };
+        }
+
+        match self {
+            AtomView::Num(_) | AtomView::Fun(_) => false,
+            AtomView::Var(_) => true,
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                if let AtomView::Num(n) = e {
+                    matches!(n.get_coeff_view(), CoefficientView::Natural(_, 1, 0, _))
+                        && b.is_rational_in(x)
+                } else {
+                    false
+                }
+            }
+            AtomView::Mul(m) => m.iter().all(|a| a.is_rational_in(x)),
+            AtomView::Add(a) => a.iter().all(|a| a.is_rational_in(x)),
+        }
+    }
+
+    /// Integrate a rational function in `x` using Hermite reduction and
+    /// the Rothstein-Trager method. The logarithmic parts that are a sum over the
+    /// roots of an irreducible polynomial of degree higher than two, such as for `1/(x^3+2)`,
+    /// are not supported and `None` is returned.
+    fn integrate_rational(&self, x: Symbol) -> Option<Atom> {
+        if !self.is_rational_in(x) {
+            return None;
+        }
+
+        let r: RationalPolynomial<IntegerRing, u16> = self.to_rational_polynomial(&Q, &Z, None);
+        let var = r
+            .get_variables()
+            .iter()
+            .position(|v| *v == Variable::Symbol(x))?;
+
+        let (rational, logs) = r.integrate(var);
+
+        let mut res = Atom::new();
+        for p in &rational {
+            res += rat_to_atom(p);
+        }
+
+        for (c, a) in &logs {
+            res += log_part(c, a, x)?;
+        }
+
+        Some(res)
+    }
+
+    /// Integrate elementary functions and powers of an argument that is linear in `x`.
+    fn integrate_elementary(&self, x: Symbol) -> Option<Atom> {
+        match self {
+            AtomView::Fun(f) if f.get_nargs() == 1 => {
+                let u = f.iter().next().unwrap();
+                let a = u.derivative(x);
+                if a.contains_symbol(x) {
+                    return None;
+                }
+
+                let u = u.to_owned();
+                let r = match f.get_symbol() {
+                    Atom::EXP => self.to_owned(),
+                    Atom::LOG => &u * self.to_owned() - &u,
+                    Atom::SIN => -u.cos(),
+                    Atom::COS => u.sin(),
+                    Atom::TAN => -u.cos().log(),
+                    Atom::SINH => u.cosh(),
+                    Atom::COSH => u.sinh(),
+                    Atom::TANH => u.cosh().log(),
+                    Atom::SQRT => Atom::num((2, 3)) * &u * self.to_owned(),
+                    _ => return None,
+                };
+
+                Some(r / a)
+            }
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                if e.contains_symbol(x) {
+                    if b.contains_symbol(x) {
+                        return None;
+                    }
+
+                    let a = e.derivative(x);
+                    if a.contains_symbol(x) {
+                        return None;
+                    }
+
+                    if matches!(b, AtomView::Var(v) if v.get_symbol() == Atom::E) {
+                        Some(self.to_owned() / a)
+                    } else {
+                        Some(self.to_owned() / (a * b.to_owned().log()))
+                    }
+                } else {
+                    let a = b.derivative(x);
+                    if a.contains_symbol(x) {
+                        return None;
+                    }
+
+                    if e == Atom::num(-1).as_view() {
+                        Some(b.to_owned().log() / a)
+                    } else {
+                        let e1 = e.to_owned() + Atom::num(1);
+                        Some(b.to_owned().pow(&e1) / (e1 * a))
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Collect subexpressions that are candidates for the substitution `u = g(x)`.
+    fn substitution_candidates(&self, x: Symbol, candidates: &mut Vec<Atom>) {
+        if !self.contains_symbol(x) || matches!(self, AtomView::Var(_)) {
+            return;
+        }
+
+        match self {
+            AtomView::Fun(f) => {
+                candidates.push(self.to_owned());
+                for a in f {
+                    a.substitution_candidates(x, candidates);
+                }
+            }
+            AtomView::Pow(p) => {
+                candidates.push(self.to_owned());
+
+                let (b, e) = p.get_base_exp();
+                b.substitution_candidates(x, candidates);
+                e.substitution_candidates(x, candidates);
+
+                if !matches!(b, AtomView::Var(_)) && b.contains_symbol(x) {
+                    candidates.push(b.to_owned());
+                }
+                if !matches!(e, AtomView::Var(_)) && e.contains_symbol(x) {
+                    candidates.push(e.to_owned());
+                }
+            }
+            AtomView::Mul(m) => {
+                for a in m {
+                    a.substitution_candidates(x, candidates);
+                }
+            }
+            AtomView::Add(a) => {
+                candidates.push(self.to_owned());
+                for a in a {
+                    a.substitution_candidates(x, candidates);
+                }
+            }
+            AtomView::Num(_) | AtomView::Var(_) => {}
+        }
+    }
+
+    /// Integrate using the substitution `u = g(x)` for a subexpression `g`
+    /// for which `f / g'` can be written as a function of `g` only.
+    fn integrate_substitution(&self, x: Symbol, depth: usize) -> Option<Atom> {
+        let mut candidates = vec![];
+        self.substitution_candidates(x, &mut candidates);
+        candidates.sort();
+        candidates.dedup();
+
+        let u = symbol!(format!("integration_var_{}", depth));
+
+        for g in candidates {
+            let dg = g.derivative(x);
+            if dg.is_zero() {
+                continue;
+            }
+
+            let q = self.to_owned() / &dg;
+            if q.contains_symbol(Atom::DERIVATIVE) {
+                continue;
+            }
+
+            // also try the quotient with common factors cancelled
+            let mut options = vec![q];
+            if !options[0].as_view().has_complex_coefficients() {
+                let t: RationalPolynomial<IntegerRing, u16> =
+                    options[0].to_rational_polynomial(&Q, &Z, None);
+                options.push(t.to_expression());
+            }
+
+            for q in options {
+                let qs = q.replace(g.to_pattern()).with(Atom::var(u).to_pattern());
+                if qs.contains_symbol(x) {
+                    continue;
+                }
+
+                if let Some(r) = qs.as_view().integrate_impl(u, depth + 1) {
+                    return Some(r.replace(Atom::var(u).to_pattern()).with(g.to_pattern()));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Check if the expression is a polynomial in `x`.
+    fn is_polynomial_in(&self, x: Symbol) -> bool {
+        if !self.contains_symbol(x) {
+            return true;
+        }
+
+        match self {
+            AtomView::Var(_) => true,
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                if let AtomView::Num(n) = e {
+                    matches!(n.get_coeff_view(), CoefficientView::Natural(n, 1, 0, _) if n > 0)
+                        && b.is_polynomial_in(x)
+                } else {
+                    false
+                }
+            }
+            AtomView::Mul(m) => m.iter().all(|a| a.is_polynomial_in(x)),
+            AtomView::Add(a) => a.iter().all(|a| a.is_polynomial_in(x)),
+            AtomView::Num(_) | AtomView::Fun(_) => false,
+        }
+    }
+
+    /// Check if the expression is a positive integer power of a logarithm or inverse
+    /// trigonometric function, whose derivative is simpler than the function itself.
+    fn is_inverse_function(&self) -> bool {
+        match self {
+            AtomView::Fun(f) => matches!(
+                f.get_symbol(),
+                Atom::LOG
+                    | Atom::ATAN
+                    | Atom::ASIN
+                    | Atom::ACOS
+                    | Atom::ASINH
+                    | Atom::ACOSH
+                    | Atom::ATANH
+            ),
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                if let AtomView::Num(n) = e {
+                    matches!(n.get_coeff_view(), CoefficientView::Natural(n, 1, 0, _) if n > 0)
+                        && b.is_inverse_function()
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Integrate by parts, differentiating either a logarithmic or inverse trigonometric
+    /// factor, or a polynomial factor.
+    fn integrate_by_parts(&self, x: Symbol, depth: usize) -> Option<Atom> {
+        let factors: Vec<AtomView> = if let AtomView::Mul(m) = self {
+            m.iter().collect()
+        } else {
+            vec![*self]
+        };
+
+        let mut poly = Atom::num(1);
+        let mut rest = vec![];
+        for f in factors {
+            if f.is_polynomial_in(x) {
+                poly *= f;
+            } else {
+                rest.push(f);
+            }
+        }
+
+        // differentiate the inverse function and integrate the polynomial
+        if rest.len() == 1 && rest[0].is_inverse_function() {
+            let u = rest[0];
+            let v = poly.as_view().integrate_impl(x, depth + 1)?;
+            let rem = &v * u.derivative(x);
+            let rem = rem.as_view().integrate_impl(x, depth + 1)?;
+            return Some(u.to_owned() * &v - rem);
+        }
+
+        if !poly.contains_symbol(x) || rest.is_empty() {
+            return None;
+        }
+
+        // differentiate the polynomial and integrate the rest
+        let mut dv = Atom::num(1);
+        for f in rest {
+            dv *= f;
+        }
+
+        let v = dv.as_view().integrate_impl(x, depth + 1)?;
+        let rem = poly.derivative(x) * &v;
+        let rem = rem.as_view().integrate_impl(x, depth + 1)?;
+        Some(&poly * &v - rem)
+    }
+}
+
+/// Create the unevaluated integral `integrate(f, x)`.
+fn unevaluated(f: AtomView, x: Symbol) -> Atom {
+    FunctionBuilder::new(Atom::INTEGRATE)
+        .add_arg(f)
+        .add_arg(Atom::var(x))
+        .finish()
+}
+
+/// Convert a rational polynomial that does not depend on the temporary variable to an atom.
+fn rat_to_atom(p: &RationalPolynomial<IntegerRing, u16>) -> Atom {
+    poly_to_atom(&p.numerator) / poly_to_atom(&p.denominator)
+}
+
+/// Convert a polynomial that does not depend on the temporary variable to an atom.
+fn poly_to_atom(p: &MultivariatePolynomial<IntegerRing, u16>) -> Atom {
+    let zero = Atom::new();
+    let mut map = HashMap::default();
+    map.insert(Variable::Temporary(0), zero.as_view());
+
+    let mut out = Atom::new();
+    Workspace::get_local().with(|ws| p.to_expression_with_map(ws, &map, &mut out));
+    out
+}
+
+/// Take the square root of a non-negative rational number, keeping the
+/// denominator free of square roots and moving small square factors out of the root.
+fn rational_sqrt(r: &Rational) -> Atom {
+    let m = r.clone().to_multi_prec();
+    let mut inside = rug::Integer::from(m.numer() * m.denom());
+    let mut outside = rug::Integer::from(1);
+
+    let mut p = 2u32;
+    while !inside.is_perfect_square() && p < 1000 && inside >= p * p {
+        while inside.is_divisible_u(p * p) {
+            inside /= p * p;
+            outside *= p;
+        }
+        p += 1;
+    }
+
+    if inside.is_perfect_square() {
+        outside *= inside.sqrt();
+        inside = rug::Integer::from(1);
+    }
+
+    let r = Atom::num(Rational::from(rug::Rational::from((
+        outside,
+        m.denom().clone(),
+    ))));
+    if inside == 1 {
+        r
+    } else {
+        r * Atom::num(Integer::from(inside)).pow(Atom::num((1, 2)))
+    }
+}
+
+/// Write the square root of `disc` as `sqrt(d)*s*sqrt(r)`, where `d` is a rational number,
+/// `s` is a rational function and `r` is a square-free polynomial.
+fn split_sqrt(disc: &Atom) -> (Rational, Atom, Atom) {
+    let p: RationalPolynomial<IntegerRing, u16> = disc.to_rational_polynomial(&Q, &Z, None);
+
+    // sqrt(n/d) = sqrt(n*d)/d
+    let mut d = Integer::one();
+    let mut s = Atom::num(1) / poly_to_atom(&p.denominator);
+    let mut r = Atom::num(1);
+    for (f, pow) in (&p.numerator * &p.denominator).square_free_factorization() {
+        if f.is_constant() {
+            d *= &f.lcoeff().pow(pow as u64);
+        } else {
+            let f = poly_to_atom(&f);
+            s *= f.npow((pow / 2) as i64);
+            r *= f.npow((pow % 2) as i64);
+        }
+    }
+
+    (Rational::from(&d), s, r)
+}
+
+/// Convert the logarithmic part `sum_(c(z) = 0) z*log(a(x, z))` to an atom
+/// by solving for the roots of `c`. If `c` does not depend on `z`, it is a root that has already
+/// been solved for and the part is `c*log(a)`. Conjugate complex roots are combined into a real
+/// logarithm and an arctangent. Returns `None` if the roots cannot be found or if
+/// the argument of a logarithm does not depend on `x`.
+fn log_part(
+    c: &RationalPolynomial<IntegerRing, u16>,
+    a: &RationalPolynomial<IntegerRing, u16>,
+    x: Symbol,
+) -> Option<Atom> {
+    let z = Variable::Temporary(0);
+
+    let log = |arg: Atom| {
+        if arg.contains_symbol(x) {
+            Some(arg.log())
+        } else {
+            None
+        }
+    };
+
+    // the denominator of the argument of the logarithm is constant in x and can be dropped
+    let zc = c.get_variables().iter().position(|v| *v == z);
+    let Some(zc) = zc.filter(|zc| c.numerator.degree(*zc) > 0) else {
+        let za = a.get_variables().iter().position(|v| *v == z);
+        if za.is_some_and(|za| a.numerator.degree(za) > 0) {
+            return None;
+        }
+        return Some(log(poly_to_atom(&a.numerator))? * rat_to_atom(c));
+    };
+
+    let c: Vec<Atom> = c
+        .numerator
+        .to_univariate(zc)
+        .coefficients
+        .iter()
+        .map(poly_to_atom)
+        .collect();
+
+    let a: Vec<Atom> = match a.get_variables().iter().position(|v| *v == z) {
+        Some(za) => a
+            .numerator
+            .to_univariate(za)
+            .coefficients
+            .iter()
+            .map(poly_to_atom)
+            .collect(),
+        None => vec![poly_to_atom(&a.numerator)],
+    };
+
+    let eval_a = |root: &Atom| {
+        let mut res = Atom::new();
+        let mut pow = Atom::num(1);
+        for ak in &a {
+            res += ak * &pow;
+            pow *= root;
+        }
+        res.expand()
+    };
+
+    match c.len() {
+        2 => {
+            let root = -&c[0] / &c[1];
+            Some(log(eval_a(&root))? * root)
+        }
+        3 => {
+            let disc = (&c[1] * &c[1] - Atom::num(4) * &c[2] * &c[0]).expand();
+            let (d, s, r) = split_sqrt(&disc);
+
+            if d.is_negative() && r.is_one() {
+                // combine the complex conjugate roots p +- i*q into
+                // p*log(A^2+B^2) + 2*q*atan(A/B) with A + i*B = a(x, p + i*q)
+                // and write B = q*B' so that the square root only appears in q
+                let p = -&c[1] / (Atom::num(2) * &c[2]);
+                let q = rational_sqrt(&-d) * s / (Atom::num(2) * &c[2]);
+                let q2 = (-&disc / (Atom::num(4) * &c[2] * &c[2])).expand();
+
+                let (mut re, mut im) = (Atom::new(), Atom::new());
+                let (mut pow_re, mut pow_im) = (Atom::num(1), Atom::new());
+                for ak in &a {
+                    re += ak * &pow_re;
+                    im += ak * &pow_im;
+                    (pow_re, pow_im) = (
+                        (&pow_re * &p - &pow_im * &q2).expand(),
+                        (&pow_re + &pow_im * &p).expand(),
+                    );
+                }
+
+                let re = re.expand();
+                let im = im.expand();
+
+                let norm = (&re * &re + &q2 * &im * &im).expand();
+                let ratio = (&re / (&q2 * &im)).to_rational_polynomial::<_, _, u16>(&Q, &Z, None);
+
+                return Some(
+                    p * norm.log() + Atom::num(2) * &q * (q * ratio.to_expression()).atan(),
+                );
+            }
+
+            // move the sign into the square root so that the roots are real for positive r
+            let sqrt_disc = if d.is_negative() {
+                rational_sqrt(&-d) * s * (-r).pow(Atom::num((1, 2)))
+            } else {
+                rational_sqrt(&d) * s * r.pow(Atom::num((1, 2)))
+            };
+            let mut res = Atom::new();
+            for root in [
+                (-&c[1] + &sqrt_disc) / (Atom::num(2) * &c[2]),
+                (-&c[1] - &sqrt_disc) / (Atom::num(2) * &c[2]),
+            ] {
+                res += log(eval_a(&root))? * root;
+            }
+            Some(res)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ahash::HashMap;
+
+    use crate::{
+        atom::{Atom, AtomCore},
+        domains::{
+            float::{Complex, Real},
+            integer::Z,
+            rational::Q,
+            rational_polynomial::RationalPolynomial,
+        },
+        parse, symbol,
+    };
+
+    fn integral(input: &str) -> Atom {
+        parse!(input).integrate(symbol!("x"))
+    }
+
+    /// Check that the derivative of the integral is the integrand.
+    fn check(input: &str) {
+        let x = symbol!("x");
+        let f = parse!(input);
+        let r = f.integrate(x);
+        assert!(
+            !r.contains_symbol(Atom::INTEGRATE),
+            "failed to integrate {}: {}",
+            f,
+            r
+        );
+
+        let diff = (r.derivative(x) - &f).expand();
+        let diff: RationalPolynomial<_, u16> = diff.to_rational_polynomial(&Q, &Z, None);
+        assert!(diff.is_zero(), "wrong integral of {}: {}", f, r);
+    }
+
+    #[test]
+    fn polynomial() {
+        check("3*x^2+2*y*x+1");
+        assert_eq!(integral("3*x^2+2*y*x+1"), parse!("x+x^2*y+x^3"));
+    }
+
+    #[test]
+    fn rational() {
+        check("1/(x^2+1)");
+        assert_eq!(integral("1/(x^2+1)"), parse!("atan(x)"));
+        check("1/(x^3+x)");
+        check("(x^2+1)/(x+1)^3");
+        check("1/(x^2+x+1)");
+        assert_eq!(
+            integral("1/(x^2-2)"),
+            parse!("2^(1/2)/4*log(x-2^(1/2))-2^(1/2)/4*log(x+2^(1/2))")
+        );
+    }
+
+    #[test]
+    fn elementary() {
+        check("exp(2*x+1)");
+        assert_eq!(integral("exp(2*x+1)"), parse!("1/2*exp(2*x+1)"));
+        check("sin(3*x)");
+        check("cos(x)");
+        check("(2*x+1)^(1/2)");
+        check("2^x");
+        check("log(x)");
+    }
+
+    #[test]
+    fn substitution() {
+        check("x*exp(x^2)");
+        assert_eq!(integral("x*exp(x^2)"), parse!("1/2*exp(x^2)"));
+        check("sin(x)*cos(x)");
+        check("cos(x)*exp(sin(x))");
+        check("2*x/(x^2+1)");
+        check("log(x)/x");
+        check("x*cos(x^2)");
+        assert_eq!(integral("x*cos(x^2)"), parse!("1/2*sin(x^2)"));
+        check("y*x*cos(x^2)");
+        check("x^2*exp(x^3)");
+    }
+
+    #[test]
+    fn parts() {
+        check("x*exp(x)");
+        assert_eq!(integral("x*exp(x)"), parse!("-exp(x)+x*exp(x)"));
+        check("x^2*sin(x)");
+        check("x*log(x)");
+        check("log(x)^2");
+        check("atan(x)");
+    }
+
+    /// Check that the derivative of the integral is the integrand at sample points,
+    /// for integrals that contain square roots of the parameters.
+    fn check_numeric(input: &str) -> Atom {
+        let x = symbol!("x");
+        let f = parse!(input);
+        let r = f.integrate(x);
+        assert!(
+            !r.contains_symbol(Atom::INTEGRATE),
+            "failed to integrate {}: {}",
+            f,
+            r
+        );
+
+        let diff = r.derivative(x) - &f;
+        for (xv, av, bv) in [(0.3, 1.7, 0.6), (-1.2, -0.4, 2.5), (2.1, 0.9, -1.3)] {
+            let const_map = HashMap::from_iter([
+                (parse!("x"), Complex::new(xv, 0.1)),
+                (parse!("a"), Complex::new(av, -0.2)),
+                (parse!("b"), Complex::new(bv, 0.3)),
+            ]);
+            let d = diff
+                .evaluate(
+                    |c| Complex::new(c.to_f64(), 0.),
+                    &const_map,
+                    &HashMap::default(),
+                )
+                .unwrap();
+            assert!(d.norm().re < 1e-10, "wrong integral of {}: {}", f, r);
+        }
+        r
+    }
+
+    #[test]
+    fn parametric() {
+        check("1/(a*x+1)");
+        check("1/((x+a)*(x+b))");
+        assert_eq!(integral("1/(a^2*x^2+1)"), parse!("a^-1*atan(a*x)"));
+        check("1/(a^2*x^2+1)");
+
+        for f in ["1/(a*x^2+1)", "1/(a*x^2+b)", "1/(a*x^2-1)", "1/(x^2-a)"] {
+            let r = check_numeric(f);
+            assert!(!r.to_string().contains("log(0)"), "{}: {}", f, r);
+        }
+    }
+
+    #[test]
+    fn unevaluated() {
+        assert_eq!(
+            integral("exp(x^2)+x"),
+            parse!("1/2*x^2+integrate(exp(x^2),x)")
+        );
+
+        // the roots of the Rothstein-Trager resultant are of degree higher than two
+        assert_eq!(integral("1/(x^3+2)"), parse!("integrate(1/(x^3+2),x)"));
+        assert_eq!(integral("1/(x^4+1)"), parse!("integrate(1/(x^4+1),x)"));
+    }
+}
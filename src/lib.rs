@@ -24,7 +24,7 @@
 //! which have optimized methods.
 //!
 //! To use Symbolica's exact numbers, see [Integer](domains::integer::Integer), [Rational](domains::rational::Rational), and [FiniteField](domains::finite_field::FiniteField).
-//! For evaluations with floating point numbers, see [Float](domains::float::Float), [F64](domains::float::F64) and [ErrorPropagatingFloat](domains::float::ErrorPropagatingFloat).
+//! For evaluations with floating point numbers, see [Float](domains::float::Float), [F64](domains::float::F64), [DoubleDouble](domains::float::DoubleDouble), [ErrorPropagatingFloat](domains::float::ErrorPropagatingFloat) and [Interval](domains::float::Interval).
 //!
 //! For linear algebra, use [Matrix](tensors::matrix::Matrix) or [Vector](tensors::matrix::Vector).
 //!
@@ -60,6 +60,8 @@ pub mod evaluate;
 mod expand;
 pub mod graph;
 pub mod id;
+mod integrate;
+pub mod limit;
 mod normalize;
 pub mod numerical_integration;
 pub mod parser;
@@ -0,0 +1,823 @@
+//! Compute limits of expressions.
+//!
+//! Limits at infinity are computed using the algorithm of Gruntz, which determines
+//! the most rapidly varying subexpressions of an expression, rewrites the expression
+//! in terms of a single such subexpression `ω` and uses a series expansion in `ω`
+//! around 0 to find the leading term. Limits at finite points are first attempted
+//! using a series expansion around the point and otherwise mapped to a limit at infinity.
+//!
+//! # Examples
+//!
+//! ```
+//! use symbolica::{atom::AtomCore, limit::{Limit, LimitDirection, LimitPoint}, parse, symbol};
+//! let x = symbol!("x");
+//! let l = parse!("x*exp(-x)").limit(x, LimitPoint::PositiveInfinity, LimitDirection::Both);
+//! assert_eq!(l, Ok(Limit::Finite(parse!("0"))));
+//! ```
+
+use std::fmt;
+
+use ahash::HashMap;
+
+use crate::{
+    atom::{Atom, AtomCore, AtomView, FunctionBuilder, Symbol},
+    domains::rational::Rational,
+    symbol,
+};
+
+/// The maximal recursion depth of the limit computation.
+const MAX_DEPTH: usize = 64;
+
+/// The point that the variable of a limit approaches.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LimitPoint {
+    Finite(Atom),
+    PositiveInfinity,
+    NegativeInfinity,
+}
+
+impl From<Atom> for LimitPoint {
+    fn from(value: Atom) -> Self {
+        LimitPoint::Finite(value)
+    }
+}
+
+/// The direction from which a finite point is approached. The direction
+/// is ignored for limits at infinity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimitDirection {
+    /// Approach the point from both sides. The limit is indeterminate if the
+    /// limits from above and below differ.
+    Both,
+    /// Approach the point from values larger than the point.
+    FromAbove,
+    /// Approach the point from values smaller than the point.
+    FromBelow,
+}
+
+/// The result of a limit computation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// The limit is a finite value.
+    Finite(Atom),
+    PositiveInfinity,
+    NegativeInfinity,
+    /// The limit does not exist, for example because the limits from above and below
+    /// differ or because the expression oscillates.
+    DoesNotExist,
+    /// The limit could not be determined, for example because the expression contains
+    /// an oscillating function whose behavior could not be analyzed.
+    Unknown,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::Finite(a) => a.fmt(f),
+            Limit::PositiveInfinity => f.write_str("∞"),
+            Limit::NegativeInfinity => f.write_str("-∞"),
+            Limit::DoesNotExist => f.write_str("does not exist"),
+            Limit::Unknown => f.write_str("unknown"),
+        }
+    }
+}
+
+impl Limit {
+    fn from_sign(sign: i8) -> Limit {
+        match sign {
+            1 => Limit::PositiveInfinity,
+            -1 => Limit::NegativeInfinity,
+            _ => Limit::Unknown,
+        }
+    }
+}
+
+impl AtomView<'_> {
+    /// Compute the limit of the expression when `x` approaches `point` from `direction`.
+    /// Returns an error if the point depends on `x`.
+    pub(crate) fn limit(
+        &self,
+        x: Symbol,
+        point: &LimitPoint,
+        direction: LimitDirection,
+    ) -> Result<Limit, String> {
+        if let LimitPoint::Finite(a) = point {
+            if a.contains_symbol(x) {
+                return Err(format!("The limit point {} depends on {}", a, x));
+            }
+        }
+
+        // the algorithm may fail to compare or expand subexpressions, in which case
+        // the limit cannot be determined
+        Ok(self
+            .limit_impl(x, point, direction)
+            .unwrap_or(Limit::Unknown))
+    }
+
+    fn limit_impl(
+        &self,
+        x: Symbol,
+        point: &LimitPoint,
+        direction: LimitDirection,
+    ) -> Result<Limit, String> {
+        match point {
+            LimitPoint::PositiveInfinity => self.limit_inf(x, 0),
+            LimitPoint::NegativeInfinity => substitute(*self, x, &-Atom::var(x))
+                .as_view()
+                .limit_inf(x, 0),
+            LimitPoint::Finite(a) => match direction {
+                LimitDirection::FromAbove => self.limit_finite(x, a.as_view(), true),
+                LimitDirection::FromBelow => self.limit_finite(x, a.as_view(), false),
+                LimitDirection::Both => {
+                    let above = self.limit_finite(x, a.as_view(), true)?;
+                    let below = self.limit_finite(x, a.as_view(), false)?;
+                    if above == Limit::Unknown || below == Limit::Unknown {
+                        Ok(Limit::Unknown)
+                    } else if above == below {
+                        Ok(above)
+                    } else {
+                        Ok(Limit::DoesNotExist)
+                    }
+                }
+            },
+        }
+    }
+
+    /// Compute the limit at the finite point `a` from above or below. A series
+    /// expansion around `a` is attempted first, and otherwise `x` is replaced by
+    /// `a + 1/t` or `a - 1/t` and the limit of `t` to infinity is computed.
+    fn limit_finite(&self, x: Symbol, a: AtomView, above: bool) -> Result<Limit, String> {
+        if !self.contains_symbol(x) {
+            return Ok(Limit::Finite(self.to_owned()));
+        }
+
+        if let Ok(s) = self.series(x, a, Rational::one(), false) {
+            match s.terms().find(|(_, c)| !c.is_zero()) {
+                None => return Ok(Limit::Finite(Atom::new())),
+                Some((k, c)) if !c.contains_symbol(x) => {
+                    if !k.is_negative() && !k.is_zero() {
+                        return Ok(Limit::Finite(Atom::new()));
+                    } else if k.is_zero() {
+                        return Ok(Limit::Finite(c.clone()));
+                    }
+
+                    let Some(sign) = constant_sign(c.as_view()) else {
+                        return Ok(Limit::Unknown);
+                    };
+                    return Ok(if above {
+                        Limit::from_sign(sign)
+                    } else if !k.is_integer() {
+                        Limit::DoesNotExist
+                    } else if k.numerator().to_i64().is_some_and(|k| k % 2 == 0) {
+                        Limit::from_sign(sign)
+                    } else {
+                        Limit::from_sign(-sign)
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let t = symbol!("limit_var");
+        let shift = Atom::var(t).npow(-1);
+        let replacement = if above {
+            a.to_owned() + shift
+        } else {
+            a.to_owned() - shift
+        };
+
+        substitute(*self, x, &replacement).as_view().limit_inf(t, 0)
+    }
+
+    /// Compute the limit of `x` to positive infinity.
+    fn limit_inf(&self, x: Symbol, depth: usize) -> Result<Limit, String> {
+        if !self.contains_symbol(x) {
+            return Ok(Limit::Finite(self.to_owned()));
+        }
+
+        if let AtomView::Var(_) = self {
+            return Ok(Limit::PositiveInfinity);
+        }
+
+        // a sine or cosine of an argument that grows without bound oscillates
+        if let AtomView::Fun(f) = self {
+            if (f.get_symbol() == Atom::SIN || f.get_symbol() == Atom::COS) && f.get_nargs() == 1 {
+                if let Limit::PositiveInfinity | Limit::NegativeInfinity =
+                    f.iter().next().unwrap().limit_inf(x, depth + 1)?
+                {
+                    return Ok(Limit::DoesNotExist);
+                }
+            }
+        }
+
+        let Some((c, e)) = self.leading_term(x, depth)? else {
+            return Ok(Limit::Unknown);
+        };
+
+        if !e.is_negative() && !e.is_zero() {
+            Ok(Limit::Finite(Atom::new()))
+        } else if e.is_zero() {
+            c.as_view().limit_inf(x, depth + 1)
+        } else {
+            Ok(match c.as_view().sign_inf(x, depth + 1)? {
+                Some(s) => Limit::from_sign(s),
+                None => Limit::Unknown,
+            })
+        }
+    }
+
+    /// Determine the sign of the expression when `x` approaches positive infinity.
+    /// Returns `None` if the expression oscillates or if the sign of a constant
+    /// cannot be determined.
+    fn sign_inf(&self, x: Symbol, depth: usize) -> Result<Option<i8>, String> {
+        if !self.contains_symbol(x) {
+            return Ok(constant_sign(*self));
+        }
+
+        match self {
+            AtomView::Var(_) => return Ok(Some(1)),
+            AtomView::Mul(m) => {
+                let mut sign = 1;
+                for f in m {
+                    match f.sign_inf(x, depth + 1)? {
+                        Some(s) => sign *= s,
+                        None => return Ok(None),
+                    }
+                }
+                return Ok(Some(sign));
+            }
+            AtomView::Fun(f) if f.get_symbol() == Atom::EXP => return Ok(Some(1)),
+            AtomView::Fun(f) if f.get_symbol() == Atom::LOG && f.get_nargs() == 1 => {
+                let arg = f.iter().next().unwrap().to_owned() - Atom::num(1);
+                return arg.as_view().sign_inf(x, depth + 1);
+            }
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                match b.sign_inf(x, depth + 1)? {
+                    Some(1) => return Ok(Some(1)),
+                    Some(s) => {
+                        if let Ok(e) = i64::try_from(e) {
+                            return Ok(Some(if e % 2 == 0 { s * s } else { s }));
+                        }
+                    }
+                    None => return Ok(None),
+                }
+            }
+            _ => {}
+        }
+
+        match self.leading_term(x, depth)? {
+            Some((c, _)) => c.as_view().sign_inf(x, depth + 1),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the leading term `c*ω^e` of the expression when `x` approaches positive infinity,
+    /// where `ω` is an element of the most rapidly varying subexpressions that goes to zero.
+    /// The coefficient `c` may be written in terms of `exp(x)` instead of `x`, which does
+    /// not change its limit. Returns `None` if the expression oscillates.
+    fn leading_term(&self, x: Symbol, depth: usize) -> Result<Option<(Atom, Rational)>, String> {
+        if depth > MAX_DEPTH {
+            return Err("Maximum recursion depth reached in the limit computation".to_owned());
+        }
+
+        let mut e = rewrite_atan(to_exp_form(*self, x).as_view(), x, depth)?;
+        if !e.contains_symbol(x) {
+            return Ok(Some((e, Rational::zero())));
+        }
+
+        let Some(mut omega) = e.as_view().mrv(x, depth)? else {
+            return Ok(None);
+        };
+
+        // move the expression up a level, such that x is no longer in the most
+        // rapidly varying set
+        let xv = Atom::var(x);
+        if omega.contains(&xv) {
+            let exp_x = xv.exp();
+            e = to_exp_form(substitute(e.as_view(), x, &exp_x).as_view(), x);
+            omega = omega
+                .iter()
+                .map(|o| to_exp_form(substitute(o.as_view(), x, &exp_x).as_view(), x))
+                .collect();
+        }
+
+        // pick an element whose argument does not contain any other element
+        let w_atom = omega
+            .iter()
+            .filter(|o| {
+                !omega
+                    .iter()
+                    .any(|o2| o2 != *o && o.as_view().contains(o2.as_view()))
+            })
+            .min_by_key(|o| o.as_view().get_byte_size())
+            .ok_or_else(|| "No most rapidly varying subexpression found".to_owned())?
+            .clone();
+
+        let mut g = exp_argument(w_atom.as_view(), x).unwrap();
+        match g.as_view().limit_inf(x, depth + 1)? {
+            Limit::PositiveInfinity => g = -g,
+            Limit::NegativeInfinity => {}
+            _ => return Err(format!("Expected {} to grow without bound", w_atom)),
+        }
+
+        let w = symbol!(format!("limit_omega_{}", depth));
+        let wv = Atom::var(w);
+
+        // rewrite every exp(h) as exp(h - c*g)*ω^c, starting with the largest elements
+        omega.sort_by_key(|o| std::cmp::Reverse(o.as_view().get_byte_size()));
+        for o in &omega {
+            let h = exp_argument(o.as_view(), x).unwrap();
+            let c = match (&h / &g).as_view().limit_inf(x, depth + 1)? {
+                Limit::Finite(c) => c,
+                _ => return Err(format!("{} and {} are not comparable", o, w_atom)),
+            };
+
+            let rewritten = (h - &c * &g).exp() * wv.pow(&c);
+            e = e.replace(o.to_pattern()).with(rewritten.to_pattern());
+        }
+
+        if omega.iter().any(|o| e.as_view().contains(o.as_view())) {
+            return Err(format!(
+                "Could not rewrite {} in terms of the most rapidly varying subexpression",
+                self
+            ));
+        }
+
+        let s = e
+            .series(w, Atom::new().as_view(), Rational::one(), false)
+            .map_err(|e| e.to_owned())?;
+
+        let Some((exp, c)) = s.terms().find(|(_, c)| !c.is_zero()) else {
+            return Ok(Some((Atom::new(), Rational::zero())));
+        };
+
+        // logarithms of ω may be present in the coefficient
+        let c = to_exp_form(substitute(c.as_view(), w, &g.exp()).as_view(), x);
+        Ok(Some((c, exp)))
+    }
+
+    /// Compute the set of most rapidly varying subexpressions when `x` approaches positive
+    /// infinity. Returns `None` if the expression contains an oscillating function.
+    fn mrv(&self, x: Symbol, depth: usize) -> Result<Option<Vec<Atom>>, String> {
+        if !self.contains_symbol(x) {
+            return Ok(Some(vec![]));
+        }
+
+        match self {
+            AtomView::Num(_) => Ok(Some(vec![])),
+            AtomView::Var(_) => Ok(Some(vec![self.to_owned()])),
+            AtomView::Pow(p) => {
+                let (b, e) = p.get_base_exp();
+                if e.contains_symbol(x) {
+                    self.mrv_exp(&(e.to_owned() * log_simplify(b, x)), x, depth)
+                } else {
+                    b.mrv(x, depth)
+                }
+            }
+            AtomView::Mul(m) => {
+                let mut res = vec![];
+                for a in m {
+                    let Some(s) = a.mrv(x, depth)? else {
+                        return Ok(None);
+                    };
+                    let Some(r) = mrv_max(res, s, x, depth)? else {
+                        return Ok(None);
+                    };
+                    res = r;
+                }
+                Ok(Some(res))
+            }
+            AtomView::Add(a) => {
+                let mut res = vec![];
+                for a in a {
+                    let Some(s) = a.mrv(x, depth)? else {
+                        return Ok(None);
+                    };
+                    let Some(r) = mrv_max(res, s, x, depth)? else {
+                        return Ok(None);
+                    };
+                    res = r;
+                }
+                Ok(Some(res))
+            }
+            AtomView::Fun(f) => {
+                if f.get_symbol() == Atom::EXP && f.get_nargs() == 1 {
+                    self.mrv_exp(&f.iter().next().unwrap().to_owned(), x, depth)
+                } else {
+                    let mut res = vec![];
+                    for a in f {
+                        if f.get_symbol() != Atom::LOG
+                            && !matches!(a.limit_inf(x, depth + 1)?, Limit::Finite(_))
+                        {
+                            return Ok(None);
+                        }
+
+                        let Some(s) = a.mrv(x, depth)? else {
+                            return Ok(None);
+                        };
+                        let Some(r) = mrv_max(res, s, x, depth)? else {
+                            return Ok(None);
+                        };
+                        res = r;
+                    }
+                    Ok(Some(res))
+                }
+            }
+        }
+    }
+
+    /// Compute the set of most rapidly varying subexpressions of `exp(arg)`, where
+    /// the expression itself is `exp(arg)` or a power with an exponent that depends on `x`.
+    fn mrv_exp(&self, arg: &Atom, x: Symbol, depth: usize) -> Result<Option<Vec<Atom>>, String> {
+        let Some(s) = arg.as_view().mrv(x, depth)? else {
+            return Ok(None);
+        };
+
+        match arg.as_view().limit_inf(x, depth + 1)? {
+            Limit::PositiveInfinity | Limit::NegativeInfinity => {
+                mrv_max(vec![self.to_owned()], s, x, depth)
+            }
+            Limit::Finite(_) => Ok(Some(s)),
+            Limit::DoesNotExist | Limit::Unknown => Ok(None),
+        }
+    }
+}
+
+/// Compute the most rapidly varying set of the union of `a` and `b`.
+fn mrv_max(
+    a: Vec<Atom>,
+    b: Vec<Atom>,
+    x: Symbol,
+    depth: usize,
+) -> Result<Option<Vec<Atom>>, String> {
+    if a.is_empty() {
+        return Ok(Some(b));
+    }
+    if b.is_empty() || a.iter().any(|e| b.contains(e)) {
+        return Ok(Some(union(a, b)));
+    }
+
+    // compare the growth of log(a) and log(b)
+    let log = |e: &Atom| exp_argument(e.as_view(), x).unwrap_or_else(|| e.log());
+
+    let ratio = log(&a[0]) / log(&b[0]);
+    match ratio.as_view().limit_inf(x, depth + 1)? {
+        Limit::Finite(c) if c.is_zero() => Ok(Some(b)),
+        Limit::Finite(_) => Ok(Some(union(a, b))),
+        Limit::PositiveInfinity | Limit::NegativeInfinity => Ok(Some(a)),
+        Limit::DoesNotExist | Limit::Unknown => Ok(None),
+    }
+}
+
+fn union(mut a: Vec<Atom>, b: Vec<Atom>) -> Vec<Atom> {
+    for e in b {
+        if !a.contains(&e) {
+            a.push(e);
+        }
+    }
+    a
+}
+
+/// Get the argument `h` of `exp(h)`, or `e*log(b)` for a power `b^e` where
+/// the exponent depends on `x`.
+fn exp_argument(e: AtomView, x: Symbol) -> Option<Atom> {
+    match e {
+        AtomView::Fun(f) if f.get_symbol() == Atom::EXP && f.get_nargs() == 1 => {
+            f.iter().next().map(|h| h.to_owned())
+        }
+        AtomView::Pow(p) => {
+            let (b, e) = p.get_base_exp();
+            if e.contains_symbol(x) {
+                Some(e.to_owned() * log_simplify(b, x))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Replace the variable `x` by `r`.
+fn substitute(e: AtomView, x: Symbol, r: &Atom) -> Atom {
+    e.replace(Atom::var(x).to_pattern()).with(r.to_pattern())
+}
+
+/// Determine the sign of an expression that does not depend on the limit variable,
+/// using the assumptions on its symbols or a numerical evaluation.
+/// Returns `None` if the sign cannot be determined.
+fn constant_sign(e: AtomView) -> Option<i8> {
+    let p = e.value_properties();
+    if p.is_zero() {
+        return Some(0);
+    } else if p.is_positive() {
+        return Some(1);
+    } else if p.is_negative() {
+        return Some(-1);
+    }
+
+    if let Ok(v) = e.evaluate(
+        |r| r.to_f64(),
+        &HashMap::<Atom, f64>::default(),
+        &HashMap::default(),
+    ) {
+        if v > 1e-12 {
+            return Some(1);
+        } else if v < -1e-12 {
+            return Some(-1);
+        }
+    }
+
+    None
+}
+
+/// Rewrite powers with an exponent that depends on `x` as exponentials,
+/// hyperbolic functions in terms of exponentials and simplify logarithms
+/// of exponentials, assuming that all arguments are real.
+fn to_exp_form(e: AtomView, x: Symbol) -> Atom {
+    if !e.contains_symbol(x) {
+        return e.to_owned();
+    }
+
+    match e {
+        AtomView::Num(_) | AtomView::Var(_) => e.to_owned(),
+        AtomView::Fun(f) => {
+            let args: Vec<_> = f.iter().map(|a| to_exp_form(a, x)).collect();
+            if args.len() != 1 {
+                let mut fb = FunctionBuilder::new(f.get_symbol());
+                for a in &args {
+                    fb = fb.add_arg(a);
+                }
+                return fb.finish();
+            }
+
+            let u = &args[0];
+            match f.get_symbol() {
+                Atom::LOG => log_simplify(u.as_view(), x),
+                Atom::EXP => {
+                    if let AtomView::Fun(g) = u.as_view() {
+                        if g.get_symbol() == Atom::LOG && g.get_nargs() == 1 {
+                            return g.iter().next().unwrap().to_owned();
+                        }
+                    }
+                    u.exp()
+                }
+                Atom::SQRT => u.pow(Atom::num((1, 2))),
+                Atom::SINH => (u.exp() - (-u).exp()) / Atom::num(2),
+                Atom::COSH => (u.exp() + (-u).exp()) / Atom::num(2),
+                Atom::TANH => {
+                    let e2 = (Atom::num(2) * u).exp();
+                    (&e2 - Atom::num(1)) / (e2 + Atom::num(1))
+                }
+                s => FunctionBuilder::new(s).add_arg(u).finish(),
+            }
+        }
+        AtomView::Pow(p) => {
+            let (b, ex) = p.get_base_exp();
+            let b = to_exp_form(b, x);
+            let ex = to_exp_form(ex, x);
+
+            b.pow(ex)
+        }
+        AtomView::Mul(m) => {
+            let mut r = Atom::num(1);
+            for a in m {
+                r *= to_exp_form(a, x);
+            }
+            r
+        }
+        AtomView::Add(a) => {
+            let mut r = Atom::new();
+            for a in a {
+                r += to_exp_form(a, x);
+            }
+            r
+        }
+    }
+}
+
+/// Rewrite `atan(u)` as `±π/2 - atan(1/u)` if `u` approaches `±∞` when `x`
+/// approaches positive infinity, so that the argument of every arctangent has a finite limit.
+fn rewrite_atan(e: AtomView, x: Symbol, depth: usize) -> Result<Atom, String> {
+    if !e.contains_symbol(x) {
+        return Ok(e.to_owned());
+    }
+
+    match e {
+        AtomView::Num(_) | AtomView::Var(_) => Ok(e.to_owned()),
+        AtomView::Fun(f) => {
+            let mut fb = FunctionBuilder::new(f.get_symbol());
+            for a in f {
+                fb = fb.add_arg(rewrite_atan(a, x, depth)?);
+            }
+            let r = fb.finish();
+
+            let AtomView::Fun(g) = r.as_view() else {
+                return Ok(r);
+            };
+            if g.get_symbol() != Atom::ATAN || g.get_nargs() != 1 {
+                return Ok(r);
+            }
+
+            let u = g.iter().next().unwrap().to_owned();
+            let half_pi = Atom::var(Atom::PI) / Atom::num(2);
+            Ok(match u.as_view().limit_inf(x, depth + 1)? {
+                Limit::PositiveInfinity => half_pi - u.npow(-1).atan(),
+                Limit::NegativeInfinity => -half_pi - u.npow(-1).atan(),
+                _ => r,
+            })
+        }
+        AtomView::Pow(p) => {
+            let (b, ex) = p.get_base_exp();
+            Ok(rewrite_atan(b, x, depth)?.pow(rewrite_atan(ex, x, depth)?))
+        }
+        AtomView::Mul(m) => {
+            let mut r = Atom::num(1);
+            for a in m {
+                r *= rewrite_atan(a, x, depth)?;
+            }
+            Ok(r)
+        }
+        AtomView::Add(a) => {
+            let mut r = Atom::new();
+            for a in a {
+                r += rewrite_atan(a, x, depth)?;
+            }
+            Ok(r)
+        }
+    }
+}
+
+/// Get `n*h` for `u = exp(h)^n`.
+fn log_of_exp(u: AtomView, x: Symbol) -> Option<Atom> {
+    if let Some(h) = exp_argument(u, x) {
+        return Some(h);
+    }
+
+    if let AtomView::Pow(p) = u {
+        let (b, e) = p.get_base_exp();
+        if let Some(h) = exp_argument(b, x) {
+            return Some(h * e);
+        }
+    }
+
+    None
+}
+
+/// Create `log(u)`, removing exponential factors from `u`.
+fn log_simplify(u: AtomView, x: Symbol) -> Atom {
+    if let Some(h) = log_of_exp(u, x) {
+        return h;
+    }
+
+    if let AtomView::Mul(m) = u {
+        let mut rest = Atom::num(1);
+        let mut res = Atom::new();
+        for f in m {
+            match log_of_exp(f, x) {
+                Some(h) => res += h,
+                None => rest *= f,
+            }
+        }
+
+        if !res.is_zero() {
+            return res + rest.log();
+        }
+    }
+
+    u.to_owned().log()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::{Assumption, Atom, AtomCore, Symbol},
+        limit::{Limit, LimitDirection, LimitPoint},
+        parse, symbol, wrap_symbol,
+    };
+
+    fn limit(input: &str, point: LimitPoint, direction: LimitDirection) -> Limit {
+        parse!(input).limit(symbol!("x"), point, direction).unwrap()
+    }
+
+    #[test]
+    fn finite_point() {
+        assert_eq!(
+            limit("(x^2-1)/(x-1)", Atom::num(1).into(), LimitDirection::Both),
+            Limit::Finite(Atom::num(2))
+        );
+        assert_eq!(
+            limit("sin(x)/x", Atom::num(0).into(), LimitDirection::Both),
+            Limit::Finite(Atom::num(1))
+        );
+        assert_eq!(
+            limit("(1-cos(x))/x^2", Atom::num(0).into(), LimitDirection::Both),
+            Limit::Finite(Atom::num((1, 2)))
+        );
+        assert_eq!(
+            limit("x*log(x)", Atom::num(0).into(), LimitDirection::FromAbove),
+            Limit::Finite(Atom::num(0))
+        );
+        assert_eq!(
+            limit("exp(-1/x)", Atom::num(0).into(), LimitDirection::FromAbove),
+            Limit::Finite(Atom::num(0))
+        );
+        assert_eq!(
+            limit("exp(-1/x)", Atom::num(0).into(), LimitDirection::FromBelow),
+            Limit::PositiveInfinity
+        );
+    }
+
+    #[test]
+    fn poles() {
+        assert_eq!(
+            limit("1/x", Atom::num(0).into(), LimitDirection::FromAbove),
+            Limit::PositiveInfinity
+        );
+        assert_eq!(
+            limit("1/x", Atom::num(0).into(), LimitDirection::FromBelow),
+            Limit::NegativeInfinity
+        );
+        assert_eq!(
+            limit("1/x", Atom::num(0).into(), LimitDirection::Both),
+            Limit::DoesNotExist
+        );
+        assert_eq!(
+            limit("-1/(x-2)^2", Atom::num(2).into(), LimitDirection::Both),
+            Limit::NegativeInfinity
+        );
+    }
+
+    #[test]
+    fn infinity() {
+        let inf = || LimitPoint::PositiveInfinity;
+        let both = LimitDirection::Both;
+        assert_eq!(limit("exp(x)/x^10", inf(), both), Limit::PositiveInfinity);
+        assert_eq!(limit("x*exp(-x)", inf(), both), Limit::Finite(Atom::num(0)));
+        assert_eq!(limit("log(x)/x", inf(), both), Limit::Finite(Atom::num(0)));
+        assert_eq!(limit("x/log(x)", inf(), both), Limit::PositiveInfinity);
+        assert_eq!(
+            limit("(1+1/x)^x", inf(), both),
+            Limit::Finite(parse!("exp(1)"))
+        );
+        assert_eq!(
+            limit("exp(x+exp(-x))-exp(x)", inf(), both),
+            Limit::Finite(Atom::num(1))
+        );
+        assert_eq!(limit("tanh(x)", inf(), both), Limit::Finite(Atom::num(1)));
+        assert_eq!(
+            limit("(x^2+1)/(2*x^2-x)", LimitPoint::NegativeInfinity, both),
+            Limit::Finite(Atom::num((1, 2)))
+        );
+        assert_eq!(
+            limit("x^3-x^4", LimitPoint::NegativeInfinity, both),
+            Limit::NegativeInfinity
+        );
+        assert_eq!(limit("sin(x)", inf(), both), Limit::DoesNotExist);
+        assert_eq!(limit("cos(x^2)", inf(), both), Limit::DoesNotExist);
+        assert_eq!(limit("sin(x)*x", inf(), both), Limit::Unknown);
+        assert_eq!(
+            limit("atan(x)", inf(), both),
+            Limit::Finite(Atom::var(Atom::PI) / Atom::num(2))
+        );
+        assert_eq!(
+            limit("atan(x)", LimitPoint::NegativeInfinity, both),
+            Limit::Finite(-Atom::var(Atom::PI) / Atom::num(2))
+        );
+        assert_eq!(
+            limit("x*(𝜋/2-atan(x))", inf(), both),
+            Limit::Finite(Atom::num(1))
+        );
+    }
+
+    #[test]
+    fn assumptions() {
+        let y = Symbol::new(wrap_symbol!("y_limit"))
+            .with_assumptions(&[Assumption::Positive])
+            .build()
+            .unwrap();
+        let l = (Atom::num(1) - Atom::var(y) * Atom::var(symbol!("x")))
+            .limit(
+                symbol!("x"),
+                LimitPoint::PositiveInfinity,
+                LimitDirection::Both,
+            )
+            .unwrap();
+        assert_eq!(l, Limit::NegativeInfinity);
+
+        // the sign of z is unknown
+        let r = parse!("x*z").limit(
+            symbol!("x"),
+            LimitPoint::PositiveInfinity,
+            LimitDirection::Both,
+        );
+        assert_eq!(r, Ok(Limit::Unknown));
+        assert_eq!(
+            limit("z/x", Atom::num(0).into(), LimitDirection::FromAbove),
+            Limit::Unknown
+        );
+
+        let r = parse!("x").limit(symbol!("x"), parse!("x+1").into(), LimitDirection::Both);
+        assert!(r.is_err());
+    }
+}
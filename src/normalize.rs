@@ -10,6 +10,7 @@ use crate::{
         integer::Z,
         rational::Q,
     },
+    id::Pattern,
     poly::Variable,
     state::{RecycledAtom, State, Workspace},
 };
@@ -104,6 +105,37 @@ impl AtomView<'_> {
         }
     }
 
+    /// Check if the atom does not commute with all other factors in a product, which is
+    /// the case for noncommutative and Grassmann symbols and functions, powers of them
+    /// and sums that contain them.
+    pub(crate) fn is_noncommutative_factor(&self) -> bool {
+        match self {
+            AtomView::Num(_) => false,
+            AtomView::Var(v) => {
+                let s = v.get_symbol();
+                s.is_noncommutative() || s.is_grassmann()
+            }
+            AtomView::Fun(f) => {
+                let s = f.get_symbol();
+                s.is_noncommutative() || s.is_grassmann()
+            }
+            AtomView::Pow(p) => p.get_base().is_noncommutative_factor(),
+            AtomView::Mul(m) => m.iter().any(|x| x.is_noncommutative_factor()),
+            AtomView::Add(a) => a.iter().any(|x| x.is_noncommutative_factor()),
+        }
+    }
+
+    /// Check if the atom is a Grassmann symbol or function that is not also noncommutative.
+    pub(crate) fn is_grassmann_factor(&self) -> bool {
+        let s = match self {
+            AtomView::Var(v) => v.get_symbol(),
+            AtomView::Fun(f) => f.get_symbol(),
+            _ => return false,
+        };
+
+        s.is_grassmann() && !s.is_noncommutative()
+    }
+
     /// Compare factors in a term. `x` and `x^2` are placed next to each other by sorting a power based on the base only.
     pub(crate) fn cmp_factors(&self, other: &AtomView<'_>) -> Ordering {
         match (&self, other) {
@@ -746,7 +778,71 @@ impl AtomView<'_> {
                     return;
                 }
 
-                atom_test_buf.sort_by(|a, b| a.as_view().cmp_factors(&b.as_view()));
+                if atom_test_buf
+                    .iter()
+                    .any(|a| a.as_view().is_noncommutative_factor())
+                {
+                    // sort the commuting factors, sort the Grassmann factors while keeping track of the sign,
+                    // and keep the relative order of the noncommutative factors
+                    let mut commuting: SmallVec<[_; 20]> = SmallVec::new();
+                    let mut grassmann: SmallVec<[_; 20]> = SmallVec::new();
+                    let mut noncommuting: SmallVec<[_; 20]> = SmallVec::new();
+                    for a in atom_test_buf.drain(..) {
+                        if a.as_view().is_grassmann_factor() {
+                            grassmann.push(a);
+                        } else if a.as_view().is_noncommutative_factor() {
+                            noncommuting.push(a);
+                        } else {
+                            commuting.push(a);
+                        }
+                    }
+
+                    commuting.sort_by(|a, b| a.as_view().cmp_factors(&b.as_view()));
+
+                    let mut odd = false;
+                    for i in 1..grassmann.len() {
+                        let mut j = i;
+                        while j > 0
+                            && grassmann[j - 1]
+                                .as_view()
+                                .cmp_factors(&grassmann[j].as_view())
+                                == Ordering::Greater
+                        {
+                            grassmann.swap(j - 1, j);
+                            odd = !odd;
+                            j -= 1;
+                        }
+                    }
+
+                    if grassmann
+                        .windows(2)
+                        .any(|w| w[0].as_view() == w[1].as_view())
+                    {
+                        out.to_num(Coefficient::zero());
+                        return;
+                    }
+
+                    let n_num = commuting
+                        .iter()
+                        .rev()
+                        .take_while(|a| matches!(a.as_view(), AtomView::Num(_)))
+                        .count();
+                    let nums: SmallVec<[_; 20]> =
+                        commuting.drain(commuting.len() - n_num..).collect();
+
+                    atom_test_buf.extend(commuting);
+                    atom_test_buf.extend(grassmann);
+                    atom_test_buf.extend(noncommuting);
+                    atom_test_buf.extend(nums);
+
+                    if odd {
+                        let mut sign = workspace.new_atom();
+                        sign.to_num((-1).into());
+                        atom_test_buf.push(sign);
+                    }
+                } else {
+                    atom_test_buf.sort_by(|a, b| a.as_view().cmp_factors(&b.as_view()));
+                }
 
                 let mut second_pass = false;
                 if !atom_test_buf.is_empty() {
@@ -951,6 +1047,30 @@ impl AtomView<'_> {
                     }
                 }
 
+                // rewrite sqrt(x^y) as x^(y/2) when x is nonnegative
+                if id == Atom::SQRT && out_f.to_fun_view().get_nargs() == 1 {
+                    if let AtomView::Pow(p) = out_f.to_fun_view().iter().next().unwrap() {
+                        let (base, exp) = p.get_base_exp();
+                        if exp.value_properties().is_real()
+                            && base.value_properties().is_nonnegative()
+                        {
+                            let mut half = workspace.new_atom();
+                            half.to_num((1, 2).into());
+                            let mut mul_h = workspace.new_atom();
+                            let mul = mul_h.to_mul();
+                            mul.extend(exp);
+                            mul.extend(half.as_view());
+                            let mut exp_h = workspace.new_atom();
+                            mul_h.as_view().normalize(workspace, &mut exp_h);
+
+                            let mut pow_h = workspace.new_atom();
+                            pow_h.to_pow(base, exp_h.as_view());
+                            pow_h.as_view().normalize(workspace, out);
+                            return;
+                        }
+                    }
+                }
+
                 // try to turn the argument into a number
                 if id == Atom::COEFF && out_f.to_fun_view().get_nargs() == 1 {
                     let arg = out_f.to_fun_view().iter().next().unwrap();
@@ -976,19 +1096,31 @@ impl AtomView<'_> {
                     }
                 }
 
-                if id.is_linear() {
-                    // linearize sums
+                if id.is_linear() || id.is_linear_in_first_argument() {
+                    let n_linear = if id.is_linear() {
+                        out_f.to_fun_view().get_nargs()
+                    } else {
+                        1
+                    };
+
+                    // linearize sums, keeping sums with wildcards so that
+                    // they can be used to match sums of functions
                     if out_f
                         .to_fun_view()
                         .iter()
-                        .any(|a| matches!(a, AtomView::Add(_)))
+                        .take(n_linear)
+                        .any(|a| matches!(a, AtomView::Add(_)) && !Pattern::has_wildcard(a))
                     {
                         let mut arg_buf = Vec::with_capacity(out_f.to_fun_view().get_nargs());
 
-                        for a in out_f.to_fun_view().iter() {
+                        for (i, a) in out_f.to_fun_view().iter().enumerate() {
                             let mut vec = vec![];
                             if let AtomView::Add(aa) = a {
-                                for a in aa.iter() {
+                                if i < n_linear && !Pattern::has_wildcard(a) {
+                                    for a in aa.iter() {
+                                        vec.push(a);
+                                    }
+                                } else {
                                     vec.push(a);
                                 }
                             } else {
@@ -1014,7 +1146,7 @@ impl AtomView<'_> {
                     }
 
                     // linearize products
-                    if out_f.to_fun_view().iter().any(|a| {
+                    if out_f.to_fun_view().iter().take(n_linear).any(|a| {
                         if let AtomView::Mul(m) = a {
                             m.has_coefficient()
                         } else {
@@ -1026,9 +1158,9 @@ impl AtomView<'_> {
                         let mut new_fun = workspace.new_atom();
                         let nf = new_fun.to_fun(id);
                         let mut coeff: Coefficient = 1.into();
-                        for a in out_f.to_fun_view().iter() {
+                        for (i, a) in out_f.to_fun_view().iter().enumerate() {
                             if let AtomView::Mul(m) = a {
-                                if m.has_coefficient() {
+                                if i < n_linear && m.has_coefficient() {
                                     let mut stripped = workspace.new_atom();
                                     let mul = stripped.to_mul();
 
@@ -1055,7 +1187,7 @@ impl AtomView<'_> {
                         return;
                     }
 
-                    for a in out_f.to_fun_view() {
+                    for a in out_f.to_fun_view().iter().take(n_linear) {
                         if let AtomView::Num(n) = a {
                             if n.is_zero() {
                                 out.to_num(Coefficient::zero());
@@ -1235,8 +1367,14 @@ impl AtomView<'_> {
                             base_handle.to_num(new_base_num);
                             exp_handle.to_num(new_exp_num);
                         } else if let AtomView::Pow(p_base) = base_handle.as_view() {
-                            if exp_num.is_integer() {
-                                // rewrite (x^y)^3 as x^(3*y)
+                            if exp_num.is_integer()
+                                || exp_handle.as_view().value_properties().is_real() && {
+                                    let (b, e) = p_base.get_base_exp();
+                                    e.value_properties().is_real()
+                                        && b.value_properties().is_nonnegative()
+                                }
+                            {
+                                // rewrite (x^y)^3 as x^(3*y), or (x^y)^z as x^(y*z) for nonnegative x
                                 let (p_base_base, p_base_exp) = p_base.get_base_exp();
 
                                 let mut mul_h = workspace.new_atom();
@@ -1250,9 +1388,25 @@ impl AtomView<'_> {
                                 mul_h.as_view().normalize(workspace, out);
                                 break 'pow_simplify;
                             }
+                        } else if base_handle.as_view().is_grassmann_factor()
+                            && matches!(exp_num, CoefficientView::Natural(n, 1, 0, 1) if n > 1)
+                        {
+                            // the square of a Grassmann variable vanishes
+                            out.to_num(Coefficient::zero());
+                            break 'pow_simplify;
                         } else if let AtomView::Mul(m) = base_handle.as_view() {
-                            // rewrite (x*y)^2 as x^2*y^2
-                            if exp_num.is_integer() {
+                            if matches!(exp_num, CoefficientView::Natural(n, 1, 0, 1) if n > 1)
+                                && m.iter().any(|a| a.is_grassmann_factor())
+                            {
+                                // the power contains the square of a Grassmann factor, which vanishes
+                                out.to_num(Coefficient::zero());
+                                break 'pow_simplify;
+                            }
+
+                            // rewrite (x*y)^2 as x^2*y^2, unless the factors do not commute
+                            if exp_num.is_integer()
+                                && m.iter().filter(|a| a.is_noncommutative_factor()).count() < 2
+                            {
                                 let mut mul_h = workspace.new_atom();
                                 let mul = mul_h.to_mul();
                                 for arg in m {
@@ -1551,9 +1705,10 @@ impl AtomView<'_> {
 #[cfg(test)]
 mod test {
     use crate::{
-        atom::{Atom, AtomCore},
+        atom::{Assumption, Atom, AtomCore, Symbol},
         parse,
         printer::PrintOptions,
+        symbol, wrap_symbol,
     };
 
     #[test]
@@ -1574,6 +1729,20 @@ mod test {
         assert_eq!(parse!("(v1^(1/2))^2"), parse!("v1"));
     }
 
+    #[test]
+    fn pow_simplify_assumptions() {
+        let _x = Symbol::new(wrap_symbol!("ps_x"))
+            .with_assumptions(&[Assumption::Positive])
+            .build()
+            .unwrap();
+
+        assert_eq!(parse!("(ps_x^2)^(1/2)"), parse!("ps_x"));
+        assert_eq!(parse!("sqrt(ps_x^4)"), parse!("ps_x^2"));
+        assert_eq!(parse!("(ps_x^2)^(1/3)"), parse!("ps_x^(2/3)"));
+        assert_ne!(parse!("(v1^2)^(1/2)"), parse!("v1"));
+        assert_ne!(parse!("sqrt(v1^2)"), parse!("v1"));
+    }
+
     #[test]
     fn linear_symmetric() {
         let res = parse!("fsl1(v2+2*v3,v1+3*v2-v3)");
@@ -1581,6 +1750,43 @@ mod test {
         assert_eq!(res, refr);
     }
 
+    #[test]
+    fn linear_first_argument() {
+        let res = parse!("flf1(v1+2*v2,v3+v4)");
+        let refr = parse!("flf1(v1,v3+v4)+2*flf1(v2,v3+v4)");
+        assert_eq!(res, refr);
+
+        let res = parse!("fl1(x_+y_,v1+v2)");
+        let refr = parse!("fl1(x_+y_,v1)+fl1(x_+y_,v2)");
+        assert_eq!(res, refr);
+
+        assert_eq!(parse!("flf1(0,v1)"), parse!("0"));
+        assert_ne!(parse!("flf1(v1,0)"), parse!("0"));
+        assert_eq!(parse!("fl1(v1,0)"), parse!("0"));
+    }
+
+    #[test]
+    fn noncommutative() {
+        let _ = symbol!("nc_a", "nc_b"; Noncommutative);
+        let _ = symbol!("gr_1", "gr_2"; Grassmann);
+
+        let res = parse!("v2*nc_b*nc_a*2*v1*nc_b");
+        assert_eq!(res, parse!("2*v1*v2*nc_b*nc_a*nc_b"));
+        assert_ne!(res, parse!("2*v1*v2*nc_a*nc_b^2"));
+        assert_eq!(parse!("nc_a*nc_a*nc_b"), parse!("nc_a^2*nc_b"));
+        assert_ne!(parse!("(nc_a*nc_b)^2"), parse!("nc_a^2*nc_b^2"));
+
+        assert_eq!(parse!("gr_2*gr_1"), parse!("-gr_1*gr_2"));
+        assert_eq!(
+            parse!("gr_2*nc_b*gr_1*nc_a"),
+            parse!("-gr_1*gr_2*nc_b*nc_a")
+        );
+        assert_eq!(parse!("gr_1*v1*gr_1"), Atom::num(0));
+        assert_eq!(parse!("gr_1^2"), Atom::num(0));
+        assert_eq!(parse!("(gr_1*gr_2)^2"), Atom::num(0));
+        assert_ne!(parse!("(gr_1*gr_2)^-1"), Atom::num(0));
+    }
+
     #[test]
     fn coeff_flag() {
         let a = parse!("-v1*v2");
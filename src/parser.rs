@@ -6,6 +6,8 @@
 //! [Token::to_polynomial], [Token::to_rational_polynomial] or [Token::to_factorized_rational_polynomial] for accelerated parsing of polynomials written
 //! in Symbolica's fast format.
 
+pub mod transformer;
+
 use std::{fmt::Write, string::String, sync::Arc};
 
 use bytes::Buf;
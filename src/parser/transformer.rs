@@ -0,0 +1,1647 @@
+//! Parsing and printing of transformer programs.
+//!
+//! A transformer program is a textual representation of a chain of [Transformer]s,
+//! so that a reduction procedure can be stored in a file and executed later
+//! with [Transformer::execute_chain]. Use [Transformer::parse_chain] to read a program
+//! and [Transformer::format_chain] to write one.
+//!
+//! A program is a list of statements that are separated by `;`. Statements that end with a block
+//! `{ ... }` do not need a separator. Comments start with `#` and run until the end of the line.
+//! For example:
+//!
+//! ```text
+//! # expand and simplify
+//! expand;
+//! repeat {
+//!     replace_all(f(x_), f(x_ - 1), is_type(x_, Num));
+//! }
+//! if @{} == 0 {
+//!     print;
+//! } else {
+//!     collect(x) coefficient { expand };
+//! }
+//! ```
+//!
+//! The following statements are supported:
+//!
+//! | Statement | Transformer |
+//! | --- | --- |
+//! | `expand`, `expand(x, via_poly = true)` | [Transformer::Expand] |
+//! | `expand_num` | [Transformer::ExpandNum] |
+//! | `derivative(x)` | [Transformer::Derivative] |
+//! | `series(x, 0, 3, depth_is_absolute = true)` | [Transformer::Series] |
+//! | `collect(x, y) key { .. } coefficient { .. }` | [Transformer::Collect] |
+//! | `collect_symbol(f) key { .. } coefficient { .. }` | [Transformer::CollectSymbol] |
+//! | `collect_factors` | [Transformer::CollectFactors] |
+//! | `collect_num` | [Transformer::CollectNum] |
+//! | `conjugate` | [Transformer::Conjugate] |
+//! | `replace_all(lhs, rhs, condition, ..)` | [Transformer::ReplaceAll] |
+//! | `replace_all_multiple { rule(lhs, rhs, condition, ..); .. }` | [Transformer::ReplaceAllRuleSet] |
+//! | `product`, `sum` | [Transformer::Product], [Transformer::Sum] |
+//! | `arg_count`, `arg_count(only_arg = true)` | [Transformer::ArgCount] |
+//! | `linearize`, `linearize(x, y)` | [Transformer::Linearize] |
+//! | `for_each { .. }` | [Transformer::ForEach] |
+//! | `map_terms { .. }` | [Transformer::MapTerms] |
+//! | `split` | [Transformer::Split] |
+//! | `partition((f, 2), (g, 1), fill_last = true, repeat = true)` | [Transformer::Partition] |
+//! | `sort`, `cycle_symmetrize`, `deduplicate` | [Transformer::Sort], [Transformer::CycleSymmetrize], [Transformer::Deduplicate] |
+//! | `permutations(f)` | [Transformer::Permutations] |
+//! | `repeat { .. }` | [Transformer::Repeat] |
+//! | `print`, `print(file)` | [Transformer::Print] |
+//! | `stats("tag") { .. }` | [Transformer::Stats] |
+//! | `from_number` | [Transformer::FromNumber] |
+//! | `break` | [Transformer::BreakChain] |
+//! | `if condition { .. } else { .. }` | [Transformer::IfElse] |
+//! | `if_changed { .. } then { .. } else { .. }` | [Transformer::IfChanged] |
+//!
+//! Expressions are written in the usual Symbolica syntax. In conditions and right-hand sides,
+//! `@{ .. }` refers to the current input, transformed by the chain between the braces, and
+//! `x_ @{ .. }` refers to the transformed match of `x_`.
+//!
+//! The condition of an `if` statement consists of the relations `==`, `!=`, `<`, `<=`, `>`, `>=`,
+//! `contains(a, b)`, `is_type(a, Num)` and `matches(a, pattern, condition, ..)`, combined
+//! with `&&`, `||` and `!`. The condition of a replacement consists of the wildcard restrictions
+//! `length(x__, 1, 3)`, `is_type(x_, Var)`, `is_literal(x_, x_)`, `not_greedy(x_)` and
+//! `satisfies(x_, positive)`, where the assumption is one of `real`, `positive`, `integer`,
+//! `nonzero` and `range(min, max)`.
+//! The match settings of a replacement can be set with the keyword arguments `min_level`, `max_level`,
+//! `level_is_tree_depth`, `allow_new_wildcards_on_rhs`, `rhs_cache_size` and `non_greedy = (x_, y_)`.
+//! The keyword argument `optional = (x_, default)` makes the wildcard `x_` of the pattern optional,
+//! see [Pattern::with_optional_wildcard](crate::id::Pattern::with_optional_wildcard).
+
+use std::fmt::Write;
+
+use crate::{
+    atom::{Assumption, Atom, AtomCore, AtomType, AtomView, DefaultNamespace, Symbol},
+    domains::rational::Rational,
+    id::{
+        Condition, MatchSettings, Pattern, PatternRestriction, Relation, ReplaceWith, Replacement,
+        RuleSet, WildcardRestriction,
+    },
+    printer::{AtomPrinter, PrintOptions},
+    transformer::{StatsOptions, Transformer},
+};
+
+const PRINT_PRESETS: [(&str, PrintOptions); 7] = [
+    ("default", PrintOptions::new()),
+    ("file", PrintOptions::file()),
+    ("file_no_namespace", PrintOptions::file_no_namespace()),
+    ("short", PrintOptions::short()),
+    ("latex", PrintOptions::latex()),
+    ("mathematica", PrintOptions::mathematica()),
+    ("sympy", PrintOptions::sympy()),
+];
+
+const ATOM_TYPES: [(&str, AtomType); 6] = [
+    ("Num", AtomType::Num),
+    ("Var", AtomType::Var),
+    ("Add", AtomType::Add),
+    ("Mul", AtomType::Mul),
+    ("Pow", AtomType::Pow),
+    ("Fun", AtomType::Fun),
+];
+
+const RELATION_OPERATORS: [&str; 6] = ["==", "!=", "<=", ">=", "<", ">"];
+
+impl Transformer {
+    /// Parse a transformer program into a chain of transformers that can
+    /// be executed with [Transformer::execute_chain].
+    /// New symbols are defined in the namespace of the input.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolica::{parse, state::Workspace, transformer::{Transformer, TransformerState}, wrap_input};
+    /// use symbolica::atom::Atom;
+    ///
+    /// let chain = Transformer::parse_chain(wrap_input!(
+    ///     "expand; replace_all(f(x_), x_^2, is_type(x_, Var))"
+    /// ))
+    /// .unwrap();
+    ///
+    /// let mut out = Atom::new();
+    /// let _ = Workspace::get_local().with(|ws| {
+    ///     Transformer::execute_chain(
+    ///         parse!("(f(x) + 1)^2").as_view(),
+    ///         &chain,
+    ///         ws,
+    ///         &TransformerState::default(),
+    ///         &mut out,
+    ///     )
+    ///     .unwrap()
+    /// });
+    /// assert_eq!(out, parse!("x^4 + 2*x^2 + 1"));
+    /// ```
+    pub fn parse_chain(input: DefaultNamespace) -> Result<Vec<Transformer>, String> {
+        let mut parser = ProgramParser {
+            scanner: Scanner::new(input.data),
+            namespace: &input,
+        };
+
+        let chain = parser.chain()?;
+        parser.scanner.skip_whitespace();
+        if !parser.scanner.at_end() {
+            return Err(parser.scanner.error("Unexpected '}'"));
+        }
+
+        Ok(chain)
+    }
+
+    /// Print a chain of transformers as a program that can be read back
+    /// with [Transformer::parse_chain]. Transformers that contain user-defined
+    /// functions, such as [Transformer::Map], cannot be printed.
+    ///
+    /// # Examples
+    /// ```
+    /// use symbolica::{transformer::Transformer, wrap_input};
+    ///
+    /// let chain = Transformer::parse_chain(wrap_input!("expand; repeat { derivative(x) }")).unwrap();
+    /// let program = Transformer::format_chain(&chain).unwrap();
+    /// assert_eq!(Transformer::format_chain(&Transformer::parse_chain(wrap_input!(&program)).unwrap()).unwrap(), program);
+    /// ```
+    pub fn format_chain(chain: &[Transformer]) -> Result<String, String> {
+        let mut out = String::new();
+        format_chain(chain, 0, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// A character-level scanner over the program text.
+struct Scanner<'a> {
+    input: &'a str,
+    pos: usize,
+    /// The line and column in the program text at which the input starts.
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(input: &'a str) -> Scanner<'a> {
+        Scanner {
+            input,
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Create a scanner for a part of the input, so that errors
+    /// are reported relative to the start of the program.
+    fn sub<'c>(&self, input: &'c str) -> Scanner<'c> {
+        let offset = (input.as_ptr() as usize)
+            .checked_sub(self.input.as_ptr() as usize)
+            .filter(|o| o + input.len() <= self.input.len());
+
+        let (line, column) = offset.map(|o| self.location(o)).unwrap_or((1, 1));
+        Scanner {
+            input,
+            pos: 0,
+            line,
+            column,
+        }
+    }
+
+    /// Get the line and column in the program text of the position `pos` in the input.
+    fn location(&self, pos: usize) -> (usize, usize) {
+        let consumed = &self.input[..pos];
+        let lines = consumed.matches('\n').count();
+        match consumed.rfind('\n') {
+            Some(i) => (self.line + lines, consumed.len() - i),
+            None => (self.line, self.column + consumed.len()),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    fn error(&self, msg: &str) -> String {
+        let (line, column) = self.location(self.pos);
+        format!("{} at line {}, column {}", msg, line, column)
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with('#') {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), String> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", s)))
+        }
+    }
+
+    fn peek_identifier(&mut self) -> Option<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        Some(&rest[..end])
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let id = self.peek_identifier()?;
+        self.pos += id.len();
+        Some(id)
+    }
+
+    /// Eat the keyword `k` if it is the next identifier.
+    fn eat_keyword(&mut self, k: &str) -> bool {
+        if self.peek_identifier() == Some(k) {
+            self.pos += k.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Read a string literal.
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut s = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(s);
+                }
+                '\\' => match chars.next() {
+                    Some((_, e)) => s.push(e),
+                    None => break,
+                },
+                _ => s.push(c),
+            }
+        }
+
+        Err(self.error("Unterminated string"))
+    }
+
+    /// Read raw text up to the first of the `stops` that is not nested in brackets.
+    fn raw(&mut self, stops: &[&str]) -> Result<&'a str, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut depth = vec![];
+        let mut in_string = false;
+
+        let mut chars = self.rest().char_indices().peekable();
+        while let Some(&(i, c)) = chars.peek() {
+            let rest = &self.input[start + i..];
+            if in_string {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => in_string = false,
+                    _ => {}
+                }
+            } else if rest.starts_with("@{") {
+                depth.push('}');
+                chars.next();
+            } else if depth.is_empty() && stops.iter().any(|s| rest.starts_with(s)) {
+                break;
+            } else {
+                match c {
+                    '"' => in_string = true,
+                    '(' => depth.push(')'),
+                    '[' => depth.push(']'),
+                    '{' => depth.push('}'),
+                    ')' | ']' | '}' if depth.pop() != Some(c) => {
+                        self.pos = start + i;
+                        return Err(self.error(&format!("Unbalanced '{}'", c)));
+                    }
+                    _ => {}
+                }
+            }
+            chars.next();
+        }
+
+        let end = chars
+            .peek()
+            .map(|(i, _)| start + i)
+            .unwrap_or(self.input.len());
+        if let Some(d) = depth.last() {
+            self.pos = end;
+            return Err(self.error(&format!("Expected '{}'", d)));
+        }
+
+        self.pos = end;
+        Ok(self.input[start..end].trim_end())
+    }
+}
+
+/// The arguments of a statement or relation.
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    keyword: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Args<'a> {
+    fn check(&self, name: &str, min: usize, max: usize, keywords: &[&str]) -> Result<(), String> {
+        if self.positional.len() < min || self.positional.len() > max {
+            return Err(if min == max {
+                format!("{} expects {} positional arguments", name, min)
+            } else if max == usize::MAX {
+                format!("{} expects at least {} positional arguments", name, min)
+            } else {
+                format!(
+                    "{} expects between {} and {} positional arguments",
+                    name, min, max
+                )
+            });
+        }
+
+        for (k, _) in &self.keyword {
+            if !keywords.contains(k) {
+                return Err(format!("Unknown keyword argument '{}' for {}", k, name));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.keyword
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    }
+}
+
+struct ProgramParser<'a, 'b> {
+    scanner: Scanner<'a>,
+    namespace: &'b DefaultNamespace<'b>,
+}
+
+impl<'a> ProgramParser<'a, '_> {
+    /// Create a parser for a part of the program.
+    fn sub(&self, input: &'a str) -> ProgramParser<'a, '_> {
+        ProgramParser {
+            scanner: self.scanner.sub(input),
+            namespace: self.namespace,
+        }
+    }
+
+    fn chain(&mut self) -> Result<Vec<Transformer>, String> {
+        let mut chain = vec![];
+        loop {
+            while self.scanner.eat(";") {}
+
+            match self.scanner.peek() {
+                None | Some('}') => return Ok(chain),
+                _ => {}
+            }
+
+            let ends_with_block = self.statement(&mut chain)?;
+
+            if !self.scanner.eat(";") && !ends_with_block {
+                match self.scanner.peek() {
+                    None | Some('}') => {}
+                    _ => return Err(self.scanner.error("Expected ';'")),
+                }
+            }
+        }
+    }
+
+    fn block(&mut self) -> Result<Vec<Transformer>, String> {
+        self.scanner.expect("{")?;
+        let chain = self.chain()?;
+        self.scanner.expect("}")?;
+        Ok(chain)
+    }
+
+    /// Parse a single statement and add it to `chain`. Returns `true` if the
+    /// statement ended with a block.
+    fn statement(&mut self, chain: &mut Vec<Transformer>) -> Result<bool, String> {
+        let Some(name) = self.scanner.identifier() else {
+            return Err(self.scanner.error("Expected a statement"));
+        };
+
+        let args = if name != "if" && self.scanner.peek() == Some('(') {
+            Some(self.args()?)
+        } else {
+            None
+        };
+        let no_args = Args {
+            positional: vec![],
+            keyword: vec![],
+        };
+        let a = args.as_ref().unwrap_or(&no_args);
+
+        let t = match name {
+            "if" => {
+                let cond = self.scanner.raw(&["{"])?;
+                let cond = self.sub(cond).relation_condition()?;
+                let t1 = self.block()?;
+                let t2 = if self.scanner.eat_keyword("else") {
+                    if self.scanner.peek_identifier() == Some("if") {
+                        let mut t2 = vec![];
+                        self.statement(&mut t2)?;
+                        t2
+                    } else {
+                        self.block()?
+                    }
+                } else {
+                    vec![]
+                };
+                chain.push(Transformer::IfElse(cond, t1, t2));
+                return Ok(true);
+            }
+            "if_changed" => {
+                a.check(name, 0, 0, &[])?;
+                let cond = self.block()?;
+                let t1 = if self.scanner.eat_keyword("then") {
+                    self.block()?
+                } else {
+                    vec![]
+                };
+                let t2 = if self.scanner.eat_keyword("else") {
+                    self.block()?
+                } else {
+                    vec![]
+                };
+                chain.push(Transformer::IfChanged(cond, t1, t2));
+                return Ok(true);
+            }
+            "repeat" | "for_each" | "map_terms" => {
+                a.check(name, 0, 0, &[])?;
+                let t = self.block()?;
+                chain.push(match name {
+                    "repeat" => Transformer::Repeat(t),
+                    "for_each" => Transformer::ForEach(t),
+                    _ => Transformer::MapTerms(t, None),
+                });
+                return Ok(true);
+            }
+            "replace_all_multiple" => {
+                a.check(name, 0, 0, &[])?;
+                self.scanner.expect("{")?;
+                let mut rules = vec![];
+                loop {
+                    while self.scanner.eat(";") {}
+                    if self.scanner.eat("}") {
+                        break;
+                    }
+
+                    if !self.scanner.eat_keyword("rule") {
+                        return Err(self.scanner.error("Expected 'rule'"));
+                    }
+                    let args = self.args()?;
+                    args.check("rule", 2, 3, SETTINGS_KEYWORDS)?;
+                    let (pat, rhs, cond, settings) = self.replacement(&args)?;
+                    let mut r = Replacement::new(pat, rhs);
+                    if let Some(cond) = cond {
+                        r = r.with_conditions(cond);
+                    }
+                    if let Some(settings) = settings {
+                        r = r.with_settings(settings);
+                    }
+                    rules.push(r);
+
+                    if !self.scanner.eat(";") && self.scanner.peek() != Some('}') {
+                        return Err(self.scanner.error("Expected ';'"));
+                    }
+                }
+                chain.push(Transformer::ReplaceAllRuleSet(RuleSet::new(rules)));
+                return Ok(true);
+            }
+            "stats" => {
+                a.check(
+                    name,
+                    1,
+                    1,
+                    &[
+                        "color_medium_change_threshold",
+                        "color_large_change_threshold",
+                    ],
+                )?;
+                let options = StatsOptions {
+                    tag: self.sub(a.positional[0]).string()?,
+                    color_medium_change_threshold: a
+                        .get("color_medium_change_threshold")
+                        .map(|x| self.float(x))
+                        .transpose()?,
+                    color_large_change_threshold: a
+                        .get("color_large_change_threshold")
+                        .map(|x| self.float(x))
+                        .transpose()?,
+                };
+                let t = self.block()?;
+                chain.push(Transformer::Stats(options, t));
+                return Ok(true);
+            }
+            "collect" | "collect_symbol" => {
+                let key = if self.scanner.eat_keyword("key") {
+                    self.block()?
+                } else {
+                    vec![]
+                };
+                let coeff = if self.scanner.eat_keyword("coefficient") {
+                    self.block()?
+                } else {
+                    vec![]
+                };
+                let ends_with_block = self.scanner.input[..self.scanner.pos].ends_with('}');
+
+                if name == "collect" {
+                    a.check(name, 1, usize::MAX, &[])?;
+                    let x = a
+                        .positional
+                        .iter()
+                        .map(|x| self.atom(x))
+                        .collect::<Result<_, _>>()?;
+                    chain.push(Transformer::Collect(x, key, coeff));
+                } else {
+                    a.check(name, 1, 1, &[])?;
+                    chain.push(Transformer::CollectSymbol(
+                        self.symbol(a.positional[0])?,
+                        key,
+                        coeff,
+                    ));
+                }
+                return Ok(ends_with_block);
+            }
+            "expand" => {
+                a.check(name, 0, 1, &["via_poly"])?;
+                Transformer::Expand(
+                    a.positional.first().map(|x| self.atom(x)).transpose()?,
+                    a.get("via_poly")
+                        .map(|x| self.bool(x))
+                        .transpose()?
+                        .unwrap_or(false),
+                )
+            }
+            "derivative" | "permutations" => {
+                a.check(name, 1, 1, &[])?;
+                let x = self.symbol(a.positional[0])?;
+                if name == "derivative" {
+                    Transformer::Derivative(x)
+                } else {
+                    Transformer::Permutations(x)
+                }
+            }
+            "series" => {
+                a.check(name, 3, 3, &["depth_is_absolute"])?;
+                let depth = self.atom(a.positional[2])?;
+                Transformer::Series(
+                    self.symbol(a.positional[0])?,
+                    self.atom(a.positional[1])?,
+                    Rational::try_from(depth.as_view())
+                        .map_err(|_| format!("Expected a rational depth, got {}", depth))?,
+                    a.get("depth_is_absolute")
+                        .map(|x| self.bool(x))
+                        .transpose()?
+                        .unwrap_or(false),
+                )
+            }
+            "replace_all" => {
+                a.check(name, 2, 3, SETTINGS_KEYWORDS)?;
+                let (pat, rhs, cond, settings) = self.replacement(a)?;
+                Transformer::ReplaceAll(
+                    pat,
+                    rhs.into(),
+                    cond.unwrap_or_default(),
+                    settings.unwrap_or_default(),
+                )
+            }
+            "arg_count" => {
+                a.check(name, 0, 0, &["only_arg"])?;
+                Transformer::ArgCount(
+                    a.get("only_arg")
+                        .map(|x| self.bool(x))
+                        .transpose()?
+                        .unwrap_or(false),
+                )
+            }
+            "linearize" => {
+                if let Some(args) = &args {
+                    args.check(name, 0, usize::MAX, &[])?;
+                    Transformer::Linearize(Some(
+                        args.positional
+                            .iter()
+                            .map(|x| self.symbol(x))
+                            .collect::<Result<_, _>>()?,
+                    ))
+                } else {
+                    Transformer::Linearize(None)
+                }
+            }
+            "partition" => {
+                a.check(name, 1, usize::MAX, &["fill_last", "repeat"])?;
+                let mut partitions = vec![];
+                for p in &a.positional {
+                    let p = self.tuple(p)?;
+                    if p.len() != 2 {
+                        return Err(format!("Expected a (symbol, size) pair, got {:?}", p));
+                    }
+                    partitions.push((self.symbol(p[0])?, self.usize(p[1])?));
+                }
+
+                Transformer::Partition(
+                    partitions,
+                    a.get("fill_last")
+                        .map(|x| self.bool(x))
+                        .transpose()?
+                        .unwrap_or(false),
+                    a.get("repeat")
+                        .map(|x| self.bool(x))
+                        .transpose()?
+                        .unwrap_or(false),
+                )
+            }
+            "print" => {
+                a.check(name, 0, 1, &[])?;
+                let preset = a.positional.first().copied().unwrap_or("default");
+                let Some((_, opts)) = PRINT_PRESETS.iter().find(|(n, _)| *n == preset) else {
+                    return Err(format!("Unknown print preset '{}'", preset));
+                };
+                Transformer::Print(*opts)
+            }
+            _ => {
+                a.check(name, 0, 0, &[])?;
+                match name {
+                    "expand_num" => Transformer::ExpandNum,
+                    "collect_factors" => Transformer::CollectFactors,
+                    "collect_num" => Transformer::CollectNum,
+                    "conjugate" => Transformer::Conjugate,
+                    "product" => Transformer::Product,
+                    "sum" => Transformer::Sum,
+                    "split" => Transformer::Split,
+                    "sort" => Transformer::Sort,
+                    "cycle_symmetrize" => Transformer::CycleSymmetrize,
+                    "deduplicate" => Transformer::Deduplicate,
+                    "from_number" => Transformer::FromNumber,
+                    "break" => Transformer::BreakChain,
+                    _ => return Err(format!("Unknown statement '{}'", name)),
+                }
+            }
+        };
+
+        chain.push(t);
+        Ok(false)
+    }
+
+    /// Parse an argument list `(a, b, key = c)`.
+    fn args(&mut self) -> Result<Args<'a>, String> {
+        let mut args = Args {
+            positional: vec![],
+            keyword: vec![],
+        };
+
+        self.scanner.expect("(")?;
+        if self.scanner.eat(")") {
+            return Ok(args);
+        }
+
+        loop {
+            let start = self.scanner.pos;
+            let key = match self.scanner.identifier() {
+                Some(k) if self.scanner.eat("=") && !self.scanner.rest().starts_with('=') => {
+                    Some(k)
+                }
+                _ => {
+                    self.scanner.pos = start;
+                    None
+                }
+            };
+
+            let value = self.scanner.raw(&[",", ")"])?;
+            if value.is_empty() {
+                return Err(self.scanner.error("Expected an argument"));
+            }
+
+            if let Some(k) = key {
+                args.keyword.push((k, value));
+            } else if !args.keyword.is_empty() {
+                return Err(self
+                    .scanner
+                    .error("Positional argument follows keyword argument"));
+            } else {
+                args.positional.push(value);
+            }
+
+            if !self.scanner.eat(",") {
+                self.scanner.expect(")")?;
+                return Ok(args);
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let s = self.scanner.string()?;
+        self.end()?;
+        Ok(s)
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        self.scanner.skip_whitespace();
+        if self.scanner.at_end() {
+            Ok(())
+        } else {
+            Err(self.scanner.error("Unexpected input"))
+        }
+    }
+
+    fn atom(&self, input: &str) -> Result<Atom, String> {
+        Atom::parse(DefaultNamespace {
+            namespace: self.namespace.namespace.clone(),
+            data: input,
+            file: self.namespace.file.clone(),
+            line: self.namespace.line,
+        })
+    }
+
+    fn symbol(&self, input: &str) -> Result<Symbol, String> {
+        if let AtomView::Var(v) = self.atom(input)?.as_view() {
+            Ok(v.get_symbol())
+        } else {
+            Err(format!("Expected a symbol, got {}", input))
+        }
+    }
+
+    fn bool(&self, input: &str) -> Result<bool, String> {
+        match input {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(format!("Expected true or false, got {}", input)),
+        }
+    }
+
+    fn usize(&self, input: &str) -> Result<usize, String> {
+        input
+            .parse()
+            .map_err(|_| format!("Expected a non-negative integer, got {}", input))
+    }
+
+    fn float(&self, input: &str) -> Result<f64, String> {
+        input
+            .parse()
+            .map_err(|_| format!("Expected a number, got {}", input))
+    }
+
+    /// Parse a tuple `(a, b)`. A single element does not need parentheses.
+    fn tuple<'c>(&self, input: &'c str) -> Result<Vec<&'c str>, String> {
+        let Some(inner) = input.strip_prefix('(').and_then(|x| x.strip_suffix(')')) else {
+            return Ok(vec![input]);
+        };
+
+        let mut s = self.scanner.sub(inner);
+        let mut elems = vec![];
+        loop {
+            elems.push(s.raw(&[","])?);
+            if !s.eat(",") {
+                break;
+            }
+        }
+        Ok(elems)
+    }
+
+    /// Parse a pattern, which is an expression optionally followed by a transformer block `@{ .. }`.
+    fn pattern(&self, input: &str) -> Result<Pattern, String> {
+        if let Some(pos) = input.find("@{") {
+            let mut s = self.sub(&input[pos + 1..]);
+            let chain = s.block()?;
+            s.end()?;
+
+            let expr = input[..pos].trim();
+            let expr = if expr.is_empty() {
+                None
+            } else {
+                Some(self.atom(expr)?.to_pattern())
+            };
+            return Ok(Pattern::Transformer(Box::new((expr, chain))));
+        }
+
+        Ok(self.atom(input)?.to_pattern())
+    }
+
+    fn match_settings(&self, args: &Args) -> Result<Option<MatchSettings>, String> {
+        if args.keyword.iter().all(|(k, _)| *k == "optional") {
+            return Ok(None);
+        }
+
+        let mut settings = MatchSettings::default();
+        for (k, v) in &args.keyword {
+            match *k {
+                "min_level" => settings.level_range.0 = self.usize(v)?,
+                "max_level" => settings.level_range.1 = Some(self.usize(v)?),
+                "level_is_tree_depth" => settings.level_is_tree_depth = self.bool(v)?,
+                "allow_new_wildcards_on_rhs" => {
+                    settings.allow_new_wildcards_on_rhs = self.bool(v)?
+                }
+                "rhs_cache_size" => settings.rhs_cache_size = self.usize(v)?,
+                "non_greedy" => {
+                    for s in self.tuple(v)? {
+                        settings.non_greedy_wildcards.push(self.symbol(s)?);
+                    }
+                }
+                "optional" => {}
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(Some(settings))
+    }
+
+    /// Make the wildcards of the `optional = (x_, default)` arguments optional in `pat`.
+    fn optional_wildcards(&self, mut pat: Pattern, args: &Args) -> Result<Pattern, String> {
+        for (k, v) in &args.keyword {
+            if *k == "optional" {
+                let t = self.tuple(v)?;
+                if t.len() != 2 {
+                    return Err(format!("Expected a (wildcard, default) pair, got {}", v));
+                }
+                pat = pat.with_optional_wildcard(self.symbol(t[0])?, self.atom(t[1])?);
+            }
+        }
+        Ok(pat)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn replacement(
+        &self,
+        args: &Args,
+    ) -> Result<
+        (
+            Pattern,
+            Pattern,
+            Option<Condition<PatternRestriction>>,
+            Option<MatchSettings>,
+        ),
+        String,
+    > {
+        let pat = self.optional_wildcards(self.atom(args.positional[0])?.to_pattern(), args)?;
+        let rhs = self.pattern(args.positional[1])?;
+        let cond = args
+            .positional
+            .get(2)
+            .map(|c| self.sub(c).restriction_condition())
+            .transpose()?;
+        Ok((pat, rhs, cond, self.match_settings(args)?))
+    }
+
+    /// Parse a logical combination of leaves parsed by `leaf`.
+    fn condition<T>(
+        &mut self,
+        leaf: &impl Fn(&mut Self) -> Result<T, String>,
+    ) -> Result<Condition<T>, String> {
+        let mut c = self.condition_and(leaf)?;
+        while self.scanner.eat("||") {
+            c = c | self.condition_and(leaf)?;
+        }
+        Ok(c)
+    }
+
+    fn condition_and<T>(
+        &mut self,
+        leaf: &impl Fn(&mut Self) -> Result<T, String>,
+    ) -> Result<Condition<T>, String> {
+        let mut c = self.condition_unary(leaf)?;
+        while self.scanner.eat("&&") {
+            c = c & self.condition_unary(leaf)?;
+        }
+        Ok(c)
+    }
+
+    fn condition_unary<T>(
+        &mut self,
+        leaf: &impl Fn(&mut Self) -> Result<T, String>,
+    ) -> Result<Condition<T>, String> {
+        if self.scanner.peek() == Some('!') && !self.scanner.rest().starts_with("!=") {
+            self.scanner.pos += 1;
+            return Ok(!self.condition_unary(leaf)?);
+        }
+
+        for (k, c) in [("true", Condition::True), ("false", Condition::False)] {
+            let start = self.scanner.pos;
+            if self.scanner.eat_keyword(k) {
+                if matches!(self.scanner.peek(), None | Some(')' | '&' | '|')) {
+                    return Ok(c);
+                }
+                self.scanner.pos = start;
+            }
+        }
+
+        // try a parenthesized condition, otherwise the parenthesis belongs to the leaf
+        let start = self.scanner.pos;
+        if self.scanner.eat("(") {
+            if let Ok(c) = self.condition(leaf) {
+                if self.scanner.eat(")") {
+                    return Ok(c);
+                }
+            }
+            self.scanner.pos = start;
+        }
+
+        Ok(Condition::Yield(leaf(self)?))
+    }
+
+    fn relation_condition(&mut self) -> Result<Condition<Relation>, String> {
+        let c = self.condition(&|p: &mut Self| p.relation())?;
+        self.end()?;
+        Ok(c)
+    }
+
+    fn restriction_condition(&mut self) -> Result<Condition<PatternRestriction>, String> {
+        let c = self.condition(&|p: &mut Self| p.restriction())?;
+        self.end()?;
+        Ok(c)
+    }
+
+    fn atom_type(&self, input: &str) -> Result<AtomType, String> {
+        ATOM_TYPES
+            .iter()
+            .find(|(n, _)| *n == input)
+            .map(|(_, t)| *t)
+            .ok_or_else(|| format!("Unknown atom type '{}'", input))
+    }
+
+    fn assumption(&self, input: &str) -> Result<Assumption, String> {
+        match input {
+            "real" => return Ok(Assumption::Real),
+            "positive" => return Ok(Assumption::Positive),
+            "integer" => return Ok(Assumption::Integer),
+            "nonzero" => return Ok(Assumption::Nonzero),
+            _ => {}
+        }
+
+        if let Some(range) = input.strip_prefix("range") {
+            if let [min, max] = self.tuple(range.trim())?[..] {
+                let bound = |x: &str| {
+                    Rational::try_from(self.atom(x)?.as_view())
+                        .map_err(|_| format!("Expected a rational number, got {}", x))
+                };
+                return Ok(Assumption::Range(bound(min)?, bound(max)?));
+            }
+        }
+
+        Err(format!("Unknown assumption '{}'", input))
+    }
+
+    fn relation(&mut self) -> Result<Relation, String> {
+        if let Some(name @ ("contains" | "is_type" | "matches")) = self.scanner.peek_identifier() {
+            let start = self.scanner.pos;
+            self.scanner.pos += name.len();
+            if self.scanner.peek() == Some('(') {
+                let a = self.args()?;
+                return match name {
+                    "contains" => {
+                        a.check(name, 2, 2, &[])?;
+                        Ok(Relation::Contains(
+                            self.pattern(a.positional[0])?,
+                            self.pattern(a.positional[1])?,
+                        ))
+                    }
+                    "is_type" => {
+                        a.check(name, 2, 2, &[])?;
+                        Ok(Relation::IsType(
+                            self.pattern(a.positional[0])?,
+                            self.atom_type(a.positional[1])?,
+                        ))
+                    }
+                    _ => {
+                        a.check(name, 2, 3, SETTINGS_KEYWORDS)?;
+                        let cond = a
+                            .positional
+                            .get(2)
+                            .map(|c| self.sub(c).restriction_condition())
+                            .transpose()?;
+                        Ok(Relation::Matches(
+                            self.pattern(a.positional[0])?,
+                            self.optional_wildcards(self.atom(a.positional[1])?.to_pattern(), &a)?,
+                            cond.unwrap_or_default(),
+                            self.match_settings(&a)?.unwrap_or_default(),
+                        ))
+                    }
+                };
+            }
+            self.scanner.pos = start;
+        }
+
+        let stops = ["==", "!=", "<=", ">=", "<", ">", "&&", "||", ")"];
+        let lhs = self.scanner.raw(&stops)?;
+        let Some(op) = RELATION_OPERATORS.iter().find(|op| self.scanner.eat(op)) else {
+            return Err(self.scanner.error("Expected a relation"));
+        };
+        let rhs = self.scanner.raw(&stops)?;
+        if lhs.is_empty() || rhs.is_empty() {
+            return Err(self.scanner.error("Expected an expression"));
+        }
+
+        let (lhs, rhs) = (self.pattern(lhs)?, self.pattern(rhs)?);
+        Ok(match *op {
+            "==" => Relation::Eq(lhs, rhs),
+            "!=" => Relation::Ne(lhs, rhs),
+            "<=" => Relation::Le(lhs, rhs),
+            ">=" => Relation::Ge(lhs, rhs),
+            "<" => Relation::Lt(lhs, rhs),
+            _ => Relation::Gt(lhs, rhs),
+        })
+    }
+
+    fn restriction(&mut self) -> Result<PatternRestriction, String> {
+        let Some(name) = self.scanner.identifier() else {
+            return Err(self.scanner.error("Expected a wildcard restriction"));
+        };
+        if self.scanner.peek() != Some('(') {
+            return Err(self.scanner.error("Expected '('"));
+        }
+        let a = self.args()?;
+
+        let r = match name {
+            "length" => {
+                a.check(name, 2, 3, &[])?;
+                WildcardRestriction::Length(
+                    self.usize(a.positional[1])?,
+                    a.positional.get(2).map(|x| self.usize(x)).transpose()?,
+                )
+            }
+            "is_type" => {
+                a.check(name, 2, 2, &[])?;
+                WildcardRestriction::IsAtomType(self.atom_type(a.positional[1])?)
+            }
+            "is_literal" => {
+                a.check(name, 2, 2, &[])?;
+                WildcardRestriction::IsLiteralWildcard(self.symbol(a.positional[1])?)
+            }
+            "not_greedy" => {
+                a.check(name, 1, 1, &[])?;
+                WildcardRestriction::NotGreedy
+            }
+            "satisfies" => {
+                a.check(name, 2, 2, &[])?;
+                WildcardRestriction::satisfies(self.assumption(a.positional[1])?)
+            }
+            _ => return Err(format!("Unknown wildcard restriction '{}'", name)),
+        };
+
+        Ok(PatternRestriction::Wildcard((
+            self.symbol(a.positional[0])?,
+            r,
+        )))
+    }
+}
+
+const SETTINGS_KEYWORDS: &[&str] = &[
+    "min_level",
+    "max_level",
+    "level_is_tree_depth",
+    "allow_new_wildcards_on_rhs",
+    "rhs_cache_size",
+    "non_greedy",
+    "optional",
+];
+
+fn format_atom(a: AtomView) -> String {
+    format!("{}", AtomPrinter::new_with_options(a, PrintOptions::file()))
+}
+
+fn format_symbol(s: Symbol) -> String {
+    format_atom(Atom::var(s).as_view())
+}
+
+fn format_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn format_pattern(p: &Pattern) -> Result<String, String> {
+    if let Pattern::Transformer(t) = p {
+        let mut out = String::new();
+        if let Some(e) = &t.0 {
+            out.push_str(&format_pattern(e)?);
+            out.push(' ');
+        }
+        out.push('@');
+        out.push_str(&format_inline_block(&t.1)?);
+        return Ok(out);
+    }
+
+    p.to_atom()
+        .map(|a| format_atom(a.as_view()))
+        .map_err(|_| "Transformers nested in a pattern cannot be printed".to_owned())
+}
+
+/// Format a chain on a single line, wrapped in braces.
+fn format_inline_block(chain: &[Transformer]) -> Result<String, String> {
+    if chain.is_empty() {
+        return Ok("{}".to_owned());
+    }
+
+    let mut out = String::new();
+    format_chain(chain, 0, &mut out)?;
+    Ok(format!(
+        "{{ {} }}",
+        out.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+    ))
+}
+
+fn format_condition<T>(
+    c: &Condition<T>,
+    leaf: &impl Fn(&T) -> Result<String, String>,
+) -> Result<String, String> {
+    Ok(match c {
+        Condition::And(a) => format!(
+            "({} && {})",
+            format_condition(&a.0, leaf)?,
+            format_condition(&a.1, leaf)?
+        ),
+        Condition::Or(o) => format!(
+            "({} || {})",
+            format_condition(&o.0, leaf)?,
+            format_condition(&o.1, leaf)?
+        ),
+        Condition::Not(n) => format!("!{}", format_condition(n, leaf)?),
+        Condition::Yield(t) => leaf(t)?,
+        Condition::True => "true".to_owned(),
+        Condition::False => "false".to_owned(),
+    })
+}
+
+fn format_atom_type(t: AtomType) -> &'static str {
+    ATOM_TYPES.iter().find(|(_, a)| *a == t).unwrap().0
+}
+
+fn format_assumption(a: &Assumption) -> String {
+    match a {
+        Assumption::Real => "real".to_owned(),
+        Assumption::Positive => "positive".to_owned(),
+        Assumption::Integer => "integer".to_owned(),
+        Assumption::Nonzero => "nonzero".to_owned(),
+        Assumption::Range(min, max) => format!("range({}, {})", min, max),
+    }
+}
+
+fn format_relation(r: &Relation) -> Result<String, String> {
+    Ok(match r {
+        Relation::Eq(a, b) => format!("{} == {}", format_pattern(a)?, format_pattern(b)?),
+        Relation::Ne(a, b) => format!("{} != {}", format_pattern(a)?, format_pattern(b)?),
+        Relation::Gt(a, b) => format!("{} > {}", format_pattern(a)?, format_pattern(b)?),
+        Relation::Ge(a, b) => format!("{} >= {}", format_pattern(a)?, format_pattern(b)?),
+        Relation::Lt(a, b) => format!("{} < {}", format_pattern(a)?, format_pattern(b)?),
+        Relation::Le(a, b) => format!("{} <= {}", format_pattern(a)?, format_pattern(b)?),
+        Relation::Contains(a, b) => {
+            format!("contains({}, {})", format_pattern(a)?, format_pattern(b)?)
+        }
+        Relation::IsType(a, t) => {
+            format!("is_type({}, {})", format_pattern(a)?, format_atom_type(*t))
+        }
+        Relation::Matches(a, p, c, s) => {
+            let mut args = vec![format_pattern(a)?, format_pattern(p)?];
+            if !matches!(c, Condition::True) {
+                args.push(format_condition(c, &format_restriction)?);
+            }
+            format_match_settings(s, &mut args);
+            format_optional_wildcards(p, &mut args);
+            format!("matches({})", args.join(", "))
+        }
+    })
+}
+
+fn format_restriction(r: &PatternRestriction) -> Result<String, String> {
+    let PatternRestriction::Wildcard((s, r)) = r else {
+        return Err("Match stack restrictions cannot be printed".to_owned());
+    };
+
+    let s = format_symbol(*s);
+    Ok(match r {
+        WildcardRestriction::Length(min, Some(max)) => format!("length({}, {}, {})", s, min, max),
+        WildcardRestriction::Length(min, None) => format!("length({}, {})", s, min),
+        WildcardRestriction::IsAtomType(t) => format!("is_type({}, {})", s, format_atom_type(*t)),
+        WildcardRestriction::IsLiteralWildcard(l) => {
+            format!("is_literal({}, {})", s, format_symbol(*l))
+        }
+        WildcardRestriction::NotGreedy => format!("not_greedy({})", s),
+        WildcardRestriction::Satisfies(a) => format!("satisfies({}, {})", s, format_assumption(a)),
+        WildcardRestriction::Filter(_) | WildcardRestriction::Cmp(_, _) => {
+            return Err("Filter functions cannot be printed".to_owned());
+        }
+    })
+}
+
+fn format_match_settings(s: &MatchSettings, args: &mut Vec<String>) {
+    let default = MatchSettings::default();
+    if s.level_range.0 != default.level_range.0 {
+        args.push(format!("min_level = {}", s.level_range.0));
+    }
+    if let Some(max) = s.level_range.1 {
+        args.push(format!("max_level = {}", max));
+    }
+    if s.level_is_tree_depth {
+        args.push("level_is_tree_depth = true".to_owned());
+    }
+    if s.allow_new_wildcards_on_rhs {
+        args.push("allow_new_wildcards_on_rhs = true".to_owned());
+    }
+    if s.rhs_cache_size != default.rhs_cache_size {
+        args.push(format!("rhs_cache_size = {}", s.rhs_cache_size));
+    }
+    if !s.non_greedy_wildcards.is_empty() {
+        args.push(format!(
+            "non_greedy = ({})",
+            s.non_greedy_wildcards
+                .iter()
+                .map(|x| format_symbol(*x))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+}
+
+fn format_optional_wildcards(pat: &Pattern, args: &mut Vec<String>) {
+    for (w, d) in pat.optional_wildcards() {
+        args.push(format!(
+            "optional = ({}, {})",
+            format_symbol(w),
+            format_atom(d.as_view())
+        ));
+    }
+}
+
+fn format_replacement(
+    pat: &Pattern,
+    rhs: &ReplaceWith,
+    cond: Option<&Condition<PatternRestriction>>,
+    settings: Option<&MatchSettings>,
+) -> Result<String, String> {
+    let ReplaceWith::Pattern(rhs) = rhs else {
+        return Err("Replacement maps cannot be printed".to_owned());
+    };
+
+    let mut args = vec![format_pattern(pat)?, format_pattern(rhs)?];
+    if let Some(c) = cond {
+        if !matches!(c, Condition::True) {
+            args.push(format_condition(c, &format_restriction)?);
+        }
+    }
+    if let Some(s) = settings {
+        format_match_settings(s, &mut args);
+    }
+    format_optional_wildcards(pat, &mut args);
+    Ok(args.join(", "))
+}
+
+fn format_block(chain: &[Transformer], indent: usize, out: &mut String) -> Result<(), String> {
+    if chain.is_empty() {
+        out.push_str("{}");
+        return Ok(());
+    }
+
+    out.push_str("{\n");
+    format_chain(chain, indent + 1, out)?;
+    out.push_str(&"    ".repeat(indent));
+    out.push('}');
+    Ok(())
+}
+
+fn format_chain(chain: &[Transformer], indent: usize, out: &mut String) -> Result<(), String> {
+    for t in chain {
+        out.push_str(&"    ".repeat(indent));
+        format_statement(t, indent, out)?;
+        out.push('\n');
+    }
+    Ok(())
+}
+
+fn format_statement(t: &Transformer, indent: usize, out: &mut String) -> Result<(), String> {
+    let simple = match t {
+        Transformer::IfElse(cond, t1, t2) => {
+            write!(out, "if {} ", format_condition(cond, &format_relation)?).unwrap();
+            format_block(t1, indent, out)?;
+            if !t2.is_empty() {
+                out.push_str(" else ");
+                format_block(t2, indent, out)?;
+            }
+            return Ok(());
+        }
+        Transformer::IfChanged(cond, t1, t2) => {
+            out.push_str("if_changed ");
+            format_block(cond, indent, out)?;
+            if !t1.is_empty() {
+                out.push_str(" then ");
+                format_block(t1, indent, out)?;
+            }
+            if !t2.is_empty() {
+                out.push_str(" else ");
+                format_block(t2, indent, out)?;
+            }
+            return Ok(());
+        }
+        Transformer::Repeat(c) | Transformer::ForEach(c) | Transformer::MapTerms(c, None) => {
+            out.push_str(match t {
+                Transformer::Repeat(_) => "repeat ",
+                Transformer::ForEach(_) => "for_each ",
+                _ => "map_terms ",
+            });
+            format_block(c, indent, out)?;
+            return Ok(());
+        }
+        Transformer::MapTerms(_, Some(_)) => {
+            return Err("Transformers with a thread pool cannot be printed".to_owned());
+        }
+        Transformer::ReplaceAllMultiple(_) | Transformer::ReplaceAllRuleSet(_) => {
+            let rules = match t {
+                Transformer::ReplaceAllMultiple(r) => r.as_slice(),
+                Transformer::ReplaceAllRuleSet(r) => r.replacements(),
+                _ => unreachable!(),
+            };
+
+            out.push_str("replace_all_multiple {\n");
+            for r in rules {
+                writeln!(
+                    out,
+                    "{}rule({});",
+                    "    ".repeat(indent + 1),
+                    format_replacement(r.pattern(), r.rhs(), r.conditions(), r.settings())?
+                )
+                .unwrap();
+            }
+            out.push_str(&"    ".repeat(indent));
+            out.push('}');
+            return Ok(());
+        }
+        Transformer::Stats(o, c) => {
+            let mut args = vec![format_string(&o.tag)];
+            if let Some(m) = o.color_medium_change_threshold {
+                args.push(format!("color_medium_change_threshold = {}", m));
+            }
+            if let Some(l) = o.color_large_change_threshold {
+                args.push(format!("color_large_change_threshold = {}", l));
+            }
+            write!(out, "stats({}) ", args.join(", ")).unwrap();
+            format_block(c, indent, out)?;
+            return Ok(());
+        }
+        Transformer::Collect(_, key, coeff) | Transformer::CollectSymbol(_, key, coeff) => {
+            match t {
+                Transformer::Collect(x, _, _) => write!(
+                    out,
+                    "collect({})",
+                    x.iter()
+                        .map(|x| format_atom(x.as_view()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .unwrap(),
+                Transformer::CollectSymbol(x, _, _) => {
+                    write!(out, "collect_symbol({})", format_symbol(*x)).unwrap()
+                }
+                _ => unreachable!(),
+            }
+
+            if !key.is_empty() {
+                out.push_str(" key ");
+                format_block(key, indent, out)?;
+            }
+            if !coeff.is_empty() {
+                out.push_str(" coefficient ");
+                format_block(coeff, indent, out)?;
+            }
+            if key.is_empty() && coeff.is_empty() {
+                out.push(';');
+            }
+            return Ok(());
+        }
+        Transformer::BreakChain => "break".to_owned(),
+        Transformer::Expand(x, via_poly) => {
+            let mut args = vec![];
+            if let Some(x) = x {
+                args.push(format_atom(x.as_view()));
+            }
+            if *via_poly {
+                args.push("via_poly = true".to_owned());
+            }
+            if args.is_empty() {
+                "expand".to_owned()
+            } else {
+                format!("expand({})", args.join(", "))
+            }
+        }
+        Transformer::ExpandNum => "expand_num".to_owned(),
+        Transformer::Derivative(x) => format!("derivative({})", format_symbol(*x)),
+        Transformer::Series(x, point, depth, depth_is_absolute) => {
+            let mut args = vec![
+                format_symbol(*x),
+                format_atom(point.as_view()),
+                depth.to_string(),
+            ];
+            if *depth_is_absolute {
+                args.push("depth_is_absolute = true".to_owned());
+            }
+            format!("series({})", args.join(", "))
+        }
+        Transformer::CollectFactors => "collect_factors".to_owned(),
+        Transformer::CollectNum => "collect_num".to_owned(),
+        Transformer::Conjugate => "conjugate".to_owned(),
+        Transformer::ReplaceAll(pat, rhs, cond, settings) => format!(
+            "replace_all({})",
+            format_replacement(pat, rhs, Some(cond), Some(settings))?
+        ),
+        Transformer::Product => "product".to_owned(),
+        Transformer::Sum => "sum".to_owned(),
+        Transformer::ArgCount(only_arg) => {
+            if *only_arg {
+                "arg_count(only_arg = true)".to_owned()
+            } else {
+                "arg_count".to_owned()
+            }
+        }
+        Transformer::Linearize(None) => "linearize".to_owned(),
+        Transformer::Linearize(Some(s)) => format!(
+            "linearize({})",
+            s.iter()
+                .map(|x| format_symbol(*x))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Transformer::Map(_) => return Err("Map transformers cannot be printed".to_owned()),
+        Transformer::Split => "split".to_owned(),
+        Transformer::Partition(p, fill_last, repeat) => {
+            let mut args: Vec<_> = p
+                .iter()
+                .map(|(s, n)| format!("({}, {})", format_symbol(*s), n))
+                .collect();
+            if *fill_last {
+                args.push("fill_last = true".to_owned());
+            }
+            if *repeat {
+                args.push("repeat = true".to_owned());
+            }
+            format!("partition({})", args.join(", "))
+        }
+        Transformer::Sort => "sort".to_owned(),
+        Transformer::CycleSymmetrize => "cycle_symmetrize".to_owned(),
+        Transformer::Deduplicate => "deduplicate".to_owned(),
+        Transformer::Permutations(f) => format!("permutations({})", format_symbol(*f)),
+        Transformer::Print(opts) => {
+            let Some((name, _)) = PRINT_PRESETS.iter().find(|(_, o)| o == opts) else {
+                return Err("Only preset print options can be printed".to_owned());
+            };
+            if *name == "default" {
+                "print".to_owned()
+            } else {
+                format!("print({})", name)
+            }
+        }
+        Transformer::FromNumber => "from_number".to_owned(),
+    };
+
+    out.push_str(&simple);
+    out.push(';');
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        atom::Atom,
+        parse,
+        state::Workspace,
+        transformer::{Transformer, TransformerState},
+        wrap_input,
+    };
+
+    fn run(program: &str, input: &str) -> Atom {
+        let chain = Transformer::parse_chain(wrap_input!(program)).unwrap();
+        let mut out = Atom::new();
+        let _ = Workspace::get_local().with(|ws| {
+            Transformer::execute_chain(
+                parse!(input).as_view(),
+                &chain,
+                ws,
+                &TransformerState::default(),
+                &mut out,
+            )
+            .unwrap()
+        });
+        out
+    }
+
+    #[test]
+    fn execute() {
+        let r = run(
+            "# expand first
+            expand;
+            repeat {
+                replace_all(f(0), 1);
+                replace_all(f(x_), f(x_ - 1) + 1, is_type(x_, Num) && !is_type(x_, Var));
+            }",
+            "f(3)*(x+1)",
+        );
+        assert_eq!(r, parse!("4*x+4"));
+
+        let r = run(
+            "if @{} == 1 { print } else if contains(@{}, y) { collect(y) coefficient { expand; } } else { sum }",
+            "y*(x+1)+y",
+        );
+        assert_eq!(r, parse!("y*(x+2)"));
+
+        let r = run(
+            "replace_all_multiple { rule(f(x_), x_, is_type(x_, Var)); rule(g(x_), x_ @{ expand }) }",
+            "f(x)+f(2)+g((1+x)^2)",
+        );
+        assert_eq!(r, parse!("f(2)+x^2+3*x+1"));
+
+        let r = run(
+            "if_changed { replace_all(y, 2) } then { expand } else { break }",
+            "(x+y)^2",
+        );
+        assert_eq!(r, parse!("x^2+4*x+4"));
+
+        let r = run("for_each { derivative(x) }; product", "arg(x^2, x^3)");
+        assert_eq!(r, parse!("6*x^3"));
+
+        let r = run("replace_all(f(x_), 1, max_level = 0)", "f(f(1))");
+        assert_eq!(r, parse!("1"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let program = "expand(x, via_poly = true);
+            derivative(x); series(x, 0, 5/2, depth_is_absolute = true);
+            collect(x, y) key { expand } coefficient { collect_num; expand_num };
+            collect_symbol(f); collect_factors; conjugate;
+            replace_all(f(x_, y__), y__ @{ product }, (is_type(x_, Var) || length(y__, 1, 3)) && !not_greedy(y__), optional = (y__, 1), non_greedy = (x_));
+            replace_all_multiple { rule(g(x_), x_, is_literal(x_, x_)); rule(h(x_), 2, min_level = 1, level_is_tree_depth = true) }
+            replace_all(sqrt(x_^2), x_, satisfies(x_, positive) || satisfies(x_, range(-1, 1/2)));
+            product; sum; arg_count(only_arg = true); linearize; linearize(x);
+            map_terms { split; sort; cycle_symmetrize; deduplicate; permutations(f) }
+            partition((f, 2), (g, 1), fill_last = true);
+            print(file); print;
+            stats(\"a \\\"tag\\\"\", color_medium_change_threshold = 0.5) { from_number }
+            if (x_ @{ expand } > 1 && matches(@{}, f(x_), is_type(x_, Num), max_level = 2)) || !is_type(@{}, Add) { break } else if true {}
+            if_changed { expand } else { sum }";
+
+        let chain = Transformer::parse_chain(wrap_input!(program)).unwrap();
+        let printed = Transformer::format_chain(&chain).unwrap();
+        let reparsed = Transformer::parse_chain(wrap_input!(&printed)).unwrap();
+        assert_eq!(Transformer::format_chain(&reparsed).unwrap(), printed);
+    }
+
+    #[test]
+    fn errors() {
+        for p in [
+            "expand(",
+            "unknown",
+            "expand; }",
+            "derivative(x + 1)",
+            "replace_all(f(x_))",
+            "if x { expand }",
+            "expand collect(x)",
+            "print(color)",
+        ] {
+            assert!(Transformer::parse_chain(wrap_input!(p)).is_err(), "{}", p);
+        }
+
+        for (p, err) in [
+            ("collect", "collect expects at least 1 positional arguments"),
+            ("expand;\n stats(1) {}", "Expected '\"' at line 2, column 8"),
+            (
+                "expand; if x {}",
+                "Expected a relation at line 1, column 13",
+            ),
+        ] {
+            assert_eq!(Transformer::parse_chain(wrap_input!(p)).unwrap_err(), err);
+        }
+
+        let chain = vec![Transformer::Map(Box::new(|i, _, o| {
+            o.set_from_view(&i);
+            Ok(())
+        }))];
+        assert!(Transformer::format_chain(&chain).is_err());
+    }
+}
@@ -525,6 +525,62 @@ impl<F: Ring> Series<F> {
             .enumerate()
             .map(|(i, c)| (self.get_exponent(i), c))
     }
+
+    /// Constructs a zero series with absolute order `order`, inheriting the field and variable from `self`.
+    fn zero_with_absolute_order(&self, order: &Rational) -> Self {
+        Self {
+            coefficients: vec![],
+            field: self.field.clone(),
+            variable: self.variable.clone(),
+            expansion_point: self.expansion_point.clone(),
+            shift: order.numerator().to_i64().unwrap() as isize,
+            order: 0,
+            ramification: order.denominator().to_i64().unwrap() as usize,
+        }
+    }
+
+    /// Constructs a constant series with absolute order `order`, inheriting the field and variable from `self`.
+    fn constant_with_absolute_order(&self, coeff: F::Element, order: &Rational) -> Self {
+        if self.field.is_zero(&coeff) || order.is_negative() || order.is_zero() {
+            return self.zero_with_absolute_order(order);
+        }
+
+        Self {
+            coefficients: vec![coeff],
+            field: self.field.clone(),
+            variable: self.variable.clone(),
+            expansion_point: self.expansion_point.clone(),
+            shift: 0,
+            order: order.numerator().to_i64().unwrap() as usize,
+            ramification: order.denominator().to_i64().unwrap() as usize,
+        }
+    }
+
+    /// Multiply the series by `x^exp`.
+    fn mul_monomial(mut self, exp: &Rational) -> Self {
+        self.change_ramification(exp.denominator().to_i64().unwrap() as usize);
+        let units = exp * &Rational::from(self.ramification as i64);
+        self.shift += units.numerator().to_i64().unwrap() as isize;
+        self
+    }
+
+    /// Replace the expansion variable and expansion point of the series and map its coefficients.
+    pub(crate) fn map_variable(
+        mut self,
+        variable: Arc<Variable>,
+        expansion_point: F::Element,
+        f: impl Fn(&F::Element) -> F::Element,
+    ) -> Self {
+        self.variable = variable;
+        self.expansion_point = expansion_point;
+        for c in &mut self.coefficients {
+            if !self.field.is_zero(c) {
+                *c = f(c);
+            }
+        }
+        self.truncate();
+        self
+    }
 }
 
 impl<F: Ring> SelfRing for Series<F> {
@@ -545,7 +601,7 @@ impl<F: Ring> SelfRing for Series<F> {
         let v = self.variable.format_string(
             opts,
             PrintState {
-                in_exp: true,
+                in_exp_base: true,
                 ..state
             },
         );
@@ -934,6 +990,14 @@ impl Series<AtomField> {
             AtomView::Pow(p) => {
                 let (b, exp) = p.get_base_exp();
 
+                // a power of a power of the variable, as obtained for an expansion around infinity
+                if let AtomView::Pow(pb) = b {
+                    if pb.get_base_exp().0 == s {
+                        let e = pb.get_base_exp().1.to_owned() * exp;
+                        return self.extract_exp_log(s.to_owned().pow(e).as_view(), s);
+                    }
+                }
+
                 if b == s {
                     if let AtomView::Num(n) = exp {
                         if let CoefficientView::Natural(n, d, ni, _di) = n.get_coeff_view() {
@@ -1016,9 +1080,19 @@ impl Series<AtomField> {
             return Err("Log argument needs to have a coefficient");
         }
 
-        // construct the log argument, which may contain x
-        let c = (self.variable.to_atom() - &self.expansion_point).npow(self.get_exponent(0))
-            * &self.coefficients[0];
+        // write the logarithm of the leading term c*(x-a)^e0 as log(c)+e0*log(x-a),
+        // as is done for log series
+        let e0 = self.get_exponent(0);
+        let mut c = FunctionBuilder::new(Atom::LOG)
+            .add_arg(&self.coefficients[0])
+            .finish();
+        if !e0.is_zero() {
+            c += FunctionBuilder::new(Atom::LOG)
+                .add_arg(self.variable.to_atom() - &self.expansion_point)
+                .finish()
+                * Atom::num(e0);
+        }
+
         // normalize the series to 1 + ..
         let p = self
             .clone()
@@ -1026,7 +1100,7 @@ impl Series<AtomField> {
             .mul_exp_units(-self.shift)
             - self.one();
 
-        let mut e = self.constant(FunctionBuilder::new(Atom::LOG).add_arg(&c).finish());
+        let mut e = self.constant(c);
         let mut sp = p.clone();
         for i in 1..=self.order {
             let s = sp.clone().div_coeff(&Atom::num(i as i64));
@@ -1154,6 +1228,57 @@ impl Series<AtomField> {
         Ok(e)
     }
 
+    /// Compute `f(self)`, where `coefficient(n, c)` yields the `n`th Taylor coefficient `f^(n)(c)/n!`
+    /// of `f` around the constant term `c` of the series. The coefficients are requested in
+    /// increasing order of `n`, starting from 0.
+    pub fn apply_taylor(
+        &self,
+        mut coefficient: impl FnMut(usize, AtomView) -> Atom,
+    ) -> Result<Self, &'static str> {
+        if self.shift < 0 {
+            return Err("Cannot series expand function with poles");
+        }
+
+        let c = if self.shift == 0 && self.order > 0 {
+            self.coefficients[0].clone()
+        } else {
+            Atom::new()
+        };
+
+        if c.contains(self.variable.to_atom()) {
+            return Err("Cannot series expand function with a constant term that depends on x");
+        }
+
+        let p = self.clone().remove_constant();
+
+        let mut e = self.constant(coefficient(0, c.as_view()));
+        if p.is_zero() {
+            return Ok(e);
+        }
+
+        let Some(terms) = (&p.absolute_order() / &p.get_trailing_exponent())
+            .floor()
+            .to_i64()
+            .and_then(|t| usize::try_from(t).ok())
+        else {
+            return Err("The order of the series is too large to expand the function");
+        };
+
+        let mut sp = p.clone();
+        for i in 1..=terms {
+            let b = coefficient(i, c.as_view());
+            if !b.is_zero() {
+                e = e + sp.clone().mul_coeff(&b);
+            }
+
+            if i < terms {
+                sp = sp * &p;
+            }
+        }
+
+        Ok(e)
+    }
+
     /// Take the series to the power of another series.
     pub fn pow(&self, pow: &Self) -> Result<Self, &'static str> {
         (self.log()? * pow).exp()
@@ -1233,3 +1358,696 @@ impl Series<AtomField> {
         }
     }
 }
+
+/// A log-extended Puiseux series in `x` around `a`, whose coefficients are polynomials in `log(x-a)`,
+/// for example `x^-1*log(x)^2+x*log(x)+𝒪(x^2)`. The truncation order is absolute and it does
+/// not count powers of the logarithm.
+///
+/// The series is stored as a list of Puiseux series, where the `i`th entry is the coefficient
+/// of `log(x-a)^i`. A power `(x-a)^p` with a non-rational exponent `p`, such as `x^eps`, is
+/// kept as a prefactor of the series, see [LogSeries::symbolic_exponent]. The orders and the
+/// exponents of the terms of the series do not include this prefactor.
+///
+/// # Examples
+///
+/// A log-extended series can be constructed from an [Atom]:
+/// ```
+/// use symbolica::{atom::{Atom, AtomCore}, parse, symbol};
+///
+/// let x = symbol!("x");
+/// let a = parse!("x^x");
+///
+/// let out = a
+///     .log_series(x, Atom::num(0), 2.into(), true)
+///     .unwrap();
+/// println!("{}", out);
+/// assert_eq!(out.to_atom(), parse!("1+x*log(x)+1/2*x^2*log(x)^2"));
+/// ```
+#[derive(Clone)]
+pub struct LogSeries<F: Ring> {
+    log_coefficients: Vec<Series<F>>,
+    symbolic_exponent: Option<F::Element>,
+}
+
+impl<F: Ring> From<Series<F>> for LogSeries<F> {
+    fn from(series: Series<F>) -> Self {
+        LogSeries {
+            log_coefficients: vec![series],
+            symbolic_exponent: None,
+        }
+    }
+}
+
+impl<F: Ring + std::fmt::Debug> std::fmt::Debug for LogSeries<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.log_coefficients.iter())
+            .finish()
+    }
+}
+
+impl<F: Ring + std::fmt::Display> std::fmt::Display for LogSeries<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.format(&PrintOptions::from_fmt(f), PrintState::from_fmt(f), f)
+            .map(|_| ())
+    }
+}
+
+impl<F: Ring> LogSeries<F> {
+    /// Get the series coefficients of the powers of the logarithm, starting at `log(x-a)^0`.
+    pub fn log_coefficients(&self) -> &[Series<F>] {
+        &self.log_coefficients
+    }
+
+    /// Get the non-rational exponent `p` of the prefactor `(x-a)^p` of the series, if any.
+    pub fn symbolic_exponent(&self) -> Option<&F::Element> {
+        self.symbolic_exponent.as_ref()
+    }
+
+    /// Get the highest power of the logarithm that appears in the series.
+    pub fn log_degree(&self) -> usize {
+        self.log_coefficients.len() - 1
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.log_coefficients.iter().all(|s| s.is_zero())
+    }
+
+    /// Get the absolute order of the series expansion.
+    pub fn absolute_order(&self) -> Rational {
+        self.log_coefficients
+            .iter()
+            .map(|s| s.absolute_order())
+            .min()
+            .unwrap()
+    }
+
+    /// Get the relative order of the series expansion, i.e., the
+    /// absolute order minus the trailing exponent.
+    pub fn relative_order(&self) -> Rational {
+        self.absolute_order() - self.get_trailing_exponent()
+    }
+
+    /// Get the lowest exponent of `x` that has a non-zero coefficient, or
+    /// the absolute order if the series is zero.
+    pub fn get_trailing_exponent(&self) -> Rational {
+        self.log_coefficients
+            .iter()
+            .filter(|s| !s.is_zero())
+            .map(|s| s.get_trailing_exponent())
+            .min()
+            .unwrap_or_else(|| self.absolute_order())
+    }
+
+    /// Get the coefficient of `x^exponent*log(x-a)^log_power`.
+    pub fn coefficient(&self, exponent: Rational, log_power: usize) -> F::Element {
+        match self.log_coefficients.get(log_power) {
+            Some(s) => s.coefficient(exponent),
+            None => self.log_coefficients[0].field.zero(),
+        }
+    }
+
+    /// Iterate over the terms of the series, yielding the exponent, the power of the logarithm
+    /// and the coefficient. The terms are sorted by exponent first.
+    pub fn terms(&self) -> impl Iterator<Item = (Rational, usize, &F::Element)> {
+        let mut terms: Vec<_> = self
+            .log_coefficients
+            .iter()
+            .enumerate()
+            .flat_map(|(i, s)| {
+                s.terms()
+                    .filter(|(_, c)| !s.field.is_zero(c))
+                    .map(move |(e, c)| (e, i, c))
+            })
+            .collect();
+        terms.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        terms.into_iter()
+    }
+
+    /// Truncate the series to the desired absolute order.
+    /// If the new order is larger, nothing happens.
+    pub fn truncate_absolute_order(&mut self, order: Rational) {
+        for s in &mut self.log_coefficients {
+            if s.absolute_order() <= order {
+                continue;
+            }
+
+            s.truncate_absolute_order(order.clone());
+            if s.is_zero() {
+                *s = s.zero_with_absolute_order(&order);
+            }
+        }
+
+        while self.log_coefficients.len() > 1 && self.log_coefficients.last().unwrap().is_zero() {
+            self.log_coefficients.pop();
+        }
+    }
+
+    /// Truncate the series to the desired relative order.
+    /// If the new order is larger, nothing happens.
+    pub fn truncate_relative_order(&mut self, order: Rational) {
+        let order = self.get_trailing_exponent() + order;
+        self.truncate_absolute_order(order);
+    }
+
+    /// Replace the expansion variable and expansion point of the series and map its coefficients.
+    pub(crate) fn map_variable(
+        mut self,
+        variable: Arc<Variable>,
+        expansion_point: F::Element,
+        f: impl Fn(&F::Element) -> F::Element,
+    ) -> Self {
+        self.log_coefficients = self
+            .log_coefficients
+            .into_iter()
+            .map(|s| s.map_variable(variable.clone(), expansion_point.clone(), &f))
+            .collect();
+        self.symbolic_exponent = self.symbolic_exponent.as_ref().map(&f);
+        self
+    }
+
+    /// Truncate all logarithmic coefficients to the same absolute order.
+    fn normalize(&mut self) {
+        let order = self.absolute_order();
+        self.truncate_absolute_order(order);
+    }
+
+    /// Constructs a constant series with absolute order `order`.
+    fn constant(&self, coeff: F::Element, order: &Rational) -> Self {
+        self.log_coefficients[0]
+            .constant_with_absolute_order(coeff, order)
+            .into()
+    }
+
+    /// Constructs the series `log(x-a)` with absolute order `order`.
+    fn log_variable(&self, order: &Rational) -> Self {
+        let s = &self.log_coefficients[0];
+        LogSeries {
+            log_coefficients: vec![
+                s.zero_with_absolute_order(order),
+                s.constant_with_absolute_order(s.field.one(), order),
+            ],
+            symbolic_exponent: None,
+        }
+    }
+
+    /// Multiply the series by `x^exp`.
+    fn mul_monomial(mut self, exp: &Rational) -> Self {
+        self.log_coefficients = self
+            .log_coefficients
+            .into_iter()
+            .map(|s| s.mul_monomial(exp))
+            .collect();
+        self
+    }
+
+    /// Remove the terms that are constant in `x`, including those multiplied by a power of the logarithm.
+    fn remove_constant(mut self) -> Self {
+        self.log_coefficients = self
+            .log_coefficients
+            .into_iter()
+            .map(|s| if s.is_zero() { s } else { s.remove_constant() })
+            .collect();
+        self.normalize();
+        self
+    }
+
+    /// Get the number of powers of `self`, a series without a constant term,
+    /// that contribute within the precision of the series.
+    fn expansion_length(&self) -> usize {
+        if self.is_zero() {
+            return 0;
+        }
+
+        (&self.absolute_order() / &self.get_trailing_exponent())
+            .floor()
+            .to_i64()
+            .unwrap() as usize
+    }
+
+    pub fn mul_coeff(mut self, coeff: &F::Element) -> Self {
+        self.log_coefficients = self
+            .log_coefficients
+            .into_iter()
+            .map(|s| s.mul_coeff(coeff))
+            .collect();
+        self.normalize();
+        self
+    }
+
+    /// Compute `self^pow`. `pow` must be a positive integer.
+    pub fn npow(&self, pow: usize) -> Self {
+        if pow == 0 {
+            panic!("Cannot create one with infinite precision");
+        }
+
+        let mut r = self.clone();
+        for _ in 1..pow {
+            r = &r * self;
+        }
+        r
+    }
+
+    fn format<W: std::fmt::Write>(
+        &self,
+        opts: &PrintOptions,
+        mut state: PrintState,
+        f: &mut W,
+    ) -> Result<bool, std::fmt::Error> {
+        let s = &self.log_coefficients[0];
+        let v = s.variable.format_string(
+            opts,
+            PrintState {
+                in_exp_base: true,
+                ..state
+            },
+        );
+
+        let log_arg = s.variable.format_string(opts, PrintState::new());
+        let (log_open, log_close) = if opts.mode.is_latex() {
+            ("\\log\\left(", "\\right)")
+        } else {
+            ("log(", ")")
+        };
+
+        let add_paren = state.in_product || state.in_exp || state.in_exp_base;
+        if add_paren {
+            if state.in_sum {
+                f.write_str("+")?;
+                state.in_sum = false;
+            }
+
+            state.in_product = false;
+            state.in_exp = false;
+            state.in_exp_base = false;
+            f.write_str("(")?;
+        }
+
+        // write the prefactor of the symbolic exponent
+        if let Some(p) = &self.symbolic_exponent {
+            if state.in_sum {
+                f.write_char('+')?;
+                state.in_sum = false;
+            }
+
+            if opts.mode.is_latex() {
+                write!(f, "{}^{{", v)?;
+            } else {
+                write!(f, "{}^(", v)?;
+            }
+            s.field.format(p, opts, PrintState::new(), f)?;
+            if opts.mode.is_latex() {
+                write!(f, "}}{}\\left(", opts.multiplication_operator)?;
+            } else {
+                write!(f, "){}(", opts.multiplication_operator)?;
+            }
+            state.in_product = false;
+        }
+        let in_product = state.in_product;
+
+        for (e, k, c) in self.terms() {
+            state.in_product = in_product || !e.is_zero() || k > 0;
+            state.suppress_one = !e.is_zero() || k > 0;
+            let mut suppressed_one = s.field.format(
+                c,
+                opts,
+                state.step(state.in_sum, state.in_product, false, false),
+                f,
+            )?;
+
+            if !e.is_zero() {
+                if !suppressed_one {
+                    f.write_char(opts.multiplication_operator)?;
+                }
+                suppressed_one = false;
+
+                if e.is_one() {
+                    write!(f, "{}", v)?;
+                } else {
+                    write!(f, "{}^", v)?;
+                    state.suppress_one = false;
+
+                    if opts.mode.is_latex() {
+                        f.write_char('{')?;
+                    }
+
+                    Q.format(&e, opts, state.step(false, false, true, false), f)?;
+
+                    if opts.mode.is_latex() {
+                        f.write_char('}')?;
+                    }
+                }
+            }
+
+            if k > 0 {
+                if !suppressed_one {
+                    f.write_char(opts.multiplication_operator)?;
+                }
+
+                write!(f, "{}{}{}", log_open, log_arg, log_close)?;
+                if k > 1 {
+                    if opts.mode.is_latex() {
+                        write!(f, "^{{{}}}", k)?;
+                    } else {
+                        write!(f, "^{}", k)?;
+                    }
+                }
+            }
+
+            state.in_sum = true;
+        }
+
+        let o = self.absolute_order();
+        state.suppress_one = false;
+
+        if opts.mode.is_latex() {
+            if state.in_sum {
+                f.write_char('+')?;
+            }
+            write!(f, "\\mathcal{{O}}\\left({}^{{{}}}\\right)", v, o)?;
+        } else {
+            if state.in_sum {
+                f.write_char('+')?;
+            }
+            write!(f, "𝒪({}^", v)?;
+            Q.format(&o, opts, state.step(false, false, true, false), f)?;
+            f.write_char(')')?;
+        }
+
+        if self.symbolic_exponent.is_some() {
+            f.write_str(if opts.mode.is_latex() {
+                "\\right)"
+            } else {
+                ")"
+            })?;
+        }
+
+        if add_paren {
+            f.write_str(")")?;
+        }
+
+        Ok(false)
+    }
+}
+
+impl<F: Ring> Add for LogSeries<F> {
+    type Output = Self;
+
+    /// Add two series. Panics if both series are non-zero and have different symbolic exponents.
+    fn add(mut self, mut other: Self) -> Self::Output {
+        if self.is_zero() {
+            self.symbolic_exponent = other.symbolic_exponent.clone();
+        } else if !other.is_zero() && self.symbolic_exponent != other.symbolic_exponent {
+            panic!("Cannot add series with different symbolic exponents");
+        }
+
+        if self.log_coefficients.len() < other.log_coefficients.len() {
+            std::mem::swap(&mut self, &mut other);
+        }
+
+        for (s, o) in self
+            .log_coefficients
+            .iter_mut()
+            .zip(other.log_coefficients.drain(..))
+        {
+            *s = s.clone() + o;
+        }
+
+        self.normalize();
+        self
+    }
+}
+
+impl<'a, F: Ring> Add<&'a LogSeries<F>> for &LogSeries<F> {
+    type Output = LogSeries<F>;
+
+    fn add(self, other: &'a LogSeries<F>) -> Self::Output {
+        (self.clone()).add(other.clone())
+    }
+}
+
+impl<F: Ring> Sub for LogSeries<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.add(other.neg())
+    }
+}
+
+impl<'a, F: Ring> Sub<&'a LogSeries<F>> for &LogSeries<F> {
+    type Output = LogSeries<F>;
+
+    fn sub(self, other: &'a LogSeries<F>) -> Self::Output {
+        (self.clone()).add(other.clone().neg())
+    }
+}
+
+impl<F: Ring> Neg for LogSeries<F> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        self.log_coefficients = self.log_coefficients.into_iter().map(|s| -s).collect();
+        self
+    }
+}
+
+impl<'a, F: Ring> Mul<&'a LogSeries<F>> for &LogSeries<F> {
+    type Output = LogSeries<F>;
+
+    fn mul(self, rhs: &'a LogSeries<F>) -> Self::Output {
+        let mut log_coefficients: Vec<Option<Series<F>>> =
+            vec![None; self.log_coefficients.len() + rhs.log_coefficients.len() - 1];
+
+        for (i, s) in self.log_coefficients.iter().enumerate() {
+            for (j, o) in rhs.log_coefficients.iter().enumerate() {
+                let p = s * o;
+                log_coefficients[i + j] = Some(match log_coefficients[i + j].take() {
+                    Some(c) => c + p,
+                    None => p,
+                });
+            }
+        }
+
+        let field = &self.log_coefficients[0].field;
+        let symbolic_exponent = match (&self.symbolic_exponent, &rhs.symbolic_exponent) {
+            (Some(a), Some(b)) => Some(field.add(a, b)).filter(|e| !field.is_zero(e)),
+            (Some(a), None) | (None, Some(a)) => Some(a.clone()),
+            (None, None) => None,
+        };
+
+        let mut res = LogSeries {
+            log_coefficients: log_coefficients.into_iter().map(|c| c.unwrap()).collect(),
+            symbolic_exponent,
+        };
+        res.normalize();
+        res
+    }
+}
+
+impl<'a, F: Ring> Mul<&'a LogSeries<F>> for LogSeries<F> {
+    type Output = LogSeries<F>;
+
+    fn mul(self, rhs: &'a LogSeries<F>) -> Self::Output {
+        (&self) * rhs
+    }
+}
+
+impl<F: EuclideanDomain> LogSeries<F> {
+    /// Divide every coefficient with `other`.
+    pub fn div_coeff(mut self, other: &F::Element) -> Self {
+        self.log_coefficients = self
+            .log_coefficients
+            .into_iter()
+            .map(|s| s.div_coeff(other))
+            .collect();
+        self
+    }
+}
+
+impl LogSeries<AtomField> {
+    /// Compute the exponential of the series. The constant term may contain
+    /// `log(x-a)`, which is converted into a power of `x-a`. The non-rational part of
+    /// the coefficient of the logarithm becomes the symbolic exponent of the result.
+    pub fn exp(&self) -> Result<Self, &'static str> {
+        if self.symbolic_exponent.is_some() {
+            return Err("Cannot compute the exponential of a series with a symbolic exponent");
+        }
+
+        if self
+            .log_coefficients
+            .iter()
+            .any(|s| !s.is_zero() && s.get_trailing_exponent().is_negative())
+        {
+            return Err("Cannot compute the exponential of a series with poles");
+        }
+
+        let order = self.absolute_order();
+        if order.is_negative() || order.is_zero() {
+            return Err("Cannot compute the exponential of a series without a known constant term");
+        }
+
+        if self
+            .log_coefficients
+            .iter()
+            .skip(2)
+            .any(|s| !s.coefficient(Rational::zero()).is_zero())
+        {
+            return Err(
+                "Cannot compute the exponential of a series with higher powers of logarithms in the constant term",
+            );
+        }
+
+        let c = self.coefficient(Rational::zero(), 0);
+
+        // split the coefficient of the logarithm into a rational shift and a symbolic exponent
+        let log_coeff = self.coefficient(Rational::zero(), 1);
+        let (shift, symbolic_exponent) = match Rational::try_from(log_coeff.as_view()) {
+            Ok(r) => (r, None),
+            Err(_) => {
+                let mut shift = Rational::zero();
+                let mut rest = Atom::new();
+                if let AtomView::Add(a) = log_coeff.as_view() {
+                    for t in a {
+                        match Rational::try_from(t) {
+                            Ok(r) => shift += &r,
+                            Err(_) => rest += t,
+                        }
+                    }
+                } else {
+                    rest = log_coeff;
+                }
+                (shift, Some(rest))
+            }
+        };
+
+        let p = self.clone().remove_constant();
+
+        let mut r = self.constant(Atom::num(1), &order);
+        let mut sp = p.clone();
+        for i in 1..=p.expansion_length() {
+            let s = sp
+                .clone()
+                .div_coeff(&Atom::num(Integer::factorial(i as u32)));
+
+            sp = sp * &p;
+
+            r = r + s;
+        }
+
+        let e = FunctionBuilder::new(Atom::EXP).add_arg(&c).finish();
+        let mut r = r.mul_coeff(&e).mul_monomial(&shift);
+        r.symbolic_exponent = symbolic_exponent;
+        Ok(r)
+    }
+
+    /// Compute the logarithm of the series. The leading term of the series may not contain
+    /// a logarithm, as `log(log(x-a))` cannot be represented. A symbolic exponent `p` yields
+    /// the term `p*log(x-a)`.
+    pub fn log(&self) -> Result<Self, &'static str> {
+        if self.is_zero() {
+            return Err("Log argument needs to have a coefficient");
+        }
+
+        if let Some(p) = &self.symbolic_exponent {
+            let mut s = self.clone();
+            s.symbolic_exponent = None;
+            let order = s.relative_order();
+            return Ok(s.log()? + self.log_variable(&order).mul_coeff(p));
+        }
+
+        let e0 = self.get_trailing_exponent();
+        if self
+            .log_coefficients
+            .iter()
+            .skip(1)
+            .any(|s| !s.is_zero() && s.get_trailing_exponent() <= e0)
+        {
+            return Err("Cannot compute the logarithm of a series with a logarithmic leading term");
+        }
+
+        let c = self.coefficient(e0.clone(), 0);
+        let order = self.relative_order();
+
+        // normalize the series to 1 + ..
+        let p = self.clone().div_coeff(&c).mul_monomial(&-e0.clone())
+            - self.constant(Atom::num(1), &order);
+
+        let mut e = self.constant(FunctionBuilder::new(Atom::LOG).add_arg(&c).finish(), &order);
+        if !e0.is_zero() {
+            e = e + self.log_variable(&order).mul_coeff(&Atom::num(e0));
+        }
+
+        let mut sp = p.clone();
+        for i in 1..=p.expansion_length() {
+            let s = sp.clone().div_coeff(&Atom::num(i as i64));
+
+            sp = sp * &p;
+
+            if i % 2 == 0 {
+                e = e - s;
+            } else {
+                e = e + s;
+            }
+        }
+
+        Ok(e)
+    }
+
+    /// Take the series to the power of another series.
+    pub fn pow(&self, pow: &Self) -> Result<Self, &'static str> {
+        (self.log()? * pow).exp()
+    }
+
+    /// Take the series to the power of a rational number.
+    pub fn rpow(&self, pow: Rational) -> Result<Self, &'static str> {
+        if self.log_coefficients.len() == 1 && self.symbolic_exponent.is_none() {
+            return Ok(self.log_coefficients[0].rpow(pow)?.into());
+        }
+
+        if pow.is_integer() && !pow.is_negative() && !pow.is_zero() {
+            return Ok(self.npow(pow.numerator().to_i64().unwrap() as usize));
+        }
+
+        self.log()?.mul_coeff(&Atom::num(pow)).exp()
+    }
+
+    /// Get the logarithm of the expansion variable as an atom.
+    fn log_variable_atom(&self) -> Atom {
+        let s = &self.log_coefficients[0];
+        let v = s.variable.to_atom() - &s.expansion_point;
+
+        if let AtomView::Pow(p) = v.as_view() {
+            let (b, e) = p.get_base_exp();
+            return FunctionBuilder::new(Atom::LOG).add_arg(b).finish() * e;
+        }
+
+        FunctionBuilder::new(Atom::LOG).add_arg(&v).finish()
+    }
+
+    /// Get the prefactor `(x-a)^p` for the symbolic exponent `p` as an atom.
+    fn prefactor_atom(&self) -> Atom {
+        let Some(p) = &self.symbolic_exponent else {
+            return Atom::num(1);
+        };
+
+        let s = &self.log_coefficients[0];
+        let v = s.variable.to_atom() - &s.expansion_point;
+        if let AtomView::Pow(pv) = v.as_view() {
+            let (b, e) = pv.get_base_exp();
+            return b.to_owned().pow(e.to_owned() * p);
+        }
+
+        v.pow(p)
+    }
+
+    pub fn to_atom(&self) -> Atom {
+        let l = self.log_variable_atom();
+
+        let mut out = Atom::new();
+        for (i, s) in self.log_coefficients.iter().enumerate() {
+            out += s.to_atom() * l.npow(i as i64);
+        }
+        out * self.prefactor_atom()
+    }
+}
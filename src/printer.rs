@@ -1,6 +1,9 @@
 //! Methods for printing atoms and polynomials.
 
-use std::fmt::{self, Error, Write};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Error, Write},
+};
 
 use colored::Colorize;
 
@@ -46,7 +49,7 @@ impl PrintMode {
 }
 
 /// Various options for printing expressions.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PrintOptions {
     pub mode: PrintMode,
     pub terms_on_new_line: bool,
@@ -482,12 +485,16 @@ impl AtomView<'_> {
                         term.push(')');
                     }
 
-                    terms.push(term);
+                    terms.push((x.is_noncommutative_factor(), term));
                 }
 
-                terms.sort();
+                // only commuting factors can be reordered
+                terms.sort_by(|(nc1, t1), (nc2, t2)| {
+                    nc1.cmp(nc2)
+                        .then(if *nc1 { Ordering::Equal } else { t1.cmp(t2) })
+                });
 
-                for (i, term) in terms.iter().enumerate() {
+                for (i, (_, term)) in terms.iter().enumerate() {
                     if i > 0 {
                         write!(out, "*").unwrap();
                     }
@@ -952,19 +959,24 @@ impl FormattedPrintMul for MulView<'_> {
         print_state.level += 1;
         print_state.in_sum = false;
 
+        let mut last_noncommutative = false;
         for x in self.iter().take(if skip_num {
             self.get_nargs() - 1
         } else {
             self.get_nargs()
         }) {
+            let noncommutative = x.is_noncommutative_factor();
             if !first {
                 if opts.mode.is_latex() {
                     f.write_char(' ')?;
+                } else if opts.mode.is_mathematica() && last_noncommutative && noncommutative {
+                    f.write_str("**")?;
                 } else {
                     f.write_char(opts.multiplication_operator)?;
                 }
             }
             first = false;
+            last_noncommutative = noncommutative;
 
             x.format(f, opts, print_state)?;
         }
@@ -1363,6 +1375,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn noncommutative() {
+        let _ = symbol!("print_nc_a", "print_nc_b"; Noncommutative);
+
+        let a = parse!("print_nc_b*x*print_nc_a");
+        assert_eq!(
+            format!("{}", a.printer(PrintOptions::file())),
+            "symbolica::x*symbolica::print_nc_b*symbolica::print_nc_a"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                AtomPrinter::new_with_options(a.as_view(), PrintOptions::mathematica())
+            ),
+            "x print_nc_b**print_nc_a"
+        );
+        assert_eq!(
+            a.to_canonical_string(),
+            "symbolica::x*symbolica::print_nc_b*symbolica::print_nc_a"
+        );
+    }
+
     #[test]
     fn custom_print() {
         let _ = symbol!("mu";;;|a, opt| {
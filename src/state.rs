@@ -19,8 +19,12 @@ use byteorder::LittleEndian;
 use once_cell::sync::Lazy;
 use smartstring::alias::String;
 
-use crate::atom::{FunctionAttribute, NamespacedSymbol, NormalizationFunction};
+use crate::atom::{
+    Assumption, FunctionAttribute, NamespacedSymbol, NormalizationFunction,
+    TaylorCoefficientFunction,
+};
 use crate::domains::finite_field::Zp64;
+use crate::domains::rational::Rational;
 use crate::poly::Variable;
 use crate::printer::PrintFunction;
 use crate::wrap_symbol;
@@ -32,7 +36,7 @@ use crate::{
 };
 
 pub(crate) const SYMBOLICA_MAGIC: u32 = 0x37871367;
-pub(crate) const EXPORT_FORMAT_VERSION: u16 = 1;
+pub(crate) const EXPORT_FORMAT_VERSION: u16 = 2;
 
 /// An id for a given finite field in a registry.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -71,8 +75,11 @@ pub(crate) struct SymbolData {
     pub(crate) namespace: Cow<'static, str>,
     pub(crate) file: Cow<'static, str>,
     pub(crate) line: usize,
+    pub(crate) assumptions: Vec<Assumption>,
     pub(crate) custom_normalization: Option<NormalizationFunction>,
     pub(crate) custom_print: Option<PrintFunction>,
+    pub(crate) custom_taylor: Option<TaylorCoefficientFunction>,
+    pub(crate) custom_derivatives: Option<(Vec<Symbol>, Vec<Atom>)>,
 }
 
 static STATE: Lazy<RwLock<State>> = Lazy::new(|| RwLock::new(State::new()));
@@ -98,20 +105,70 @@ impl Default for State {
 }
 
 impl State {
-    pub(crate) const ARG: Symbol = Symbol::raw_fn(0, 0, false, false, false, false);
-    pub(crate) const COEFF: Symbol = Symbol::raw_fn(1, 0, false, false, false, false);
-    pub(crate) const EXP: Symbol = Symbol::raw_fn(2, 0, false, false, false, false);
-    pub(crate) const LOG: Symbol = Symbol::raw_fn(3, 0, false, false, false, false);
-    pub(crate) const SIN: Symbol = Symbol::raw_fn(4, 0, false, false, false, false);
-    pub(crate) const COS: Symbol = Symbol::raw_fn(5, 0, false, false, false, false);
-    pub(crate) const SQRT: Symbol = Symbol::raw_fn(6, 0, false, false, false, false);
-    pub(crate) const DERIVATIVE: Symbol = Symbol::raw_fn(7, 0, false, false, false, false);
+    pub(crate) const ARG: Symbol =
+        Symbol::raw_fn(0, 0, false, false, false, false, false, false, false);
+    pub(crate) const COEFF: Symbol =
+        Symbol::raw_fn(1, 0, false, false, false, false, false, false, false);
+    pub(crate) const EXP: Symbol =
+        Symbol::raw_fn(2, 0, false, false, false, false, false, false, false);
+    pub(crate) const LOG: Symbol =
+        Symbol::raw_fn(3, 0, false, false, false, false, false, false, false);
+    pub(crate) const SIN: Symbol =
+        Symbol::raw_fn(4, 0, false, false, false, false, false, false, false);
+    pub(crate) const COS: Symbol =
+        Symbol::raw_fn(5, 0, false, false, false, false, false, false, false);
+    pub(crate) const SQRT: Symbol =
+        Symbol::raw_fn(6, 0, false, false, false, false, false, false, false);
+    pub(crate) const DERIVATIVE: Symbol =
+        Symbol::raw_fn(7, 0, false, false, false, false, false, false, false);
     pub(crate) const E: Symbol = Symbol::raw_var(8, 0);
     pub(crate) const PI: Symbol = Symbol::raw_var(9, 0);
+    pub(crate) const TAN: Symbol =
+        Symbol::raw_fn(10, 0, false, false, false, false, false, false, false);
+    pub(crate) const ASIN: Symbol =
+        Symbol::raw_fn(11, 0, false, false, false, false, false, false, false);
+    pub(crate) const ACOS: Symbol =
+        Symbol::raw_fn(12, 0, false, false, false, false, false, false, false);
+    pub(crate) const ATAN: Symbol =
+        Symbol::raw_fn(13, 0, false, false, false, false, false, false, false);
+    pub(crate) const SINH: Symbol =
+        Symbol::raw_fn(14, 0, false, false, false, false, false, false, false);
+    pub(crate) const COSH: Symbol =
+        Symbol::raw_fn(15, 0, false, false, false, false, false, false, false);
+    pub(crate) const TANH: Symbol =
+        Symbol::raw_fn(16, 0, false, false, false, false, false, false, false);
+    pub(crate) const ASINH: Symbol =
+        Symbol::raw_fn(17, 0, false, false, false, false, false, false, false);
+    pub(crate) const ACOSH: Symbol =
+        Symbol::raw_fn(18, 0, false, false, false, false, false, false, false);
+    pub(crate) const ATANH: Symbol =
+        Symbol::raw_fn(19, 0, false, false, false, false, false, false, false);
+    pub(crate) const INTEGRATE: Symbol =
+        Symbol::raw_fn(20, 0, false, false, false, false, false, false, false);
 
     /// The list of built-in symbols.
-    pub const BUILTIN_SYMBOL_NAMES: [&'static str; 10] = [
-        "arg", "coeff", "exp", "log", "sin", "cos", "sqrt", "der", "𝑒", "𝜋",
+    pub const BUILTIN_SYMBOL_NAMES: [&'static str; 21] = [
+        "arg",
+        "coeff",
+        "exp",
+        "log",
+        "sin",
+        "cos",
+        "sqrt",
+        "der",
+        "𝑒",
+        "𝜋",
+        "tan",
+        "asin",
+        "acos",
+        "atan",
+        "sinh",
+        "cosh",
+        "tanh",
+        "asinh",
+        "acosh",
+        "atanh",
+        "integrate",
     ];
 
     pub fn is_builtin_name<S: AsRef<str>>(str: S) -> bool {
@@ -160,6 +217,9 @@ impl State {
             let _ = self.get_symbol_with_attributes(
                 wrap_symbol!(format!("fs{}", i)),
                 &[FunctionAttribute::Symmetric],
+                vec![],
+                None,
+                None,
                 None,
                 None,
             );
@@ -168,6 +228,9 @@ impl State {
             let _ = self.get_symbol_with_attributes(
                 wrap_symbol!(format!("fc{}", i)),
                 &[FunctionAttribute::Cyclesymmetric],
+                vec![],
+                None,
+                None,
                 None,
                 None,
             );
@@ -176,6 +239,9 @@ impl State {
             let _ = self.get_symbol_with_attributes(
                 wrap_symbol!(format!("fa{}", i)),
                 &[FunctionAttribute::Antisymmetric],
+                vec![],
+                None,
+                None,
                 None,
                 None,
             );
@@ -184,6 +250,20 @@ impl State {
             let _ = self.get_symbol_with_attributes(
                 wrap_symbol!(format!("fl{}", i)),
                 &[FunctionAttribute::Linear],
+                vec![],
+                None,
+                None,
+                None,
+                None,
+            );
+        }
+        for i in 0..5 {
+            let _ = self.get_symbol_with_attributes(
+                wrap_symbol!(format!("flf{}", i)),
+                &[FunctionAttribute::LinearInFirstArgument],
+                vec![],
+                None,
+                None,
                 None,
                 None,
             );
@@ -192,6 +272,9 @@ impl State {
             let _ = self.get_symbol_with_attributes(
                 wrap_symbol!(format!("fsl{}", i)),
                 &[FunctionAttribute::Symmetric, FunctionAttribute::Linear],
+                vec![],
+                None,
+                None,
                 None,
                 None,
             );
@@ -307,8 +390,11 @@ impl State {
                         file: name.file,
                         namespace: name.namespace,
                         line: name.line,
+                        assumptions: vec![],
                         custom_normalization: None,
                         custom_print: None,
+                        custom_taylor: None,
+                        custom_derivatives: None,
                     },
                 )) - offset;
                 assert_eq!(id, id_ret);
@@ -329,12 +415,16 @@ impl State {
     /// normalization functions must be registered explicitly.
     ///
     /// If the symbol already exists, an error is returned.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn get_symbol_with_attributes(
         &mut self,
         name: NamespacedSymbol,
         attributes: &[FunctionAttribute],
+        assumptions: Vec<Assumption>,
         normalization_function: Option<NormalizationFunction>,
         print_function: Option<PrintFunction>,
+        taylor_function: Option<TaylorCoefficientFunction>,
+        derivatives: Option<(Vec<Symbol>, Vec<Atom>)>,
     ) -> Result<Symbol, String> {
         match self.str_to_id.entry(name.symbol.into()) {
             Entry::Occupied(o) => {
@@ -347,17 +437,23 @@ impl State {
                     attributes.contains(&FunctionAttribute::Antisymmetric),
                     attributes.contains(&FunctionAttribute::Cyclesymmetric),
                     attributes.contains(&FunctionAttribute::Linear),
+                    attributes.contains(&FunctionAttribute::LinearInFirstArgument),
+                    attributes.contains(&FunctionAttribute::Noncommutative),
+                    attributes.contains(&FunctionAttribute::Grassmann),
                 );
 
-                if r == new_id && normalization_function.is_none() {
+                let data = &ID_TO_STR[r.get_id() as usize].1;
+                if r == new_id
+                    && normalization_function.is_none()
+                    && taylor_function.is_none()
+                    && data.assumptions == assumptions
+                    && data.custom_derivatives == derivatives
+                {
                     Ok(r)
+                } else if data.file.is_empty() {
+                    Err(format!("Symbol {} redefined with new attributes.", data.name).into())
                 } else {
-                    let data = &ID_TO_STR[r.get_id() as usize].1;
-                    if data.file.is_empty() {
-                        Err(format!("Symbol {} redefined with new attributes.", data.name).into())
-                    } else {
-                        Err(format!("Symbol {} redefined with new attributes. The first definition occurred here: {}:{}.", data.name, data.file, data.line).into())
-                    }
+                    Err(format!("Symbol {} redefined with new attributes. The first definition occurred here: {}:{}.", data.name, data.file, data.line).into())
                 }
             }
             Entry::Vacant(v) => {
@@ -387,6 +483,9 @@ impl State {
                     attributes.contains(&FunctionAttribute::Antisymmetric),
                     attributes.contains(&FunctionAttribute::Cyclesymmetric),
                     attributes.contains(&FunctionAttribute::Linear),
+                    attributes.contains(&FunctionAttribute::LinearInFirstArgument),
+                    attributes.contains(&FunctionAttribute::Noncommutative),
+                    attributes.contains(&FunctionAttribute::Grassmann),
                 );
 
                 let id_ret = ID_TO_STR.push((
@@ -396,8 +495,11 @@ impl State {
                         file: name.file,
                         namespace: name.namespace,
                         line: name.line,
+                        assumptions,
                         custom_normalization: normalization_function,
                         custom_print: print_function,
+                        custom_taylor: taylor_function,
+                        custom_derivatives: derivatives,
                     },
                 )) - offset;
                 assert_eq!(id, id_ret);
@@ -457,6 +559,37 @@ impl State {
             .as_ref()
     }
 
+    /// Get the user-specified Taylor coefficient function for the symbol.
+    #[inline]
+    pub(crate) fn get_taylor_coefficient_function(
+        id: Symbol,
+    ) -> Option<&'static TaylorCoefficientFunction> {
+        if ID_TO_STR.len() == 0 {
+            let _ = *STATE; // initialize the state
+        }
+
+        ID_TO_STR[id.get_id() as usize + SYMBOL_OFFSET.load(Ordering::Relaxed)]
+            .1
+            .custom_taylor
+            .as_ref()
+    }
+
+    /// Get the user-specified derivative templates for the symbol,
+    /// together with the argument placeholders that appear in them.
+    #[inline]
+    pub(crate) fn get_derivative_templates(
+        id: Symbol,
+    ) -> Option<&'static (Vec<Symbol>, Vec<Atom>)> {
+        if ID_TO_STR.len() == 0 {
+            let _ = *STATE; // initialize the state
+        }
+
+        ID_TO_STR[id.get_id() as usize + SYMBOL_OFFSET.load(Ordering::Relaxed)]
+            .1
+            .custom_derivatives
+            .as_ref()
+    }
+
     pub(crate) fn get_finite_field(fi: FiniteFieldIndex) -> &'static Zp64 {
         &FINITE_FIELDS[fi.0]
     }
@@ -524,7 +657,28 @@ impl State {
             dest.write_u8(s.is_symmetric() as u8)?;
             dest.write_u8(s.is_antisymmetric() as u8)?;
             dest.write_u8(s.is_cyclesymmetric() as u8)?;
-            dest.write_u8(s.is_linear() as u8)?;
+            dest.write_u8(
+                s.is_linear() as u8
+                    | (s.is_linear_in_first_argument() as u8) << 1
+                    | (s.is_noncommutative() as u8) << 2
+                    | (s.is_grassmann() as u8) << 3,
+            )?;
+
+            let assumptions = s.get_assumptions();
+            dest.write_u8(assumptions.len() as u8)?;
+            for a in assumptions {
+                match a {
+                    Assumption::Real => dest.write_u8(0)?,
+                    Assumption::Positive => dest.write_u8(1)?,
+                    Assumption::Integer => dest.write_u8(2)?,
+                    Assumption::Nonzero => dest.write_u8(3)?,
+                    Assumption::Range(min, max) => {
+                        dest.write_u8(4)?;
+                        Atom::num(min.clone()).as_view().write(dest.by_ref())?;
+                        Atom::num(max.clone()).as_view().write(dest.by_ref())?;
+                    }
+                }
+            }
         }
 
         dest.write_u64::<LittleEndian>(FINITE_FIELDS.len() as u64)?;
@@ -579,8 +733,9 @@ impl State {
             ));
         }
 
+        // version 1 has no assumptions and is loaded with empty assumptions
         let version = source.read_u16::<LittleEndian>()?;
-        if version != EXPORT_FORMAT_VERSION {
+        if version == 0 || version > EXPORT_FORMAT_VERSION {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 "Invalid export format version",
@@ -595,6 +750,7 @@ impl State {
 
         let n_symbols = source.read_u64::<LittleEndian>()?;
         let mut attributes = vec![];
+        let mut assumptions = vec![];
         for x in 0..n_symbols {
             let l = source.read_u32::<LittleEndian>()?;
             let mut v = vec![0; l as usize];
@@ -612,7 +768,11 @@ impl State {
             let is_symmetric = source.read_u8()? != 0;
             let is_antisymmetric = source.read_u8()? != 0;
             let is_cyclesymmetric = source.read_u8()? != 0;
-            let is_linear = source.read_u8()? != 0;
+            let flags = source.read_u8()?;
+            let is_linear = flags & 1 != 0;
+            let is_linear_in_first_argument = flags & 2 != 0;
+            let is_noncommutative = flags & 4 != 0;
+            let is_grassmann = flags & 8 != 0;
 
             attributes.clear();
             if is_antisymmetric {
@@ -627,6 +787,45 @@ impl State {
             if is_linear {
                 attributes.push(FunctionAttribute::Linear);
             }
+            if is_linear_in_first_argument {
+                attributes.push(FunctionAttribute::LinearInFirstArgument);
+            }
+            if is_noncommutative {
+                attributes.push(FunctionAttribute::Noncommutative);
+            }
+            if is_grassmann {
+                attributes.push(FunctionAttribute::Grassmann);
+            }
+
+            assumptions.clear();
+            let n_assumptions = if version >= 2 { source.read_u8()? } else { 0 };
+            for _ in 0..n_assumptions {
+                assumptions.push(match source.read_u8()? {
+                    0 => Assumption::Real,
+                    1 => Assumption::Positive,
+                    2 => Assumption::Integer,
+                    3 => Assumption::Nonzero,
+                    4 => {
+                        let mut bounds = [Atom::new(), Atom::new()];
+                        for b in &mut bounds {
+                            b.read(&mut *source)?;
+                        }
+
+                        let [min, max] = bounds.map(|b| {
+                            Rational::try_from(&b).map_err(|e| {
+                                std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+                            })
+                        });
+                        Assumption::Range(min?, max?)
+                    }
+                    _ => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "Invalid assumption",
+                        ));
+                    }
+                });
+            }
 
             loop {
                 match Symbol::new(NamespacedSymbol {
@@ -636,6 +835,7 @@ impl State {
                     line: 0,
                 })
                 .with_attributes(attributes.clone())
+                .with_assumptions(&assumptions)
                 .build()
                 {
                     Ok(id) => {
@@ -908,14 +1108,22 @@ mod tests {
     use std::io::Cursor;
 
     use crate::{
-        atom::{Atom, AtomView},
-        parse, symbol,
+        atom::{Assumption, Atom, AtomView, FunctionAttribute, Symbol},
+        parse, symbol, wrap_symbol,
     };
 
     use super::State;
 
     #[test]
     fn state_export_import() {
+        let _t = Symbol::new(wrap_symbol!("export_t"))
+            .with_assumptions(&[
+                Assumption::Nonzero,
+                Assumption::Range((-1, 2).into(), 3.into()),
+            ])
+            .build()
+            .unwrap();
+
         let mut export = vec![];
         State::export(&mut export).unwrap();
 
@@ -923,6 +1131,107 @@ mod tests {
         assert!(i.is_empty());
     }
 
+    #[test]
+    fn state_round_trip() {
+        let symbols = [
+            (
+                "export_rt_f_a",
+                vec![
+                    FunctionAttribute::Symmetric,
+                    FunctionAttribute::Linear,
+                    FunctionAttribute::Noncommutative,
+                ],
+                vec![Assumption::Real, Assumption::Positive],
+            ),
+            (
+                "export_rt_g_a",
+                vec![
+                    FunctionAttribute::Antisymmetric,
+                    FunctionAttribute::LinearInFirstArgument,
+                ],
+                vec![Assumption::Integer, Assumption::Nonzero],
+            ),
+            (
+                "export_rt_h_a",
+                vec![
+                    FunctionAttribute::Cyclesymmetric,
+                    FunctionAttribute::Grassmann,
+                ],
+                vec![Assumption::Range((-1, 2).into(), 3.into())],
+            ),
+        ];
+
+        for (name, attributes, assumptions) in &symbols {
+            Symbol::new(wrap_symbol!(*name))
+                .with_attributes(attributes.clone())
+                .with_assumptions(assumptions)
+                .build()
+                .unwrap();
+        }
+
+        let mut export = vec![];
+        State::export(&mut export).unwrap();
+
+        // rename the symbols in the export, so that the import defines new symbols
+        for (name, _, _) in &symbols {
+            let name = format!("symbolica::{}", name);
+            let pos = export
+                .windows(name.len())
+                .position(|w| w == name.as_bytes())
+                .unwrap();
+            *export.get_mut(pos + name.len() - 1).unwrap() = b'b';
+        }
+
+        State::import(&mut Cursor::new(&export), None).unwrap();
+
+        for (name, _, _) in &symbols {
+            let old = symbol!(&format!("symbolica::{}", name));
+            let new = symbol!(&format!("symbolica::{}b", &name[..name.len() - 1]));
+            assert_ne!(old, new);
+            assert_eq!(
+                (
+                    old.is_symmetric(),
+                    old.is_antisymmetric(),
+                    old.is_cyclesymmetric(),
+                    old.is_linear(),
+                    old.is_linear_in_first_argument(),
+                    old.is_noncommutative(),
+                    old.is_grassmann(),
+                ),
+                (
+                    new.is_symmetric(),
+                    new.is_antisymmetric(),
+                    new.is_cyclesymmetric(),
+                    new.is_linear(),
+                    new.is_linear_in_first_argument(),
+                    new.is_noncommutative(),
+                    new.is_grassmann(),
+                )
+            );
+            assert_eq!(old.get_assumptions(), new.get_assumptions());
+        }
+    }
+
+    /// A state exported in version 1 of the format, containing the symmetric
+    /// and linear function `symbolica::import_v1_f`.
+    const STATE_V1: &[u8] = &[
+        0x67, 0x13, 0x87, 0x37, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16,
+        0x00, 0x00, 0x00, 0x73, 0x79, 0x6d, 0x62, 0x6f, 0x6c, 0x69, 0x63, 0x61, 0x3a, 0x3a, 0x69,
+        0x6d, 0x70, 0x6f, 0x72, 0x74, 0x5f, 0x76, 0x31, 0x5f, 0x66, 0x09, 0x00, 0x00, 0x00, 0x73,
+        0x79, 0x6d, 0x62, 0x6f, 0x6c, 0x69, 0x63, 0x61, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn state_import_v1() {
+        let i = State::import(&mut Cursor::new(STATE_V1), None).unwrap();
+        assert!(!i.is_empty());
+
+        let f = symbol!("symbolica::import_v1_f");
+        assert!(f.is_symmetric() && f.is_linear());
+        assert!(f.get_assumptions().is_empty());
+    }
+
     #[test]
     fn custom_normalization() {
         let _real_log = symbol!(
@@ -15,7 +15,9 @@ use smartstring::{LazyCompact, SmartString};
 use crate::{
     LicenseManager,
     atom::{Atom, AtomView},
+    id::ReplaceTrace,
     state::{RecycledAtom, State, Workspace},
+    transformer::{Transformer, TransformerError, TransformerState},
 };
 
 static TEMP_FILES_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
@@ -588,6 +590,83 @@ impl<W: WriteableNamedStream> TermStreamer<W> {
         new_out
     }
 
+    /// Execute the transformer `chain` on every term in the stream, in parallel if the
+    /// streamer has more than one core. The resulting terms are collected in a new stream,
+    /// which is normalized and returned by this function. The execution is aborted
+    /// on the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::BufWriter;
+    /// # use std::fs::File;
+    /// use symbolica::{parse, wrap_input};
+    /// use symbolica::streaming::{TermStreamer, TermStreamerConfig};
+    /// use symbolica::transformer::Transformer;
+    ///
+    /// let mut stream = TermStreamer::<BufWriter<File>>::new(TermStreamerConfig {
+    ///     n_cores: 1,
+    ///     ..Default::default()
+    /// });
+    /// stream.push(parse!("f(2)*(x+1) + f(1)*x"));
+    ///
+    /// let chain = Transformer::parse_chain(wrap_input!(
+    ///     "expand; repeat { replace_all(f(x_), x_*f(x_ - 1), is_type(x_, Num)); replace_all(f(0), 1) }"
+    /// ))
+    /// .unwrap();
+    /// let mut stream = stream.execute_chain(&chain, &Default::default()).unwrap();
+    ///
+    /// assert_eq!(stream.to_expression(), parse!("3*x + 2"));
+    /// ```
+    pub fn execute_chain(
+        &mut self,
+        chain: &[Transformer],
+        state: &TransformerState,
+    ) -> Result<Self, TransformerError> {
+        let f = |x: Atom| {
+            Workspace::get_local().with(|ws| {
+                let mut out = Atom::new();
+                let _ = Transformer::execute_chain(x.as_view(), chain, ws, state, &mut out)?;
+                Ok(out)
+            })
+        };
+
+        let mut new_out = if self.thread_pool.current_num_threads() == 1 {
+            let mut new_out = self.next_generation();
+            for x in self.reader() {
+                new_out.push(f(x)?);
+            }
+            new_out
+        } else {
+            let t = self.thread_pool.clone();
+            // record the replacements on the worker threads in the active trace
+            let trace = ReplaceTrace::active();
+
+            let new_out = self.next_generation();
+            let reader = self.reader();
+            let out_wrap = Mutex::new(new_out);
+
+            t.install(
+                #[inline(always)]
+                || {
+                    reader.par_bridge().try_for_each(|x| {
+                        let r = match &trace {
+                            Some(trace) => trace.record(|| f(x))?,
+                            None => f(x)?,
+                        };
+                        out_wrap.lock().unwrap().push(r);
+                        Ok(())
+                    })
+                },
+            )?;
+
+            out_wrap.into_inner().unwrap()
+        };
+
+        new_out.normalize();
+        Ok(new_out)
+    }
+
     /// Check if two term streams are equal. This will normalize both streams.
     pub fn eq(&mut self, other: &mut Self) -> bool {
         self.normalize();
@@ -715,6 +794,8 @@ mod test {
         parse,
         streaming::{TermStreamer, TermStreamerConfig},
         symbol,
+        transformer::{Transformer, TransformerError, TransformerState},
+        wrap_input,
     };
 
     #[test]
@@ -822,6 +903,50 @@ mod test {
         assert_eq!(r, res);
     }
 
+    #[test]
+    fn transformer_stream() {
+        let mut streamer =
+            TermStreamer::<CompressorWriter<BufWriter<File>>>::new(TermStreamerConfig {
+                n_cores: 1,
+                path: ".".to_owned(),
+                max_mem_bytes: 20,
+            });
+
+        streamer.push(parse!("(v1+v2)^3 + f1(3)*v1 + f1(2)*v3"));
+
+        let chain = Transformer::parse_chain(wrap_input!(
+            "stats(\"expand\") { expand }
+            repeat {
+                if_changed { replace_all(f1(x_), f1(x_ - 1) + 1, is_type(x_, Num)) }
+                then { replace_all(f1(0), 0) }
+            }"
+        ))
+        .unwrap();
+
+        let mut streamer = streamer
+            .execute_chain(&chain, &TransformerState::default())
+            .unwrap();
+        assert_eq!(
+            streamer.to_expression(),
+            parse!("v1^3+3*v1^2*v2+3*v1*v2^2+v2^3+3*v1+2*v3")
+        );
+
+        streamer.push(parse!("f1(v1)"));
+        let chain = vec![Transformer::Map(Box::new(|i, _, o| {
+            if i == parse!("f1(v1)").as_view() {
+                Err(TransformerError::ValueError("unexpected term".into()))
+            } else {
+                o.set_from_view(&i);
+                Ok(())
+            }
+        }))];
+        assert!(
+            streamer
+                .execute_chain(&chain, &TransformerState::default())
+                .is_err()
+        );
+    }
+
     #[test]
     fn term_map() {
         let input = parse!("v1 + v2 + v3 + v4");
@@ -1650,10 +1650,11 @@ impl<F: Field> Matrix<F> {
 #[cfg(test)]
 mod test {
     use crate::{
-        atom::Atom,
+        atom::{Atom, FunctionBuilder, Symbol},
         domains::{atom::AtomField, integer::Z, rational::Q},
         parse, symbol,
         tensors::matrix::{Matrix, Vector},
+        wrap_symbol,
     };
 
     #[test]
@@ -1908,6 +1909,29 @@ mod test {
         );
     }
 
+    #[test]
+    fn jacobian_derivative_templates() {
+        let (a, x, y) = symbol!("a", "x", "y");
+        let f = Symbol::new(wrap_symbol!("f_jac_template"))
+            .with_derivatives(&[a], &[parse!("2*a")])
+            .build()
+            .unwrap();
+
+        let f_xy = FunctionBuilder::new(f).add_arg(parse!("x*y")).finish();
+        let a = Vector::new(vec![f_xy, parse!("x+y")], AtomField::new());
+
+        let b = a.jacobian(&[x.into(), y.into()]);
+        assert_eq!(
+            b.data,
+            [
+                parse!("2*x*y^2"),
+                parse!("2*x^2*y"),
+                Atom::num(1),
+                Atom::num(1)
+            ]
+        );
+    }
+
     #[test]
     fn split_augment() {
         let a = Matrix::from_linear(
@@ -14,8 +14,8 @@ use crate::{
     combinatorics::{partitions, unique_permutations},
     domains::rational::Rational,
     id::{
-        Condition, Evaluate, MatchSettings, Pattern, PatternRestriction, Relation, ReplaceWith,
-        Replacement,
+        Condition, Evaluate, MatchSettings, Pattern, PatternRestriction, Relation, ReplaceTrace,
+        ReplaceWith, Replacement, RuleSet,
     },
     printer::{AtomPrinter, PrintOptions},
     state::{RecycledAtom, Workspace},
@@ -210,6 +210,10 @@ pub enum Transformer {
     ),
     /// Apply multiple find-and-replace on the lhs.
     ReplaceAllMultiple(Vec<Replacement>),
+    /// Apply the rules of a rule set on the lhs, using the head-symbol index
+    /// of the rule set to select candidate rules. This is the indexed equivalent
+    /// of [Transformer::ReplaceAllMultiple].
+    ReplaceAllRuleSet(RuleSet),
     /// Take the product of a list of arguments in the rhs.
     Product,
     /// Take the sum of a list of arguments in the rhs.
@@ -268,6 +272,9 @@ impl std::fmt::Debug for Transformer {
             Transformer::ReplaceAllMultiple(pats) => {
                 f.debug_tuple("ReplaceAllMultiple").field(pats).finish()
             }
+            Transformer::ReplaceAllRuleSet(rules) => {
+                f.debug_tuple("ReplaceAllRuleSet").field(rules).finish()
+            }
             Transformer::Product => f.debug_tuple("Product").finish(),
             Transformer::Sum => f.debug_tuple("Sum").finish(),
             Transformer::ArgCount(p) => f.debug_tuple("ArgCount").field(p).finish(),
@@ -573,16 +580,20 @@ impl Transformer {
                 }
                 Transformer::MapTerms(t, p) => {
                     if let Some(p) = p {
-                        *out = cur_input.map_terms_with_pool(
-                            |arg| {
-                                Workspace::get_local().with(|ws| {
-                                    let mut a = Atom::new();
-                                    let _ = Self::execute_chain(arg, t, ws, state, &mut a).unwrap();
-                                    a
-                                })
-                            },
-                            p,
-                        );
+                        let map = |arg: AtomView| {
+                            Workspace::get_local().with(|ws| {
+                                let mut a = Atom::new();
+                                let _ = Self::execute_chain(arg, t, ws, state, &mut a).unwrap();
+                                a
+                            })
+                        };
+
+                        // record the replacements on the worker threads in the active trace
+                        *out = if let Some(trace) = ReplaceTrace::active() {
+                            cur_input.map_terms_with_pool(|arg| trace.record(|| map(arg)), p)
+                        } else {
+                            cur_input.map_terms_with_pool(map, p)
+                        };
                     } else {
                         *out = cur_input.map_terms_single_core(|arg| {
                             Workspace::get_local().with(|ws| {
@@ -720,6 +731,9 @@ impl Transformer {
                 Transformer::ReplaceAllMultiple(replacements) => {
                     cur_input.replace_multiple_into(replacements, out);
                 }
+                Transformer::ReplaceAllRuleSet(rules) => {
+                    cur_input.replace_rule_set_into(rules, out);
+                }
                 Transformer::Product => {
                     if let AtomView::Fun(f) = cur_input {
                         if f.get_symbol() == Atom::ARG {
@@ -1019,13 +1033,14 @@ impl Transformer {
 #[cfg(test)]
 mod test {
     use crate::{
-        atom::{Atom, AtomCore, FunctionBuilder},
-        id::{Condition, Match, MatchSettings, WildcardRestriction},
+        atom::{Atom, AtomCore, FunctionBuilder, Symbol},
+        id::{Condition, Match, MatchSettings, Replacement, WildcardRestriction},
         parse,
         printer::PrintOptions,
         state::Workspace,
         symbol,
         transformer::{StatsOptions, TransformerState},
+        wrap_symbol,
     };
 
     use super::Transformer;
@@ -1053,6 +1068,58 @@ mod test {
         assert_eq!(out, r);
     }
 
+    #[test]
+    fn replace_all_multiple() {
+        let p = parse!("f(1) + g(2) + h(3)");
+        let rules = vec![
+            Replacement::new(parse!("f(x_)").to_pattern(), parse!("x_+1").to_pattern()),
+            Replacement::new(parse!("g(x_)").to_pattern(), parse!("x_^2").to_pattern()),
+        ];
+
+        for t in [
+            Transformer::ReplaceAllMultiple(rules.clone()),
+            Transformer::ReplaceAllRuleSet(rules.clone().into()),
+        ] {
+            let mut out = Atom::new();
+            let _ = Workspace::get_local().with(|ws| {
+                Transformer::execute_chain(
+                    p.as_view(),
+                    &[t],
+                    ws,
+                    &TransformerState::default(),
+                    &mut out,
+                )
+                .unwrap()
+            });
+
+            assert_eq!(out, parse!("6 + h(3)"));
+        }
+    }
+
+    #[test]
+    fn derivative_template() {
+        let a = symbol!("a");
+        let f = Symbol::new(wrap_symbol!("f_der_transformer"))
+            .with_derivatives(&[a], &[parse!("a^-1")])
+            .build()
+            .unwrap();
+        let p = FunctionBuilder::new(f).add_arg(parse!("v1^3")).finish();
+
+        let mut out = Atom::new();
+        let _ = Workspace::get_local().with(|ws| {
+            Transformer::execute_chain(
+                p.as_view(),
+                &[Transformer::Derivative(symbol!("v1"))],
+                ws,
+                &TransformerState::default(),
+                &mut out,
+            )
+            .unwrap()
+        });
+
+        assert_eq!(out, parse!("3*v1^-1"));
+    }
+
     #[test]
     fn split_argcount() {
         let p = parse!("v1+v2+v3");
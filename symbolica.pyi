@@ -55,6 +55,9 @@ def S(name: str,
       is_antisymmetric: Optional[bool] = None,
       is_cyclesymmetric: Optional[bool] = None,
       is_linear: Optional[bool] = None,
+      is_linear_in_first_argument: Optional[bool] = None,
+      is_noncommutative: Optional[bool] = None,
+      is_grassmann: Optional[bool] = None,
       custom_normalization: Optional[Transformer] = None,
       custom_print: Optional[Callable[..., Optional[str]]] = None) -> Expression:
     """
@@ -85,7 +88,7 @@ def S(name: str,
     f(1,2)
 
 
-    Define a linear and symmetric function:
+    Define a multilinear and symmetric function:
     >>> p1, p2, p3, p4 = ES('p1', 'p2', 'p3', 'p4')
     >>> dot = S('dot', is_symmetric=True, is_linear=True)
     >>> e = dot(p2+2*p3,p1+3*p2-p3)
@@ -119,7 +122,13 @@ def S(name: str,
     is_cyclesymmetric : Optional[bool]
         Set to true if the symbol is cyclesymmetric.
     is_linear : Optional[bool]
-        Set to true if the symbol is linear.
+        Set to true if the symbol is linear in every argument, which makes it multilinear.
+    is_linear_in_first_argument : Optional[bool]
+        Set to true if the symbol is linear in its first argument only.
+    is_noncommutative : Optional[bool]
+        Set to true if the symbol keeps its order with respect to other noncommutative symbols in a product.
+    is_grassmann : Optional[bool]
+        Set to true if the symbol anticommutes with other Grassmann symbols in a product.
     custom_normalization : Optional[Transformer]
         A transformer that is called after every normalization. Note that the symbol
         name cannot be used in the transformer as this will lead to a definition of the
@@ -136,7 +145,10 @@ def S(*names: str,
       is_symmetric: Optional[bool] = None,
       is_antisymmetric: Optional[bool] = None,
       is_cyclesymmetric: Optional[bool] = None,
-      is_linear: Optional[bool] = None) -> Sequence[Expression]:
+      is_linear: Optional[bool] = None,
+      is_linear_in_first_argument: Optional[bool] = None,
+      is_noncommutative: Optional[bool] = None,
+      is_grassmann: Optional[bool] = None) -> Sequence[Expression]:
     """
     Create new symbols from `names`. Symbols can have attributes,
     such as symmetries. If no attributes
@@ -165,7 +177,13 @@ def S(*names: str,
     is_cyclesymmetric : Optional[bool]
         Set to true if the symbol is cyclesymmetric.
     is_linear : Optional[bool]
-        Set to true if the symbol is multilinear.
+        Set to true if the symbol is linear in every argument, which makes it multilinear.
+    is_linear_in_first_argument : Optional[bool]
+        Set to true if the symbol is linear in its first argument only.
+    is_noncommutative : Optional[bool]
+        Set to true if the symbol keeps its order with respect to other noncommutative symbols in a product.
+    is_grassmann : Optional[bool]
+        Set to true if the symbol anticommutes with other Grassmann symbols in a product.
     """
 
 
@@ -321,6 +339,9 @@ class Expression:
                is_antisymmetric: Optional[bool] = None,
                is_cyclesymmetric: Optional[bool] = None,
                is_linear: Optional[bool] = None,
+               is_linear_in_first_argument: Optional[bool] = None,
+               is_noncommutative: Optional[bool] = None,
+               is_grassmann: Optional[bool] = None,
                custom_normalization: Optional[Transformer] = None,
                custom_print: Optional[Callable[[Expression], Optional[str]]] = None) -> Expression:
         """
@@ -351,7 +372,7 @@ class Expression:
         f(1,2)
 
 
-        Define a linear and symmetric function:
+        Define a multilinear and symmetric function:
         >>> p1, p2, p3, p4 = S('p1', 'p2', 'p3', 'p4')
         >>> dot = S('dot', is_symmetric=True, is_linear=True)
         >>> e = dot(p2+2*p3,p1+3*p2-p3)
@@ -385,7 +406,13 @@ class Expression:
         is_cyclesymmetric : Optional[bool]
             Set to true if the symbol is cyclesymmetric.
         is_linear : Optional[bool]
-            Set to true if the symbol is linear.
+            Set to true if the symbol is linear in every argument, which makes it multilinear.
+        is_linear_in_first_argument : Optional[bool]
+            Set to true if the symbol is linear in its first argument only.
+        is_noncommutative : Optional[bool]
+            Set to true if the symbol keeps its order with respect to other noncommutative symbols in a product.
+        is_grassmann : Optional[bool]
+            Set to true if the symbol anticommutes with other Grassmann symbols in a product.
         custom_normalization : Optional[Transformer]
             A transformer that is called after every normalization. Note that the symbol
             name cannot be used in the transformer as this will lead to a definition of the
@@ -403,7 +430,10 @@ class Expression:
                is_symmetric: Optional[bool] = None,
                is_antisymmetric: Optional[bool] = None,
                is_cyclesymmetric: Optional[bool] = None,
-               is_linear: Optional[bool] = None) -> Sequence[Expression]:
+               is_linear: Optional[bool] = None,
+               is_linear_in_first_argument: Optional[bool] = None,
+               is_noncommutative: Optional[bool] = None,
+               is_grassmann: Optional[bool] = None) -> Sequence[Expression]:
         """
         Create new symbols from `names`. Symbols can have attributes,
         such as symmetries. If no attributes
@@ -432,7 +462,13 @@ class Expression:
         is_cyclesymmetric : Optional[bool]
             Set to true if the symbol is cyclesymmetric.
         is_linear : Optional[bool]
-            Set to true if the symbol is multilinear.
+            Set to true if the symbol is linear in every argument, which makes it multilinear.
+        is_linear_in_first_argument : Optional[bool]
+            Set to true if the symbol is linear in its first argument only.
+        is_noncommutative : Optional[bool]
+            Set to true if the symbol keeps its order with respect to other noncommutative symbols in a product.
+        is_grassmann : Optional[bool]
+            Set to true if the symbol anticommutes with other Grassmann symbols in a product.
         """
 
     @overload
@@ -1247,6 +1283,42 @@ class Expression:
     def derivative(self, x: Expression) -> Expression:
         """Derive the expression w.r.t the variable `x`."""
 
+    def integrate(self, x: Expression) -> Expression:
+        """Integrate the expression w.r.t the variable `x`. Terms that cannot be
+        integrated are returned as an unevaluated `integrate(term, x)`. Rational functions
+        whose integral requires the roots of an irreducible polynomial of degree higher
+        than two, such as `1/(x^3+2)`, are returned unevaluated.
+
+        Examples
+        --------
+        >>> from symbolica import Expression
+        >>> x = Expression.symbol('x')
+        >>> print((1/(x**2+1)).integrate(x))
+
+        yields `atan(x)`.
+        """
+
+    def limit(
+        self,
+        x: Expression,
+        point: Expression | int | float | complex | Decimal,
+        direction: str = "both",
+    ) -> Expression | float | None:
+        """Compute the limit of the expression for `x` going to `point`, which is an expression
+        or `float('inf')`/`float('-inf')`. The `direction` is `both`, `+` (from above) or `-` (from below).
+
+        The result is an expression, `float('inf')`, `float('-inf')`, or `None` if the limit
+        does not exist. An error is raised if the limit could not be determined.
+
+        Examples
+        --------
+        >>> from symbolica import Expression
+        >>> x = Expression.symbol('x')
+        >>> print((x*(-x).exp()).limit(x, float('inf')))
+
+        yields `0`.
+        """
+
     def series(
         self,
         x: Expression,
@@ -1257,6 +1329,25 @@ class Expression:
     ) -> Series:
         """Series expand in `x` around `expansion_point` to depth `depth`."""
 
+    def series_at_infinity(
+        self,
+        x: Expression,
+        depth: int,
+        depth_denom: int = 1,
+        depth_is_absolute: bool = True
+    ) -> Series:
+        """Series expand in `x` around infinity to depth `depth`, i.e., in powers of `x^-1`.
+
+        Examples
+        --------
+        >>> from symbolica import Expression
+        >>> x = Expression.symbol('x')
+        >>> e = (x**2/(x-1)).series_at_infinity(x, 2)
+        >>> print(e.to_expression())
+
+        yields `x+1+x^-1+x^-2`.
+        """
+
     def apart(self, x: Optional[Expression] = None) -> Expression:
         """Compute the partial fraction decomposition in `x`.
 
@@ -2847,7 +2938,8 @@ class TermStreamer:
         """Convert the term stream into an expression. This may exceed the available memory."""
 
     def map(self, f: Transformer, stats_to_file: Optional[str] = None) -> TermStreamer:
-        """Apply a transformer to all terms in the stream.
+        """Apply a transformer to all terms in the stream. The resulting stream is normalized.
+        The execution is aborted on the first error, which is raised.
 
         Parameters
         ----------